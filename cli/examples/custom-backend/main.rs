@@ -34,6 +34,7 @@ use jj_lib::backend::Conflict;
 use jj_lib::backend::ConflictId;
 use jj_lib::backend::CopyRecord;
 use jj_lib::backend::FileId;
+use jj_lib::backend::GcStats;
 use jj_lib::backend::SigningFn;
 use jj_lib::backend::SymlinkId;
 use jj_lib::backend::Tree;
@@ -208,7 +209,12 @@ impl Backend for JitBackend {
         self.inner.get_copy_records(paths, root, head)
     }
 
-    fn gc(&self, index: &dyn Index, keep_newer: SystemTime) -> BackendResult<()> {
-        self.inner.gc(index, keep_newer)
+    fn gc(
+        &self,
+        index: &dyn Index,
+        keep_newer: SystemTime,
+        dry_run: bool,
+    ) -> BackendResult<GcStats> {
+        self.inner.gc(index, keep_newer, dry_run)
     }
 }
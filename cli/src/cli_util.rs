@@ -21,9 +21,12 @@ use std::env;
 use std::ffi::OsString;
 use std::fmt;
 use std::fmt::Debug;
+use std::fs;
 use std::io;
+use std::io::Read as _;
 use std::io::Write as _;
 use std::mem;
+use std::ops::Range;
 use std::path::Path;
 use std::path::PathBuf;
 use std::rc::Rc;
@@ -65,11 +68,14 @@ use jj_lib::config::ConfigNamePathBuf;
 use jj_lib::config::ConfigSource;
 use jj_lib::config::StackedConfig;
 use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::file_util;
 use jj_lib::fileset;
 use jj_lib::fileset::FilesetDiagnostics;
 use jj_lib::fileset::FilesetExpression;
 use jj_lib::gitignore::GitIgnoreError;
 use jj_lib::gitignore::GitIgnoreFile;
+use jj_lib::hunk_selection::select_hunks;
+use jj_lib::hunk_selection::HunkSelection;
 use jj_lib::id_prefix::IdPrefixContext;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::MergedTreeValue;
@@ -83,7 +89,6 @@ use jj_lib::op_walk;
 use jj_lib::op_walk::OpsetEvaluationError;
 use jj_lib::operation::Operation;
 use jj_lib::ref_name::RefName;
-use jj_lib::ref_name::RefNameBuf;
 use jj_lib::ref_name::WorkspaceName;
 use jj_lib::ref_name::WorkspaceNameBuf;
 use jj_lib::repo::merge_factories_map;
@@ -137,11 +142,15 @@ use jj_lib::workspace::Workspace;
 use jj_lib::workspace::WorkspaceLoadError;
 use jj_lib::workspace::WorkspaceLoader;
 use jj_lib::workspace::WorkspaceLoaderFactory;
+use jj_lib::workspace_store::SimpleWorkspaceStore;
+use jj_lib::workspace_store::WorkspaceEntry;
+use pollster::FutureExt as _;
 use tracing::instrument;
 use tracing_chrome::ChromeLayerBuilder;
 use tracing_subscriber::prelude::*;
 
 use crate::command_error::cli_error;
+use crate::command_error::config_error;
 use crate::command_error::config_error_with_message;
 use crate::command_error::handle_command_result;
 use crate::command_error::internal_error;
@@ -149,6 +158,7 @@ use crate::command_error::internal_error_with_message;
 use crate::command_error::print_parse_diagnostics;
 use crate::command_error::user_error;
 use crate::command_error::user_error_with_hint;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::commit_templater::CommitTemplateLanguage;
 use crate::commit_templater::CommitTemplateLanguageExtension;
@@ -704,20 +714,6 @@ impl ReadonlyUserRepo {
     }
 }
 
-/// A advanceable bookmark to satisfy the "advance-bookmarks" feature.
-///
-/// This is a helper for `WorkspaceCommandTransaction`. It provides a
-/// type-safe way to separate the work of checking whether a bookmark
-/// can be advanced and actually advancing it. Advancing the bookmark
-/// never fails, but can't be done until the new `CommitId` is
-/// available. Splitting the work in this way also allows us to
-/// identify eligible bookmarks without actually moving them and
-/// return config errors to the user early.
-pub struct AdvanceableBookmark {
-    name: RefNameBuf,
-    old_commit_id: CommitId,
-}
-
 /// Helper for parsing and evaluating settings for the advance-bookmarks
 /// feature. Settings are configured in the jj config.toml as lists of
 /// [`StringPattern`]s for enabled and disabled bookmarks. Example:
@@ -1022,6 +1018,8 @@ pub struct WorkspaceCommandHelper {
     op_summary_template_text: String,
     may_update_working_copy: bool,
     working_copy_shared_with_git: bool,
+    #[cfg(feature = "git")]
+    git_worktree: Option<jj_lib::git::GitWorktree>,
 }
 
 enum SnapshotWorkingCopyError {
@@ -1059,8 +1057,12 @@ impl WorkspaceCommandHelper {
         let op_summary_template_text = settings.get_string("templates.op_summary")?;
         let may_update_working_copy =
             loaded_at_head && !env.command.global_args().ignore_working_copy;
-        let working_copy_shared_with_git =
-            crate::git_util::is_colocated_git_workspace(&workspace, &repo);
+        #[cfg(feature = "git")]
+        let git_worktree = crate::git_util::colocated_git_worktree(&workspace, &repo);
+        #[cfg(feature = "git")]
+        let working_copy_shared_with_git = git_worktree.is_some();
+        #[cfg(not(feature = "git"))]
+        let working_copy_shared_with_git = false;
 
         let helper = Self {
             workspace,
@@ -1070,6 +1072,8 @@ impl WorkspaceCommandHelper {
             op_summary_template_text,
             may_update_working_copy,
             working_copy_shared_with_git,
+            #[cfg(feature = "git")]
+            git_worktree,
         };
         // Parse commit_summary template early to report error before starting
         // mutable operation.
@@ -1110,8 +1114,9 @@ impl WorkspaceCommandHelper {
         }
 
         #[cfg(feature = "git")]
-        if self.working_copy_shared_with_git {
-            self.import_git_head(ui).map_err(snapshot_command_error)?;
+        if let Some(worktree) = self.git_worktree.clone() {
+            self.import_git_head(ui, &worktree)
+                .map_err(snapshot_command_error)?;
         }
         // Because the Git refs (except HEAD) aren't imported yet, the ref
         // pointing to the new working-copy commit might not be exported.
@@ -1138,7 +1143,7 @@ impl WorkspaceCommandHelper {
         Ok(())
     }
 
-    /// Imports new HEAD from the colocated Git repo.
+    /// Imports new HEAD of the Git worktree the working copy is shared with.
     ///
     /// If the Git HEAD has changed, this function checks out the new Git HEAD.
     /// The old working-copy commit will be abandoned if it's discardable. The
@@ -1146,10 +1151,14 @@ impl WorkspaceCommandHelper {
     /// working-copy contents won't be updated.
     #[cfg(feature = "git")]
     #[instrument(skip_all)]
-    fn import_git_head(&mut self, ui: &Ui) -> Result<(), CommandError> {
+    fn import_git_head(
+        &mut self,
+        ui: &Ui,
+        worktree: &jj_lib::git::GitWorktree,
+    ) -> Result<(), CommandError> {
         assert!(self.may_update_working_copy);
         let mut tx = self.start_transaction();
-        jj_lib::git::import_head(tx.repo_mut())?;
+        jj_lib::git::import_head(tx.repo_mut(), worktree)?;
         if !tx.repo().has_changes() {
             return Ok(());
         }
@@ -1165,8 +1174,8 @@ impl WorkspaceCommandHelper {
         //   out yet.
 
         let mut tx = tx.into_inner();
-        let old_git_head = self.repo().view().git_head().clone();
-        let new_git_head = tx.repo().view().git_head().clone();
+        let old_git_head = worktree.git_head(self.repo().view()).clone();
+        let new_git_head = worktree.git_head(tx.repo().view()).clone();
         if let Some(new_git_head_id) = new_git_head.as_normal() {
             let workspace_name = self.workspace_name().to_owned();
             let new_git_head_commit = tx.repo().store().get_commit(new_git_head_id)?;
@@ -1190,7 +1199,7 @@ impl WorkspaceCommandHelper {
             }
         } else {
             // Unlikely, but the HEAD ref got deleted by git?
-            self.finish_transaction(ui, tx, "import git head")?;
+            self.finish_transaction(ui, tx, "import git head", false)?;
         }
         Ok(())
     }
@@ -1223,7 +1232,7 @@ impl WorkspaceCommandHelper {
                 "Rebased {num_rebased} descendant commits off of commits rewritten from git"
             )?;
         }
-        self.finish_transaction(ui, tx, "import git refs")?;
+        self.finish_transaction(ui, tx, "import git refs", false)?;
         writeln!(
             ui.status(),
             "Done importing changes from the underlying Git repo."
@@ -1537,6 +1546,38 @@ to the current parents may contain changes from multiple commits.
         }
     }
 
+    /// Creates hunk selector from the `--select` and `--select-patch`
+    /// arguments. Returns `None` if neither is specified.
+    pub fn hunk_selector(
+        &self,
+        args: &HunkSelectionArgs,
+    ) -> Result<Option<DiffSelector>, CommandError> {
+        let mut selection = match args.select_patch.as_deref() {
+            None if args.select.is_empty() => return Ok(None),
+            None => HunkSelection::new(),
+            Some("-") => {
+                let mut patch = vec![];
+                io::stdin().read_to_end(&mut patch)?;
+                HunkSelection::from_patch(&patch)?
+            }
+            Some(file) => {
+                let patch = std::fs::read(file).map_err(|err| {
+                    user_error_with_message(format!("Failed to read patch file {file}"), err)
+                })?;
+                HunkSelection::from_patch(&patch)?
+            }
+        };
+        for value in &args.select {
+            let (path, lines) = parse_line_range_selection(value).map_err(user_error)?;
+            let path = self.parse_file_path(path)?;
+            match lines {
+                Some(lines) => selection.select_lines(path, lines),
+                None => selection.select_file(path),
+            }
+        }
+        Ok(Some(DiffSelector::Hunks(selection)))
+    }
+
     /// Loads 3-way merge editor from the settings.
     ///
     /// If the `tool_name` isn't specified, the default editor will be returned.
@@ -1965,13 +2006,14 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
             }
 
             #[cfg(feature = "git")]
-            if self.working_copy_shared_with_git {
+            if let Some(worktree) = &self.git_worktree {
                 let old_tree = wc_commit.tree().map_err(snapshot_command_error)?;
                 let new_tree = commit.tree().map_err(snapshot_command_error)?;
                 jj_lib::git::update_intent_to_add(
                     self.user_repo.repo.as_ref(),
                     &old_tree,
                     &new_tree,
+                    worktree,
                 )
                 .map_err(snapshot_command_error)?;
 
@@ -2006,7 +2048,72 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
             new_commit,
             &checkout_options,
         )?;
-        self.print_updated_working_copy_stats(ui, maybe_old_commit, new_commit, &stats)
+        self.print_updated_working_copy_stats(ui, maybe_old_commit, new_commit, &stats)?;
+        #[cfg(feature = "git")]
+        self.check_out_git_submodules(ui, maybe_old_commit, new_commit)?;
+        Ok(())
+    }
+
+    /// Checks out the commits recorded for Git submodules in the new
+    /// working-copy commit if `git.update-submodules` is enabled. Failures are
+    /// reported as warnings since the rest of the working copy has already
+    /// been updated.
+    #[cfg(feature = "git")]
+    fn check_out_git_submodules(
+        &self,
+        ui: &Ui,
+        maybe_old_commit: Option<&Commit>,
+        new_commit: &Commit,
+    ) -> Result<(), CommandError> {
+        use std::error::Error as _;
+
+        use jj_lib::git_submodule;
+        use jj_lib::sparse::sparse_patterns_to_expression;
+
+        use crate::command_error::print_error_sources;
+        use crate::git_util::get_submodule_remote;
+
+        let repo = self.repo().as_ref();
+        let git_settings = self.settings().git_settings()?;
+        if !git_settings.update_submodules || jj_lib::git::get_git_backend(repo.store()).is_err() {
+            return Ok(());
+        }
+        let old_tree = maybe_old_commit.map(|commit| commit.tree()).transpose()?;
+        let new_tree = new_commit.tree()?;
+        // Like the working copy, match all files if the patterns can't be parsed.
+        let sparse_matcher = sparse_patterns_to_expression(
+            self.working_copy().sparse_patterns()?,
+            self.workspace_root(),
+        )
+        .unwrap_or_else(|_| FilesetExpression::all())
+        .to_matcher();
+        let remote_name = get_submodule_remote(self.settings(), repo.store())?;
+        let result = git_submodule::check_out_submodules(
+            repo,
+            old_tree.as_ref(),
+            &new_tree,
+            sparse_matcher.as_ref(),
+            self.workspace_root(),
+            remote_name.as_deref(),
+            &git_settings,
+        );
+        let stats = match result {
+            Ok(stats) => stats,
+            Err(err) => {
+                writeln!(ui.warning_default(), "Failed to update submodules: {err}")?;
+                print_error_sources(ui, err.source())?;
+                return Ok(());
+            }
+        };
+        for (path, err) in &stats.failed {
+            writeln!(
+                ui.warning_default(),
+                "Failed to update submodule {}: {err}",
+                self.format_file_path(path)
+            )?;
+            print_error_sources(ui, err.source())?;
+        }
+        Ok(())
     }
 
     fn print_updated_working_copy_stats(
@@ -2054,14 +2161,198 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
             helper: self,
             tx,
             id_prefix_context,
+            advance_bookmarks: true,
         }
     }
 
+    /// Advances bookmarks if the working-copy commit of this workspace was
+    /// moved onto a different parent, as if `jj new <parent>` had been run.
+    /// This happens when the working-copy commit is committed (`jj commit`,
+    /// `jj split`) or squashed into its parent (`jj squash`), when a new
+    /// working-copy commit is created (`jj new`), and when the working-copy
+    /// commit is rebased onto another commit (`jj rebase -r @`).
+    ///
+    /// Eligible bookmarks pointing to the parents of the new parent commit are
+    /// moved to the new parent commit.
+    fn advance_bookmarks_to_new_wc_parent(&self, tx: &mut Transaction) -> Result<(), CommandError> {
+        let ab_settings = AdvanceBookmarksSettings::from_settings(self.settings())?;
+        if !ab_settings.feature_enabled() {
+            return Ok(());
+        }
+        let base_repo = tx.base_repo().clone();
+        let (Some(old_wc_commit_id), Some(new_wc_commit_id)) = (
+            base_repo.view().get_wc_commit_id(self.workspace_name()),
+            tx.repo().view().get_wc_commit_id(self.workspace_name()),
+        ) else {
+            return Ok(());
+        };
+        // Checking out an existing commit shouldn't move bookmarks.
+        if base_repo.index().has_id(new_wc_commit_id) {
+            return Ok(());
+        }
+        let old_wc_commit = base_repo.store().get_commit(old_wc_commit_id)?;
+        let new_wc_commit = tx.repo().store().get_commit(new_wc_commit_id)?;
+        let [parent_id] = new_wc_commit.parent_ids() else {
+            return Ok(());
+        };
+        if old_wc_commit.parent_ids().contains(parent_id) {
+            return Ok(());
+        }
+        let parent = tx.repo().store().get_commit(parent_id)?;
+        // If the working-copy commit was rewritten along with its parent (e.g.
+        // by `jj rebase -b @`), bookmarks in the rebased stack shouldn't move.
+        if new_wc_commit.change_id() == old_wc_commit.change_id() {
+            let old_parents: Vec<_> = old_wc_commit.parents().try_collect()?;
+            if old_parents
+                .iter()
+                .any(|old_parent| old_parent.change_id() == parent.change_id())
+            {
+                return Ok(());
+            }
+        }
+        for grandparent_id in parent.parent_ids() {
+            let names = tx
+                .repo()
+                .view()
+                .local_bookmarks_for_commit(grandparent_id)
+                .map(|(name, _)| name)
+                .filter(|name| ab_settings.bookmark_is_eligible(name))
+                .map(|name| name.to_owned())
+                .collect_vec();
+            for name in names {
+                tx.repo_mut().merge_local_bookmark(
+                    &name,
+                    &RefTarget::normal(grandparent_id.clone()),
+                    &RefTarget::normal(parent_id.clone()),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Forgets the temporary workspaces other than the current one whose
+    /// working-copy commit was abandoned in the transaction. The returned
+    /// workspaces should be deleted once the transaction is committed.
+    fn forget_abandoned_temporary_workspaces(
+        &self,
+        tx: &mut Transaction,
+    ) -> Result<Vec<WorkspaceEntry>, CommandError> {
+        let workspace_store = SimpleWorkspaceStore::load(self.repo_path());
+        let mut abandoned = vec![];
+        for entry in workspace_store.list()? {
+            if !entry.temporary || entry.name == self.workspace_name() {
+                continue;
+            }
+            let (Some(old_wc_commit_id), Some(new_wc_commit_id)) = (
+                tx.base_repo().view().get_wc_commit_id(&entry.name),
+                tx.repo().view().get_wc_commit_id(&entry.name),
+            ) else {
+                continue;
+            };
+            if old_wc_commit_id == new_wc_commit_id {
+                continue;
+            }
+            // A rewritten working-copy commit keeps its change ID.
+            let old_wc_commit = tx.base_repo().store().get_commit(old_wc_commit_id)?;
+            if tx
+                .repo()
+                .resolve_change_id(old_wc_commit.change_id())
+                .is_none()
+            {
+                tx.repo_mut().remove_wc_commit(&entry.name)?;
+                abandoned.push(entry);
+            }
+        }
+        if !abandoned.is_empty() {
+            tx.repo_mut().rebase_descendants()?;
+        }
+        Ok(abandoned)
+    }
+
+    /// Returns true if the files in the workspace at `workspace_root` match
+    /// the tree that was last checked out there, so deleting the workspace
+    /// doesn't lose any changes.
+    fn is_workspace_snapshotted(
+        &self,
+        ui: &Ui,
+        workspace_root: &Path,
+    ) -> Result<bool, CommandError> {
+        let mut workspace = self
+            .env
+            .command
+            .load_workspace_at(workspace_root, self.settings())?;
+        let auto_tracking_matcher = self.auto_tracking_matcher(ui)?;
+        let options = self.snapshot_options_with_start_tracking_matcher(&auto_tracking_matcher)?;
+        // The lock is released without writing the snapshot.
+        let mut locked_ws = workspace.start_working_copy_mutation()?;
+        let old_tree_id = locked_ws.locked_wc().old_tree_id().clone();
+        let (new_tree_id, _stats) = locked_ws.locked_wc().snapshot(&options)?;
+        Ok(new_tree_id == old_tree_id)
+    }
+
+    /// Forgets the given temporary workspaces and deletes their directories.
+    /// Workspaces with changes that weren't snapshotted are kept on disk.
+    fn delete_temporary_workspaces(
+        &self,
+        ui: &Ui,
+        workspaces: &[WorkspaceEntry],
+    ) -> Result<(), CommandError> {
+        if workspaces.is_empty() {
+            return Ok(());
+        }
+        let names = workspaces.iter().map(|entry| &*entry.name).collect_vec();
+        SimpleWorkspaceStore::load(self.repo_path()).forget(&names)?;
+        crate::git_util::remove_git_worktrees(self.repo(), &names)?;
+        for entry in workspaces {
+            let path = file_util::relative_path(self.env.command.cwd(), &entry.path);
+            match self.is_workspace_snapshotted(ui, &entry.path) {
+                Ok(true) => {}
+                Ok(false) => {
+                    writeln!(
+                        ui.warning_default(),
+                        "Temporary workspace {name} in \"{path}\" has changes that weren't \
+                         snapshotted, so it was forgotten but not deleted",
+                        name = entry.name.as_symbol(),
+                        path = path.display()
+                    )?;
+                    continue;
+                }
+                Err(err) => {
+                    writeln!(
+                        ui.warning_default(),
+                        "Failed to snapshot temporary workspace {name} in \"{path}\", so it was \
+                         forgotten but not deleted: {err}",
+                        name = entry.name.as_symbol(),
+                        path = path.display(),
+                        err = err.error
+                    )?;
+                    continue;
+                }
+            }
+            match fs::remove_dir_all(&entry.path) {
+                Ok(()) => writeln!(
+                    ui.status(),
+                    "Deleted temporary workspace {name} in \"{path}\"",
+                    name = entry.name.as_symbol(),
+                    path = path.display()
+                )?,
+                Err(err) => writeln!(
+                    ui.warning_default(),
+                    "Failed to delete temporary workspace {name} in \"{path}\": {err}",
+                    name = entry.name.as_symbol(),
+                    path = path.display()
+                )?,
+            }
+        }
+        Ok(())
+    }
+
     fn finish_transaction(
         &mut self,
         ui: &Ui,
         mut tx: Transaction,
         description: impl Into<String>,
+        advance_bookmarks: bool,
     ) -> Result<(), CommandError> {
         if !tx.repo().has_changes() {
             writeln!(ui.status(), "Nothing changed.")?;
@@ -2071,6 +2362,9 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
         if num_rebased > 0 {
             writeln!(ui.status(), "Rebased {num_rebased} descendant commits")?;
         }
+        if advance_bookmarks {
+            self.advance_bookmarks_to_new_wc_parent(&mut tx)?;
+        }
 
         for (name, wc_commit_id) in &tx.repo().view().wc_commit_ids().clone() {
             if self
@@ -2089,6 +2383,8 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
             }
         }
 
+        let abandoned_temporary_workspaces = self.forget_abandoned_temporary_workspaces(&mut tx)?;
+
         let old_repo = tx.base_repo().clone();
 
         let maybe_old_wc_commit = old_repo
@@ -2104,12 +2400,12 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
             .transpose()?;
 
         #[cfg(feature = "git")]
-        if self.working_copy_shared_with_git {
+        if let Some(worktree) = &self.git_worktree {
             use std::error::Error as _;
             if let Some(wc_commit) = &maybe_new_wc_commit {
                 // This can fail if HEAD was updated concurrently. In that case,
                 // the actual state will be imported on the next snapshot.
-                match jj_lib::git::reset_head(tx.repo_mut(), wc_commit) {
+                match jj_lib::git::reset_head(tx.repo_mut(), wc_commit, worktree) {
                     Ok(()) => {}
                     Err(err @ jj_lib::git::GitResetHeadError::UpdateHeadRef(_)) => {
                         writeln!(ui.warning_default(), "{err}")?;
@@ -2123,6 +2419,7 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
         }
 
         self.user_repo = ReadonlyUserRepo::new(tx.commit(description)?);
+        self.delete_temporary_workspaces(ui, &abandoned_temporary_workspaces)?;
 
         // Update working copy before reporting repo changes, so that
         // potential errors while reporting changes (broken pipe, etc)
@@ -2339,45 +2636,6 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
         )?;
         Ok(())
     }
-
-    /// Identifies bookmarks which are eligible to be moved automatically
-    /// during `jj commit` and `jj new`. Whether a bookmark is eligible is
-    /// determined by its target and the user and repo config for
-    /// "advance-bookmarks".
-    ///
-    /// Returns a Vec of bookmarks in `repo` that point to any of the `from`
-    /// commits and that are eligible to advance. The `from` commits are
-    /// typically the parents of the target commit of `jj commit` or `jj new`.
-    ///
-    /// Bookmarks are not moved until
-    /// `WorkspaceCommandTransaction::advance_bookmarks()` is called with the
-    /// `AdvanceableBookmark`s returned by this function.
-    ///
-    /// Returns an empty `std::Vec` if no bookmarks are eligible to advance.
-    pub fn get_advanceable_bookmarks<'a>(
-        &self,
-        from: impl IntoIterator<Item = &'a CommitId>,
-    ) -> Result<Vec<AdvanceableBookmark>, CommandError> {
-        let ab_settings = AdvanceBookmarksSettings::from_settings(self.settings())?;
-        if !ab_settings.feature_enabled() {
-            // Return early if we know that there's no work to do.
-            return Ok(Vec::new());
-        }
-
-        let mut advanceable_bookmarks = Vec::new();
-        for from_commit in from {
-            for (name, _) in self.repo().view().local_bookmarks_for_commit(from_commit) {
-                if ab_settings.bookmark_is_eligible(name) {
-                    advanceable_bookmarks.push(AdvanceableBookmark {
-                        name: name.to_owned(),
-                        old_commit_id: from_commit.clone(),
-                    });
-                }
-            }
-        }
-
-        Ok(advanceable_bookmarks)
-    }
 }
 
 /// An ongoing [`Transaction`] tied to a particular workspace.
@@ -2393,6 +2651,9 @@ pub struct WorkspaceCommandTransaction<'a> {
     tx: Transaction,
     /// Cache of index built against the current MutableRepo state.
     id_prefix_context: OnceCell<IdPrefixContext>,
+    /// Whether to advance bookmarks to the parent of the new working-copy
+    /// commit when the transaction is finished.
+    advance_bookmarks: bool,
 }
 
 impl WorkspaceCommandTransaction<'_> {
@@ -2419,6 +2680,10 @@ impl WorkspaceCommandTransaction<'_> {
         self.tx.repo_mut()
     }
 
+    pub fn set_tag(&mut self, key: String, value: String) {
+        self.tx.set_tag(key, value);
+    }
+
     pub fn check_out(&mut self, commit: &Commit) -> Result<Commit, CheckOutCommitError> {
         let name = self.helper.workspace_name().to_owned();
         self.id_prefix_context.take(); // invalidate
@@ -2475,8 +2740,15 @@ impl WorkspaceCommandTransaction<'_> {
         self.helper.env.parse_template(ui, &language, template_text)
     }
 
+    /// Prevents bookmarks from being advanced to the parent of the new
+    /// working-copy commit when the transaction is finished.
+    pub fn skip_advance_bookmarks(&mut self) {
+        self.advance_bookmarks = false;
+    }
+
     pub fn finish(self, ui: &Ui, description: impl Into<String>) -> Result<(), CommandError> {
-        self.helper.finish_transaction(ui, self.tx, description)
+        self.helper
+            .finish_transaction(ui, self.tx, description, self.advance_bookmarks)
     }
 
     /// Returns the wrapped [`Transaction`] for circumstances where
@@ -2486,23 +2758,6 @@ impl WorkspaceCommandTransaction<'_> {
     pub fn into_inner(self) -> Transaction {
         self.tx
     }
-
-    /// Moves each bookmark in `bookmarks` from an old commit it's associated
-    /// with (configured by `get_advanceable_bookmarks`) to the `move_to`
-    /// commit. If the bookmark is conflicted before the update, it will
-    /// remain conflicted after the update, but the conflict will involve
-    /// the `move_to` commit instead of the old commit.
-    pub fn advance_bookmarks(&mut self, bookmarks: Vec<AdvanceableBookmark>, move_to: &CommitId) {
-        for bookmark in bookmarks {
-            // This removes the old commit ID from the bookmark's RefTarget and
-            // replaces it with the `move_to` ID.
-            self.repo_mut().merge_local_bookmark(
-                &bookmark.name,
-                &RefTarget::normal(bookmark.old_commit_id),
-                &RefTarget::normal(move_to.clone()),
-            );
-        }
-    }
 }
 
 pub fn find_workspace_dir(cwd: &Path) -> &Path {
@@ -2543,6 +2798,8 @@ jj git init --colocate",
             err @ (StoreLoadError::ReadError { .. } | StoreLoadError::Backend(_)),
         ) => internal_error_with_message("The repository appears broken or inaccessible", err),
         WorkspaceLoadError::StoreLoadError(StoreLoadError::Signing(err)) => user_error(err),
+        WorkspaceLoadError::StoreLoadError(StoreLoadError::Config(err)) => err.into(),
+        WorkspaceLoadError::StoreLoadError(StoreLoadError::MergeDrivers(err)) => config_error(err),
         WorkspaceLoadError::WorkingCopyState(err) => internal_error(err),
         WorkspaceLoadError::NonUnicodePath | WorkspaceLoadError::Path(_) => user_error(err),
     }
@@ -2804,6 +3061,9 @@ pub fn print_checkout_stats(
             stats.removed_files
         )?;
     }
+    for warning in &stats.sparse_warnings {
+        writeln!(ui.warning_default(), "{warning}")?;
+    }
     if stats.skipped_files != 0 {
         writeln!(
             ui.warning_default(),
@@ -2987,6 +3247,8 @@ pub fn short_operation_hash(operation_id: &OperationId) -> String {
 pub enum DiffSelector {
     NonInteractive,
     Interactive(DiffEditor),
+    /// Selects hunks specified by `--select` or `--select-patch`.
+    Hunks(HunkSelection),
 }
 
 impl DiffSelector {
@@ -3000,6 +3262,7 @@ impl DiffSelector {
     /// Only files matching the `matcher` will be copied to the new tree.
     pub fn select(
         &self,
+        ui: &Ui,
         left_tree: &MergedTree,
         right_tree: &MergedTree,
         matcher: &dyn Matcher,
@@ -3008,17 +3271,66 @@ impl DiffSelector {
         let selected_tree_id = restore_tree(right_tree, left_tree, matcher)?;
         match self {
             DiffSelector::NonInteractive => Ok(selected_tree_id),
+            DiffSelector::Hunks(selection) => {
+                let right_tree = right_tree.store().get_root_tree(&selected_tree_id)?;
+                Ok(select_hunks(left_tree, &right_tree, matcher, selection).block_on()?)
+            }
             DiffSelector::Interactive(editor) => {
                 // edit_diff_external() is designed to edit the right tree,
                 // whereas we want to update the left tree. Unmatched paths
                 // shouldn't be based off the right tree.
                 let right_tree = right_tree.store().get_root_tree(&selected_tree_id)?;
-                Ok(editor.edit(left_tree, &right_tree, matcher, format_instructions)?)
+                Ok(editor.edit(ui, left_tree, &right_tree, matcher, format_instructions)?)
             }
         }
     }
 }
 
+/// Arguments to select changes without using a diff editor.
+#[derive(clap::Args, Clone, Debug)]
+pub struct HunkSelectionArgs {
+    /// Select changes to the given file, or only the hunks overlapping the
+    /// given lines
+    ///
+    /// The value is of the form `PATH[:START[-END]]`. Line numbers are 1-based
+    /// and refer to the new content of the file, i.e. the content the changes
+    /// are taken from. This option can be repeated.
+    #[arg(
+        long,
+        value_name = "PATH[:LINES]",
+        conflicts_with_all = ["interactive", "tool"],
+        value_hint = clap::ValueHint::FilePath,
+    )]
+    select: Vec<String>,
+    /// Select the hunks included in the given patch file (`-` for stdin)
+    ///
+    /// The patch should be in the unified diff format, such as the output of
+    /// `jj diff --git` for the same changes.
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["interactive", "tool"],
+        value_hint = clap::ValueHint::FilePath,
+    )]
+    select_patch: Option<String>,
+}
+
+/// Splits `PATH[:START[-END]]` into path and 0-based line range.
+fn parse_line_range_selection(value: &str) -> Result<(&str, Option<Range<usize>>), String> {
+    let Some((path, lines)) = value
+        .rsplit_once(':')
+        .filter(|(_, lines)| lines.starts_with(|c: char| c.is_ascii_digit()))
+    else {
+        return Ok((value, None));
+    };
+    let (start, end) = lines.split_once('-').unwrap_or((lines, lines));
+    let parse = |n: &str| n.parse::<usize>().ok().filter(|&n| n > 0);
+    match (parse(start), parse(end)) {
+        (Some(start), Some(end)) if start <= end => Ok((path, Some(start - 1..end))),
+        _ => Err(format!("Invalid line range: {lines}")),
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct RemoteBookmarkNamePattern {
     pub bookmark: StringPattern,
@@ -34,6 +34,7 @@ use jj_lib::fileset::FilesetParseError;
 use jj_lib::fileset::FilesetParseErrorKind;
 use jj_lib::fix::FixError;
 use jj_lib::gitignore::GitIgnoreError;
+use jj_lib::hunk_selection::PatchParseError;
 use jj_lib::op_heads_store::OpHeadResolutionError;
 use jj_lib::op_heads_store::OpHeadsStoreError;
 use jj_lib::op_store::OpStoreError;
@@ -59,6 +60,7 @@ use jj_lib::working_copy::ResetError;
 use jj_lib::working_copy::SnapshotError;
 use jj_lib::working_copy::WorkingCopyStateError;
 use jj_lib::workspace::WorkspaceInitError;
+use jj_lib::workspace_store::WorkspaceStoreError;
 use thiserror::Error;
 
 use crate::cli_util::short_operation_hash;
@@ -356,11 +358,19 @@ impl From<WorkspaceInitError> for CommandError {
                 internal_error_with_message("Failed to access the repository", err)
             }
             WorkspaceInitError::SignInit(err) => user_error(err),
+            WorkspaceInitError::Config(err) => err.into(),
+            WorkspaceInitError::MergeDrivers(err) => config_error(err),
             WorkspaceInitError::TransactionCommit(err) => err.into(),
         }
     }
 }
 
+impl From<WorkspaceStoreError> for CommandError {
+    fn from(err: WorkspaceStoreError) -> Self {
+        internal_error_with_message("Failed to access the workspace store", err)
+    }
+}
+
 impl From<OpHeadResolutionError> for CommandError {
     fn from(err: OpHeadResolutionError) -> Self {
         match err {
@@ -525,6 +535,7 @@ mod git {
     use jj_lib::git::GitPushError;
     use jj_lib::git::GitRemoteManagementError;
     use jj_lib::git::GitResetHeadError;
+    use jj_lib::git::GitWorktreeError;
     use jj_lib::git::UnexpectedGitBackendError;
 
     use super::*;
@@ -605,6 +616,17 @@ jj currently does not support partial clones. To use jj with this repository, tr
         }
     }
 
+    impl From<GitWorktreeError> for CommandError {
+        fn from(err: GitWorktreeError) -> Self {
+            match err {
+                GitWorktreeError::InvalidName(_) | GitWorktreeError::AlreadyExists(_) => {
+                    user_error(err)
+                }
+                _ => user_error_with_message("Failed to update Git worktree", err),
+            }
+        }
+    }
+
     impl From<UnexpectedGitBackendError> for CommandError {
         fn from(err: UnexpectedGitBackendError) -> Self {
             user_error(err)
@@ -684,6 +706,12 @@ impl From<UiPathParseError> for CommandError {
     }
 }
 
+impl From<PatchParseError> for CommandError {
+    fn from(err: PatchParseError) -> Self {
+        user_error(err)
+    }
+}
+
 impl From<clap::Error> for CommandError {
     fn from(err: clap::Error) -> Self {
         let hint = find_source_parse_error_hint(&err);
@@ -69,6 +69,18 @@ pub(crate) struct AbandonArgs {
     /// Do not modify the content of the children of the abandoned commits
     #[arg(long)]
     restore_descendants: bool,
+    /// Only abandon the revisions whose changes already landed in the given
+    /// revisions or their ancestors
+    ///
+    /// This abandons the revisions that were cherry-picked or squash-merged
+    /// upstream, e.g. `jj abandon --landed 'trunk()' 'mutable()'`. See the
+    /// `landed()` revset function for how the changes are compared.
+    #[arg(
+        long,
+        value_name = "REVSETS",
+        add = ArgValueCompleter::new(complete::revset_expression_all),
+    )]
+    landed: Option<RevisionArg>,
 }
 
 #[instrument(skip_all)]
@@ -81,14 +93,24 @@ pub(crate) fn cmd_abandon(
         writeln!(ui.warning_default(), "--summary is no longer supported.")?;
     }
     let mut workspace_command = command.workspace_helper(ui)?;
-    let to_abandon: Vec<_> = if !args.revisions_pos.is_empty() || !args.revisions_opt.is_empty() {
-        workspace_command
-            .parse_union_revsets(ui, &[&*args.revisions_pos, &*args.revisions_opt].concat())?
-    } else {
-        workspace_command.parse_revset(ui, &RevisionArg::AT)?
+    let mut to_abandon_evaluator =
+        if !args.revisions_pos.is_empty() || !args.revisions_opt.is_empty() {
+            workspace_command
+                .parse_union_revsets(ui, &[&*args.revisions_pos, &*args.revisions_opt].concat())?
+        } else {
+            workspace_command.parse_revset(ui, &RevisionArg::AT)?
+        };
+    if let Some(landed) = &args.landed {
+        // Only compare with the upstream commits that aren't ancestors of the
+        // revisions, so their patch IDs don't have to be computed.
+        let upstream = workspace_command.parse_revset(ui, landed)?;
+        let revisions = to_abandon_evaluator
+            .expression()
+            .minus(&upstream.expression().ancestors());
+        let expression = revisions.landed(&revisions.range(upstream.expression()));
+        to_abandon_evaluator = workspace_command.attach_revset_evaluator(expression);
     }
-    .evaluate_to_commits()?
-    .try_collect()?;
+    let to_abandon: Vec<_> = to_abandon_evaluator.evaluate_to_commits()?.try_collect()?;
     if to_abandon.is_empty() {
         writeln!(ui.status(), "No revisions to abandon.")?;
         return Ok(());
@@ -14,6 +14,7 @@
 
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
+use jj_lib::op_store::BookmarkMetadata;
 use jj_lib::op_store::RefTarget;
 use jj_lib::str_util::StringPattern;
 
@@ -62,6 +63,8 @@ pub fn cmd_bookmark_delete(
     for (name, _) in &matched_bookmarks {
         tx.repo_mut()
             .set_local_bookmark_target(name, RefTarget::absent());
+        tx.repo_mut()
+            .set_bookmark_metadata(name, BookmarkMetadata::default());
     }
     writeln!(
         ui.status(),
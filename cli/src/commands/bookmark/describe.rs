@@ -0,0 +1,124 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::ref_name::RefNameBuf;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::revset_util;
+use crate::ui::Ui;
+
+/// Set the description, owner, or base bookmark of bookmarks
+///
+/// The metadata is stored locally in the repository and is available in
+/// templates via the `description()`, `owner()`, and `base()` methods of the
+/// `CommitRef` type. The base bookmark is used by `jj rebase` as the default
+/// destination for the bookmark's stack.
+///
+/// Metadata is removed when the bookmark is deleted or forgotten.
+#[derive(clap::Args, Clone, Debug)]
+pub struct BookmarkDescribeArgs {
+    /// The bookmarks to update
+    #[arg(
+        required = true,
+        value_parser = revset_util::parse_bookmark_name,
+        add = ArgValueCandidates::new(complete::local_bookmarks),
+    )]
+    names: Vec<RefNameBuf>,
+
+    /// The description to set
+    ///
+    /// Use an empty string to clear the description.
+    #[arg(long = "message", short = 'm', value_name = "MESSAGE")]
+    description: Option<String>,
+
+    /// The person or team responsible for the bookmarks
+    ///
+    /// Use an empty string to clear the owner.
+    #[arg(long)]
+    owner: Option<String>,
+
+    /// The bookmark the bookmarks are based on
+    #[arg(
+        long,
+        value_parser = revset_util::parse_bookmark_name,
+        add = ArgValueCandidates::new(complete::local_bookmarks),
+    )]
+    base: Option<RefNameBuf>,
+
+    /// Clear the base bookmark
+    #[arg(long, conflicts_with = "base")]
+    no_base: bool,
+}
+
+pub fn cmd_bookmark_describe(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BookmarkDescribeArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let view = workspace_command.repo().view();
+    let names = args.names.iter().unique().collect_vec();
+    for name in &names {
+        if view.get_local_bookmark(name).is_absent() {
+            return Err(user_error(format!(
+                "No such bookmark: {name}",
+                name = name.as_symbol()
+            )));
+        }
+    }
+    if let Some(base) = &args.base {
+        if view.get_local_bookmark(base).is_absent() {
+            return Err(user_error(format!(
+                "No such bookmark: {base}",
+                base = base.as_symbol()
+            )));
+        }
+        if names.contains(&base) {
+            return Err(user_error(format!(
+                "Bookmark {base} cannot be based on itself",
+                base = base.as_symbol()
+            )));
+        }
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    for name in &names {
+        let mut metadata = tx.repo().get_bookmark_metadata(name).unwrap_or_default();
+        if let Some(description) = &args.description {
+            metadata.description = description.clone();
+        }
+        if let Some(owner) = &args.owner {
+            metadata.owner = owner.clone();
+        }
+        if let Some(base) = &args.base {
+            metadata.base = Some(base.clone());
+        } else if args.no_base {
+            metadata.base = None;
+        }
+        tx.repo_mut().set_bookmark_metadata(name, metadata);
+    }
+    tx.finish(
+        ui,
+        format!(
+            "describe bookmark {}",
+            names.iter().map(|name| name.as_symbol()).join(", ")
+        ),
+    )?;
+    Ok(())
+}
@@ -14,6 +14,7 @@
 
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
+use jj_lib::op_store::BookmarkMetadata;
 use jj_lib::op_store::BookmarkTarget;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
@@ -71,6 +72,8 @@ pub fn cmd_bookmark_forget(
     for (name, bookmark_target) in &matched_bookmarks {
         tx.repo_mut()
             .set_local_bookmark_target(name, RefTarget::absent());
+        tx.repo_mut()
+            .set_bookmark_metadata(name, BookmarkMetadata::default());
         for (remote, _) in &bookmark_target.remote_refs {
             let symbol = name.to_remote_symbol(remote);
             // If `--include-remotes` is specified, we forget the corresponding remote
@@ -214,10 +214,11 @@ pub fn cmd_bookmark_list(
 
         let include_local_only = !args.tracked && args.remotes.is_none();
         if include_local_only && local_target.is_present() || !tracked_remote_refs.is_empty() {
-            let primary = CommitRef::local(
+            let primary = CommitRef::local_bookmark(
                 name,
                 local_target.clone(),
                 remote_refs.iter().map(|&(_, remote_ref)| remote_ref),
+                view.get_bookmark_metadata(name),
             );
             let tracked = tracked_remote_refs
                 .iter()
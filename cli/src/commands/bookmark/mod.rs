@@ -14,11 +14,13 @@
 
 mod create;
 mod delete;
+mod describe;
 mod forget;
 mod list;
 mod r#move;
 mod rename;
 mod set;
+mod status;
 mod track;
 mod untrack;
 
@@ -36,6 +38,8 @@ use self::create::cmd_bookmark_create;
 use self::create::BookmarkCreateArgs;
 use self::delete::cmd_bookmark_delete;
 use self::delete::BookmarkDeleteArgs;
+use self::describe::cmd_bookmark_describe;
+use self::describe::BookmarkDescribeArgs;
 use self::forget::cmd_bookmark_forget;
 use self::forget::BookmarkForgetArgs;
 use self::list::cmd_bookmark_list;
@@ -46,6 +50,8 @@ use self::rename::cmd_bookmark_rename;
 use self::rename::BookmarkRenameArgs;
 use self::set::cmd_bookmark_set;
 use self::set::BookmarkSetArgs;
+use self::status::cmd_bookmark_status;
+use self::status::BookmarkStatusArgs;
 use self::track::cmd_bookmark_track;
 use self::track::BookmarkTrackArgs;
 use self::untrack::cmd_bookmark_untrack;
@@ -71,6 +77,7 @@ pub enum BookmarkCommand {
     Create(BookmarkCreateArgs),
     #[command(visible_alias("d"))]
     Delete(BookmarkDeleteArgs),
+    Describe(BookmarkDescribeArgs),
     #[command(visible_alias("f"))]
     Forget(BookmarkForgetArgs),
     #[command(visible_alias("l"))]
@@ -81,6 +88,7 @@ pub enum BookmarkCommand {
     Rename(BookmarkRenameArgs),
     #[command(visible_alias("s"))]
     Set(BookmarkSetArgs),
+    Status(BookmarkStatusArgs),
     #[command(visible_alias("t"))]
     Track(BookmarkTrackArgs),
     Untrack(BookmarkUntrackArgs),
@@ -94,11 +102,13 @@ pub fn cmd_bookmark(
     match subcommand {
         BookmarkCommand::Create(args) => cmd_bookmark_create(ui, command, args),
         BookmarkCommand::Delete(args) => cmd_bookmark_delete(ui, command, args),
+        BookmarkCommand::Describe(args) => cmd_bookmark_describe(ui, command, args),
         BookmarkCommand::Forget(args) => cmd_bookmark_forget(ui, command, args),
         BookmarkCommand::List(args) => cmd_bookmark_list(ui, command, args),
         BookmarkCommand::Move(args) => cmd_bookmark_move(ui, command, args),
         BookmarkCommand::Rename(args) => cmd_bookmark_rename(ui, command, args),
         BookmarkCommand::Set(args) => cmd_bookmark_set(ui, command, args),
+        BookmarkCommand::Status(args) => cmd_bookmark_status(ui, command, args),
         BookmarkCommand::Track(args) => cmd_bookmark_track(ui, command, args),
         BookmarkCommand::Untrack(args) => cmd_bookmark_untrack(ui, command, args),
     }
@@ -13,8 +13,11 @@
 // limitations under the License.
 
 use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::op_store::BookmarkMetadata;
 use jj_lib::op_store::RefTarget;
 use jj_lib::ref_name::RefNameBuf;
+use jj_lib::repo::Repo as _;
 
 use crate::cli_util::has_tracked_remote_bookmarks;
 use crate::cli_util::CommandHelper;
@@ -70,6 +73,26 @@ pub fn cmd_bookmark_rename(
         .set_local_bookmark_target(new_bookmark, ref_target);
     tx.repo_mut()
         .set_local_bookmark_target(old_bookmark, RefTarget::absent());
+    if let Some(metadata) = tx.repo().get_bookmark_metadata(old_bookmark) {
+        tx.repo_mut().set_bookmark_metadata(new_bookmark, metadata);
+        tx.repo_mut()
+            .set_bookmark_metadata(old_bookmark, BookmarkMetadata::default());
+    }
+    // Keep stacks based on the renamed bookmark intact.
+    let dependents = tx
+        .repo()
+        .view()
+        .bookmark_metadata()
+        .filter(|(_, metadata)| metadata.base.as_ref() == Some(old_bookmark))
+        .map(|(name, metadata)| (name.to_owned(), metadata.clone()))
+        .collect_vec();
+    for (name, metadata) in dependents {
+        let metadata = BookmarkMetadata {
+            base: Some(new_bookmark.clone()),
+            ..metadata
+        };
+        tx.repo_mut().set_bookmark_metadata(&name, metadata);
+    }
     tx.finish(
         ui,
         format!(
@@ -0,0 +1,237 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::evolution::walk_predecessors;
+use jj_lib::op_store::RefTarget;
+use jj_lib::ref_name::RefName;
+use jj_lib::ref_name::RemoteName;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::repo::Repo as _;
+use jj_lib::revset;
+use jj_lib::str_util::StringPattern;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::commit_templater::BookmarkStatus;
+use crate::commit_templater::BookmarkSyncState;
+use crate::commit_templater::CommitRef;
+use crate::complete;
+use crate::templater::TemplateRenderer;
+use crate::ui::Ui;
+
+/// Show how local bookmarks relate to their tracked remote bookmarks
+///
+/// For each tracked remote bookmark, this reports whether the local bookmark
+/// has commits to push, whether the remote bookmark has commits to pull in,
+/// and whether the bookmarks have diverged or are conflicted. If the commits
+/// last pushed to or fetched from the remote have been rewritten locally
+/// (according to the operation log), the bookmark is reported as rewritten.
+/// A command to reconcile the bookmarks is suggested when there's something to
+/// do.
+///
+/// See [`jj help -k bookmarks`] for more information.
+///
+/// [`jj help -k bookmarks`]:
+///     https://jj-vcs.github.io/jj/latest/bookmarks
+#[derive(clap::Args, Clone, Debug)]
+pub struct BookmarkStatusArgs {
+    /// Show bookmarks whose local name matches
+    ///
+    /// By default, the specified name matches exactly. Use `glob:` prefix to
+    /// select bookmarks by [wildcard pattern].
+    ///
+    /// [wildcard pattern]:
+    ///     https://jj-vcs.github.io/jj/latest/revsets/#string-patterns
+    #[arg(value_parser = StringPattern::parse, add = ArgValueCandidates::new(complete::bookmarks))]
+    names: Option<Vec<StringPattern>>,
+
+    /// Show bookmarks tracking remotes whose name matches
+    ///
+    /// By default, the specified remote name matches exactly. Use `glob:`
+    /// prefix to select remotes by [wildcard pattern].
+    ///
+    /// [wildcard pattern]:
+    ///     https://jj-vcs.github.io/jj/latest/revsets/#string-patterns
+    #[arg(
+        long = "remote",
+        value_name = "REMOTE",
+        value_parser = StringPattern::parse,
+        add = ArgValueCandidates::new(complete::git_remotes),
+    )]
+    remotes: Option<Vec<StringPattern>>,
+
+    /// Render each bookmark status using the given template
+    ///
+    /// All 0-argument methods of the [`BookmarkStatus` type] are available as
+    /// keywords in the template expression. See [`jj help -k templates`]
+    /// for more information.
+    ///
+    /// [`BookmarkStatus` type]:
+    ///     https://jj-vcs.github.io/jj/latest/templates/#bookmarkstatus-type
+    ///
+    /// [`jj help -k templates`]:
+    ///     https://jj-vcs.github.io/jj/latest/templates/
+    #[arg(long, short = 'T', add = ArgValueCandidates::new(complete::template_aliases))]
+    template: Option<String>,
+}
+
+pub fn cmd_bookmark_status(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BookmarkStatusArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo();
+    let view = repo.view();
+    let matches_any = |patterns: &Option<Vec<StringPattern>>, value: &str| {
+        patterns
+            .as_ref()
+            .is_none_or(|patterns| patterns.iter().any(|pattern| pattern.matches(value)))
+    };
+
+    let template: TemplateRenderer<BookmarkStatus> = {
+        let language = workspace_command.commit_template_language();
+        let text = match &args.template {
+            Some(value) => value.to_owned(),
+            None => workspace_command
+                .settings()
+                .get("templates.bookmark_status")?,
+        };
+        workspace_command
+            .parse_template(ui, &language, &text)?
+            .labeled(["bookmark_status"])
+    };
+
+    let mut statuses = vec![];
+    for (name, bookmark_target) in view.bookmarks() {
+        if !matches_any(&args.names, name.as_str()) {
+            continue;
+        }
+        let local_target = bookmark_target.local_target;
+        let tracked_remote_refs =
+            bookmark_target
+                .remote_refs
+                .iter()
+                .filter(|&&(remote, remote_ref)| {
+                    remote_ref.is_tracked()
+                        && !jj_lib::git::is_special_git_remote(remote)
+                        && matches_any(&args.remotes, remote.as_str())
+                });
+        for &(remote, remote_ref) in tracked_remote_refs {
+            let (state, rewritten) = classify(repo, local_target, &remote_ref.target)?;
+            statuses.push(BookmarkStatus {
+                local_ref: CommitRef::local_bookmark(
+                    name,
+                    local_target.clone(),
+                    bookmark_target.remote_refs.iter().map(|&(_, r)| r),
+                    view.get_bookmark_metadata(name),
+                ),
+                remote_ref: CommitRef::remote(name, remote, remote_ref.clone(), local_target),
+                state,
+                rewritten,
+                suggestion: suggest_command(state, rewritten, name, remote),
+            });
+        }
+    }
+
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    for status in &statuses {
+        template.format(status, formatter.as_mut())?;
+    }
+    Ok(())
+}
+
+/// Determines how the local target relates to the tracked remote target, and
+/// whether the remote commits were rewritten locally.
+fn classify(
+    repo: &ReadonlyRepo,
+    local_target: &RefTarget,
+    remote_target: &RefTarget,
+) -> Result<(BookmarkSyncState, bool), CommandError> {
+    if local_target == remote_target {
+        return Ok((BookmarkSyncState::Synced, false));
+    }
+    if local_target.has_conflict() || remote_target.has_conflict() {
+        return Ok((BookmarkSyncState::Conflicted, false));
+    }
+    let (Some(local_id), Some(remote_id)) = (local_target.as_normal(), remote_target.as_normal())
+    else {
+        let state = if local_target.is_absent() {
+            BookmarkSyncState::Deleted
+        } else {
+            BookmarkSyncState::Ahead
+        };
+        return Ok((state, false));
+    };
+    let index = repo.index();
+    if index.is_ancestor(remote_id, local_id) {
+        Ok((BookmarkSyncState::Ahead, false))
+    } else if index.is_ancestor(local_id, remote_id) {
+        Ok((BookmarkSyncState::Behind, false))
+    } else {
+        let rewritten = is_rewritten(repo, local_id, remote_id)?;
+        Ok((BookmarkSyncState::Diverged, rewritten))
+    }
+}
+
+/// Returns true if any of the commits only reachable from `remote_id` is a
+/// predecessor of a commit only reachable from `local_id`.
+fn is_rewritten(
+    repo: &ReadonlyRepo,
+    local_id: &CommitId,
+    remote_id: &CommitId,
+) -> Result<bool, CommandError> {
+    let local_ids = [local_id.clone()];
+    let remote_ids = [remote_id.clone()];
+    let remote_only: HashSet<CommitId> = revset::walk_revs(repo, &remote_ids, &local_ids)?
+        .iter()
+        .try_collect()?;
+    let local_only: Vec<CommitId> = revset::walk_revs(repo, &local_ids, &remote_ids)?
+        .iter()
+        .try_collect()?;
+    for entry in walk_predecessors(repo, &local_only) {
+        if remote_only.contains(entry?.commit.id()) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn suggest_command(
+    state: BookmarkSyncState,
+    rewritten: bool,
+    name: &RefName,
+    remote: &RemoteName,
+) -> String {
+    let name = name.as_symbol();
+    let remote = remote.as_symbol();
+    match state {
+        BookmarkSyncState::Synced => String::new(),
+        BookmarkSyncState::Ahead => format!("jj git push --bookmark {name} --remote {remote}"),
+        // Pushing a rewritten bookmark replaces the remote commits.
+        BookmarkSyncState::Diverged if rewritten => {
+            format!("jj git push --bookmark {name} --remote {remote}")
+        }
+        BookmarkSyncState::Behind => format!("jj bookmark set {name} -r {name}@{remote}"),
+        BookmarkSyncState::Diverged => format!("jj rebase -b {name} -d {name}@{remote}"),
+        BookmarkSyncState::Conflicted => format!("jj bookmark set {name} -r <REVISION>"),
+        BookmarkSyncState::Deleted => format!("jj git push --deleted --remote {remote}"),
+    }
+}
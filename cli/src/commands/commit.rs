@@ -86,7 +86,6 @@ pub(crate) fn cmd_commit(
     let matcher = workspace_command
         .parse_file_patterns(ui, &args.paths)?
         .to_matcher();
-    let advanceable_bookmarks = workspace_command.get_advanceable_bookmarks(commit.parent_ids())?;
     let diff_selector =
         workspace_command.diff_selector(ui, args.tool.as_deref(), args.interactive)?;
     let text_editor = workspace_command.text_editor()?;
@@ -105,6 +104,7 @@ new working-copy commit.
         )
     };
     let tree_id = diff_selector.select(
+        ui,
         &base_tree,
         &commit.tree()?,
         matcher.as_ref(),
@@ -172,9 +172,6 @@ new working-copy commit.
             .new_commit(vec![new_commit.id().clone()], commit.tree_id().clone())
             .write()?;
 
-        // Does nothing if there's no bookmarks to advance.
-        tx.advance_bookmarks(advanceable_bookmarks, new_commit.id());
-
         for name in workspace_names {
             tx.repo_mut().edit(name, &new_wc_commit).unwrap();
         }
@@ -29,6 +29,10 @@ use crate::ui::Ui;
 ///
 /// The simple backend does not support cloning, fetching, or pushing.
 ///
+/// With `--store-type=chunked`, file contents are split into content-defined
+/// chunks so that large files which change in small regions share storage
+/// between versions.
+///
 /// This command is otherwise analogous to `jj git init`. If the given directory
 /// does not exist, it will be created. If no directory is given, the current
 /// directory is used.
@@ -37,6 +41,17 @@ pub(crate) struct DebugInitSimpleArgs {
     /// The destination directory
     #[arg(default_value = ".", value_hint = clap::ValueHint::DirPath)]
     destination: String,
+    /// The format in which the repo objects are stored
+    #[arg(long, value_enum, default_value = "simple")]
+    store_type: StoreType,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum StoreType {
+    /// Store each object in its own file
+    Simple,
+    /// Split file contents into content-defined chunks shared between files
+    Chunked,
 }
 
 #[instrument(skip_all)]
@@ -57,7 +72,11 @@ pub(crate) fn cmd_debug_init_simple(
         .and_then(|_| dunce::canonicalize(wc_path))
         .map_err(|e| user_error_with_message("Failed to create workspace", e))?;
 
-    Workspace::init_simple(&command.settings_for_new_workspace(&wc_path)?, &wc_path)?;
+    let settings = command.settings_for_new_workspace(&wc_path)?;
+    match args.store_type {
+        StoreType::Simple => Workspace::init_simple(&settings, &wc_path)?,
+        StoreType::Chunked => Workspace::init_chunked(&settings, &wc_path)?,
+    };
 
     let relative_wc_path = file_util::relative_path(cwd, &wc_path);
     writeln!(
@@ -0,0 +1,86 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use jj_lib::file_util;
+use jj_lib::lock::LockHolder;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Show the lock files in the repo and the processes holding them
+///
+/// Lock files left by processes which no longer exist on this host are marked
+/// as stale. Such lock files don't block other processes since the lock itself
+/// is released by the OS.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugLocksArgs {}
+
+pub fn cmd_debug_locks(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &DebugLocksArgs,
+) -> Result<(), CommandError> {
+    // Don't take any locks by loading the repo or snapshotting.
+    let workspace = command.load_workspace()?;
+    let repo_path = workspace.repo_path();
+    let lock_dirs = [
+        workspace.workspace_root().join(".jj").join("working_copy"),
+        repo_path.join("op_heads").join("heads"),
+        repo_path.join("store").join("extra"),
+    ];
+    let mut lock_paths: Vec<PathBuf> = vec![];
+    for dir in &lock_dirs {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err.into()),
+        };
+        for entry in entries {
+            let path = entry?.path();
+            let is_lock_file = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name == "lock" || name.ends_with(".lock"));
+            if is_lock_file {
+                lock_paths.push(path);
+            }
+        }
+    }
+    lock_paths.sort();
+
+    if lock_paths.is_empty() {
+        writeln!(ui.status(), "No locks are held")?;
+        return Ok(());
+    }
+    for path in &lock_paths {
+        let display_path = file_util::relative_path(command.cwd(), path);
+        match LockHolder::read(path) {
+            Some(holder) if holder.is_stale() => writeln!(
+                ui.stdout(),
+                "{}: stale, held by {holder} which no longer exists",
+                display_path.display()
+            )?,
+            Some(holder) => writeln!(ui.stdout(), "{}: held by {holder}", display_path.display())?,
+            None => writeln!(ui.stdout(), "{}: unknown holder", display_path.display())?,
+        }
+    }
+    Ok(())
+}
@@ -36,6 +36,7 @@ use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::commands::git::maybe_add_gitignore;
 use crate::git_util::absolute_git_url;
+use crate::git_util::fetch_git_submodules;
 use crate::git_util::print_git_import_stats;
 use crate::git_util::with_remote_git_callbacks;
 use crate::ui::Ui;
@@ -233,6 +234,7 @@ fn fetch_new_remote(
         }
     }
     print_git_import_stats(ui, tx.repo(), &import_stats, true)?;
+    fetch_git_submodules(ui, tx.repo(), &import_stats, &git_settings)?;
     if git_settings.auto_local_bookmark && !track_default {
         writeln!(
             ui.hint_default(),
@@ -20,6 +20,7 @@ use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::git;
 use jj_lib::git::GitFetch;
 use jj_lib::ref_name::RemoteName;
+use jj_lib::ref_name::RemoteNameBuf;
 use jj_lib::repo::Repo as _;
 use jj_lib::str_util::StringPattern;
 
@@ -31,6 +32,7 @@ use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::commands::git::get_single_remote;
 use crate::complete;
+use crate::git_util::fetch_git_submodules;
 use crate::git_util::print_git_import_stats;
 use crate::git_util::with_remote_git_callbacks;
 use crate::ui::Ui;
@@ -85,12 +87,36 @@ pub fn cmd_git_fetch(
     args: &GitFetchArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
-    let remote_patterns = if args.all_remotes {
+    let remotes = get_fetch_remotes(ui, &workspace_command, &args.remotes, args.all_remotes)?;
+    let remotes = remotes.iter().map(|r| r.as_ref()).collect_vec();
+
+    let mut tx = workspace_command.start_transaction();
+    do_git_fetch(ui, &mut tx, &remotes, &args.branch)?;
+    tx.finish(
+        ui,
+        format!(
+            "fetch from git remote(s) {}",
+            remotes.iter().map(|n| n.as_symbol()).join(",")
+        ),
+    )?;
+    Ok(())
+}
+
+/// Resolves the remotes to fetch from, sorted by name.
+///
+/// If no remote patterns are given, the `git.fetch` setting is used.
+pub fn get_fetch_remotes(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    remote_patterns: &[StringPattern],
+    all_remotes: bool,
+) -> Result<Vec<RemoteNameBuf>, CommandError> {
+    let remote_patterns = if all_remotes {
         vec![StringPattern::everything()]
-    } else if args.remotes.is_empty() {
-        get_default_fetch_remotes(ui, &workspace_command)?
+    } else if remote_patterns.is_empty() {
+        get_default_fetch_remotes(ui, workspace_command)?
     } else {
-        args.remotes.clone()
+        remote_patterns.to_vec()
     };
 
     let all_remotes = git::get_all_remote_names(workspace_command.repo().store())?;
@@ -112,22 +138,7 @@ pub fn cmd_git_fetch(
         return Err(user_error("No git remotes to push"));
     }
 
-    let remotes = matching_remotes
-        .iter()
-        .map(|r| r.as_ref())
-        .sorted()
-        .collect_vec();
-
-    let mut tx = workspace_command.start_transaction();
-    do_git_fetch(ui, &mut tx, &remotes, &args.branch)?;
-    tx.finish(
-        ui,
-        format!(
-            "fetch from git remote(s) {}",
-            remotes.iter().map(|n| n.as_symbol()).join(",")
-        ),
-    )?;
-    Ok(())
+    Ok(matching_remotes.into_iter().cloned().sorted().collect())
 }
 
 const DEFAULT_REMOTE: &RemoteName = RemoteName::new("origin");
@@ -164,7 +175,7 @@ fn parse_remote_pattern(remote: &str) -> Result<StringPattern, CommandError> {
     StringPattern::parse(remote).map_err(config_error)
 }
 
-fn do_git_fetch(
+pub fn do_git_fetch(
     ui: &mut Ui,
     tx: &mut WorkspaceCommandTransaction,
     remotes: &[&RemoteName],
@@ -180,6 +191,7 @@ fn do_git_fetch(
     }
     let import_stats = git_fetch.import_refs()?;
     print_git_import_stats(ui, tx.repo(), &import_stats, true)?;
+    fetch_git_submodules(ui, tx.repo(), &import_stats, &git_settings)?;
     warn_if_branches_not_found(ui, tx, branch_names, remotes)
 }
 
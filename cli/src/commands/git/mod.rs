@@ -37,6 +37,8 @@ use self::clone::GitCloneArgs;
 use self::export::cmd_git_export;
 use self::export::GitExportArgs;
 use self::fetch::cmd_git_fetch;
+pub use self::fetch::do_git_fetch;
+pub use self::fetch::get_fetch_remotes;
 use self::fetch::GitFetchArgs;
 use self::import::cmd_git_import;
 use self::import::GitImportArgs;
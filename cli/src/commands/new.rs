@@ -169,21 +169,14 @@ pub(crate) fn cmd_new(
         .iter()
         .map(|commit_id| workspace_command.repo().store().get_commit(commit_id))
         .try_collect()?;
-    let mut advance_bookmarks_target = None;
-    let mut advanceable_bookmarks = vec![];
-
-    if args.insert_before.is_none() && args.insert_after.is_none() {
-        let should_advance_bookmarks = parent_commits.len() == 1;
-        if should_advance_bookmarks {
-            advance_bookmarks_target = Some(parent_commit_ids[0].clone());
-            advanceable_bookmarks =
-                workspace_command.get_advanceable_bookmarks(parent_commits[0].parent_ids())?;
-        }
-    };
-
     let parent_commit_ids_set: HashSet<CommitId> = parent_commit_ids.iter().cloned().collect();
 
     let mut tx = workspace_command.start_transaction();
+    // Bookmarks are advanced only if the new commit is created on top of its
+    // parent, not inserted between commits.
+    if args.insert_before.is_some() || args.insert_after.is_some() {
+        tx.skip_advance_bookmarks();
+    }
     let merged_tree = merge_commit_trees(tx.repo(), &parent_commits)?;
     let mut commit_builder = tx
         .repo_mut()
@@ -233,11 +226,6 @@ pub(crate) fn cmd_new(
         writeln!(ui.status(), "Rebased {num_rebased} descendant commits")?;
     }
 
-    // Does nothing if there's no bookmarks to advance.
-    if let Some(target) = advance_bookmarks_target {
-        tx.advance_bookmarks(advanceable_bookmarks, &target);
-    }
-
     tx.finish(ui, "new empty commit")?;
     Ok(())
 }
@@ -19,11 +19,20 @@ mod restore;
 mod show;
 pub mod undo;
 
+use std::collections::BTreeSet;
+
 use abandon::cmd_op_abandon;
 use abandon::OperationAbandonArgs;
 use clap::Subcommand;
+use clap_complete::ArgValueCandidates;
 use diff::cmd_op_diff;
 use diff::OperationDiffArgs;
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::ref_name::RefName;
+use jj_lib::ref_name::WorkspaceNameBuf;
+use jj_lib::repo::Repo as _;
+use jj_lib::str_util::StringPattern;
 use log::cmd_op_log;
 use log::OperationLogArgs;
 use restore::cmd_op_restore;
@@ -34,7 +43,10 @@ use undo::cmd_op_undo;
 use undo::OperationUndoArgs;
 
 use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::CommandError;
+use crate::complete;
 use crate::ui::Ui;
 
 /// Commands for working with the operation log
@@ -80,12 +92,107 @@ enum UndoWhatToRestore {
 const DEFAULT_UNDO_WHAT: [UndoWhatToRestore; 2] =
     [UndoWhatToRestore::Repo, UndoWhatToRestore::RemoteTracking];
 
-/// Restore only the portions of the view specified by the `what` argument
+/// Restrict the restored state to selected bookmarks, workspaces, or commits
+#[derive(clap::Args, Clone, Debug, Default)]
+struct RestoreSelectionArgs {
+    /// Restore only the given bookmarks (can be repeated)
+    ///
+    /// The local bookmarks and their remote-tracking bookmarks are restored as
+    /// selected by `--what`. By default, the bookmark names are matched
+    /// exactly. Use `glob:` prefix to select bookmarks by [wildcard pattern].
+    ///
+    /// [wildcard pattern]:
+    ///     https://jj-vcs.github.io/jj/latest/revsets/#string-patterns
+    #[arg(
+        long = "bookmark",
+        value_name = "BOOKMARK",
+        value_parser = StringPattern::parse,
+        add = ArgValueCandidates::new(complete::bookmarks),
+    )]
+    bookmarks: Vec<StringPattern>,
+
+    /// Restore only the working-copy commits of the given workspaces (can be
+    /// repeated)
+    #[arg(
+        long = "workspace",
+        value_name = "WORKSPACE",
+        add = ArgValueCandidates::new(complete::workspaces),
+    )]
+    workspaces: Vec<WorkspaceNameBuf>,
+
+    /// Restore only the visibility of the given commits (can be repeated)
+    ///
+    /// The commits that were visible at the restored operation are made
+    /// visible again. Hidden commits can be specified by commit ID.
+    #[arg(long = "revision", value_name = "REVSETS")]
+    revisions: Vec<RevisionArg>,
+}
+
+impl RestoreSelectionArgs {
+    fn is_empty(&self) -> bool {
+        self.bookmarks.is_empty() && self.workspaces.is_empty() && self.revisions.is_empty()
+    }
+
+    /// Resolves the selected commits that are visible in `view_being_restored`.
+    /// Returns `None` if nothing is selected.
+    fn resolve(
+        &self,
+        ui: &Ui,
+        workspace_command: &WorkspaceCommandHelper,
+        view_being_restored: &jj_lib::op_store::View,
+    ) -> Result<Option<RestoreSelection>, CommandError> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let commit_ids = if self.revisions.is_empty() {
+            vec![]
+        } else {
+            let index = workspace_command.repo().index();
+            let ids: Vec<_> = workspace_command
+                .parse_union_revsets(ui, &self.revisions)?
+                .evaluate_to_commit_ids()?
+                .try_collect()?;
+            ids.into_iter()
+                .filter(|id| {
+                    view_being_restored
+                        .head_ids
+                        .iter()
+                        .any(|head_id| index.is_ancestor(id, head_id))
+                })
+                .collect()
+        };
+        Ok(Some(RestoreSelection {
+            bookmarks: self.bookmarks.clone(),
+            workspaces: self.workspaces.clone(),
+            commit_ids,
+        }))
+    }
+}
+
+/// Portions of the view to restore, resolved from [`RestoreSelectionArgs`].
+#[derive(Clone, Debug)]
+struct RestoreSelection {
+    bookmarks: Vec<StringPattern>,
+    workspaces: Vec<WorkspaceNameBuf>,
+    commit_ids: Vec<CommitId>,
+}
+
+/// Restore only the portions of the view specified by the `what` and
+/// `selection` arguments
 fn view_with_desired_portions_restored(
     view_being_restored: &jj_lib::op_store::View,
     current_view: &jj_lib::op_store::View,
     what: &[UndoWhatToRestore],
+    selection: Option<&RestoreSelection>,
 ) -> jj_lib::op_store::View {
+    if let Some(selection) = selection {
+        return view_with_selected_portions_restored(
+            view_being_restored,
+            current_view,
+            what,
+            selection,
+        );
+    }
     let repo_source = if what.contains(&UndoWhatToRestore::Repo) {
         view_being_restored
     } else {
@@ -99,10 +206,92 @@ fn view_with_desired_portions_restored(
     jj_lib::op_store::View {
         head_ids: repo_source.head_ids.clone(),
         local_bookmarks: repo_source.local_bookmarks.clone(),
+        bookmark_metadata: repo_source.bookmark_metadata.clone(),
         tags: repo_source.tags.clone(),
         remote_views: remote_source.remote_views.clone(),
         git_refs: current_view.git_refs.clone(),
         git_head: current_view.git_head.clone(),
+        git_worktree_heads: current_view.git_worktree_heads.clone(),
         wc_commit_ids: repo_source.wc_commit_ids.clone(),
     }
 }
+
+/// Restores the selected bookmarks, working-copy commits, and commits on top of
+/// the `current_view`. Restored commits are made visible.
+fn view_with_selected_portions_restored(
+    view_being_restored: &jj_lib::op_store::View,
+    current_view: &jj_lib::op_store::View,
+    what: &[UndoWhatToRestore],
+    selection: &RestoreSelection,
+) -> jj_lib::op_store::View {
+    let mut new_view = current_view.clone();
+    let bookmark_names: BTreeSet<&RefName> = [view_being_restored, current_view]
+        .into_iter()
+        .flat_map(|view| {
+            let remote_names = view
+                .remote_views
+                .values()
+                .flat_map(|remote_view| remote_view.bookmarks.keys());
+            view.local_bookmarks.keys().chain(remote_names)
+        })
+        .map(|name| name.as_ref())
+        .filter(|name: &&RefName| {
+            selection
+                .bookmarks
+                .iter()
+                .any(|pattern| pattern.matches(name.as_str()))
+        })
+        .collect();
+    for name in bookmark_names {
+        if what.contains(&UndoWhatToRestore::Repo) {
+            match view_being_restored.local_bookmarks.get(name) {
+                Some(target) => {
+                    new_view.head_ids.extend(target.added_ids().cloned());
+                    new_view
+                        .local_bookmarks
+                        .insert(name.to_owned(), target.clone());
+                }
+                None => {
+                    new_view.local_bookmarks.remove(name);
+                }
+            }
+            match view_being_restored.bookmark_metadata.get(name) {
+                Some(metadata) => {
+                    new_view
+                        .bookmark_metadata
+                        .insert(name.to_owned(), metadata.clone());
+                }
+                None => {
+                    new_view.bookmark_metadata.remove(name);
+                }
+            }
+        }
+        if what.contains(&UndoWhatToRestore::RemoteTracking) {
+            for remote_view in new_view.remote_views.values_mut() {
+                remote_view.bookmarks.remove(name);
+            }
+            for (remote, remote_view) in &view_being_restored.remote_views {
+                if let Some(remote_ref) = remote_view.bookmarks.get(name) {
+                    new_view
+                        .remote_views
+                        .entry(remote.clone())
+                        .or_default()
+                        .bookmarks
+                        .insert(name.to_owned(), remote_ref.clone());
+                }
+            }
+        }
+    }
+    for name in &selection.workspaces {
+        if let Some(commit_id) = view_being_restored.wc_commit_ids.get(name) {
+            new_view.head_ids.insert(commit_id.clone());
+            new_view
+                .wc_commit_ids
+                .insert(name.clone(), commit_id.clone());
+        }
+    }
+    new_view
+        .head_ids
+        .extend(selection.commit_ids.iter().cloned());
+    new_view
+}
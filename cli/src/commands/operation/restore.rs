@@ -16,6 +16,7 @@ use clap_complete::ArgValueCandidates;
 use jj_lib::object_id::ObjectId as _;
 
 use super::view_with_desired_portions_restored;
+use super::RestoreSelectionArgs;
 use super::UndoWhatToRestore;
 use super::DEFAULT_UNDO_WHAT;
 use crate::cli_util::CommandHelper;
@@ -42,6 +43,9 @@ pub struct OperationRestoreArgs {
     /// This option is EXPERIMENTAL.
     #[arg(long, value_enum, default_values_t = DEFAULT_UNDO_WHAT)]
     what: Vec<UndoWhatToRestore>,
+
+    #[command(flatten)]
+    selection: RestoreSelectionArgs,
 }
 
 pub fn cmd_op_restore(
@@ -51,11 +55,16 @@ pub fn cmd_op_restore(
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
     let target_op = workspace_command.resolve_single_op(&args.operation)?;
+    let target_view = target_op.view()?;
+    let selection = args
+        .selection
+        .resolve(ui, &workspace_command, target_view.store_view())?;
     let mut tx = workspace_command.start_transaction();
     let new_view = view_with_desired_portions_restored(
-        target_op.view()?.store_view(),
+        target_view.store_view(),
         tx.base_repo().view().store_view(),
         &args.what,
+        selection.as_ref(),
     );
     tx.repo_mut().set_view(new_view);
     if let Some(mut formatter) = ui.status_formatter() {
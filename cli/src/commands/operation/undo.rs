@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use clap::ValueEnum as _;
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
 use jj_lib::object_id::ObjectId as _;
@@ -20,10 +21,14 @@ use jj_lib::operation::Operation;
 use jj_lib::repo::Repo as _;
 
 use super::view_with_desired_portions_restored;
+use super::RestoreSelectionArgs;
 use super::UndoWhatToRestore;
 use super::DEFAULT_UNDO_WHAT;
+use crate::cli_util::short_operation_hash;
 use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandTransaction;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::ui::Ui;
@@ -40,6 +45,38 @@ pub struct OperationUndoArgs {
     #[arg(default_value = "@", add = ArgValueCandidates::new(complete::operations))]
     operation: String,
 
+    /// The parent operation to revert to
+    ///
+    /// This is required to undo a merge operation, which has multiple parents.
+    /// The changes from the given parent operation to the merge operation are
+    /// reverted.
+    #[arg(long, value_name = "OPERATION", add = ArgValueCandidates::new(complete::operations))]
+    parent: Option<String>,
+
+    /// What portions of the local state to restore (can be repeated)
+    ///
+    /// This option is EXPERIMENTAL.
+    #[arg(long, value_enum, default_values_t = DEFAULT_UNDO_WHAT)]
+    what: Vec<UndoWhatToRestore>,
+
+    #[command(flatten)]
+    selection: RestoreSelectionArgs,
+}
+
+/// Undo the last operation, or the operation before the last undone one
+///
+/// Running `jj undo` repeatedly walks back through the operation log one
+/// operation at a time. Undone operations can be re-applied with `jj redo`.
+/// Running any other command that modifies the repo starts a new undo history.
+#[derive(clap::Args, Clone, Debug)]
+pub struct UndoArgs {
+    /// The operation to undo
+    ///
+    /// If specified, this works like `jj op undo <operation>` and starts a new
+    /// undo history.
+    #[arg(add = ArgValueCandidates::new(complete::operations))]
+    operation: Option<String>,
+
     /// What portions of the local state to restore (can be repeated)
     ///
     /// This option is EXPERIMENTAL.
@@ -47,6 +84,134 @@ pub struct OperationUndoArgs {
     what: Vec<UndoWhatToRestore>,
 }
 
+/// Redo the last operation undone by `jj undo`
+///
+/// The same portions of the repo state that were restored by `jj undo --what`
+/// are re-applied.
+#[derive(clap::Args, Clone, Debug)]
+pub struct RedoArgs {}
+
+/// Operation tag recording how many operations have been undone by `jj undo`
+/// and not redone by `jj redo`.
+const UNDO_DEPTH_TAG: &str = "undo-depth";
+
+/// Operation tag recording the `--what` portions restored by `jj undo`, which
+/// are also restored by the corresponding `jj redo`.
+const UNDO_WHAT_TAG: &str = "undo-what";
+
+/// Position in the stack of operations undone by `jj undo` and `jj redo`.
+struct UndoStack {
+    /// The operation that was the head when the first `jj undo` was run.
+    origin: Operation,
+    /// Number of operations undone, counted back from the `origin`.
+    depth: usize,
+}
+
+fn undo_depth(op: &Operation) -> Option<usize> {
+    op.metadata().tags.get(UNDO_DEPTH_TAG)?.parse().ok()
+}
+
+fn undo_what(op: &Operation) -> Option<Vec<UndoWhatToRestore>> {
+    let value = op.metadata().tags.get(UNDO_WHAT_TAG)?;
+    value
+        .split(',')
+        .map(|name| UndoWhatToRestore::from_str(name, false).ok())
+        .collect()
+}
+
+fn format_undo_what(what: &[UndoWhatToRestore]) -> String {
+    what.iter()
+        .filter_map(|what| what.to_possible_value())
+        .map(|value| value.get_name().to_owned())
+        .join(",")
+}
+
+fn single_parent(op: &Operation) -> Result<Option<Operation>, OpStoreError> {
+    op.parents().exactly_one().ok().transpose()
+}
+
+// Skips snapshot operations so that the undo stack isn't reset by a concurrent
+// or automatic snapshot of the working copy.
+fn skip_snapshots(op: &Operation) -> Result<Option<Operation>, OpStoreError> {
+    let mut op = op.clone();
+    while op.metadata().is_snapshot {
+        let Some(parent) = single_parent(&op)? else {
+            return Ok(None);
+        };
+        op = parent;
+    }
+    Ok(Some(op))
+}
+
+/// Finds the undo stack that `head_op` is part of, if any.
+fn find_undo_stack(head_op: &Operation) -> Result<Option<UndoStack>, OpStoreError> {
+    let Some(last_op) = skip_snapshots(head_op)? else {
+        return Ok(None);
+    };
+    let Some(depth) = undo_depth(&last_op) else {
+        return Ok(None);
+    };
+    let mut first_op = last_op;
+    loop {
+        let Some(parent) = single_parent(&first_op)? else {
+            return Ok(None);
+        };
+        match skip_snapshots(&parent)? {
+            Some(op) if undo_depth(&op).is_some() => first_op = op,
+            _ => {
+                return Ok(Some(UndoStack {
+                    origin: parent,
+                    depth,
+                }))
+            }
+        }
+    }
+}
+
+/// Finds the portions restored by the latest `jj undo` in the stack that
+/// `head_op` is part of, which left the stack at `depth`.
+fn find_undo_what(
+    head_op: &Operation,
+    depth: usize,
+) -> Result<Option<Vec<UndoWhatToRestore>>, OpStoreError> {
+    let mut next_op = skip_snapshots(head_op)?;
+    while let Some(op) = next_op {
+        let Some(op_depth) = undo_depth(&op) else {
+            break;
+        };
+        if op_depth == depth {
+            if let Some(what) = undo_what(&op) {
+                return Ok(Some(what));
+            }
+        }
+        next_op = match single_parent(&op)? {
+            Some(parent) => skip_snapshots(&parent)?,
+            None => None,
+        };
+    }
+    Ok(None)
+}
+
+/// Returns the `n`th ancestor of `op`, which must be reachable through
+/// non-merge operations.
+fn nth_ancestor(op: &Operation, n: usize) -> Result<Operation, CommandError> {
+    let mut op = op.clone();
+    for _ in 0..n {
+        let parent = match op.parents().at_most_one() {
+            Ok(Some(parent)) => parent?,
+            Ok(None) => return Err(user_error("Cannot undo root operation")),
+            Err(_) => {
+                return Err(user_error_with_hint(
+                    "Cannot undo a merge operation",
+                    "Use `jj op undo --parent` to choose the parent operation to revert to",
+                ));
+            }
+        };
+        op = parent;
+    }
+    Ok(op)
+}
+
 // Checks whether `op` resets the view of `parent_op` to the view of the
 // grandparent op.
 //
@@ -64,6 +229,89 @@ fn tx_description(op: &Operation) -> String {
     format!("undo operation {}", op.id().hex())
 }
 
+/// Applies the changes from `from_op` to `to_op` to the repo state.
+fn apply_operation_diff(
+    tx: &mut WorkspaceCommandTransaction,
+    from_op: &Operation,
+    to_op: &Operation,
+    what: &[UndoWhatToRestore],
+) -> Result<(), CommandError> {
+    let repo_loader = tx.base_repo().loader();
+    let from_repo = repo_loader.load_at(from_op)?;
+    let to_repo = repo_loader.load_at(to_op)?;
+    tx.repo_mut().merge(&from_repo, &to_repo)?;
+    let new_view = view_with_desired_portions_restored(
+        tx.repo().view().store_view(),
+        tx.base_repo().view().store_view(),
+        what,
+        None,
+    );
+    tx.repo_mut().set_view(new_view);
+    Ok(())
+}
+
+pub fn cmd_undo(ui: &mut Ui, command: &CommandHelper, args: &UndoArgs) -> Result<(), CommandError> {
+    if let Some(operation) = &args.operation {
+        let args = OperationUndoArgs {
+            operation: operation.clone(),
+            parent: None,
+            what: args.what.clone(),
+            selection: RestoreSelectionArgs::default(),
+        };
+        return cmd_op_undo(ui, command, &args);
+    }
+
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let head_op = workspace_command.repo().operation().clone();
+    let (origin, depth) = match find_undo_stack(&head_op)? {
+        Some(UndoStack { origin, depth }) => (origin, depth),
+        None => (head_op, 0),
+    };
+    let bad_op = nth_ancestor(&origin, depth)?;
+    let parent_of_bad_op = nth_ancestor(&bad_op, 1)?;
+
+    let mut tx = workspace_command.start_transaction();
+    apply_operation_diff(&mut tx, &bad_op, &parent_of_bad_op, &args.what)?;
+    tx.set_tag(UNDO_DEPTH_TAG.to_owned(), (depth + 1).to_string());
+    tx.set_tag(UNDO_WHAT_TAG.to_owned(), format_undo_what(&args.what));
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Undid operation: ")?;
+        let template = tx.base_workspace_helper().operation_summary_template();
+        template.format(&bad_op, formatter.as_mut())?;
+        writeln!(formatter)?;
+    }
+    tx.finish(ui, tx_description(&bad_op))?;
+    Ok(())
+}
+
+pub fn cmd_redo(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &RedoArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let head_op = workspace_command.repo().operation().clone();
+    let Some(UndoStack { origin, depth }) = find_undo_stack(&head_op)?.filter(|s| s.depth > 0)
+    else {
+        return Err(user_error("Nothing to redo"));
+    };
+    let undone_op = nth_ancestor(&origin, depth - 1)?;
+    let parent_of_undone_op = nth_ancestor(&undone_op, 1)?;
+    let what = find_undo_what(&head_op, depth)?.unwrap_or_else(|| DEFAULT_UNDO_WHAT.to_vec());
+
+    let mut tx = workspace_command.start_transaction();
+    apply_operation_diff(&mut tx, &parent_of_undone_op, &undone_op, &what)?;
+    tx.set_tag(UNDO_DEPTH_TAG.to_owned(), (depth - 1).to_string());
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Redid operation: ")?;
+        let template = tx.base_workspace_helper().operation_summary_template();
+        template.format(&undone_op, formatter.as_mut())?;
+        writeln!(formatter)?;
+    }
+    tx.finish(ui, format!("redo operation {}", undone_op.id().hex()))?;
+    Ok(())
+}
+
 pub fn cmd_op_undo(
     ui: &mut Ui,
     command: &CommandHelper,
@@ -71,10 +319,27 @@ pub fn cmd_op_undo(
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
     let bad_op = workspace_command.resolve_single_op(&args.operation)?;
-    let parent_of_bad_op = match bad_op.parents().at_most_one() {
-        Ok(Some(parent_of_bad_op)) => parent_of_bad_op?,
-        Ok(None) => return Err(user_error("Cannot undo root operation")),
-        Err(_) => return Err(user_error("Cannot undo a merge operation")),
+    let parent_of_bad_op = if let Some(parent) = &args.parent {
+        let parent_op = workspace_command.resolve_single_op(parent)?;
+        if !bad_op.parent_ids().contains(parent_op.id()) {
+            return Err(user_error(format!(
+                "Operation {} is not a parent of operation {}",
+                short_operation_hash(parent_op.id()),
+                short_operation_hash(bad_op.id()),
+            )));
+        }
+        parent_op
+    } else {
+        match bad_op.parents().at_most_one() {
+            Ok(Some(parent_of_bad_op)) => parent_of_bad_op?,
+            Ok(None) => return Err(user_error("Cannot undo root operation")),
+            Err(_) => {
+                return Err(user_error_with_hint(
+                    "Cannot undo a merge operation",
+                    "Use `--parent` to choose the parent operation to revert to",
+                ));
+            }
+        }
     };
 
     let mut tx = workspace_command.start_transaction();
@@ -82,10 +347,16 @@ pub fn cmd_op_undo(
     let bad_repo = repo_loader.load_at(&bad_op)?;
     let parent_repo = repo_loader.load_at(&parent_of_bad_op)?;
     tx.repo_mut().merge(&bad_repo, &parent_repo)?;
+    let selection = args.selection.resolve(
+        ui,
+        tx.base_workspace_helper(),
+        tx.repo().view().store_view(),
+    )?;
     let new_view = view_with_desired_portions_restored(
         tx.repo().view().store_view(),
         tx.base_repo().view().store_view(),
         &args.what,
+        selection.as_ref(),
     );
     tx.repo_mut().set_view(new_view);
     if let Some(mut formatter) = ui.status_formatter() {
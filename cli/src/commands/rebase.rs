@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeSet;
+use std::collections::HashSet;
 use std::io::Write as _;
 use std::sync::Arc;
 
@@ -21,11 +23,13 @@ use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
 use jj_lib::object_id::ObjectId as _;
+use jj_lib::ref_name::RefName;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo as _;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::rewrite::compute_move_commits;
 use jj_lib::rewrite::find_duplicate_divergent_commits;
+use jj_lib::rewrite::find_landed_commits;
 use jj_lib::rewrite::EmptyBehaviour;
 use jj_lib::rewrite::MoveCommitsLocation;
 use jj_lib::rewrite::MoveCommitsStats;
@@ -41,6 +45,7 @@ use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::ui::Ui;
@@ -263,6 +268,12 @@ use crate::ui::Ui;
 /// |/          |/
 /// J           J
 /// ```
+///
+/// If no destination is specified when rebasing a branch, the branch is
+/// rebased onto the base bookmark of its stack, as set by `jj bookmark
+/// describe --base`. The stack consists of the mutable ancestors and
+/// descendants of the branch revisions, and the bookmarks in it must agree on
+/// a single base bookmark.
 #[derive(clap::Args, Clone, Debug)]
 #[command(verbatim_doc_comment)]
 #[command(group(ArgGroup::new("to_rebase").args(&["branch", "source", "revisions"])))]
@@ -319,6 +330,11 @@ pub(crate) struct RebaseArgs {
     /// abandoned. It will not be abandoned if it was already empty before the
     /// rebase. Will never skip merge commits with multiple non-empty
     /// parents.
+    ///
+    /// Commits whose changes are already present in the destination, e.g.
+    /// because they were cherry-picked or squash-merged there, are also
+    /// abandoned, even if rebasing them wouldn't produce an empty commit. See
+    /// the `landed()` revset function for how the changes are compared.
     #[arg(long)]
     skip_emptied: bool,
 
@@ -332,7 +348,6 @@ pub(crate) struct RebaseArgs {
 }
 
 #[derive(clap::Args, Clone, Debug)]
-#[group(required = true)]
 pub struct RebaseDestinationArgs {
     /// The revision(s) to rebase onto (can be repeated to create a merge
     /// commit)
@@ -367,6 +382,12 @@ pub struct RebaseDestinationArgs {
     insert_before: Option<Vec<RevisionArg>>,
 }
 
+impl RebaseDestinationArgs {
+    fn is_empty(&self) -> bool {
+        self.destination.is_none() && self.insert_after.is_none() && self.insert_before.is_none()
+    }
+}
+
 #[instrument(skip_all)]
 pub(crate) fn cmd_rebase(
     ui: &mut Ui,
@@ -384,6 +405,13 @@ pub(crate) fn cmd_rebase(
         simplify_ancestor_merge: false,
     };
     let mut workspace_command = command.workspace_helper(ui)?;
+    if args.destination.is_empty() && (!args.revisions.is_empty() || !args.source.is_empty()) {
+        return Err(user_error_with_hint(
+            "No destination specified",
+            "Use --destination, --insert-after, or --insert-before to specify where to rebase the \
+             revisions to.",
+        ));
+    }
     let loc = if !args.revisions.is_empty() {
         plan_rebase_revisions(ui, &workspace_command, &args.revisions, &args.destination)?
     } else if !args.source.is_empty() {
@@ -394,8 +422,9 @@ pub(crate) fn cmd_rebase(
 
     let mut tx = workspace_command.start_transaction();
     let mut computed_move = compute_move_commits(tx.repo(), &loc)?;
+    let mut abandoned_divergent = vec![];
     if !args.keep_divergent {
-        let abandoned_divergent =
+        abandoned_divergent =
             find_duplicate_divergent_commits(tx.repo(), &loc.new_parent_ids, &loc.target)?;
         computed_move.record_to_abandon(abandoned_divergent.iter().map(Commit::id).cloned());
         if !abandoned_divergent.is_empty() {
@@ -413,6 +442,27 @@ pub(crate) fn cmd_rebase(
             }
         }
     };
+    if args.skip_emptied {
+        let abandoned_landed = find_landed_commits(tx.repo(), &loc.new_parent_ids, &loc.target)?
+            .into_iter()
+            .filter(|commit| !abandoned_divergent.contains(commit))
+            .collect_vec();
+        computed_move.record_to_abandon(abandoned_landed.iter().map(Commit::id).cloned());
+        if !abandoned_landed.is_empty() {
+            if let Some(mut formatter) = ui.status_formatter() {
+                writeln!(
+                    formatter,
+                    "Abandoned {} commits that were already present in the destination:",
+                    abandoned_landed.len(),
+                )?;
+                print_updated_commits(
+                    formatter.as_mut(),
+                    &tx.base_workspace_helper().commit_summary_template(),
+                    &abandoned_landed,
+                )?;
+            }
+        }
+    }
     let stats = computed_move.apply(tx.repo_mut(), &rebase_options)?;
     print_move_commits_stats(ui, &stats)?;
     tx.finish(ui, tx_description(&loc.target))?;
@@ -507,14 +557,19 @@ fn plan_rebase_branch(
             .collect()
     };
 
-    let (new_parent_ids, new_child_ids) = compute_commit_location(
-        ui,
-        workspace_command,
-        rebase_destination.destination.as_deref(),
-        rebase_destination.insert_after.as_deref(),
-        rebase_destination.insert_before.as_deref(),
-        "rebased commits",
-    )?;
+    let (new_parent_ids, new_child_ids) = if rebase_destination.is_empty() {
+        let base_commit_id = find_base_bookmark_target(workspace_command, &branch_commit_ids)?;
+        (vec![base_commit_id], vec![])
+    } else {
+        compute_commit_location(
+            ui,
+            workspace_command,
+            rebase_destination.destination.as_deref(),
+            rebase_destination.insert_after.as_deref(),
+            rebase_destination.insert_before.as_deref(),
+            "rebased commits",
+        )?
+    };
     let roots_expression = RevsetExpression::commits(new_parent_ids.clone())
         .range(&RevsetExpression::commits(branch_commit_ids))
         .roots();
@@ -524,7 +579,10 @@ fn plan_rebase_branch(
         .iter()
         .try_collect()?;
     workspace_command.check_rewritable(&root_commit_ids)?;
-    if rebase_destination.destination.is_some() && new_child_ids.is_empty() {
+    if rebase_destination.insert_after.is_none()
+        && rebase_destination.insert_before.is_none()
+        && new_child_ids.is_empty()
+    {
         for id in &root_commit_ids {
             let commit = workspace_command.repo().store().get_commit(id)?;
             check_rebase_destinations(workspace_command.repo(), &new_parent_ids, &commit)?;
@@ -538,6 +596,72 @@ fn plan_rebase_branch(
     })
 }
 
+/// Finds the default destination for the stack containing the given commits.
+///
+/// The stack is made of the mutable ancestors and descendants of the commits.
+/// Bookmarks in the stack may declare a base bookmark (see `jj bookmark
+/// describe --base`). Bases are followed through bookmarks in the same stack,
+/// and the base at the bottom of the stack must be unique.
+fn find_base_bookmark_target(
+    workspace_command: &WorkspaceCommandHelper,
+    branch_commit_ids: &[CommitId],
+) -> Result<CommitId, CommandError> {
+    let branch_expression = RevsetExpression::commits(branch_commit_ids.to_vec());
+    let stack_commit_ids: HashSet<CommitId> = workspace_command
+        .attach_revset_evaluator(
+            branch_expression
+                .ancestors()
+                .union(&branch_expression.descendants())
+                .minus(&workspace_command.env().immutable_expression()),
+        )
+        .evaluate_to_commit_ids()?
+        .try_collect()?;
+    let view = workspace_command.repo().view();
+    let stack_bookmarks: BTreeSet<&RefName> = view
+        .local_bookmarks()
+        .filter(|(_, target)| target.added_ids().any(|id| stack_commit_ids.contains(id)))
+        .map(|(name, _)| name)
+        .collect();
+    let bases: BTreeSet<&RefName> = stack_bookmarks
+        .iter()
+        .filter_map(|name| view.get_bookmark_metadata(name)?.base.as_deref())
+        .filter(|base| {
+            // A base within the stack that has its own base only links the
+            // bookmarks of the stack together.
+            !stack_bookmarks.contains(base)
+                || view
+                    .get_bookmark_metadata(base)
+                    .is_none_or(|metadata| metadata.base.is_none())
+        })
+        .collect();
+    let base = match bases.iter().exactly_one() {
+        Ok(base) => base,
+        Err(bases) if bases.len() == 0 => {
+            return Err(user_error_with_hint(
+                "No destination specified and no base bookmark found",
+                "Use --destination to specify where to rebase the revisions to, or set a base \
+                 bookmark with `jj bookmark describe --base`.",
+            ));
+        }
+        Err(bases) => {
+            return Err(user_error_with_hint(
+                format!(
+                    "Multiple base bookmarks found: {}",
+                    bases.map(|base| base.as_symbol()).join(", ")
+                ),
+                "Use --destination to specify where to rebase the revisions to.",
+            ));
+        }
+    };
+    match view.get_local_bookmark(base).as_normal() {
+        Some(id) => Ok(id.clone()),
+        None => Err(user_error(format!(
+            "Base bookmark {} is conflicted or deleted",
+            base.as_symbol()
+        ))),
+    }
+}
+
 fn check_rebase_destinations(
     repo: &Arc<ReadonlyRepo>,
     new_parents: &[CommitId],
@@ -21,6 +21,7 @@ use jj_lib::object_id::ObjectId as _;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
+use crate::cli_util::HunkSelectionArgs;
 use crate::cli_util::RevisionArg;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
@@ -98,6 +99,8 @@ pub(crate) struct RestoreArgs {
     /// Specify diff editor to be used (implies --interactive)
     #[arg(long, value_name = "NAME")]
     tool: Option<String>,
+    #[command(flatten)]
+    hunk_selection: HunkSelectionArgs,
     /// Preserve the content (not the diff) when rebasing descendants
     #[arg(long)]
     restore_descendants: bool,
@@ -137,7 +140,11 @@ pub(crate) fn cmd_restore(
         .parse_file_patterns(ui, &args.paths)?
         .to_matcher();
     let diff_selector =
-        workspace_command.diff_selector(ui, args.tool.as_deref(), args.interactive)?;
+        if let Some(selector) = workspace_command.hunk_selector(&args.hunk_selection)? {
+            selector
+        } else {
+            workspace_command.diff_selector(ui, args.tool.as_deref(), args.interactive)?
+        };
     let to_tree = to_commit.tree()?;
     let format_instructions = || {
         formatdoc! {"
@@ -155,7 +162,8 @@ pub(crate) fn cmd_restore(
             to_commit = workspace_command.format_commit_summary(&to_commit),
         }
     };
-    let new_tree_id = diff_selector.select(&to_tree, &from_tree, &matcher, format_instructions)?;
+    let new_tree_id =
+        diff_selector.select(ui, &to_tree, &from_tree, &matcher, format_instructions)?;
     if &new_tree_id == to_commit.tree_id() {
         writeln!(ui.status(), "Nothing changed.")?;
     } else {
@@ -16,7 +16,7 @@ use std::fmt::Write as _;
 use std::path::Path;
 
 use itertools::Itertools as _;
-use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::sparse::SparsePattern;
 use tracing::instrument;
 
 use super::update_sparse_patterns_with;
@@ -39,8 +39,9 @@ pub fn cmd_sparse_edit(
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
     let editor = workspace_command.text_editor()?;
+    let workspace_root = workspace_command.workspace_root().to_owned();
     update_sparse_patterns_with(ui, &mut workspace_command, |_ui, old_patterns| {
-        let mut new_patterns = edit_sparse(&editor, old_patterns)?;
+        let mut new_patterns = edit_sparse(&editor, old_patterns, &workspace_root)?;
         new_patterns.sort_unstable();
         new_patterns.dedup();
         Ok(new_patterns)
@@ -49,10 +50,18 @@ pub fn cmd_sparse_edit(
 
 fn edit_sparse(
     editor: &TextEditor,
-    sparse: &[RepoPathBuf],
-) -> Result<Vec<RepoPathBuf>, CommandError> {
+    sparse: &[SparsePattern],
+    workspace_root: &Path,
+) -> Result<Vec<SparsePattern>, CommandError> {
     let mut content = String::new();
-    for sparse_path in sparse {
+    for pattern in sparse {
+        let sparse_path = match pattern {
+            SparsePattern::Prefix(sparse_path) => sparse_path,
+            SparsePattern::Fileset(text) => {
+                writeln!(&mut content, "{text}").unwrap();
+                continue;
+            }
+        };
         // Invalid path shouldn't block editing. Edited paths will be validated.
         let workspace_relative_sparse_path = sparse_path.to_fs_path_unchecked(Path::new(""));
         let path_string = workspace_relative_sparse_path.to_str().ok_or_else(|| {
@@ -74,7 +83,7 @@ fn edit_sparse(
         .map(|line| line.trim())
         .filter(|line| !line.is_empty())
         .map(|line| {
-            RepoPathBuf::from_relative_path(line).map_err(|err| {
+            SparsePattern::parse(line, workspace_root).map_err(|err| {
                 user_error_with_message(format!("Failed to parse sparse pattern: {line}"), err)
             })
         })
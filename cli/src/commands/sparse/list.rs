@@ -15,6 +15,7 @@
 use std::io::Write as _;
 use std::path::Path;
 
+use jj_lib::sparse::SparsePattern;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
@@ -26,6 +27,9 @@ use crate::ui::Ui;
 /// By default, a newly cloned or initialized repo will have have a pattern
 /// matching all files from the repo root. That pattern is rendered as `.` (a
 /// single period).
+///
+/// If the patterns follow a sparse profile set by `jj sparse use`, the name of
+/// the profile is also printed.
 #[derive(clap::Args, Clone, Debug)]
 pub struct SparseListArgs {}
 
@@ -36,12 +40,19 @@ pub fn cmd_sparse_list(
     _args: &SparseListArgs,
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
-    for path in workspace_command.working_copy().sparse_patterns()? {
-        writeln!(
-            ui.stdout(),
-            "{}",
-            path.to_fs_path_unchecked(Path::new("")).display()
-        )?;
+    let working_copy = workspace_command.working_copy();
+    if let Some(profile) = working_copy.sparse_profile()? {
+        writeln!(ui.status(), "Using sparse profile {profile}")?;
+    }
+    for pattern in working_copy.sparse_patterns()? {
+        match pattern {
+            SparsePattern::Prefix(path) => writeln!(
+                ui.stdout(),
+                "{}",
+                path.to_fs_path_unchecked(Path::new("")).display()
+            )?,
+            SparsePattern::Fileset(text) => writeln!(ui.stdout(), "{text}")?,
+        }
     }
     Ok(())
 }
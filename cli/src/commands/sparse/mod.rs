@@ -16,15 +16,21 @@ mod edit;
 mod list;
 mod reset;
 mod set;
+mod r#use;
 
 use clap::Subcommand;
-use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::sparse::SparsePattern;
+use jj_lib::working_copy::CheckoutOptions;
+use jj_lib::working_copy::CheckoutStats;
+use jj_lib::working_copy::LockedWorkingCopy;
 use tracing::instrument;
 
 use self::edit::cmd_sparse_edit;
 use self::edit::SparseEditArgs;
 use self::list::cmd_sparse_list;
 use self::list::SparseListArgs;
+use self::r#use::cmd_sparse_use;
+use self::r#use::SparseUseArgs;
 use self::reset::cmd_sparse_reset;
 use self::reset::SparseResetArgs;
 use self::set::cmd_sparse_set;
@@ -44,6 +50,7 @@ pub(crate) enum SparseCommand {
     List(SparseListArgs),
     Reset(SparseResetArgs),
     Set(SparseSetArgs),
+    Use(SparseUseArgs),
 }
 
 #[instrument(skip_all)]
@@ -57,21 +64,35 @@ pub(crate) fn cmd_sparse(
         SparseCommand::List(args) => cmd_sparse_list(ui, command, args),
         SparseCommand::Reset(args) => cmd_sparse_reset(ui, command, args),
         SparseCommand::Set(args) => cmd_sparse_set(ui, command, args),
+        SparseCommand::Use(args) => cmd_sparse_use(ui, command, args),
     }
 }
 
 fn update_sparse_patterns_with(
     ui: &mut Ui,
     workspace_command: &mut WorkspaceCommandHelper,
-    f: impl FnOnce(&mut Ui, &[RepoPathBuf]) -> Result<Vec<RepoPathBuf>, CommandError>,
+    f: impl FnOnce(&mut Ui, &[SparsePattern]) -> Result<Vec<SparsePattern>, CommandError>,
+) -> Result<(), CommandError> {
+    update_sparse_with(ui, workspace_command, |ui, locked_wc, options| {
+        let new_patterns = f(ui, locked_wc.sparse_patterns()?)?;
+        locked_wc
+            .set_sparse_patterns(new_patterns, options)
+            .map_err(|err| internal_error_with_message("Failed to update working copy paths", err))
+    })
+}
+
+fn update_sparse_with(
+    ui: &mut Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    f: impl FnOnce(
+        &mut Ui,
+        &mut dyn LockedWorkingCopy,
+        &CheckoutOptions,
+    ) -> Result<CheckoutStats, CommandError>,
 ) -> Result<(), CommandError> {
     let checkout_options = workspace_command.checkout_options();
     let (mut locked_ws, wc_commit) = workspace_command.start_working_copy_mutation()?;
-    let new_patterns = f(ui, locked_ws.locked_wc().sparse_patterns()?)?;
-    let stats = locked_ws
-        .locked_wc()
-        .set_sparse_patterns(new_patterns, &checkout_options)
-        .map_err(|err| internal_error_with_message("Failed to update working copy paths", err))?;
+    let stats = f(ui, locked_ws.locked_wc(), &checkout_options)?;
     let operation_id = locked_ws.locked_wc().old_operation_id().clone();
     locked_ws.finish(operation_id)?;
     print_checkout_stats(ui, &stats, &wc_commit)?;
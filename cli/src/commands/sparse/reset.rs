@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::sparse::SparsePattern;
 use tracing::instrument;
 
 use super::update_sparse_patterns_with;
@@ -32,6 +33,6 @@ pub fn cmd_sparse_reset(
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
     update_sparse_patterns_with(ui, &mut workspace_command, |_ui, _old_patterns| {
-        Ok(vec![RepoPathBuf::root()])
+        Ok(vec![SparsePattern::Prefix(RepoPathBuf::root())])
     })
 }
@@ -15,7 +15,7 @@
 use std::collections::HashSet;
 
 use itertools::Itertools as _;
-use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::sparse::SparsePattern;
 use tracing::instrument;
 
 use super::update_sparse_patterns_with;
@@ -28,23 +28,22 @@ use crate::ui::Ui;
 /// For example, if all you need is the `README.md` and the `lib/`
 /// directory, use `jj sparse set --clear --add README.md --add lib`.
 /// If you no longer need the `lib` directory, use `jj sparse set --remove lib`.
+///
+/// Patterns are filesets relative to the workspace root. A plain path includes
+/// the file or the whole directory, and other expressions such as
+/// `'glob:"services/*/api/**"'` can be used to include files more selectively.
+/// See [`jj help -k filesets`] for information about the syntax.
+///
+/// [`jj help -k filesets`]:
+///     https://jj-vcs.github.io/jj/latest/filesets/
 #[derive(clap::Args, Clone, Debug)]
 pub struct SparseSetArgs {
     /// Patterns to add to the working copy
-    #[arg(
-        long,
-        value_hint = clap::ValueHint::AnyPath,
-        value_parser = |s: &str| RepoPathBuf::from_relative_path(s),
-    )]
-    add: Vec<RepoPathBuf>,
+    #[arg(long, value_hint = clap::ValueHint::AnyPath)]
+    add: Vec<String>,
     /// Patterns to remove from the working copy
-    #[arg(
-        long,
-        conflicts_with = "clear",
-        value_hint = clap::ValueHint::AnyPath,
-        value_parser = |s: &str| RepoPathBuf::from_relative_path(s),
-    )]
-    remove: Vec<RepoPathBuf>,
+    #[arg(long, conflicts_with = "clear", value_hint = clap::ValueHint::AnyPath)]
+    remove: Vec<String>,
     /// Include no files in the working copy (combine with --add)
     #[arg(long)]
     clear: bool,
@@ -57,17 +56,25 @@ pub fn cmd_sparse_set(
     args: &SparseSetArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
+    let workspace_root = workspace_command.workspace_root().to_owned();
+    let parse_patterns = |texts: &[String]| -> Result<Vec<_>, CommandError> {
+        let patterns = texts
+            .iter()
+            .map(|text| SparsePattern::parse(text, &workspace_root))
+            .try_collect()?;
+        Ok(patterns)
+    };
+    let added_patterns = parse_patterns(&args.add)?;
+    let removed_patterns = parse_patterns(&args.remove)?;
     update_sparse_patterns_with(ui, &mut workspace_command, |_ui, old_patterns| {
         let mut new_patterns = HashSet::new();
         if !args.clear {
             new_patterns.extend(old_patterns.iter().cloned());
-            for path in &args.remove {
-                new_patterns.remove(path);
+            for pattern in &removed_patterns {
+                new_patterns.remove(pattern);
             }
         }
-        for path in &args.add {
-            new_patterns.insert(path.to_owned());
-        }
+        new_patterns.extend(added_patterns);
         Ok(new_patterns.into_iter().sorted_unstable().collect())
     })
 }
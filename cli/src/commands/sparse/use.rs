@@ -0,0 +1,62 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use jj_lib::working_copy::CheckoutError;
+use tracing::instrument;
+
+use super::update_sparse_with;
+use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error_with_message;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Use the patterns of a sparse profile committed in the repo
+///
+/// A sparse profile is a `.jj-sparse/<name>.toml` file in the working-copy
+/// commit. It lists the `patterns` to check out, and other profiles to
+/// `include`:
+///
+/// ```toml
+/// include = ["common"]
+/// patterns = ["backend", 'glob:"docs/*.md"']
+/// ```
+///
+/// The patterns are updated when a commit with a different version of the
+/// profile is checked out, until other patterns are set by `jj sparse set`,
+/// `jj sparse edit`, or `jj sparse reset`. The `.jj-sparse` directory is
+/// always included so the profiles can be edited.
+#[derive(clap::Args, Clone, Debug)]
+#[command(verbatim_doc_comment)]
+pub struct SparseUseArgs {
+    /// The name of the profile
+    profile: String,
+}
+
+#[instrument(skip_all)]
+pub fn cmd_sparse_use(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &SparseUseArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    update_sparse_with(ui, &mut workspace_command, |_ui, locked_wc, options| {
+        locked_wc
+            .set_sparse_profile(args.profile.clone(), options)
+            .map_err(|err| match err {
+                CheckoutError::SparseProfile(err) => user_error(err),
+                err => internal_error_with_message("Failed to update working copy paths", err),
+            })
+    })
+}
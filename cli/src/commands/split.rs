@@ -33,6 +33,7 @@ use tracing::instrument;
 use crate::cli_util::compute_commit_location;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::DiffSelector;
+use crate::cli_util::HunkSelectionArgs;
 use crate::cli_util::RevisionArg;
 use crate::cli_util::WorkspaceCommandHelper;
 use crate::cli_util::WorkspaceCommandTransaction;
@@ -43,6 +44,7 @@ use crate::description_util::add_trailers;
 use crate::description_util::description_template;
 use crate::description_util::edit_description;
 use crate::description_util::join_message_paragraphs;
+use crate::merge_tools::PROMPT_EDITOR_NAME;
 use crate::ui::Ui;
 
 /// Split a revision in two
@@ -72,6 +74,13 @@ pub(crate) struct SplitArgs {
     /// Specify diff editor to be used (implies --interactive)
     #[arg(long, value_name = "NAME")]
     tool: Option<String>,
+    /// Choose which parts to split by answering a prompt for each hunk
+    ///
+    /// This is a shorthand for `--tool :prompt`.
+    #[arg(long, conflicts_with_all = ["tool", "select", "select_patch"])]
+    patch: bool,
+    #[command(flatten)]
+    hunk_selection: HunkSelectionArgs,
     /// The revision to split
     #[arg(
         long, short,
@@ -155,11 +164,18 @@ impl SplitArgs {
         let matcher = workspace_command
             .parse_file_patterns(ui, &self.paths)?
             .to_matcher();
-        let diff_selector = workspace_command.diff_selector(
-            ui,
-            self.tool.as_deref(),
-            self.interactive || self.paths.is_empty(),
-        )?;
+        let tool = if self.patch {
+            Some(PROMPT_EDITOR_NAME)
+        } else {
+            self.tool.as_deref()
+        };
+        let diff_selector = if let Some(selector) =
+            workspace_command.hunk_selector(&self.hunk_selection)?
+        {
+            selector
+        } else {
+            workspace_command.diff_selector(ui, tool, self.interactive || self.paths.is_empty())?
+        };
         let use_move_flags = self.destination.is_some()
             || self.insert_after.is_some()
             || self.insert_before.is_some();
@@ -455,6 +471,7 @@ The changes that are not selected will replace the original commit.
     };
     let parent_tree = target_commit.parent_tree(tx.repo())?;
     let selected_tree_id = diff_selector.select(
+        ui,
         &parent_tree,
         &target_commit.tree()?,
         matcher,
@@ -26,6 +26,7 @@ use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::DiffSelector;
+use crate::cli_util::HunkSelectionArgs;
 use crate::cli_util::RevisionArg;
 use crate::cli_util::WorkspaceCommandTransaction;
 use crate::command_error::user_error;
@@ -38,6 +39,7 @@ use crate::description_util::description_template;
 use crate::description_util::edit_description;
 use crate::description_util::join_message_paragraphs;
 use crate::description_util::try_combine_messages;
+use crate::merge_tools::PROMPT_EDITOR_NAME;
 use crate::ui::Ui;
 
 /// Move changes from a revision into another revision
@@ -101,9 +103,16 @@ pub(crate) struct SquashArgs {
     /// Specify diff editor to be used (implies --interactive)
     #[arg(long, value_name = "NAME")]
     tool: Option<String>,
+    /// Choose which parts to squash by answering a prompt for each hunk
+    ///
+    /// This is a shorthand for `--tool :prompt`.
+    #[arg(long, conflicts_with_all = ["tool", "select", "select_patch"])]
+    patch: bool,
+    #[command(flatten)]
+    hunk_selection: HunkSelectionArgs,
     /// Move only changes to these paths (instead of all paths)
     #[arg(
-        conflicts_with_all = ["interactive", "tool"],
+        conflicts_with_all = ["interactive", "tool", "patch"],
         value_name = "FILESETS",
         value_hint = clap::ValueHint::AnyPath,
         add = ArgValueCompleter::new(complete::squash_revision_files),
@@ -158,8 +167,17 @@ pub(crate) fn cmd_squash(
     let matcher = workspace_command
         .parse_file_patterns(ui, &args.paths)?
         .to_matcher();
+    let tool = if args.patch {
+        Some(PROMPT_EDITOR_NAME)
+    } else {
+        args.tool.as_deref()
+    };
     let diff_selector =
-        workspace_command.diff_selector(ui, args.tool.as_deref(), args.interactive)?;
+        if let Some(selector) = workspace_command.hunk_selector(&args.hunk_selection)? {
+            selector
+        } else {
+            workspace_command.diff_selector(ui, tool, args.interactive)?
+        };
     let text_editor = workspace_command.text_editor()?;
     let description = SquashedDescription::from_args(args);
     workspace_command
@@ -167,7 +185,7 @@ pub(crate) fn cmd_squash(
 
     let mut tx = workspace_command.start_transaction();
     let tx_description = format!("squash commits into {}", destination.id().hex());
-    let source_commits = select_diff(&tx, &sources, &destination, &matcher, &diff_selector)?;
+    let source_commits = select_diff(ui, &tx, &sources, &destination, &matcher, &diff_selector)?;
     if let Some(squashed) = rewrite::squash_commits(
         tx.repo_mut(),
         &source_commits,
@@ -272,6 +290,7 @@ impl SquashedDescription {
 }
 
 fn select_diff(
+    ui: &Ui,
     tx: &WorkspaceCommandTransaction,
     sources: &[Commit],
     destination: &Commit,
@@ -300,7 +319,7 @@ fn select_diff(
             }
         };
         let selected_tree_id =
-            diff_selector.select(&parent_tree, &source_tree, matcher, format_instructions)?;
+            diff_selector.select(ui, &parent_tree, &source_tree, matcher, format_instructions)?;
         let selected_tree = tx.repo().store().get_root_tree(&selected_tree_id)?;
         source_commits.push(CommitWithSelection {
             commit: source.clone(),
@@ -0,0 +1,275 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::io::Write as _;
+use std::rc::Rc;
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::backend::ChangeId;
+use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
+use jj_lib::id_prefix::IdPrefixContext;
+use jj_lib::ref_name::RemoteName;
+use jj_lib::repo::Repo;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::revset::RevsetIteratorExt as _;
+use jj_lib::revset::UserRevsetExpression;
+use jj_lib::rewrite::compute_move_commits;
+use jj_lib::rewrite::find_landed_commits;
+use jj_lib::rewrite::EmptyBehaviour;
+use jj_lib::rewrite::MoveCommitsLocation;
+use jj_lib::rewrite::MoveCommitsTarget;
+use jj_lib::rewrite::RebaseOptions;
+use jj_lib::rewrite::RebasedCommit;
+use jj_lib::rewrite::RewriteRefsOptions;
+use jj_lib::str_util::StringPattern;
+use tracing::instrument;
+
+use crate::cli_util::print_updated_commits;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::commands::git::do_git_fetch;
+use crate::commands::git::get_fetch_remotes;
+use crate::complete;
+use crate::revset_util::RevsetExpressionEvaluator;
+use crate::ui::Ui;
+
+/// Fetch from Git remotes and rebase mutable stacks onto the updated trunk
+///
+/// This fetches all bookmarks like `jj git fetch`, then rebases every stack of
+/// mutable revisions that is based on an older version of `trunk()` onto the
+/// new `trunk()`. Stacks based on other revisions are left alone.
+///
+/// Revisions that landed upstream are abandoned. These are the revisions whose
+/// change ID is now in `trunk()`, the revisions whose changes are now in
+/// `trunk()` (e.g. because they were cherry-picked or squash-merged upstream,
+/// see the `landed()` revset function), and the revisions that become empty
+/// after the rebase. Stacks that have conflicts after the rebase are reported.
+#[derive(clap::Args, Clone, Debug)]
+pub struct SyncArgs {
+    /// The remote to fetch from (only named remotes are supported, can be
+    /// repeated)
+    ///
+    /// This defaults to the `git.fetch` setting. If that is not configured, and
+    /// if there are multiple remotes, the remote named "origin" will be used.
+    ///
+    /// By default, the specified remote names matches exactly. Use a [string
+    /// pattern], e.g. `--remote 'glob:*'`, to select remotes using
+    /// patterns.
+    ///
+    /// [string pattern]:
+    ///     https://jj-vcs.github.io/jj/latest/revsets#string-patterns
+    #[arg(
+        long = "remote",
+        value_name = "REMOTE",
+        value_parser = StringPattern::parse,
+        add = ArgValueCandidates::new(complete::git_remotes),
+    )]
+    remotes: Vec<StringPattern>,
+    /// Fetch from all remotes
+    #[arg(long, conflicts_with = "remotes")]
+    all_remotes: bool,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_sync(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &SyncArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let remotes = get_fetch_remotes(ui, &workspace_command, &args.remotes, args.all_remotes)?;
+    let remotes = remotes.iter().map(|r| r.as_ref()).collect_vec();
+    let trunk_expression = workspace_command
+        .parse_revset(ui, &RevisionArg::from("trunk()".to_owned()))?
+        .expression()
+        .clone();
+    let old_trunk_ids: Vec<CommitId> = workspace_command
+        .attach_revset_evaluator(trunk_expression.clone())
+        .evaluate_to_commit_ids()?
+        .try_collect()?;
+
+    let mut tx = workspace_command.start_transaction();
+    do_git_fetch(ui, &mut tx, &remotes, &[StringPattern::everything()])?;
+    // Move descendants of the commits abandoned by the import first, so they
+    // are rebased as part of their stacks.
+    tx.repo_mut().rebase_descendants()?;
+
+    // Not using the id prefix context of the workspace because trunk() and
+    // immutable_heads() have to be evaluated against the fetched repo.
+    let id_prefix_context = IdPrefixContext::new(command.revset_extensions().clone());
+    let evaluate = |repo: &dyn Repo,
+                    expression: Rc<UserRevsetExpression>|
+     -> Result<Vec<CommitId>, CommandError> {
+        let evaluator = RevsetExpressionEvaluator::new(
+            repo,
+            command.revset_extensions().clone(),
+            &id_prefix_context,
+            expression,
+        );
+        Ok(evaluator.evaluate_to_commit_ids()?.try_collect()?)
+    };
+    let Some(new_trunk_id) = evaluate(tx.repo(), trunk_expression)?.into_iter().next() else {
+        writeln!(
+            ui.warning_default(),
+            "trunk() is empty, not rebasing any stacks"
+        )?;
+        tx.finish(ui, sync_description(&remotes))?;
+        return Ok(());
+    };
+    let immutable_expression = tx.base_workspace_helper().env().immutable_expression();
+    let mutable_root_ids = evaluate(tx.repo(), immutable_expression.negated().roots())?;
+
+    // Only rebase the stacks based on an older version of trunk().
+    let mut root_ids = vec![];
+    for root_id in mutable_root_ids.into_iter().rev() {
+        let root = tx.repo().store().get_commit(&root_id)?;
+        let index = tx.repo().index();
+        let root_commit_id = tx.repo().store().root_commit_id();
+        let is_based_on_old_trunk = root.parent_ids() != [new_trunk_id.clone()]
+            && root
+                .parent_ids()
+                .iter()
+                .all(|id| id != root_commit_id && index.is_ancestor(id, &new_trunk_id));
+        if is_based_on_old_trunk {
+            root_ids.push(root_id);
+        }
+    }
+    if root_ids.is_empty() {
+        writeln!(ui.status(), "All stacks are already based on trunk()")?;
+        tx.finish(ui, sync_description(&remotes))?;
+        return Ok(());
+    }
+
+    // Commits in trunk() keep their change IDs if they were pushed as is or
+    // rebased upstream.
+    let landed_change_ids: HashSet<ChangeId> = RevsetExpression::commit(new_trunk_id.clone())
+        .ancestors()
+        .minus(&RevsetExpression::commits(old_trunk_ids).ancestors())
+        .evaluate(tx.repo())?
+        .iter()
+        .commits(tx.repo().store())
+        .map_ok(|commit| commit.change_id().clone())
+        .try_collect()?;
+    let stacks: Vec<Vec<Commit>> = root_ids
+        .iter()
+        .map(|root_id| -> Result<_, CommandError> {
+            let commits = RevsetExpression::commit(root_id.clone())
+                .descendants()
+                .evaluate(tx.repo())?
+                .iter()
+                .commits(tx.repo().store())
+                .try_collect()?;
+            Ok(commits)
+        })
+        .try_collect()?;
+    let mut landed_commits = stacks
+        .iter()
+        .flatten()
+        .filter(|commit| landed_change_ids.contains(commit.change_id()))
+        .unique_by(|commit| commit.id())
+        .cloned()
+        .collect_vec();
+    // Commits that were cherry-picked or squash-merged upstream have new change
+    // IDs, but the same changes.
+    let loc = MoveCommitsLocation {
+        new_parent_ids: vec![new_trunk_id],
+        new_child_ids: vec![],
+        target: MoveCommitsTarget::Roots(root_ids),
+    };
+    for commit in find_landed_commits(tx.repo(), &loc.new_parent_ids, &loc.target)? {
+        if !landed_commits.contains(&commit) {
+            landed_commits.push(commit);
+        }
+    }
+    let mut computed_move = compute_move_commits(tx.repo(), &loc)?;
+    computed_move.record_to_abandon(landed_commits.iter().map(Commit::id).cloned());
+    let rebase_options = RebaseOptions {
+        empty: EmptyBehaviour::AbandonNewlyEmpty,
+        rewrite_refs: RewriteRefsOptions {
+            delete_abandoned_bookmarks: false,
+        },
+        simplify_ancestor_merge: false,
+    };
+    let stats = computed_move.apply(tx.repo_mut(), &rebase_options)?;
+
+    // Report each conflicted stack by its rebased root commit.
+    let mut conflicted_roots = vec![];
+    for stack in &stacks {
+        let rebased_commits = stack
+            .iter()
+            .filter_map(|commit| match stats.rebased_commits.get(commit.id())? {
+                RebasedCommit::Rewritten(new_commit) => Some(new_commit),
+                RebasedCommit::Abandoned { .. } => None,
+            })
+            .collect_vec();
+        let mut has_conflict = false;
+        for commit in &rebased_commits {
+            has_conflict |= commit.has_conflict()?;
+        }
+        // Commits are in reverse topological order.
+        if let (true, Some(&root)) = (has_conflict, rebased_commits.last()) {
+            conflicted_roots.push(root.clone());
+        }
+    }
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        writeln!(formatter, "Rebased {} stacks onto trunk()", stacks.len())?;
+        if !landed_commits.is_empty() {
+            writeln!(
+                formatter,
+                "Abandoned {} commits that landed upstream:",
+                landed_commits.len()
+            )?;
+            print_updated_commits(
+                formatter.as_mut(),
+                &tx.base_workspace_helper().commit_summary_template(),
+                &landed_commits,
+            )?;
+        }
+        if stats.num_abandoned_empty > 0 {
+            writeln!(
+                formatter,
+                "Abandoned {} commits that became empty",
+                stats.num_abandoned_empty
+            )?;
+        }
+    }
+    if !conflicted_roots.is_empty() {
+        writeln!(
+            ui.warning_default(),
+            "{} stacks have conflicts after the rebase:",
+            conflicted_roots.len()
+        )?;
+        if let Some(mut formatter) = ui.status_formatter() {
+            print_updated_commits(
+                formatter.as_mut(),
+                &tx.commit_summary_template(),
+                &conflicted_roots,
+            )?;
+        }
+    }
+    tx.finish(ui, sync_description(&remotes))?;
+    Ok(())
+}
+
+fn sync_description(remotes: &[&RemoteName]) -> String {
+    format!(
+        "sync with git remote(s) {}",
+        remotes.iter().map(|n| n.as_symbol()).join(",")
+    )
+}
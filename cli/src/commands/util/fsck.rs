@@ -0,0 +1,106 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error as _;
+use std::io::Write as _;
+
+use jj_lib::fsck;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error;
+use crate::command_error::print_error_sources;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Check the integrity of the repository
+///
+/// Every operation and view reachable from the current operation heads is
+/// read, as well as every commit, tree, and file reachable from the views.
+/// The commit index and the working-copy state are checked for consistency
+/// with them.
+///
+/// Unlike most commands, this doesn't snapshot the working copy or resolve
+/// divergent operations.
+#[derive(clap::Args, Clone, Debug)]
+pub struct UtilFsckArgs {
+    /// Fix the problems that can be fixed automatically
+    ///
+    /// Operation heads pointing to unreadable operations are removed if there
+    /// are other operation heads. The commit index is rebuilt if it's corrupt
+    /// or inconsistent.
+    #[arg(long)]
+    repair: bool,
+}
+
+pub fn cmd_util_fsck(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &UtilFsckArgs,
+) -> Result<(), CommandError> {
+    // Don't load the repo, which would fail if the operation heads are broken,
+    // and would silently rebuild the index.
+    let workspace = command.load_workspace()?;
+    let repo_loader = workspace.repo_loader();
+    let mut report = fsck::check_repo(repo_loader)?;
+    report.problems.extend(fsck::check_working_copy(
+        repo_loader,
+        workspace.working_copy(),
+    ));
+
+    for problem in &report.problems {
+        writeln!(ui.warning_default(), "{problem}")?;
+        print_error_sources(ui, problem.source())?;
+    }
+    writeln!(
+        ui.status(),
+        "Checked {} operations, {} commits, and {} objects",
+        report.num_operations,
+        report.num_commits,
+        report.num_objects
+    )?;
+    let num_problems = report.problems.len();
+    if num_problems == 0 {
+        writeln!(ui.status(), "No problems found")?;
+        return Ok(());
+    }
+
+    let num_repairable = report
+        .problems
+        .iter()
+        .filter(|problem| problem.is_repairable())
+        .count();
+    if args.repair {
+        let num_repaired = fsck::repair(repo_loader, &report.problems).map_err(internal_error)?;
+        writeln!(
+            ui.status(),
+            "Repaired {num_repaired} of {num_problems} problems"
+        )?;
+        if num_repaired == num_problems {
+            return Ok(());
+        }
+        Err(user_error(format!(
+            "Found {} problems which cannot be repaired",
+            num_problems - num_repaired
+        )))
+    } else if num_repairable > 0 {
+        Err(
+            user_error(format!("Found {num_problems} problems")).hinted(format!(
+                "Run `jj util fsck --repair` to repair {num_repairable} of them."
+            )),
+        )
+    } else {
+        Err(user_error(format!("Found {num_problems} problems")))
+    }
+}
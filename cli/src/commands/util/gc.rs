@@ -12,11 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::Write as _;
 use std::slice;
 use std::time::Duration;
 use std::time::SystemTime;
 
 use jj_lib::repo::Repo as _;
+use jj_lib::settings::HumanByteSize;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::user_error;
@@ -30,6 +32,9 @@ use crate::ui::Ui;
 ///
 /// Previous versions of a change that are reachable via the evolution log are
 /// not garbage-collected.
+///
+/// The remaining operations and views are packed into a single file, which
+/// speeds up `jj op log` on slow file systems.
 #[derive(clap::Args, Clone, Debug)]
 pub struct UtilGcArgs {
     /// Time threshold
@@ -42,6 +47,12 @@ pub struct UtilGcArgs {
     /// release.
     #[arg(long)]
     expire: Option<String>,
+    /// Don't remove anything, but report how much space would be reclaimed
+    ///
+    /// Only objects in the commit backend are checked. Operations aren't
+    /// garbage-collected in this mode.
+    #[arg(long)]
+    dry_run: bool,
 }
 
 pub fn cmd_util_gc(
@@ -62,8 +73,25 @@ pub fn cmd_util_gc(
     let workspace_command = command.workspace_helper(ui)?;
 
     let repo = workspace_command.repo();
-    repo.op_store()
-        .gc(slice::from_ref(repo.op_id()), keep_newer)?;
-    repo.store().gc(repo.index(), keep_newer)?;
+    if !args.dry_run {
+        repo.op_store()
+            .gc(slice::from_ref(repo.op_id()), keep_newer)?;
+    }
+    let stats = repo.store().gc(repo.index(), keep_newer, args.dry_run)?;
+    if args.dry_run {
+        writeln!(
+            ui.status(),
+            "Would remove {} unreachable objects ({})",
+            stats.removed_objects,
+            HumanByteSize(stats.removed_bytes)
+        )?;
+    } else if stats.removed_objects > 0 {
+        writeln!(
+            ui.status(),
+            "Removed {} unreachable objects ({})",
+            stats.removed_objects,
+            HumanByteSize(stats.removed_bytes)
+        )?;
+    }
     Ok(())
 }
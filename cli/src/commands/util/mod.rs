@@ -15,6 +15,7 @@
 mod completion;
 mod config_schema;
 mod exec;
+mod fsck;
 mod gc;
 mod install_man_pages;
 mod markdown_help;
@@ -28,6 +29,8 @@ use self::config_schema::cmd_util_config_schema;
 use self::config_schema::UtilConfigSchemaArgs;
 use self::exec::cmd_util_exec;
 use self::exec::UtilExecArgs;
+use self::fsck::cmd_util_fsck;
+use self::fsck::UtilFsckArgs;
 use self::gc::cmd_util_gc;
 use self::gc::UtilGcArgs;
 use self::install_man_pages::cmd_util_install_man_pages;
@@ -44,6 +47,7 @@ pub(crate) enum UtilCommand {
     Completion(UtilCompletionArgs),
     ConfigSchema(UtilConfigSchemaArgs),
     Exec(UtilExecArgs),
+    Fsck(UtilFsckArgs),
     Gc(UtilGcArgs),
     InstallManPages(UtilInstallManPagesArgs),
     MarkdownHelp(UtilMarkdownHelp),
@@ -59,6 +63,7 @@ pub(crate) fn cmd_util(
         UtilCommand::Completion(args) => cmd_util_completion(ui, command, args),
         UtilCommand::ConfigSchema(args) => cmd_util_config_schema(ui, command, args),
         UtilCommand::Exec(args) => cmd_util_exec(ui, command, args),
+        UtilCommand::Fsck(args) => cmd_util_fsck(ui, command, args),
         UtilCommand::Gc(args) => cmd_util_gc(ui, command, args),
         UtilCommand::InstallManPages(args) => cmd_util_install_man_pages(ui, command, args),
         UtilCommand::MarkdownHelp(args) => cmd_util_markdown_help(ui, command, args),
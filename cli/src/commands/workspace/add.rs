@@ -22,6 +22,8 @@ use jj_lib::ref_name::WorkspaceNameBuf;
 use jj_lib::repo::Repo as _;
 use jj_lib::rewrite::merge_commit_trees;
 use jj_lib::workspace::Workspace;
+use jj_lib::workspace_store::SimpleWorkspaceStore;
+use jj_lib::workspace_store::WorkspaceEntry;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
@@ -29,6 +31,7 @@ use crate::cli_util::RevisionArg;
 use crate::command_error::internal_error_with_message;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
+use crate::git_util::add_git_worktree;
 use crate::ui::Ui;
 
 /// How to handle sparse patterns when creating a new workspace.
@@ -73,6 +76,13 @@ pub struct WorkspaceAddArgs {
     /// How to handle sparse patterns when creating a new workspace.
     #[arg(long, value_enum, default_value_t = SparseInheritance::Copy)]
     sparse_patterns: SparseInheritance,
+    /// Create a throwaway workspace
+    ///
+    /// The workspace is forgotten and its directory is deleted when its
+    /// working-copy commit is abandoned from another workspace, e.g. by `jj
+    /// abandon <name>@`.
+    #[arg(long)]
+    temporary: bool,
 }
 
 #[instrument(skip_all)]
@@ -116,6 +126,35 @@ pub fn cmd_workspace_add(
         working_copy_factory,
         workspace_name.clone(),
     )?;
+    // Back the new workspace by a linked Git worktree so that Git commands
+    // work in every workspace of a colocated repo.
+    if old_workspace_command.working_copy_shared_with_git() {
+        add_git_worktree(ui, &repo, &workspace_name, new_workspace.workspace_root())?;
+    }
+    let workspace_store = SimpleWorkspaceStore::load(repo_path);
+    // Workspaces created before the workspace store existed aren't recorded.
+    let old_workspace_name = old_workspace_command.workspace_name();
+    if workspace_store.get(old_workspace_name)?.is_none() {
+        let entry = WorkspaceEntry {
+            name: old_workspace_name.to_owned(),
+            path: old_workspace_command.workspace_root().to_owned(),
+            temporary: false,
+        };
+        if let Err(err) = workspace_store.add(entry) {
+            writeln!(
+                ui.warning_default(),
+                "Failed to record the location of workspace {name}: {err}",
+                name = old_workspace_name.as_symbol()
+            )?;
+        }
+    }
+    if args.temporary {
+        workspace_store.add(WorkspaceEntry {
+            name: workspace_name.clone(),
+            path: new_workspace.workspace_root().to_owned(),
+            temporary: true,
+        })?;
+    }
     writeln!(
         ui.status(),
         "Created workspace in \"{}\"",
@@ -15,18 +15,21 @@
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
 use jj_lib::ref_name::WorkspaceNameBuf;
+use jj_lib::workspace_store::SimpleWorkspaceStore;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::git_util::remove_git_worktrees;
 use crate::ui::Ui;
 
 /// Stop tracking a workspace's working-copy commit in the repo
 ///
 /// The workspace will not be touched on disk. It can be deleted from disk
-/// before or after running this command.
+/// before or after running this command. Use `jj workspace prune` to forget
+/// the workspaces that were deleted from disk.
 #[derive(clap::Args, Clone, Debug)]
 pub struct WorkspaceForgetArgs {
     /// Names of the workspaces to forget. By default, forgets only the current
@@ -75,5 +78,8 @@ pub fn cmd_workspace_forget(
     };
 
     tx.finish(ui, description)?;
+    let names = wss.iter().map(|ws| &**ws).collect_vec();
+    SimpleWorkspaceStore::load(workspace_command.repo_path()).forget(&names)?;
+    remove_git_worktrees(workspace_command.repo(), &names)?;
     Ok(())
 }
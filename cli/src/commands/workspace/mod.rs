@@ -15,6 +15,8 @@
 mod add;
 mod forget;
 mod list;
+mod r#move;
+mod prune;
 mod rename;
 mod root;
 mod update_stale;
@@ -28,6 +30,10 @@ use self::forget::cmd_workspace_forget;
 use self::forget::WorkspaceForgetArgs;
 use self::list::cmd_workspace_list;
 use self::list::WorkspaceListArgs;
+use self::prune::cmd_workspace_prune;
+use self::prune::WorkspacePruneArgs;
+use self::r#move::cmd_workspace_move;
+use self::r#move::WorkspaceMoveArgs;
 use self::rename::cmd_workspace_rename;
 use self::rename::WorkspaceRenameArgs;
 use self::root::cmd_workspace_root;
@@ -54,6 +60,8 @@ pub(crate) enum WorkspaceCommand {
     Add(WorkspaceAddArgs),
     Forget(WorkspaceForgetArgs),
     List(WorkspaceListArgs),
+    Move(WorkspaceMoveArgs),
+    Prune(WorkspacePruneArgs),
     Rename(WorkspaceRenameArgs),
     Root(WorkspaceRootArgs),
     UpdateStale(WorkspaceUpdateStaleArgs),
@@ -69,6 +77,8 @@ pub(crate) fn cmd_workspace(
         WorkspaceCommand::Add(args) => cmd_workspace_add(ui, command, args),
         WorkspaceCommand::Forget(args) => cmd_workspace_forget(ui, command, args),
         WorkspaceCommand::List(args) => cmd_workspace_list(ui, command, args),
+        WorkspaceCommand::Move(args) => cmd_workspace_move(ui, command, args),
+        WorkspaceCommand::Prune(args) => cmd_workspace_prune(ui, command, args),
         WorkspaceCommand::Rename(args) => cmd_workspace_rename(ui, command, args),
         WorkspaceCommand::Root(args) => cmd_workspace_root(ui, command, args),
         WorkspaceCommand::UpdateStale(args) => cmd_workspace_update_stale(ui, command, args),
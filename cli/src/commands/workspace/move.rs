@@ -0,0 +1,154 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::path::Path;
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::file_util;
+use jj_lib::file_util::IoResultExt as _;
+use jj_lib::ref_name::WorkspaceNameBuf;
+use jj_lib::workspace_store::SimpleWorkspaceStore;
+use jj_lib::workspace_store::WorkspaceEntry;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::git_util::relocate_git_worktree;
+use crate::ui::Ui;
+
+/// Move a workspace to another directory
+///
+/// If the workspace contains the repo, the other workspaces and their Git
+/// worktrees are updated to point to the new location of the repo.
+#[derive(clap::Args, Clone, Debug)]
+pub struct WorkspaceMoveArgs {
+    /// Where to move the workspace to
+    destination: String,
+    /// The workspace to move (default: the current workspace)
+    #[arg(long, add = ArgValueCandidates::new(complete::workspaces))]
+    workspace: Option<WorkspaceNameBuf>,
+}
+
+#[instrument(skip_all)]
+pub fn cmd_workspace_move(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &WorkspaceMoveArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let name = args
+        .workspace
+        .as_deref()
+        .unwrap_or(workspace_command.workspace_name());
+    if workspace_command
+        .repo()
+        .view()
+        .get_wc_commit_id(name)
+        .is_none()
+    {
+        return Err(user_error(format!(
+            "No such workspace: {}",
+            name.as_symbol()
+        )));
+    }
+    let workspace_store = SimpleWorkspaceStore::load(workspace_command.repo_path());
+    let old_entry = workspace_store.get(name)?;
+    let (source_path, temporary) = if name == workspace_command.workspace_name() {
+        let temporary = old_entry.is_some_and(|entry| entry.temporary);
+        (workspace_command.workspace_root().to_owned(), temporary)
+    } else if let Some(entry) = old_entry {
+        (entry.path, entry.temporary)
+    } else {
+        return Err(user_error_with_hint(
+            format!("The location of workspace {} is unknown", name.as_symbol()),
+            "Run the command from within the workspace to move it.",
+        ));
+    };
+
+    let destination_path = file_util::normalize_path(&command.cwd().join(&args.destination));
+    if destination_path.exists() {
+        return Err(user_error("Destination already exists"));
+    }
+    // Read the other workspaces before the store is possibly moved along with
+    // the workspace.
+    let other_entries = workspace_store
+        .list()?
+        .into_iter()
+        .filter(|entry| entry.name != name)
+        .collect_vec();
+    fs::rename(&source_path, &destination_path)
+        .context(&source_path)
+        .map_err(|err| user_error_with_message("Failed to move workspace", err))?;
+    let destination_path = dunce::canonicalize(&destination_path).context(&destination_path)?;
+
+    let repo_path = workspace_command.repo_path();
+    let workspace_store = if let Ok(relative_repo_path) = repo_path.strip_prefix(&source_path) {
+        // Secondary workspaces refer to the repo by its absolute path.
+        let new_repo_path = destination_path.join(relative_repo_path);
+        let new_repo_path_str = new_repo_path
+            .to_str()
+            .ok_or_else(|| user_error("Destination path is not valid UTF-8"))?;
+        for entry in &other_entries {
+            let repo_file_path = entry.path.join(".jj").join("repo");
+            if repo_file_path.is_file() {
+                fs::write(&repo_file_path, new_repo_path_str).context(&repo_file_path)?;
+            }
+            // Linked Git worktrees refer to the Git repo by its absolute path.
+            let dot_git_path = entry.path.join(".git");
+            if dot_git_path.is_file() {
+                let contents = fs::read_to_string(&dot_git_path).context(&dot_git_path)?;
+                let git_dir = contents.trim_end().strip_prefix("gitdir: ").map(Path::new);
+                if let Some(relative_git_dir) =
+                    git_dir.and_then(|git_dir| git_dir.strip_prefix(&source_path).ok())
+                {
+                    let new_git_dir = destination_path.join(relative_git_dir);
+                    fs::write(
+                        &dot_git_path,
+                        format!("gitdir: {}\n", new_git_dir.display()),
+                    )
+                    .context(&dot_git_path)?;
+                }
+            }
+        }
+        SimpleWorkspaceStore::load(&new_repo_path)
+    } else {
+        relocate_git_worktree(workspace_command.repo(), name, &destination_path)?;
+        workspace_store
+    };
+    workspace_store.add(WorkspaceEntry {
+        name: name.to_owned(),
+        path: destination_path.clone(),
+        temporary,
+    })?;
+
+    writeln!(
+        ui.status(),
+        "Moved workspace {name} to \"{path}\"",
+        name = name.as_symbol(),
+        path = file_util::relative_path(command.cwd(), &destination_path).display()
+    )?;
+    if command.cwd().starts_with(&source_path) {
+        writeln!(
+            ui.hint_default(),
+            "The current directory was moved. Run `cd` to go to the new location."
+        )?;
+    }
+    Ok(())
+}
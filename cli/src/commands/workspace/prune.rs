@@ -0,0 +1,84 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools as _;
+use jj_lib::file_util;
+use jj_lib::repo::Repo as _;
+use jj_lib::workspace_store::SimpleWorkspaceStore;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::git_util::remove_git_worktrees;
+use crate::ui::Ui;
+
+/// Forget workspaces whose directories no longer exist
+///
+/// Only the workspaces whose location is known are considered, i.e. those
+/// created with a version of jj that records the workspace locations.
+#[derive(clap::Args, Clone, Debug)]
+pub struct WorkspacePruneArgs {}
+
+#[instrument(skip_all)]
+pub fn cmd_workspace_prune(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &WorkspacePruneArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let workspace_store = SimpleWorkspaceStore::load(workspace_command.repo_path());
+    let missing = workspace_store
+        .list()?
+        .into_iter()
+        .filter(|entry| !entry.path.exists())
+        .collect_vec();
+    if missing.is_empty() {
+        writeln!(ui.status(), "No workspaces to prune.")?;
+        return Ok(());
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    for entry in &missing {
+        if tx.repo().view().get_wc_commit_id(&entry.name).is_some() {
+            tx.repo_mut().remove_wc_commit(&entry.name)?;
+        }
+    }
+    if let Some(mut formatter) = ui.status_formatter() {
+        writeln!(
+            formatter,
+            "Forgot {} workspaces whose directories no longer exist:",
+            missing.len()
+        )?;
+        for entry in &missing {
+            writeln!(
+                formatter,
+                "  {name}: \"{path}\"",
+                name = entry.name.as_symbol(),
+                path = file_util::relative_path(command.cwd(), &entry.path).display()
+            )?;
+        }
+    }
+    let description = format!(
+        "prune workspaces {}",
+        missing
+            .iter()
+            .map(|entry| entry.name.as_symbol())
+            .join(", ")
+    );
+    tx.finish(ui, description)?;
+    let names = missing.iter().map(|entry| &*entry.name).collect_vec();
+    workspace_store.forget(&names)?;
+    remove_git_worktrees(workspace_command.repo(), &names)?;
+    Ok(())
+}
@@ -13,11 +13,13 @@
 // limitations under the License.
 
 use jj_lib::ref_name::WorkspaceNameBuf;
+use jj_lib::workspace_store::SimpleWorkspaceStore;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
+use crate::git_util::rename_git_worktree;
 use crate::ui::Ui;
 
 /// Renames the current workspace
@@ -58,6 +60,14 @@ pub fn cmd_workspace_rename(
         )));
     }
 
+    // The linked Git worktree is named after the workspace.
+    rename_git_worktree(
+        workspace_command.repo(),
+        &old_name,
+        new_name,
+        workspace_command.workspace_root(),
+    )?;
+
     let mut tx = workspace_command.start_transaction().into_inner();
     let (mut locked_ws, _wc_commit) = workspace_command.start_working_copy_mutation()?;
 
@@ -71,6 +81,7 @@ pub fn cmd_workspace_rename(
         new = new_name.as_symbol()
     ))?;
     locked_ws.finish(repo.op_id().clone())?;
+    SimpleWorkspaceStore::load(workspace_command.repo_path()).rename(&old_name, new_name)?;
 
     Ok(())
 }
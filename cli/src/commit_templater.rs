@@ -46,6 +46,7 @@ use jj_lib::matchers::Matcher;
 use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
 use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_store::BookmarkMetadata;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
 use jj_lib::ref_name::WorkspaceName;
@@ -312,6 +313,11 @@ impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo> {
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(self, diagnostics, build_ctx, property, function)
             }
+            CommitTemplatePropertyKind::BookmarkStatus(property) => {
+                let table = &self.build_fn_table.bookmark_status_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                build(self, diagnostics, build_ctx, property, function)
+            }
             CommitTemplatePropertyKind::Trailer(property) => {
                 let table = &self.build_fn_table.trailer_methods;
                 let build = template_parser::lookup_method(type_name, table, function)?;
@@ -368,6 +374,7 @@ pub enum CommitTemplatePropertyKind<'repo> {
     DiffStats(BoxedTemplateProperty<'repo, DiffStatsFormatted<'repo>>),
     CryptographicSignatureOpt(BoxedTemplateProperty<'repo, Option<CryptographicSignature>>),
     AnnotationLine(BoxedTemplateProperty<'repo, AnnotationLine>),
+    BookmarkStatus(BoxedTemplateProperty<'repo, BookmarkStatus>),
     Trailer(BoxedTemplateProperty<'repo, Trailer>),
     TrailerList(BoxedTemplateProperty<'repo, Vec<Trailer>>),
 }
@@ -394,6 +401,7 @@ template_builder::impl_property_wrappers!(<'repo> CommitTemplatePropertyKind<'re
     DiffStats(DiffStatsFormatted<'repo>),
     CryptographicSignatureOpt(Option<CryptographicSignature>),
     AnnotationLine(AnnotationLine),
+    BookmarkStatus(BookmarkStatus),
     Trailer(Trailer),
     TrailerList(Vec<Trailer>),
 });
@@ -430,6 +438,7 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             Self::DiffStats(_) => "DiffStats",
             Self::CryptographicSignatureOpt(_) => "Option<CryptographicSignature>",
             Self::AnnotationLine(_) => "AnnotationLine",
+            Self::BookmarkStatus(_) => "BookmarkStatus",
             Self::Trailer(_) => "Trailer",
             Self::TrailerList(_) => "List<Trailer>",
         }
@@ -462,6 +471,7 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
                 Some(property.map(|sig| sig.is_some()).into_dyn())
             }
             Self::AnnotationLine(_) => None,
+            Self::BookmarkStatus(_) => None,
             Self::Trailer(_) => None,
             Self::TrailerList(property) => Some(property.map(|l| !l.is_empty()).into_dyn()),
         }
@@ -514,6 +524,7 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             Self::DiffStats(property) => Some(property.into_template()),
             Self::CryptographicSignatureOpt(_) => None,
             Self::AnnotationLine(_) => None,
+            Self::BookmarkStatus(_) => None,
             Self::Trailer(property) => Some(property.into_template()),
             Self::TrailerList(property) => Some(property.into_template()),
         }
@@ -572,6 +583,7 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             (Self::DiffStats(_), _) => None,
             (Self::CryptographicSignatureOpt(_), _) => None,
             (Self::AnnotationLine(_), _) => None,
+            (Self::BookmarkStatus(_), _) => None,
             (Self::Trailer(_), _) => None,
             (Self::TrailerList(_), _) => None,
         }
@@ -601,6 +613,7 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             (Self::DiffStats(_), _) => None,
             (Self::CryptographicSignatureOpt(_), _) => None,
             (Self::AnnotationLine(_), _) => None,
+            (Self::BookmarkStatus(_), _) => None,
             (Self::Trailer(_), _) => None,
             (Self::TrailerList(_), _) => None,
         }
@@ -630,6 +643,7 @@ pub struct CommitTemplateBuildFnTable<'repo> {
     pub cryptographic_signature_methods:
         CommitTemplateBuildMethodFnMap<'repo, CryptographicSignature>,
     pub annotation_line_methods: CommitTemplateBuildMethodFnMap<'repo, AnnotationLine>,
+    pub bookmark_status_methods: CommitTemplateBuildMethodFnMap<'repo, BookmarkStatus>,
     pub trailer_methods: CommitTemplateBuildMethodFnMap<'repo, Trailer>,
     pub trailer_list_methods: CommitTemplateBuildMethodFnMap<'repo, Vec<Trailer>>,
 }
@@ -654,6 +668,7 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             diff_stats_methods: builtin_diff_stats_methods(),
             cryptographic_signature_methods: builtin_cryptographic_signature_methods(),
             annotation_line_methods: builtin_annotation_line_methods(),
+            bookmark_status_methods: builtin_bookmark_status_methods(),
             trailer_methods: builtin_trailer_methods(),
             trailer_list_methods: builtin_trailer_list_methods(),
         }
@@ -677,6 +692,7 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             diff_stats_methods: HashMap::new(),
             cryptographic_signature_methods: HashMap::new(),
             annotation_line_methods: HashMap::new(),
+            bookmark_status_methods: HashMap::new(),
             trailer_methods: HashMap::new(),
             trailer_list_methods: HashMap::new(),
         }
@@ -700,6 +716,7 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             diff_stats_methods,
             cryptographic_signature_methods,
             annotation_line_methods,
+            bookmark_status_methods,
             trailer_methods,
             trailer_list_methods,
         } = extension;
@@ -729,6 +746,7 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             cryptographic_signature_methods,
         );
         merge_fn_map(&mut self.annotation_line_methods, annotation_line_methods);
+        merge_fn_map(&mut self.bookmark_status_methods, bookmark_status_methods);
         merge_fn_map(&mut self.trailer_methods, trailer_methods);
         merge_fn_map(&mut self.trailer_list_methods, trailer_list_methods);
     }
@@ -1150,6 +1168,8 @@ pub struct CommitRef {
     /// Local ref is synchronized with all tracking remotes, or tracking remote
     /// ref is synchronized with the local.
     synced: bool,
+    /// Metadata of local bookmark.
+    metadata: BookmarkMetadata,
 }
 
 #[derive(Debug)]
@@ -1172,6 +1192,16 @@ impl CommitRef {
         name: impl Into<String>,
         target: RefTarget,
         remote_refs: impl IntoIterator<Item = &'a RemoteRef>,
+    ) -> Rc<Self> {
+        Self::local_bookmark(name, target, remote_refs, None)
+    }
+
+    /// Creates local bookmark representation with the given `metadata`.
+    pub fn local_bookmark<'a>(
+        name: impl Into<String>,
+        target: RefTarget,
+        remote_refs: impl IntoIterator<Item = &'a RemoteRef>,
+        metadata: Option<&BookmarkMetadata>,
     ) -> Rc<Self> {
         let synced = remote_refs
             .into_iter()
@@ -1182,6 +1212,7 @@ impl CommitRef {
             target,
             tracking_ref: None,
             synced,
+            metadata: metadata.cloned().unwrap_or_default(),
         })
     }
 
@@ -1217,6 +1248,7 @@ impl CommitRef {
             target: remote_ref.target,
             tracking_ref,
             synced,
+            metadata: BookmarkMetadata::default(),
         })
     }
 
@@ -1232,6 +1264,7 @@ impl CommitRef {
             target,
             tracking_ref: None,
             synced: false, // has no local counterpart
+            metadata: BookmarkMetadata::default(),
         })
     }
 
@@ -1419,6 +1452,34 @@ fn builtin_commit_ref_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo,
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "description",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.map(|commit_ref| commit_ref.metadata.description.clone());
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "owner",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|commit_ref| commit_ref.metadata.owner.clone());
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "base",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|commit_ref| {
+                let base = commit_ref.metadata.base.as_ref();
+                base.map(|name| RefSymbolBuf(name.as_symbol().to_string()))
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map.insert(
         "tracked",
         |_language, _diagnostics, _build_ctx, self_property, function| {
@@ -1483,10 +1544,11 @@ fn build_bookmarks_index(repo: &dyn Repo) -> CommitRefsIndex {
         let local_target = bookmark_target.local_target;
         let remote_refs = bookmark_target.remote_refs;
         if local_target.is_present() {
-            let commit_ref = CommitRef::local(
+            let commit_ref = CommitRef::local_bookmark(
                 bookmark_name,
                 local_target.clone(),
                 remote_refs.iter().map(|&(_, remote_ref)| remote_ref),
+                repo.view().get_bookmark_metadata(bookmark_name),
             );
             index.insert(local_target.added_ids(), commit_ref);
         }
@@ -1943,8 +2005,11 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
                 })
                 .transpose()?;
             let path_converter = language.path_converter;
-            // No user configuration exists for diff stat.
-            let options = diff_util::DiffStatOptions::default();
+            let options =
+                diff_util::DiffStatOptions::from_settings(language.settings()).map_err(|err| {
+                    let message = "Failed to load diff settings";
+                    TemplateParseError::expression(message, function.name_span).with_source(err)
+                })?;
             let conflict_marker_style = language.conflict_marker_style;
             // TODO: cache and reuse stats within the current evaluation?
             let out_property = (self_property, width_property).and_then(move |(diff, width)| {
@@ -2291,6 +2356,152 @@ fn builtin_annotation_line_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'r
     map
 }
 
+/// Synchronization status of a local bookmark and one of its tracked remote
+/// bookmarks.
+#[derive(Debug, Clone)]
+pub struct BookmarkStatus {
+    /// Local bookmark.
+    pub local_ref: Rc<CommitRef>,
+    /// Remote bookmark tracked by the local bookmark.
+    pub remote_ref: Rc<CommitRef>,
+    /// How the local and remote targets relate.
+    pub state: BookmarkSyncState,
+    /// Whether the commits last pushed to (or fetched from) the remote were
+    /// rewritten locally.
+    pub rewritten: bool,
+    /// Command that would reconcile the local and remote bookmarks, or empty
+    /// if there's nothing to do.
+    pub suggestion: String,
+}
+
+/// How a local bookmark relates to one of its tracked remote bookmarks.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BookmarkSyncState {
+    /// The local and remote bookmarks point to the same target.
+    Synced,
+    /// The local bookmark has commits to push.
+    Ahead,
+    /// The remote bookmark has commits to pull in.
+    Behind,
+    /// Both bookmarks have commits the other doesn't have.
+    Diverged,
+    /// Either bookmark is conflicted.
+    Conflicted,
+    /// The local bookmark was deleted.
+    Deleted,
+}
+
+impl BookmarkSyncState {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Synced => "synced",
+            Self::Ahead => "ahead",
+            Self::Behind => "behind",
+            Self::Diverged => "diverged",
+            Self::Conflicted => "conflicted",
+            Self::Deleted => "deleted",
+        }
+    }
+}
+
+fn builtin_bookmark_status_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, BookmarkStatus>
+{
+    let mut map = CommitTemplateBuildMethodFnMap::<BookmarkStatus>::new();
+    map.insert(
+        "name",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|status| status.local_ref.name.clone());
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "remote",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|status| {
+                status
+                    .remote_ref
+                    .remote
+                    .clone()
+                    .expect("status should refer to remote ref")
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "local_ref",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|status| status.local_ref);
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "remote_ref",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|status| status.remote_ref);
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "ahead_count",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let repo = language.repo;
+            // The remote ref is behind by the number of local commits to push.
+            let out_property =
+                self_property.and_then(|status| status.remote_ref.tracking_behind_count(repo));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "behind_count",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let repo = language.repo;
+            let out_property =
+                self_property.and_then(|status| status.remote_ref.tracking_ahead_count(repo));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "conflict",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property
+                .map(|status| status.local_ref.has_conflict() || status.remote_ref.has_conflict());
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "state",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|status| status.state.as_str().to_owned());
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "rewritten",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|status| status.rewritten);
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "suggestion",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|status| status.suggestion);
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map
+}
+
 impl Template for Trailer {
     fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
         write!(formatter, "{}: {}", self.key, self.value)
@@ -14,17 +14,25 @@
 
 use std::borrow::Borrow;
 use std::cmp::max;
+use std::cmp::min;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::io;
 use std::iter;
 use std::mem;
 use std::ops::Range;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use bstr::BStr;
 use bstr::BString;
+use bstr::ByteSlice as _;
+use clap::builder::TypedValueParser as _;
 use futures::executor::block_on_stream;
+use futures::stream;
 use futures::stream::BoxStream;
+use futures::stream::LocalBoxStream;
 use futures::StreamExt as _;
 use futures::TryStreamExt as _;
 use itertools::Itertools as _;
@@ -50,6 +58,7 @@ use jj_lib::diff::CompareBytesExactly;
 use jj_lib::diff::CompareBytesIgnoreAllWhitespace;
 use jj_lib::diff::CompareBytesIgnoreWhitespaceAmount;
 use jj_lib::diff::Diff;
+use jj_lib::diff::DiffAlgorithm;
 use jj_lib::diff::DiffHunk;
 use jj_lib::diff::DiffHunkKind;
 use jj_lib::files;
@@ -57,6 +66,7 @@ use jj_lib::files::ConflictDiffHunk;
 use jj_lib::files::DiffLineHunkSide;
 use jj_lib::files::DiffLineIterator;
 use jj_lib::files::DiffLineNumber;
+use jj_lib::files::MergeOptions;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::Merge;
 use jj_lib::merge::MergedTreeValue;
@@ -65,6 +75,7 @@ use jj_lib::object_id::ObjectId as _;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::InvalidRepoPathError;
 use jj_lib::repo_path::RepoPath;
+use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::rewrite::rebase_to_dest_parent;
 use jj_lib::settings::UserSettings;
@@ -72,6 +83,7 @@ use jj_lib::store::Store;
 use pollster::FutureExt as _;
 use thiserror::Error;
 use tracing::instrument;
+use unicode_width::UnicodeWidthChar as _;
 use unicode_width::UnicodeWidthStr as _;
 
 use crate::command_error::cli_error;
@@ -91,7 +103,7 @@ use crate::ui::Ui;
 #[derive(clap::Args, Clone, Debug)]
 #[command(next_help_heading = "Diff Formatting Options")]
 #[command(group(clap::ArgGroup::new("short-format").args(&["summary", "stat", "types", "name_only"])))]
-#[command(group(clap::ArgGroup::new("long-format").args(&["git", "color_words"])))]
+#[command(group(clap::ArgGroup::new("long-format").args(&["git", "color_words", "side_by_side"])))]
 pub struct DiffFormatArgs {
     /// For each path, show only whether it was modified, added, or deleted
     #[arg(long, short)]
@@ -120,6 +132,9 @@ pub struct DiffFormatArgs {
     /// Show a word-level diff with changes indicated only by color
     #[arg(long)]
     pub color_words: bool,
+    /// Show old and new contents in two columns
+    #[arg(long)]
+    pub side_by_side: bool,
     /// Generate diff by external command
     ///
     /// A builtin format can also be specified as `:<name>`. For example,
@@ -137,6 +152,17 @@ pub struct DiffFormatArgs {
     /// Ignore changes in amount of whitespace when comparing lines.
     #[arg(long, conflicts_with = "ignore_all_space")] // short = 'b'
     ignore_space_change: bool,
+    /// Algorithm used to match up lines when computing the diff
+    #[arg(
+        long,
+        value_name = "ALGORITHM",
+        value_parser = clap::builder::PossibleValuesParser::new(["histogram", "myers", "patience"])
+            .map(|s| s.parse::<DiffAlgorithm>().unwrap()),
+    )]
+    diff_algorithm: Option<DiffAlgorithm>,
+    /// Highlight blocks of lines moved within the diff, possibly across files
+    #[arg(long)]
+    color_moved: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -148,6 +174,7 @@ pub enum DiffFormat {
     NameOnly,
     Git(Box<UnifiedDiffOptions>),
     ColorWords(Box<ColorWordsDiffOptions>),
+    SideBySide(Box<SideBySideDiffOptions>),
     Tool(Box<ExternalMergeTool>),
 }
 
@@ -159,6 +186,7 @@ enum BuiltinFormatKind {
     NameOnly,
     Git,
     ColorWords,
+    SideBySide,
 }
 
 impl BuiltinFormatKind {
@@ -170,6 +198,7 @@ impl BuiltinFormatKind {
             "name-only" => Ok(Self::NameOnly),
             "git" => Ok(Self::Git),
             "color-words" => Ok(Self::ColorWords),
+            "side-by-side" => Ok(Self::SideBySide),
             _ => Err(format!("Invalid builtin diff format: {name}")),
         }
     }
@@ -193,6 +222,8 @@ impl BuiltinFormatKind {
             Some(Self::Git)
         } else if args.color_words {
             Some(Self::ColorWords)
+        } else if args.side_by_side {
+            Some(Self::SideBySide)
         } else {
             None
         }
@@ -201,7 +232,7 @@ impl BuiltinFormatKind {
     fn is_short(self) -> bool {
         match self {
             Self::Summary | Self::Stat | Self::Types | Self::NameOnly => true,
-            Self::Git | Self::ColorWords => false,
+            Self::Git | Self::ColorWords | Self::SideBySide => false,
         }
     }
 
@@ -213,6 +244,7 @@ impl BuiltinFormatKind {
             Self::NameOnly => "name-only",
             Self::Git => "git",
             Self::ColorWords => "color-words",
+            Self::SideBySide => "side-by-side",
         }
     }
 
@@ -224,7 +256,7 @@ impl BuiltinFormatKind {
         match self {
             Self::Summary => Ok(DiffFormat::Summary),
             Self::Stat => {
-                let mut options = DiffStatOptions::default();
+                let mut options = DiffStatOptions::from_settings(settings)?;
                 options.merge_args(args);
                 Ok(DiffFormat::Stat(Box::new(options)))
             }
@@ -240,6 +272,11 @@ impl BuiltinFormatKind {
                 options.merge_args(args);
                 Ok(DiffFormat::ColorWords(Box::new(options)))
             }
+            Self::SideBySide => {
+                let mut options = SideBySideDiffOptions::from_settings(settings)?;
+                options.merge_args(args);
+                Ok(DiffFormat::SideBySide(Box::new(options)))
+            }
         }
     }
 }
@@ -467,6 +504,19 @@ impl<'a> DiffRenderer<'a> {
                         self.conflict_marker_style,
                     )?;
                 }
+                DiffFormat::SideBySide(options) => {
+                    let tree_diff =
+                        from_tree.diff_stream_with_copies(to_tree, matcher, copy_records);
+                    show_side_by_side_diff(
+                        formatter,
+                        store,
+                        tree_diff,
+                        path_converter,
+                        options,
+                        width,
+                        self.conflict_marker_style,
+                    )?;
+                }
                 DiffFormat::Tool(tool) => {
                     match tool.diff_invocation_mode {
                         DiffToolMode::FileByFile => {
@@ -557,7 +607,7 @@ impl<'a> DiffRenderer<'a> {
 }
 
 pub fn get_copy_records<'a>(
-    store: &'a Store,
+    store: &'a Arc<Store>,
     root: &CommitId,
     head: &CommitId,
     matcher: &'a dyn Matcher,
@@ -584,9 +634,18 @@ pub struct LineDiffOptions {
     /// How equivalence of lines is tested.
     pub compare_mode: LineCompareMode,
     // TODO: add --ignore-blank-lines, etc. which aren't mutually exclusive.
+    /// Algorithm used to match up lines.
+    pub algorithm: DiffAlgorithm,
 }
 
 impl LineDiffOptions {
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        Ok(LineDiffOptions {
+            compare_mode: LineCompareMode::default(),
+            algorithm: settings.get("diff.algorithm")?,
+        })
+    }
+
     fn merge_args(&mut self, args: &DiffFormatArgs) {
         self.compare_mode = if args.ignore_all_space {
             LineCompareMode::IgnoreAllSpace
@@ -595,6 +654,9 @@ impl LineDiffOptions {
         } else {
             LineCompareMode::Exact
         };
+        if let Some(algorithm) = args.diff_algorithm {
+            self.algorithm = algorithm;
+        }
     }
 }
 
@@ -617,17 +679,237 @@ fn diff_by_line<'input, T: AsRef<[u8]> + ?Sized + 'input>(
     // blank lines to the preceding range. Maybe it can also be implemented as a
     // post-process (similar to refine_changed_regions()) that expands unchanged
     // regions across blank lines.
+    let algorithm = options.algorithm;
     match options.compare_mode {
-        LineCompareMode::Exact => {
-            Diff::for_tokenizer(inputs, find_line_ranges, CompareBytesExactly)
+        LineCompareMode::Exact => Diff::for_tokenizer_with_algorithm(
+            inputs,
+            find_line_ranges,
+            CompareBytesExactly,
+            algorithm,
+        ),
+        LineCompareMode::IgnoreAllSpace => Diff::for_tokenizer_with_algorithm(
+            inputs,
+            find_line_ranges,
+            CompareBytesIgnoreAllWhitespace,
+            algorithm,
+        ),
+        LineCompareMode::IgnoreSpaceChange => Diff::for_tokenizer_with_algorithm(
+            inputs,
+            find_line_ranges,
+            CompareBytesIgnoreWhitespaceAmount,
+            algorithm,
+        ),
+    }
+}
+
+/// Minimum number of alphanumeric characters in a block of moved lines. Shorter
+/// blocks (e.g. lone closing braces) are likely to match by accident. This is
+/// the same threshold as Git's `--color-moved`.
+const MOVED_BLOCK_MIN_ALNUM_COUNT: usize = 20;
+
+/// Lines removed from one place and added to another within a diff.
+#[derive(Clone, Debug, Default)]
+struct MovedLines {
+    /// Indices of moved lines in the left (old) file contents.
+    removed: HashMap<RepoPathBuf, HashSet<usize>>,
+    /// Indices of moved lines in the right (new) file contents.
+    added: HashMap<RepoPathBuf, HashSet<usize>>,
+}
+
+/// Blocks of removed or added lines with their 0-based line indices.
+struct ChangedLineBlock {
+    path: RepoPathBuf,
+    lines: Vec<(usize, BString)>,
+}
+
+/// Contents of a changed file to look for moved lines in.
+struct MovedLinesFile {
+    path: CopiesTreeDiffEntryPath,
+    left: Arc<[u8]>,
+    right: Arc<[u8]>,
+}
+
+impl MovedLines {
+    /// Finds blocks of removed lines which are added elsewhere in the `files`,
+    /// and vice versa.
+    ///
+    /// Lines are split by the same line diff as the one used for rendering, so
+    /// the indices match the rendered lines.
+    fn detect(files: &[MovedLinesFile], options: &LineDiffOptions) -> Self {
+        let mut removed_blocks = vec![];
+        let mut added_blocks = vec![];
+        for MovedLinesFile { path, left, right } in files {
+            let mut left_index = 0;
+            let mut right_index = 0;
+            for hunk in diff_by_line([&**left, &**right], options).hunks() {
+                let [left_lines, right_lines] = [0, 1].map(|side| {
+                    hunk.contents[side]
+                        .split_inclusive(|b| *b == b'\n')
+                        .collect_vec()
+                });
+                if hunk.kind == DiffHunkKind::Different {
+                    let to_block =
+                        |path: &RepoPath, start: usize, lines: &[&[u8]]| ChangedLineBlock {
+                            path: path.to_owned(),
+                            lines: iter::zip(start.., lines.iter().map(|&line| line.into()))
+                                .collect(),
+                        };
+                    if !left_lines.is_empty() {
+                        removed_blocks.push(to_block(path.source(), left_index, &left_lines));
+                    }
+                    if !right_lines.is_empty() {
+                        added_blocks.push(to_block(path.target(), right_index, &right_lines));
+                    }
+                }
+                left_index += left_lines.len();
+                right_index += right_lines.len();
+            }
         }
-        LineCompareMode::IgnoreAllSpace => {
-            Diff::for_tokenizer(inputs, find_line_ranges, CompareBytesIgnoreAllWhitespace)
+
+        let collect_keys = |blocks: &[ChangedLineBlock]| -> HashSet<BString> {
+            blocks
+                .iter()
+                .flat_map(|block| &block.lines)
+                .map(|(_, line)| moved_line_key(line).into())
+                .collect()
+        };
+        let removed_keys = collect_keys(&removed_blocks);
+        let added_keys = collect_keys(&added_blocks);
+        MovedLines {
+            removed: find_moved_lines(&removed_blocks, &added_keys),
+            added: find_moved_lines(&added_blocks, &removed_keys),
+        }
+    }
+
+    fn for_path(&self, path: &CopiesTreeDiffEntryPath) -> MovedLineIndices<'_> {
+        MovedLineIndices {
+            left: self.removed.get(path.source()),
+            right: self.added.get(path.target()),
+        }
+    }
+}
+
+/// Moved lines in the left and right contents of a single file.
+#[derive(Clone, Copy, Debug, Default)]
+struct MovedLineIndices<'a> {
+    left: Option<&'a HashSet<usize>>,
+    right: Option<&'a HashSet<usize>>,
+}
+
+impl MovedLineIndices<'_> {
+    fn left_contains(&self, index: usize) -> bool {
+        self.left.is_some_and(|indices| indices.contains(&index))
+    }
+
+    fn right_contains(&self, index: usize) -> bool {
+        self.right.is_some_and(|indices| indices.contains(&index))
+    }
+}
+
+/// Materializes the `tree_diff`, and detects the moved lines if `color_moved`
+/// is enabled. In that case, the whole diff is read in advance, and the file
+/// contents are kept in memory for rendering the returned entries.
+fn materialize_with_moved_lines<'a>(
+    store: &'a Store,
+    tree_diff: BoxStream<'a, CopiesTreeDiffEntry>,
+    color_moved: bool,
+    options: &LineDiffOptions,
+    conflict_marker_style: ConflictMarkerStyle,
+) -> BackendResult<(LocalBoxStream<'a, MaterializedTreeDiffEntry>, MovedLines)> {
+    let diff_stream = materialized_diff_stream(store, tree_diff);
+    if !color_moved {
+        return Ok((diff_stream.boxed_local(), MovedLines::default()));
+    }
+    let mut entries: Vec<_> = diff_stream.collect().block_on();
+    let mut files = vec![];
+    for MaterializedTreeDiffEntry { path, values } in &mut entries {
+        // Errors are reported when rendering the entry.
+        let Ok((left_value, right_value)) = values else {
+            continue;
+        };
+        let left = buffer_moved_lines_content(path.source(), left_value, conflict_marker_style)?;
+        let right = buffer_moved_lines_content(path.target(), right_value, conflict_marker_style)?;
+        if let (Some(left), Some(right)) = (left, right) {
+            files.push(MovedLinesFile {
+                path: path.clone(),
+                left,
+                right,
+            });
+        }
+    }
+    let moved_lines = MovedLines::detect(&files, options);
+    Ok((stream::iter(entries).boxed_local(), moved_lines))
+}
+
+/// Returns the contents to look for moved lines in, or `None` for binary files
+/// and non-file entries. File contents are read into memory, and the `value`
+/// is updated to read from the same buffer.
+fn buffer_moved_lines_content(
+    path: &RepoPath,
+    value: &mut MaterializedTreeValue,
+    conflict_marker_style: ConflictMarkerStyle,
+) -> BackendResult<Option<Arc<[u8]>>> {
+    match value {
+        MaterializedTreeValue::Absent => Ok(Some(Arc::from([]))),
+        MaterializedTreeValue::File(file) => {
+            let contents: Arc<[u8]> = file.read_all(path).block_on()?.into();
+            file.reader = Box::pin(io::Cursor::new(contents.clone()));
+            Ok((!is_binary(&contents)).then_some(contents))
+        }
+        MaterializedTreeValue::FileConflict(file) => {
+            let contents = materialize_merge_result_to_bytes(&file.contents, conflict_marker_style);
+            Ok(Some(Vec::from(contents).into()))
         }
-        LineCompareMode::IgnoreSpaceChange => {
-            Diff::for_tokenizer(inputs, find_line_ranges, CompareBytesIgnoreWhitespaceAmount)
+        _ => Ok(None),
+    }
+}
+
+fn moved_line_key(line: &[u8]) -> &[u8] {
+    line.strip_suffix(b"\n").unwrap_or(line)
+}
+
+/// Picks runs of lines in `blocks` which also appear on the other side.
+fn find_moved_lines(
+    blocks: &[ChangedLineBlock],
+    other_keys: &HashSet<BString>,
+) -> HashMap<RepoPathBuf, HashSet<usize>> {
+    let mut moved: HashMap<RepoPathBuf, HashSet<usize>> = HashMap::new();
+    for block in blocks {
+        let runs = block
+            .lines
+            .iter()
+            .chunk_by(|(_, line)| other_keys.contains(moved_line_key(line)));
+        for (found, run) in &runs {
+            if !found {
+                continue;
+            }
+            let run = run.collect_vec();
+            let alnum_count: usize = run
+                .iter()
+                .map(|(_, line)| line.iter().filter(|b| b.is_ascii_alphanumeric()).count())
+                .sum();
+            if alnum_count >= MOVED_BLOCK_MIN_ALNUM_COUNT {
+                moved
+                    .entry(block.path.clone())
+                    .or_default()
+                    .extend(run.iter().map(|(index, _)| *index));
+            }
         }
     }
+    moved
+}
+
+/// Labels output of `write_inner` as moved if `is_moved` is true.
+fn with_moved_label(
+    formatter: &mut dyn Formatter,
+    is_moved: bool,
+    write_inner: impl FnOnce(&mut dyn Formatter) -> io::Result<()>,
+) -> io::Result<()> {
+    if is_moved {
+        formatter.with_label("moved", write_inner)
+    } else {
+        write_inner(formatter)
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -640,6 +922,8 @@ pub struct ColorWordsDiffOptions {
     pub line_diff: LineDiffOptions,
     /// Maximum number of removed/added word alternation to inline.
     pub max_inline_alternation: Option<usize>,
+    /// Whether to highlight moved lines.
+    pub color_moved: bool,
 }
 
 impl ColorWordsDiffOptions {
@@ -658,8 +942,9 @@ impl ColorWordsDiffOptions {
         Ok(ColorWordsDiffOptions {
             conflict: settings.get("diff.color-words.conflict")?,
             context: settings.get("diff.color-words.context")?,
-            line_diff: LineDiffOptions::default(),
+            line_diff: LineDiffOptions::from_settings(settings)?,
             max_inline_alternation,
+            color_moved: settings.get("diff.color-moved")?,
         })
     }
 
@@ -668,12 +953,16 @@ impl ColorWordsDiffOptions {
             self.context = context;
         }
         self.line_diff.merge_args(args);
+        if args.color_moved {
+            self.color_moved = true;
+        }
     }
 }
 
 fn show_color_words_diff_hunks(
     formatter: &mut dyn Formatter,
     [lefts, rights]: [&Merge<BString>; 2],
+    moved: MovedLineIndices,
     options: &ColorWordsDiffOptions,
     conflict_marker_style: ConflictMarkerStyle,
 ) -> io::Result<()> {
@@ -681,7 +970,7 @@ fn show_color_words_diff_hunks(
     let labels = ["removed", "added"];
     if let (Some(left), Some(right)) = (lefts.as_resolved(), rights.as_resolved()) {
         let contents = [left, right].map(BStr::new);
-        show_color_words_resolved_hunks(formatter, contents, line_number, labels, options)?;
+        show_color_words_resolved_hunks(formatter, contents, line_number, labels, moved, options)?;
         return Ok(());
     }
     match options.conflict {
@@ -689,11 +978,18 @@ fn show_color_words_diff_hunks(
             let left = materialize_merge_result_to_bytes(lefts, conflict_marker_style);
             let right = materialize_merge_result_to_bytes(rights, conflict_marker_style);
             let contents = [&left, &right].map(BStr::new);
-            show_color_words_resolved_hunks(formatter, contents, line_number, labels, options)?;
+            show_color_words_resolved_hunks(
+                formatter,
+                contents,
+                line_number,
+                labels,
+                moved,
+                options,
+            )?;
         }
         ConflictDiffMethod::Pair => {
-            let lefts = files::merge(lefts);
-            let rights = files::merge(rights);
+            let lefts = files::merge(lefts, &MergeOptions::default());
+            let rights = files::merge(rights, &MergeOptions::default());
             let contents = [&lefts, &rights];
             show_color_words_conflict_hunks(formatter, contents, line_number, labels, options)?;
         }
@@ -741,7 +1037,14 @@ fn show_color_words_conflict_hunks(
                     (hunk.lefts.as_resolved(), hunk.rights.as_resolved())
                 {
                     let contents = [left, right];
-                    show_color_words_diff_lines(formatter, contents, line_number, labels, options)?
+                    show_color_words_diff_lines(
+                        formatter,
+                        contents,
+                        line_number,
+                        labels,
+                        MovedLineIndices::default(),
+                        options,
+                    )?
                 } else {
                     show_color_words_unresolved_hunk(
                         formatter,
@@ -815,8 +1118,14 @@ fn show_color_words_unresolved_hunk(
             false => [label2, label1],
         };
         // Individual hunk pair may be largely the same, so diff it again.
-        let new_line_number =
-            show_color_words_resolved_hunks(formatter, contents, line_number, labels, options)?;
+        let new_line_number = show_color_words_resolved_hunks(
+            formatter,
+            contents,
+            line_number,
+            labels,
+            MovedLineIndices::default(),
+            options,
+        )?;
         // Take max to assign unique line numbers to trailing hunks. The line
         // numbers can't be real anyway because preceding conflict hunks might
         // have been resolved.
@@ -833,6 +1142,7 @@ fn show_color_words_resolved_hunks(
     contents: [&BStr; 2],
     mut line_number: DiffLineNumber,
     labels: [&str; 2],
+    moved: MovedLineIndices,
     options: &ColorWordsDiffOptions,
 ) -> io::Result<DiffLineNumber> {
     let line_diff = diff_by_line(contents, &options.line_diff);
@@ -865,6 +1175,7 @@ fn show_color_words_resolved_hunks(
                     hunk_contents,
                     line_number,
                     labels,
+                    moved,
                     options,
                 )?;
             }
@@ -927,11 +1238,13 @@ fn show_color_words_context_lines(
         } else {
             let left = left_lines.concat();
             let right = right_lines.concat();
+            // Context lines are matched, so they can't be moved.
             show_color_words_diff_lines(
                 formatter,
                 [&left, &right].map(BStr::new),
                 line_number,
                 labels,
+                MovedLineIndices::default(),
                 options,
             )
         }
@@ -964,8 +1277,10 @@ fn show_color_words_diff_lines(
     contents: [&BStr; 2],
     mut line_number: DiffLineNumber,
     labels: [&str; 2],
+    moved: MovedLineIndices,
     options: &ColorWordsDiffOptions,
 ) -> io::Result<DiffLineNumber> {
+    let to_index = |line_number: u32| usize::try_from(line_number).unwrap() - 1;
     let word_diff_hunks = Diff::by_word(contents).hunks().collect_vec();
     let can_inline = match options.max_inline_alternation {
         None => true,     // unlimited
@@ -979,32 +1294,43 @@ fn show_color_words_diff_lines(
         let mut diff_line_iter =
             DiffLineIterator::with_line_number(word_diff_hunks.iter(), line_number);
         for diff_line in diff_line_iter.by_ref() {
-            show_color_words_line_number(
-                formatter,
-                [
-                    diff_line
-                        .has_left_content()
-                        .then_some(diff_line.line_number.left),
-                    diff_line
-                        .has_right_content()
-                        .then_some(diff_line.line_number.right),
-                ],
-                labels,
-            )?;
-            show_color_words_inline_hunks(formatter, &diff_line.hunks, labels)?;
+            let has_left = diff_line.has_left_content();
+            let has_right = diff_line.has_right_content();
+            let is_moved = match (has_left, has_right) {
+                (true, false) => moved.left_contains(to_index(diff_line.line_number.left)),
+                (false, true) => moved.right_contains(to_index(diff_line.line_number.right)),
+                _ => false,
+            };
+            with_moved_label(formatter, is_moved, |formatter| {
+                show_color_words_line_number(
+                    formatter,
+                    [
+                        has_left.then_some(diff_line.line_number.left),
+                        has_right.then_some(diff_line.line_number.right),
+                    ],
+                    labels,
+                )?;
+                show_color_words_inline_hunks(formatter, &diff_line.hunks, labels)
+            })?;
         }
         line_number = diff_line_iter.next_line_number();
     } else {
         let [left_lines, right_lines] = unzip_diff_hunks_to_lines(&word_diff_hunks);
         let [left_label, right_label] = labels;
         for tokens in &left_lines {
-            show_color_words_line_number(formatter, [Some(line_number.left), None], labels)?;
-            show_color_words_single_sided_line(formatter, tokens, left_label)?;
+            let is_moved = moved.left_contains(to_index(line_number.left));
+            with_moved_label(formatter, is_moved, |formatter| {
+                show_color_words_line_number(formatter, [Some(line_number.left), None], labels)?;
+                show_color_words_single_sided_line(formatter, tokens, left_label)
+            })?;
             line_number.left += 1;
         }
         for tokens in &right_lines {
-            show_color_words_line_number(formatter, [None, Some(line_number.right)], labels)?;
-            show_color_words_single_sided_line(formatter, tokens, right_label)?;
+            let is_moved = moved.right_contains(to_index(line_number.right));
+            with_moved_label(formatter, is_moved, |formatter| {
+                show_color_words_line_number(formatter, [None, Some(line_number.right)], labels)?;
+                show_color_words_single_sided_line(formatter, tokens, right_label)
+            })?;
             line_number.right += 1;
         }
     }
@@ -1124,22 +1450,26 @@ impl FileContent<Merge<BString>> {
     }
 }
 
+fn is_binary(contents: &[u8]) -> bool {
+    // Determine whether it's binary by whether the first 8k bytes contain a null
+    // character; this is the same heuristic used by git as of writing: https://github.com/git/git/blob/eea0e59ffbed6e33d171ace5be13cde9faa41639/xdiff-interface.c#L192-L198
+    const PEEK_SIZE: usize = 8000;
+    let start = &contents[..PEEK_SIZE.min(contents.len())];
+    start.contains(&b'\0')
+}
+
 fn file_content_for_diff<T>(
     path: &RepoPath,
     file: &mut MaterializedFileValue,
     map_resolved: impl FnOnce(BString) -> T,
 ) -> BackendResult<FileContent<T>> {
     // If this is a binary file, don't show the full contents.
-    // Determine whether it's binary by whether the first 8k bytes contain a null
-    // character; this is the same heuristic used by git as of writing: https://github.com/git/git/blob/eea0e59ffbed6e33d171ace5be13cde9faa41639/xdiff-interface.c#L192-L198
-    const PEEK_SIZE: usize = 8000;
     // TODO: currently we look at the whole file, even though for binary files we
     // only need to know the file size. To change that we'd have to extend all
     // the data backends to support getting the length.
     let contents = BString::new(file.read_all(path).block_on()?);
-    let start = &contents[..PEEK_SIZE.min(contents.len())];
     Ok(FileContent {
-        is_binary: start.contains(&b'\0'),
+        is_binary: is_binary(&contents),
         contents: map_resolved(contents),
     })
 }
@@ -1228,6 +1558,52 @@ fn basic_diff_file_type(value: &MaterializedTreeValue) -> &'static str {
     }
 }
 
+/// Describes how the file at a path changed, e.g. "Modified regular file".
+fn modified_diff_description(
+    left_value: &MaterializedTreeValue,
+    right_value: &MaterializedTreeValue,
+) -> String {
+    match (left_value, right_value) {
+        (MaterializedTreeValue::File(left), MaterializedTreeValue::File(right)) => {
+            if left.executable && right.executable {
+                "Modified executable file".to_string()
+            } else if left.executable {
+                "Executable file became non-executable at".to_string()
+            } else if right.executable {
+                "Non-executable file became executable at".to_string()
+            } else {
+                "Modified regular file".to_string()
+            }
+        }
+        (
+            MaterializedTreeValue::FileConflict(_) | MaterializedTreeValue::OtherConflict { .. },
+            MaterializedTreeValue::FileConflict(_) | MaterializedTreeValue::OtherConflict { .. },
+        ) => "Modified conflict in".to_string(),
+        (
+            MaterializedTreeValue::FileConflict(_) | MaterializedTreeValue::OtherConflict { .. },
+            _,
+        ) => "Resolved conflict in".to_string(),
+        (
+            _,
+            MaterializedTreeValue::FileConflict(_) | MaterializedTreeValue::OtherConflict { .. },
+        ) => "Created conflict in".to_string(),
+        (MaterializedTreeValue::Symlink { .. }, MaterializedTreeValue::Symlink { .. }) => {
+            "Symlink target changed at".to_string()
+        }
+        (_, _) => {
+            let left_type = basic_diff_file_type(left_value);
+            let right_type = basic_diff_file_type(right_value);
+            let (first, rest) = left_type.split_at(1);
+            format!(
+                "{}{} became {} at",
+                first.to_ascii_uppercase(),
+                rest,
+                right_type
+            )
+        }
+    }
+}
+
 pub fn show_color_words_diff(
     formatter: &mut dyn Formatter,
     store: &Store,
@@ -1237,9 +1613,16 @@ pub fn show_color_words_diff(
     conflict_marker_style: ConflictMarkerStyle,
 ) -> Result<(), DiffRenderError> {
     let empty_content = || Merge::resolved(BString::default());
-    let mut diff_stream = materialized_diff_stream(store, tree_diff);
+    let (mut diff_stream, moved_lines) = materialize_with_moved_lines(
+        store,
+        tree_diff,
+        options.color_moved,
+        &options.line_diff,
+        conflict_marker_style,
+    )?;
     async {
         while let Some(MaterializedTreeDiffEntry { path, values }) = diff_stream.next().await {
+            let moved = moved_lines.for_path(&path);
             let left_path = path.source();
             let right_path = path.target();
             let left_ui_path = path_converter.format_file_path(left_path);
@@ -1280,55 +1663,13 @@ pub fn show_color_words_diff(
                     show_color_words_diff_hunks(
                         formatter,
                         [&empty_content(), &right_content.contents],
+                        moved,
                         options,
                         conflict_marker_style,
                     )?;
                 }
             } else if right_value.is_present() {
-                let description = match (&left_value, &right_value) {
-                    (MaterializedTreeValue::File(left), MaterializedTreeValue::File(right)) => {
-                        if left.executable && right.executable {
-                            "Modified executable file".to_string()
-                        } else if left.executable {
-                            "Executable file became non-executable at".to_string()
-                        } else if right.executable {
-                            "Non-executable file became executable at".to_string()
-                        } else {
-                            "Modified regular file".to_string()
-                        }
-                    }
-                    (
-                        MaterializedTreeValue::FileConflict(_)
-                        | MaterializedTreeValue::OtherConflict { .. },
-                        MaterializedTreeValue::FileConflict(_)
-                        | MaterializedTreeValue::OtherConflict { .. },
-                    ) => "Modified conflict in".to_string(),
-                    (
-                        MaterializedTreeValue::FileConflict(_)
-                        | MaterializedTreeValue::OtherConflict { .. },
-                        _,
-                    ) => "Resolved conflict in".to_string(),
-                    (
-                        _,
-                        MaterializedTreeValue::FileConflict(_)
-                        | MaterializedTreeValue::OtherConflict { .. },
-                    ) => "Created conflict in".to_string(),
-                    (
-                        MaterializedTreeValue::Symlink { .. },
-                        MaterializedTreeValue::Symlink { .. },
-                    ) => "Symlink target changed at".to_string(),
-                    (_, _) => {
-                        let left_type = basic_diff_file_type(&left_value);
-                        let right_type = basic_diff_file_type(&right_value);
-                        let (first, rest) = left_type.split_at(1);
-                        format!(
-                            "{}{} became {} at",
-                            first.to_ascii_uppercase(),
-                            rest,
-                            right_type
-                        )
-                    }
-                };
+                let description = modified_diff_description(&left_value, &right_value);
                 let left_content = diff_content_as_merge(left_path, left_value)?;
                 let right_content = diff_content_as_merge(right_path, right_value)?;
                 if left_path == right_path {
@@ -1348,6 +1689,7 @@ pub fn show_color_words_diff(
                     show_color_words_diff_hunks(
                         formatter,
                         [&left_content.contents, &right_content.contents],
+                        moved,
                         options,
                         conflict_marker_style,
                     )?;
@@ -1367,6 +1709,7 @@ pub fn show_color_words_diff(
                     show_color_words_diff_hunks(
                         formatter,
                         [&left_content.contents, &empty_content()],
+                        moved,
                         options,
                         conflict_marker_style,
                     )?;
@@ -1542,13 +1885,16 @@ pub struct UnifiedDiffOptions {
     pub context: usize,
     /// How lines are tokenized and compared.
     pub line_diff: LineDiffOptions,
+    /// Whether to highlight moved lines.
+    pub color_moved: bool,
 }
 
 impl UnifiedDiffOptions {
     pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
         Ok(UnifiedDiffOptions {
             context: settings.get("diff.git.context")?,
-            line_diff: LineDiffOptions::default(),
+            line_diff: LineDiffOptions::from_settings(settings)?,
+            color_moved: settings.get("diff.color-moved")?,
         })
     }
 
@@ -1557,6 +1903,9 @@ impl UnifiedDiffOptions {
             self.context = context;
         }
         self.line_diff.merge_args(args);
+        if args.color_moved {
+            self.color_moved = true;
+        }
     }
 }
 
@@ -1609,7 +1958,8 @@ impl<'content> UnifiedDiffHunk<'content> {
 
 fn unified_diff_hunks<'content>(
     contents: [&'content BStr; 2],
-    options: &UnifiedDiffOptions,
+    num_context_lines: usize,
+    line_diff_options: &LineDiffOptions,
 ) -> Vec<UnifiedDiffHunk<'content>> {
     let mut hunks = vec![];
     let mut current_hunk = UnifiedDiffHunk {
@@ -1617,7 +1967,7 @@ fn unified_diff_hunks<'content>(
         right_line_range: 0..0,
         lines: vec![],
     };
-    let diff = diff_by_line(contents, &options.line_diff);
+    let diff = diff_by_line(contents, line_diff_options);
     let mut diff_hunks = diff.hunks().peekable();
     while let Some(hunk) = diff_hunks.next() {
         match hunk.kind {
@@ -1629,10 +1979,10 @@ fn unified_diff_hunks<'content>(
                 let mut lines = right.split_inclusive(|b| *b == b'\n').fuse();
                 if !current_hunk.lines.is_empty() {
                     // The previous hunk line should be either removed/added.
-                    current_hunk.extend_context_lines(lines.by_ref().take(options.context));
+                    current_hunk.extend_context_lines(lines.by_ref().take(num_context_lines));
                 }
                 let before_lines = if diff_hunks.peek().is_some() {
-                    lines.by_ref().rev().take(options.context).collect()
+                    lines.by_ref().rev().take(num_context_lines).collect()
                 } else {
                     vec![] // No more hunks
                 };
@@ -1724,6 +2074,7 @@ where
 fn show_unified_diff_hunks(
     formatter: &mut dyn Formatter,
     contents: [&BStr; 2],
+    moved: MovedLineIndices,
     options: &UnifiedDiffOptions,
 ) -> io::Result<()> {
     // "If the chunk size is 0, the first number is one lower than one would
@@ -1741,7 +2092,7 @@ fn show_unified_diff_hunks(
         }
     }
 
-    for hunk in unified_diff_hunks(contents, options) {
+    for hunk in unified_diff_hunks(contents, options.context, &options.line_diff) {
         writeln!(
             formatter.labeled("hunk_header"),
             "@@ -{},{} +{},{} @@",
@@ -1750,15 +2101,29 @@ fn show_unified_diff_hunks(
             to_line_number(hunk.right_line_range.clone()),
             hunk.right_line_range.len()
         )?;
+        let mut left_index = hunk.left_line_range.start;
+        let mut right_index = hunk.right_line_range.start;
         for (line_type, tokens) in &hunk.lines {
-            let (label, sigil) = match line_type {
-                DiffLineType::Context => ("context", " "),
-                DiffLineType::Removed => ("removed", "-"),
-                DiffLineType::Added => ("added", "+"),
+            let (label, sigil, is_moved) = match line_type {
+                DiffLineType::Context => {
+                    left_index += 1;
+                    right_index += 1;
+                    ("context", " ", false)
+                }
+                DiffLineType::Removed => {
+                    left_index += 1;
+                    ("removed", "-", moved.left_contains(left_index - 1))
+                }
+                DiffLineType::Added => {
+                    right_index += 1;
+                    ("added", "+", moved.right_contains(right_index - 1))
+                }
             };
-            formatter.with_label(label, |formatter| {
-                write!(formatter, "{sigil}")?;
-                show_diff_line_tokens(formatter, tokens)
+            with_moved_label(formatter, is_moved, |formatter| {
+                formatter.with_label(label, |formatter| {
+                    write!(formatter, "{sigil}")?;
+                    show_diff_line_tokens(formatter, tokens)
+                })
             })?;
             let (_, content) = tokens.last().expect("hunk line must not be empty");
             if !content.ends_with(b"\n") {
@@ -1791,9 +2156,16 @@ pub fn show_git_diff(
     options: &UnifiedDiffOptions,
     conflict_marker_style: ConflictMarkerStyle,
 ) -> Result<(), DiffRenderError> {
-    let mut diff_stream = materialized_diff_stream(store, tree_diff);
+    let (mut diff_stream, moved_lines) = materialize_with_moved_lines(
+        store,
+        tree_diff,
+        options.color_moved,
+        &options.line_diff,
+        conflict_marker_style,
+    )?;
     async {
         while let Some(MaterializedTreeDiffEntry { path, values }) = diff_stream.next().await {
+            let moved = moved_lines.for_path(&path);
             let left_path = path.source();
             let right_path = path.target();
             let left_path_string = left_path.as_internal_file_string();
@@ -1871,7 +2243,126 @@ pub fn show_git_diff(
                 show_unified_diff_hunks(
                     formatter,
                     [&left_part.content.contents, &right_part.content.contents].map(BStr::new),
+                    moved,
+                    options,
+                )?;
+            }
+        }
+        Ok(())
+    }
+    .block_on()
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SideBySideDiffOptions {
+    /// Number of context lines to show.
+    pub context: usize,
+    /// How lines are tokenized and compared.
+    pub line_diff: LineDiffOptions,
+}
+
+impl SideBySideDiffOptions {
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        Ok(SideBySideDiffOptions {
+            context: settings.get("diff.side-by-side.context")?,
+            line_diff: LineDiffOptions::from_settings(settings)?,
+        })
+    }
+
+    fn merge_args(&mut self, args: &DiffFormatArgs) {
+        if let Some(context) = args.context {
+            self.context = context;
+        }
+        self.line_diff.merge_args(args);
+    }
+}
+
+/// Line to be rendered in one column of side-by-side diff.
+struct SideBySideLine<'a, 'content> {
+    line_number: usize,
+    tokens: &'a [(DiffTokenType, &'content [u8])],
+    /// Whether the line is removed or added (as opposed to context.)
+    changed: bool,
+}
+
+pub fn show_side_by_side_diff(
+    formatter: &mut dyn Formatter,
+    store: &Store,
+    tree_diff: BoxStream<CopiesTreeDiffEntry>,
+    path_converter: &RepoPathUiConverter,
+    options: &SideBySideDiffOptions,
+    width: usize,
+    conflict_marker_style: ConflictMarkerStyle,
+) -> Result<(), DiffRenderError> {
+    let mut diff_stream = materialized_diff_stream(store, tree_diff);
+    async {
+        while let Some(MaterializedTreeDiffEntry { path, values }) = diff_stream.next().await {
+            let left_path = path.source();
+            let right_path = path.target();
+            let left_ui_path = path_converter.format_file_path(left_path);
+            let right_ui_path = path_converter.format_file_path(right_path);
+            let (left_value, right_value) = values?;
+
+            match (&left_value, &right_value) {
+                (MaterializedTreeValue::AccessDenied(source), _) => {
+                    write!(
+                        formatter.labeled("access-denied"),
+                        "Access denied to {left_ui_path}:"
+                    )?;
+                    writeln!(formatter, " {source}")?;
+                    continue;
+                }
+                (_, MaterializedTreeValue::AccessDenied(source)) => {
+                    write!(
+                        formatter.labeled("access-denied"),
+                        "Access denied to {right_ui_path}:"
+                    )?;
+                    writeln!(formatter, " {source}")?;
+                    continue;
+                }
+                _ => {}
+            }
+            let is_modified = left_value.is_present() && right_value.is_present();
+            if left_value.is_absent() {
+                let description = basic_diff_file_type(&right_value);
+                writeln!(
+                    formatter.labeled("header"),
+                    "Added {description} {right_ui_path}:"
+                )?;
+            } else if right_value.is_present() {
+                let description = modified_diff_description(&left_value, &right_value);
+                if left_path == right_path {
+                    writeln!(
+                        formatter.labeled("header"),
+                        "{description} {right_ui_path}:"
+                    )?;
+                } else {
+                    writeln!(
+                        formatter.labeled("header"),
+                        "{description} {right_ui_path} ({left_ui_path} => {right_ui_path}):"
+                    )?;
+                }
+            } else {
+                let description = basic_diff_file_type(&left_value);
+                writeln!(
+                    formatter.labeled("header"),
+                    "Removed {description} {right_ui_path}:"
+                )?;
+            }
+            let left_content = diff_content(left_path, left_value, conflict_marker_style)?;
+            let right_content = diff_content(right_path, right_value, conflict_marker_style)?;
+            if left_content.is_binary || right_content.is_binary {
+                writeln!(formatter.labeled("binary"), "    (binary)")?;
+            } else if left_content.contents == right_content.contents {
+                if !is_modified {
+                    writeln!(formatter.labeled("empty"), "    (empty)")?;
+                }
+            } else {
+                show_side_by_side_diff_hunks(
+                    formatter,
+                    [&left_content.contents, &right_content.contents].map(BStr::new),
                     options,
+                    width,
                 )?;
             }
         }
@@ -1880,6 +2371,194 @@ pub fn show_git_diff(
     .block_on()
 }
 
+fn show_side_by_side_diff_hunks(
+    formatter: &mut dyn Formatter,
+    contents: [&BStr; 2],
+    options: &SideBySideDiffOptions,
+    width: usize,
+) -> io::Result<()> {
+    const SKIPPED_CONTEXT_LINE: &str = "    ...\n";
+    // Each column consists of 4-digit line number, space, and content. The
+    // content may overflow if the terminal is too narrow.
+    const MIN_CONTENT_WIDTH: usize = 10;
+    let column_width = width.saturating_sub(SIDE_BY_SIDE_SEPARATOR.len()) / 2;
+    let content_width = max(column_width.saturating_sub(5), MIN_CONTENT_WIDTH);
+
+    let hunks = unified_diff_hunks(contents, options.context, &options.line_diff);
+    for (i, hunk) in hunks.iter().enumerate() {
+        if i > 0 || hunk.left_line_range.start > 0 || hunk.right_line_range.start > 0 {
+            write!(formatter, "{SKIPPED_CONTEXT_LINE}")?;
+        }
+        let mut left_number = hunk.left_line_range.start + 1;
+        let mut right_number = hunk.right_line_range.start + 1;
+        let line_groups = hunk
+            .lines
+            .iter()
+            .chunk_by(|(line_type, _)| *line_type == DiffLineType::Context);
+        for (is_context, lines) in &line_groups {
+            let lines = lines.collect_vec();
+            if is_context {
+                for (_, tokens) in lines {
+                    let to_line = |line_number| SideBySideLine {
+                        line_number,
+                        tokens,
+                        changed: false,
+                    };
+                    let row = [Some(to_line(left_number)), Some(to_line(right_number))];
+                    show_side_by_side_row(formatter, row, content_width)?;
+                    left_number += 1;
+                    right_number += 1;
+                }
+            } else {
+                let to_lines = |line_type: DiffLineType, start: usize| {
+                    lines
+                        .iter()
+                        .filter(move |(typ, _)| *typ == line_type)
+                        .enumerate()
+                        .map(move |(offset, (_, tokens))| SideBySideLine {
+                            line_number: start + offset,
+                            tokens,
+                            changed: true,
+                        })
+                };
+                let removed_lines = to_lines(DiffLineType::Removed, left_number).collect_vec();
+                let added_lines = to_lines(DiffLineType::Added, right_number).collect_vec();
+                left_number += removed_lines.len();
+                right_number += added_lines.len();
+                for pair in removed_lines.into_iter().zip_longest(added_lines) {
+                    let (left, right) = pair.map_any(Some, Some).or(None, None);
+                    show_side_by_side_row(formatter, [left, right], content_width)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+const SIDE_BY_SIDE_SEPARATOR: &str = " | ";
+
+/// Prints a pair of lines, wrapping each of them at `content_width`.
+fn show_side_by_side_row<'content>(
+    formatter: &mut dyn Formatter,
+    [left, right]: [Option<SideBySideLine<'_, 'content>>; 2],
+    content_width: usize,
+) -> io::Result<()> {
+    let wrap = |line: &Option<SideBySideLine<'_, 'content>>| -> Vec<DiffTokenVec<'content>> {
+        line.as_ref()
+            .map(|line| wrap_diff_line_tokens(line.tokens, content_width))
+            .unwrap_or_default()
+    };
+    let left_rows = wrap(&left);
+    let right_rows = wrap(&right);
+    let num_rows = max(left_rows.len(), right_rows.len());
+    for i in 0..num_rows {
+        // Left column is padded so the separator is aligned
+        let left_width = if let Some(line) = &left {
+            let line_number = (i == 0).then_some(line.line_number);
+            let tokens = left_rows.get(i).map_or(&[][..], |tokens| tokens);
+            show_side_by_side_column(formatter, line_number, tokens, line.changed, "removed")?
+        } else {
+            0
+        };
+        let padding = (content_width + 5).saturating_sub(left_width);
+        write!(formatter, "{:padding$}", "")?;
+        if let (Some(line), Some(tokens)) = (&right, right_rows.get(i)) {
+            write!(formatter, "{SIDE_BY_SIDE_SEPARATOR}")?;
+            let line_number = (i == 0).then_some(line.line_number);
+            show_side_by_side_column(formatter, line_number, tokens, line.changed, "added")?;
+        } else {
+            write!(formatter, "{}", SIDE_BY_SIDE_SEPARATOR.trim_end())?;
+        }
+        writeln!(formatter)?;
+    }
+    Ok(())
+}
+
+/// Prints line number and content of one column. Returns the display width.
+fn show_side_by_side_column(
+    formatter: &mut dyn Formatter,
+    line_number: Option<usize>,
+    tokens: &[(DiffTokenType, &[u8])],
+    changed: bool,
+    label: &str,
+) -> io::Result<usize> {
+    if let Some(line_number) = line_number {
+        formatter.with_label(label, |formatter| {
+            write!(formatter.labeled("line_number"), "{line_number:>4}")
+        })?;
+        write!(formatter, " ")?;
+    } else {
+        write!(formatter, "     ")?;
+    }
+    if changed {
+        formatter.with_label(label, |formatter| show_diff_line_tokens(formatter, tokens))?;
+    } else {
+        show_diff_line_tokens(formatter, tokens)?;
+    }
+    let content_width: usize = tokens
+        .iter()
+        .map(|(_, content)| String::from_utf8_lossy(content).width())
+        .sum();
+    Ok(5 + content_width)
+}
+
+/// Splits line tokens into rows of the given display width. The trailing
+/// newline is omitted.
+fn wrap_diff_line_tokens<'content>(
+    tokens: &[(DiffTokenType, &'content [u8])],
+    width: usize,
+) -> Vec<DiffTokenVec<'content>> {
+    let mut token_ranges = Vec::with_capacity(tokens.len());
+    let mut text = Vec::new();
+    for (_, content) in tokens {
+        let start = text.len();
+        text.extend_from_slice(content);
+        token_ranges.push(start..text.len());
+    }
+    let line = text.strip_suffix(b"\n").unwrap_or(&text);
+    // Keep indentation on the first row instead of wrapping at it.
+    let indent_len = line.iter().take_while(|&&b| b == b' ').count();
+    let mut rows = text_util::wrap_bytes_to_ranges(
+        &line[indent_len..],
+        max(width.saturating_sub(indent_len), 1),
+    )
+    .into_iter()
+    .map(|row| row.start + indent_len..row.end + indent_len)
+    .collect_vec();
+    rows[0].start = 0;
+    rows.into_iter()
+        .flat_map(|row| split_wide_row(line, row, width))
+        .map(|row| {
+            iter::zip(tokens, &token_ranges)
+                .filter_map(|(&(token_type, content), range)| {
+                    let start = max(range.start, row.start) - range.start;
+                    let end = min(range.end, row.end) - range.start;
+                    (start < end).then(|| (token_type, &content[start..end]))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Splits `row` of `text` at character boundaries if it contains a word wider
+/// than `width`.
+fn split_wide_row(text: &[u8], row: Range<usize>, width: usize) -> Vec<Range<usize>> {
+    let mut rows = vec![];
+    let mut row_start = row.start;
+    let mut row_width = 0;
+    for (start, _end, c) in text[row.clone()].char_indices() {
+        let char_width = c.width().unwrap_or(0);
+        if row_width + char_width > width && row_start < row.start + start {
+            rows.push(row_start..row.start + start);
+            row_start = row.start + start;
+            row_width = 0;
+        }
+        row_width += char_width;
+    }
+    rows.push(row_start..row.end);
+    rows
+}
+
 #[instrument(skip_all)]
 pub fn show_diff_summary(
     formatter: &mut dyn Formatter,
@@ -1929,6 +2608,12 @@ pub struct DiffStatOptions {
 }
 
 impl DiffStatOptions {
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        Ok(DiffStatOptions {
+            line_diff: LineDiffOptions::from_settings(settings)?,
+        })
+    }
+
     fn merge_args(&mut self, args: &DiffFormatArgs) {
         self.line_diff.merge_args(args);
     }
@@ -14,6 +14,7 @@
 
 //! Git utilities shared by various commands.
 
+use std::collections::HashSet;
 use std::error;
 use std::io;
 use std::io::Read as _;
@@ -30,22 +31,34 @@ use crossterm::terminal::Clear;
 use crossterm::terminal::ClearType;
 use indoc::writedoc;
 use itertools::Itertools as _;
+use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::fmt_util::binary_prefix;
 use jj_lib::git;
 use jj_lib::git::FailedRefExportReason;
 use jj_lib::git::GitExportStats;
 use jj_lib::git::GitImportStats;
 use jj_lib::git::GitRefKind;
+use jj_lib::git::GitWorktree;
+use jj_lib::git_submodule;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
+use jj_lib::ref_name::RemoteName;
+use jj_lib::ref_name::RemoteNameBuf;
 use jj_lib::ref_name::RemoteRefSymbol;
+use jj_lib::ref_name::WorkspaceName;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
+use jj_lib::settings::GitSettings;
+use jj_lib::settings::UserSettings;
+use jj_lib::store::Store;
+use jj_lib::str_util::StringPattern;
 use jj_lib::workspace::Workspace;
 use unicode_width::UnicodeWidthStr as _;
 
 use crate::cleanup_guard::CleanupGuard;
 use crate::command_error::cli_error;
+use crate::command_error::config_error;
+use crate::command_error::print_error_sources;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::formatter::Formatter;
@@ -53,21 +66,91 @@ use crate::ui::ProgressOutput;
 use crate::ui::Ui;
 
 pub fn is_colocated_git_workspace(workspace: &Workspace, repo: &ReadonlyRepo) -> bool {
-    let Ok(git_backend) = git::get_git_backend(repo.store()) else {
-        return false;
-    };
-    let Some(git_workdir) = git_backend.git_workdir() else {
-        return false; // Bare repository
-    };
-    if git_workdir == workspace.workspace_root() {
-        return true;
+    colocated_git_worktree(workspace, repo).is_some()
+}
+
+/// Returns the Git worktree the working copy of the workspace is shared with.
+pub fn colocated_git_worktree(workspace: &Workspace, repo: &ReadonlyRepo) -> Option<GitWorktree> {
+    let git_backend = git::get_git_backend(repo.store()).ok()?;
+    if let Some(git_workdir) = git_backend.git_workdir() {
+        if git_workdir == workspace.workspace_root() {
+            return Some(GitWorktree::Main);
+        }
+        // Colocated workspace should have ".git" directory, file, or symlink.
+        // Compare its parent as the git_workdir might be resolved from the real
+        // ".git" path.
+        if let Ok(dot_git_path) = dunce::canonicalize(workspace.workspace_root().join(".git")) {
+            if dunce::canonicalize(git_workdir).ok().as_deref() == dot_git_path.parent() {
+                return Some(GitWorktree::Main);
+            }
+        }
     }
-    // Colocated workspace should have ".git" directory, file, or symlink. Compare
-    // its parent as the git_workdir might be resolved from the real ".git" path.
-    let Ok(dot_git_path) = dunce::canonicalize(workspace.workspace_root().join(".git")) else {
-        return false;
-    };
-    dunce::canonicalize(git_workdir).ok().as_deref() == dot_git_path.parent()
+    // Secondary workspaces are backed by linked worktrees named after them.
+    let name = workspace.workspace_name();
+    let worktree_root = git::linked_worktree_root(repo.store(), name).ok()??;
+    let worktree_root = dunce::canonicalize(worktree_root).ok()?;
+    let workspace_root = dunce::canonicalize(workspace.workspace_root()).ok()?;
+    (worktree_root == workspace_root).then(|| GitWorktree::Linked(name.to_owned()))
+}
+
+/// Creates a linked Git worktree for the new workspace. Failure is reported as
+/// a warning since the workspace is still usable without Git.
+pub fn add_git_worktree(
+    ui: &Ui,
+    repo: &ReadonlyRepo,
+    name: &WorkspaceName,
+    workspace_root: &Path,
+) -> Result<(), CommandError> {
+    if let Err(err) = git::add_worktree(repo.store(), name, workspace_root) {
+        writeln!(
+            ui.warning_default(),
+            "Failed to create Git worktree for workspace {name}: {err}",
+            name = name.as_symbol()
+        )?;
+    }
+    Ok(())
+}
+
+/// Removes the linked Git worktrees of the forgotten workspaces.
+pub fn remove_git_worktrees(
+    repo: &ReadonlyRepo,
+    names: &[&WorkspaceName],
+) -> Result<(), CommandError> {
+    if git::get_git_backend(repo.store()).is_err() {
+        return Ok(());
+    }
+    for name in names {
+        git::remove_worktree(repo.store(), name)?;
+    }
+    Ok(())
+}
+
+/// Renames the linked Git worktree of the renamed workspace, if any.
+pub fn rename_git_worktree(
+    repo: &ReadonlyRepo,
+    old_name: &WorkspaceName,
+    new_name: &WorkspaceName,
+    workspace_root: &Path,
+) -> Result<(), CommandError> {
+    if git::get_git_backend(repo.store()).is_err() {
+        return Ok(());
+    }
+    git::rename_worktree(repo.store(), old_name, new_name, workspace_root)?;
+    Ok(())
+}
+
+/// Points the linked Git worktree of the moved workspace, if any, to the new
+/// location.
+pub fn relocate_git_worktree(
+    repo: &ReadonlyRepo,
+    name: &WorkspaceName,
+    new_workspace_root: &Path,
+) -> Result<(), CommandError> {
+    if git::get_git_backend(repo.store()).is_err() {
+        return Ok(());
+    }
+    git::relocate_worktree(repo.store(), name, new_workspace_root)?;
+    Ok(())
 }
 
 /// Parses user-specified remote URL or path to absolute form.
@@ -572,6 +655,83 @@ enum ImportStatus {
     Updated,
 }
 
+/// Returns the remote which relative submodule URLs are resolved against when
+/// checking out submodules. This is the first remote in `git.fetch`, or the
+/// only remote if `git.fetch` isn't set.
+pub fn get_submodule_remote(
+    settings: &UserSettings,
+    store: &Store,
+) -> Result<Option<RemoteNameBuf>, CommandError> {
+    const KEY: &str = "git.fetch";
+    let first_remote = if let Ok(remotes) = settings.get::<Vec<String>>(KEY) {
+        remotes.into_iter().next()
+    } else {
+        settings.get_string(KEY).optional()?
+    };
+    if let Some(remote) = first_remote {
+        let pattern = StringPattern::parse(&remote).map_err(config_error)?;
+        return Ok(pattern.as_exact().map(RemoteNameBuf::from));
+    }
+    let mut names = git::get_all_remote_names(store)?;
+    Ok(match names.len() {
+        1 => names.pop(),
+        _ => Some(RemoteName::new("origin").to_owned()),
+    })
+}
+
+/// Fetches the commits of the Git submodules in the remote bookmarks updated
+/// by `import_stats` if `git.update-submodules` is enabled. Failures are
+/// reported as warnings.
+pub fn fetch_git_submodules(
+    ui: &Ui,
+    repo: &dyn Repo,
+    import_stats: &GitImportStats,
+    git_settings: &GitSettings,
+) -> Result<(), CommandError> {
+    use std::error::Error as _;
+
+    if !git_settings.update_submodules {
+        return Ok(());
+    }
+
+    let mut visited_commit_ids = HashSet::new();
+    let mut updated_paths = HashSet::new();
+    for (symbol, (_, new_target)) in &import_stats.changed_remote_bookmarks {
+        for commit_id in new_target.added_ids() {
+            if !visited_commit_ids.insert(commit_id) {
+                continue;
+            }
+            let tree = repo.store().get_commit(commit_id)?.tree()?;
+            let stats = match git_submodule::fetch_submodules(
+                repo,
+                &tree,
+                Some(&symbol.remote),
+                git_settings,
+            ) {
+                Ok(stats) => stats,
+                Err(err) => {
+                    writeln!(ui.warning_default(), "Failed to fetch submodules: {err}")?;
+                    print_error_sources(ui, err.source())?;
+                    continue;
+                }
+            };
+            for (path, err) in &stats.failed {
+                writeln!(
+                    ui.warning_default(),
+                    "Failed to fetch submodule {}: {err}",
+                    path.as_internal_file_string()
+                )?;
+                print_error_sources(ui, err.source())?;
+            }
+            updated_paths.extend(stats.updated_paths);
+        }
+    }
+    if !updated_paths.is_empty() {
+        writeln!(ui.status(), "Fetched {} submodules", updated_paths.len())?;
+    }
+    Ok(())
+}
+
 pub fn print_git_export_stats(ui: &Ui, stats: &GitExportStats) -> Result<(), std::io::Error> {
     if !stats.failed_bookmarks.is_empty() {
         writeln!(ui.warning_default(), "Failed to export some bookmarks:")?;
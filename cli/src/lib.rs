@@ -31,12 +31,51 @@ pub mod git_util;
 /// A stub module that provides a no-op implementation of some of the functions
 /// in the `git` module.
 pub mod git_util {
+    use std::path::Path;
+
+    use jj_lib::ref_name::WorkspaceName;
     use jj_lib::repo::ReadonlyRepo;
     use jj_lib::workspace::Workspace;
 
+    use crate::command_error::CommandError;
+    use crate::ui::Ui;
+
     pub fn is_colocated_git_workspace(_workspace: &Workspace, _repo: &ReadonlyRepo) -> bool {
         false
     }
+
+    pub fn add_git_worktree(
+        _ui: &Ui,
+        _repo: &ReadonlyRepo,
+        _name: &WorkspaceName,
+        _workspace_root: &Path,
+    ) -> Result<(), CommandError> {
+        Ok(())
+    }
+
+    pub fn remove_git_worktrees(
+        _repo: &ReadonlyRepo,
+        _names: &[&WorkspaceName],
+    ) -> Result<(), CommandError> {
+        Ok(())
+    }
+
+    pub fn rename_git_worktree(
+        _repo: &ReadonlyRepo,
+        _old_name: &WorkspaceName,
+        _new_name: &WorkspaceName,
+        _workspace_root: &Path,
+    ) -> Result<(), CommandError> {
+        Ok(())
+    }
+
+    pub fn relocate_git_worktree(
+        _repo: &ReadonlyRepo,
+        _name: &WorkspaceName,
+        _new_workspace_root: &Path,
+    ) -> Result<(), CommandError> {
+        Ok(())
+    }
 }
 pub mod graphlog;
 pub mod merge_tools;
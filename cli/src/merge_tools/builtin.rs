@@ -19,6 +19,7 @@ use jj_lib::copies::CopyRecords;
 use jj_lib::diff::Diff;
 use jj_lib::diff::DiffHunkKind;
 use jj_lib::files;
+use jj_lib::files::MergeOptions;
 use jj_lib::files::MergeResult;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::Merge;
@@ -33,6 +34,7 @@ use pollster::FutureExt as _;
 use thiserror::Error;
 
 use super::MergeToolFile;
+use crate::description_util::TempTextEditError;
 
 #[derive(Debug, Error)]
 pub enum BuiltinToolError {
@@ -47,6 +49,10 @@ pub enum BuiltinToolError {
     Unimplemented { item: &'static str, id: String },
     #[error("Backend error")]
     BackendError(#[from] jj_lib::backend::BackendError),
+    #[error("Failed to prompt for changes")]
+    Prompt(#[source] std::io::Error),
+    #[error(transparent)]
+    EditHunk(#[from] TempTextEditError),
 }
 
 #[derive(Clone, Debug)]
@@ -259,7 +265,7 @@ fn make_diff_sections(
     Ok(sections)
 }
 
-async fn make_diff_files(
+pub(super) async fn make_diff_files(
     store: &Arc<Store>,
     tree_diff: BoxStream<'_, CopiesTreeDiffEntry>,
     conflict_marker_style: ConflictMarkerStyle,
@@ -363,7 +369,7 @@ async fn make_diff_files(
     Ok((changed_files, files))
 }
 
-fn apply_diff_builtin(
+pub(super) fn apply_diff_builtin(
     store: &Arc<Store>,
     left_tree: &MergedTree,
     right_tree: &MergedTree,
@@ -573,6 +579,7 @@ fn make_merge_sections(
 
 fn make_merge_file(
     merge_tool_file: &MergeToolFile,
+    options: &MergeOptions,
 ) -> Result<scm_record::File<'static>, BuiltinToolError> {
     let file = &merge_tool_file.file;
     let file_mode = if file.executable.expect("should have been resolved") {
@@ -582,7 +589,7 @@ fn make_merge_file(
     };
     // TODO: Maybe we should test binary contents here, and generate per-file
     // Binary section to select either "our" or "their" file.
-    let merge_result = files::merge_hunks(&file.contents);
+    let merge_result = files::merge_hunks(&file.contents, options);
     let sections = make_merge_sections(merge_result)?;
     Ok(scm_record::File {
         old_path: None,
@@ -605,7 +612,10 @@ pub fn edit_merge_builtin(
     let recorder = scm_record::Recorder::new(
         scm_record::RecordState {
             is_read_only: false,
-            files: merge_tool_files.iter().map(make_merge_file).try_collect()?,
+            files: merge_tool_files
+                .iter()
+                .map(|file| make_merge_file(file, tree.store().merge_options()))
+                .try_collect()?,
             commits: Default::default(),
         },
         &mut input,
@@ -1294,7 +1304,7 @@ mod tests {
         let content = extract_as_single_hunk(&merge, store, path)
             .block_on()
             .unwrap();
-        let merge_result = files::merge_hunks(&content);
+        let merge_result = files::merge_hunks(&content, &MergeOptions::default());
         let sections = make_merge_sections(merge_result).unwrap();
         insta::assert_debug_snapshot!(sections, @r#"
         [
@@ -15,6 +15,7 @@
 mod builtin;
 mod diff_working_copies;
 mod external;
+mod prompt;
 
 use std::sync::Arc;
 
@@ -54,10 +55,14 @@ pub use self::external::invoke_external_diff;
 pub use self::external::DiffToolMode;
 pub use self::external::ExternalMergeTool;
 use self::external::ExternalToolError;
+use self::prompt::edit_diff_prompt;
 use crate::config::CommandNameAndArgs;
+use crate::description_util::TextEditor;
 use crate::ui::Ui;
 
 const BUILTIN_EDITOR_NAME: &str = ":builtin";
+/// Name of the builtin diff editor that prompts for each hunk.
+pub const PROMPT_EDITOR_NAME: &str = ":prompt";
 const OURS_TOOL_NAME: &str = ":ours";
 const THEIRS_TOOL_NAME: &str = ":theirs";
 
@@ -163,6 +168,7 @@ impl MergeTool {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DiffTool {
     Builtin,
+    Prompt,
     // Boxed because ExternalMergeTool is big compared to the Builtin variant.
     External(Box<ExternalMergeTool>),
 }
@@ -180,6 +186,7 @@ impl DiffTool {
     ) -> Result<Option<Self>, MergeToolConfigError> {
         match name {
             BUILTIN_EDITOR_NAME => Ok(Some(DiffTool::Builtin)),
+            PROMPT_EDITOR_NAME => Ok(Some(DiffTool::Prompt)),
             _ => Ok(get_external_tool_config(settings, name)?.map(DiffTool::external)),
         }
     }
@@ -229,6 +236,7 @@ pub struct DiffEditor {
     base_ignores: Arc<GitIgnoreFile>,
     use_instructions: bool,
     conflict_marker_style: ConflictMarkerStyle,
+    text_editor: TextEditor,
 }
 
 impl DiffEditor {
@@ -273,6 +281,7 @@ impl DiffEditor {
             base_ignores,
             use_instructions: settings.get_bool("ui.diff-instructions")?,
             conflict_marker_style,
+            text_editor: TextEditor::from_settings(settings)?,
         })
     }
 
@@ -286,6 +295,7 @@ impl DiffEditor {
     // DiffSelector::select() should also be updated.
     pub fn edit(
         &self,
+        ui: &Ui,
         left_tree: &MergedTree,
         right_tree: &MergedTree,
         matcher: &dyn Matcher,
@@ -298,6 +308,15 @@ impl DiffEditor {
                         .map_err(Box::new)?,
                 )
             }
+            DiffTool::Prompt => Ok(edit_diff_prompt(
+                ui,
+                &self.text_editor,
+                left_tree,
+                right_tree,
+                matcher,
+                self.conflict_marker_style,
+            )
+            .map_err(Box::new)?),
             DiffTool::External(editor) => {
                 let instructions = self.use_instructions.then(format_instructions);
                 edit_diff_external(
@@ -0,0 +1,917 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Line-oriented diff editor modeled after `git add --patch`.
+
+use std::io;
+use std::io::Write;
+use std::ops::Range;
+
+use itertools::Itertools as _;
+use jj_lib::backend::MergedTreeId;
+use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::copies::CopyRecords;
+use jj_lib::matchers::Matcher;
+use jj_lib::merged_tree::MergedTree;
+use pollster::FutureExt as _;
+
+use super::builtin::apply_diff_builtin;
+use super::builtin::make_diff_files;
+use super::builtin::BuiltinToolError;
+use crate::description_util::TempTextEditError;
+use crate::description_util::TextEditor;
+use crate::ui::Ui;
+
+const NUM_CONTEXT_LINES: usize = 3;
+
+const EDIT_HUNK_INSTRUCTIONS: &str = "\
+# ---
+# To remove '-' lines, make them ' ' lines (context).
+# To remove '+' lines, delete them.
+# Lines starting with # will be removed.
+# Other modifications are not supported.
+";
+
+/// Selects changes between `left_tree` and `right_tree` by asking about each
+/// hunk in turn.
+pub fn edit_diff_prompt(
+    ui: &Ui,
+    text_editor: &TextEditor,
+    left_tree: &MergedTree,
+    right_tree: &MergedTree,
+    matcher: &dyn Matcher,
+    conflict_marker_style: ConflictMarkerStyle,
+) -> Result<MergedTreeId, BuiltinToolError> {
+    let store = left_tree.store().clone();
+    // TODO: handle copy tracking
+    let copy_records = CopyRecords::default();
+    let tree_diff = left_tree.diff_stream_with_copies(right_tree, matcher, &copy_records);
+    let (changed_files, mut files) =
+        make_diff_files(&store, tree_diff, conflict_marker_style).block_on()?;
+    let mut prompter = HunkPrompter {
+        io: ui,
+        edit_hunk: |text: &str| text_editor.edit_str(text, Some(".diff")),
+    };
+    prompter.select_files(&mut files)?;
+    apply_diff_builtin(
+        &store,
+        left_tree,
+        right_tree,
+        changed_files,
+        &files,
+        conflict_marker_style,
+    )
+    .map_err(BuiltinToolError::BackendError)
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum HunkKind {
+    /// Changed lines of a file that exists on both sides.
+    Text,
+    /// Change of the executable bit or the file type.
+    FileMode,
+    /// Change of a binary file.
+    Binary,
+    /// Creation or deletion of the file. All sections are selected together.
+    WholeFile,
+}
+
+/// Unit of selection, which covers a range of file sections.
+#[derive(Clone, Debug)]
+struct Hunk {
+    kind: HunkKind,
+    sections: Range<usize>,
+}
+
+/// Line to be displayed as part of a text hunk.
+#[derive(Clone, Copy, Debug)]
+enum HunkLine<'a> {
+    Context(&'a str),
+    Changed { section: usize, line: usize },
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Choice {
+    Yes,
+    No,
+    All,
+    Done,
+    Split,
+    Edit,
+    Quit,
+    Help,
+}
+
+impl Choice {
+    fn key(self) -> &'static str {
+        match self {
+            Choice::Yes => "y",
+            Choice::No => "n",
+            Choice::All => "a",
+            Choice::Done => "d",
+            Choice::Split => "s",
+            Choice::Edit => "e",
+            Choice::Quit => "q",
+            Choice::Help => "?",
+        }
+    }
+
+    fn help(self) -> &'static str {
+        match self {
+            Choice::Yes => "select this hunk",
+            Choice::No => "do not select this hunk",
+            Choice::All => "select this hunk and all later hunks in the file",
+            Choice::Done => "do not select this hunk or any of the later hunks in the file",
+            Choice::Split => "split the current hunk into smaller hunks",
+            Choice::Edit => "manually edit the current hunk",
+            Choice::Quit => "quit; do not select this hunk or any of the remaining ones",
+            Choice::Help => "print help",
+        }
+    }
+}
+
+/// Terminal interaction of the prompt, which can be replaced in tests.
+trait PromptIo {
+    /// Returns the writer to show the hunks and messages.
+    fn output(&self) -> Box<dyn Write + '_>;
+
+    /// Asks until one of the `choices` is entered, and returns its index.
+    fn prompt_choice(&self, prompt: &str, choices: &[&str]) -> io::Result<usize>;
+}
+
+impl PromptIo for Ui {
+    fn output(&self) -> Box<dyn Write + '_> {
+        Box::new(self.stderr())
+    }
+
+    fn prompt_choice(&self, prompt: &str, choices: &[&str]) -> io::Result<usize> {
+        Ui::prompt_choice(self, prompt, choices, None)
+    }
+}
+
+struct HunkPrompter<'a, E> {
+    io: &'a dyn PromptIo,
+    edit_hunk: E,
+}
+
+impl<E> HunkPrompter<'_, E>
+where
+    E: FnMut(&str) -> Result<String, TempTextEditError>,
+{
+    /// Updates the selection state of the `files` according to the answers.
+    fn select_files(
+        &mut self,
+        files: &mut [scm_record::File<'static>],
+    ) -> Result<(), BuiltinToolError> {
+        for file in files {
+            if !self.select_hunks(file).map_err(BuiltinToolError::Prompt)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Asks about each hunk of the `file`. Returns `false` if the user wants
+    /// to quit.
+    fn select_hunks(&mut self, file: &mut scm_record::File<'static>) -> io::Result<bool> {
+        let mut hunks = build_hunks(file);
+        if !hunks.is_empty() {
+            write_file_header(&mut *self.io.output(), file)?;
+        }
+        let mut index = 0;
+        while index < hunks.len() {
+            let hunk = hunks[index].clone();
+            write_hunk(&mut *self.io.output(), file, &hunk)?;
+            let split_hunks = split_hunk(file, &hunk);
+            let choices = [
+                Some(Choice::Yes),
+                Some(Choice::No),
+                Some(Choice::All),
+                Some(Choice::Done),
+                (split_hunks.len() > 1).then_some(Choice::Split),
+                (hunk.kind == HunkKind::Text).then_some(Choice::Edit),
+                Some(Choice::Quit),
+                Some(Choice::Help),
+            ]
+            .into_iter()
+            .flatten()
+            .collect_vec();
+            let keys = choices.iter().map(|choice| choice.key()).collect_vec();
+            let prompt = format!(
+                "({}/{}) {} [{}]",
+                index + 1,
+                hunks.len(),
+                hunk_question(file, &hunk),
+                keys.join(",")
+            );
+            let choice = match self.io.prompt_choice(&prompt, &keys) {
+                Ok(choice_index) => choices[choice_index],
+                // Treat EOF as quit.
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                    writeln!(self.io.output())?;
+                    return Ok(false);
+                }
+                Err(err) => return Err(err),
+            };
+            match choice {
+                Choice::Yes => {
+                    set_hunk_checked(file, &hunk, true);
+                    index += 1;
+                }
+                Choice::No => {
+                    set_hunk_checked(file, &hunk, false);
+                    index += 1;
+                }
+                Choice::All => {
+                    for hunk in &hunks[index..] {
+                        set_hunk_checked(file, hunk, true);
+                    }
+                    index = hunks.len();
+                }
+                Choice::Done => {
+                    for hunk in &hunks[index..] {
+                        set_hunk_checked(file, hunk, false);
+                    }
+                    index = hunks.len();
+                }
+                Choice::Split => {
+                    writeln!(self.io.output(), "Split into {} hunks.", split_hunks.len())?;
+                    hunks.splice(index..index + 1, split_hunks);
+                }
+                Choice::Edit => {
+                    if self.edit_hunk(file, &hunk)? {
+                        index += 1;
+                    }
+                }
+                Choice::Quit => return Ok(false),
+                Choice::Help => {
+                    let mut output = self.io.output();
+                    for choice in &choices {
+                        writeln!(output, "{} - {}", choice.key(), choice.help())?;
+                    }
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// Lets the user edit the text `hunk`, and selects the lines that were
+    /// kept. Returns `false` if the edited hunk couldn't be applied.
+    fn edit_hunk(&mut self, file: &mut scm_record::File<'static>, hunk: &Hunk) -> io::Result<bool> {
+        let mut text = "# Manual hunk edit mode -- see bottom for a quick guide.\n".to_owned();
+        let (header, lines) = text_hunk_lines(file, hunk);
+        text.push_str(&header);
+        text.push('\n');
+        for line in &lines {
+            text.push_str(&format_hunk_line(file, *line));
+        }
+        text.push_str(EDIT_HUNK_INSTRUCTIONS);
+        let edited = match (self.edit_hunk)(&text) {
+            Ok(edited) => edited,
+            Err(err) => {
+                writeln!(self.io.output(), "{err}")?;
+                return Ok(false);
+            }
+        };
+        let Some(selection) = parse_edited_hunk(file, &lines, &edited) else {
+            writeln!(
+                self.io.output(),
+                "Your edited hunk does not match the original hunk; the edit was ignored."
+            )?;
+            return Ok(false);
+        };
+        for ((section, line), is_checked) in selection {
+            if let scm_record::Section::Changed { lines } = &mut file.sections[section] {
+                lines[line].is_checked = is_checked;
+            }
+        }
+        Ok(true)
+    }
+}
+
+fn is_whole_file_change(file: &scm_record::File) -> bool {
+    file.file_mode == scm_record::FileMode::Absent
+        || file.sections.iter().any(|section| {
+            matches!(
+                section,
+                scm_record::Section::FileMode {
+                    mode: scm_record::FileMode::Absent,
+                    ..
+                }
+            )
+        })
+}
+
+/// Groups the sections of the `file` into hunks. Changed sections separated
+/// by only a few unchanged lines are put into the same hunk.
+fn build_hunks(file: &scm_record::File) -> Vec<Hunk> {
+    if file.sections.is_empty() {
+        return vec![];
+    }
+    if is_whole_file_change(file) {
+        return vec![Hunk {
+            kind: HunkKind::WholeFile,
+            sections: 0..file.sections.len(),
+        }];
+    }
+    let mut hunks: Vec<Hunk> = vec![];
+    for (index, section) in file.sections.iter().enumerate() {
+        let kind = match section {
+            scm_record::Section::Unchanged { .. } => continue,
+            scm_record::Section::Changed { .. } => HunkKind::Text,
+            scm_record::Section::FileMode { .. } => HunkKind::FileMode,
+            scm_record::Section::Binary { .. } => HunkKind::Binary,
+        };
+        match hunks.last_mut() {
+            Some(last)
+                if kind == HunkKind::Text
+                    && last.kind == HunkKind::Text
+                    && num_unchanged_lines(&file.sections[last.sections.end..index])
+                        <= 2 * NUM_CONTEXT_LINES =>
+            {
+                last.sections.end = index + 1;
+            }
+            _ => hunks.push(Hunk {
+                kind,
+                sections: index..index + 1,
+            }),
+        }
+    }
+    hunks
+}
+
+fn num_unchanged_lines(sections: &[scm_record::Section]) -> usize {
+    sections
+        .iter()
+        .map(|section| match section {
+            scm_record::Section::Unchanged { lines } => lines.len(),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Splits the text `hunk` into hunks of single changed sections.
+fn split_hunk(file: &scm_record::File, hunk: &Hunk) -> Vec<Hunk> {
+    if hunk.kind != HunkKind::Text {
+        return vec![];
+    }
+    hunk.sections
+        .clone()
+        .filter(|&index| matches!(file.sections[index], scm_record::Section::Changed { .. }))
+        .map(|index| Hunk {
+            kind: HunkKind::Text,
+            sections: index..index + 1,
+        })
+        .collect()
+}
+
+fn set_hunk_checked(file: &mut scm_record::File, hunk: &Hunk, checked: bool) {
+    for section in &mut file.sections[hunk.sections.clone()] {
+        match section {
+            scm_record::Section::Unchanged { .. } => {}
+            scm_record::Section::Changed { lines } => {
+                for line in lines {
+                    line.is_checked = checked;
+                }
+            }
+            scm_record::Section::FileMode { is_checked, .. }
+            | scm_record::Section::Binary { is_checked, .. } => *is_checked = checked,
+        }
+    }
+}
+
+fn hunk_question(file: &scm_record::File, hunk: &Hunk) -> &'static str {
+    match hunk.kind {
+        HunkKind::Text => "Select this hunk",
+        HunkKind::FileMode => "Select mode change",
+        HunkKind::Binary => "Select binary change",
+        HunkKind::WholeFile if file.file_mode == scm_record::FileMode::Absent => {
+            "Select creation of this file"
+        }
+        HunkKind::WholeFile => "Select deletion of this file",
+    }
+}
+
+fn write_file_header(output: &mut dyn Write, file: &scm_record::File) -> io::Result<()> {
+    let path = file.path.display();
+    if file.file_mode == scm_record::FileMode::Absent {
+        writeln!(output, "--- /dev/null")?;
+    } else {
+        writeln!(output, "--- a/{path}")?;
+    }
+    if is_whole_file_change(file) && file.file_mode != scm_record::FileMode::Absent {
+        writeln!(output, "+++ /dev/null")?;
+    } else {
+        writeln!(output, "+++ b/{path}")?;
+    }
+    Ok(())
+}
+
+fn write_hunk(output: &mut dyn Write, file: &scm_record::File, hunk: &Hunk) -> io::Result<()> {
+    match hunk.kind {
+        HunkKind::Text => {
+            let (header, lines) = text_hunk_lines(file, hunk);
+            writeln!(output, "{header}")?;
+            for line in lines {
+                write!(output, "{}", format_hunk_line(file, line))?;
+            }
+        }
+        HunkKind::FileMode | HunkKind::Binary | HunkKind::WholeFile => {
+            for (index, section) in file.sections[hunk.sections.clone()].iter().enumerate() {
+                let index = hunk.sections.start + index;
+                match section {
+                    scm_record::Section::Unchanged { .. } => {}
+                    scm_record::Section::Changed { lines } => {
+                        for line in 0..lines.len() {
+                            let line = HunkLine::Changed {
+                                section: index,
+                                line,
+                            };
+                            write!(output, "{}", format_hunk_line(file, line))?;
+                        }
+                    }
+                    scm_record::Section::FileMode { mode, .. } => {
+                        if file.file_mode == scm_record::FileMode::Absent {
+                            writeln!(output, "new file mode {mode}")?;
+                        } else if *mode == scm_record::FileMode::Absent {
+                            writeln!(output, "deleted file mode {}", file.file_mode)?;
+                        } else {
+                            writeln!(output, "old mode {}", file.file_mode)?;
+                            writeln!(output, "new mode {mode}")?;
+                        }
+                    }
+                    scm_record::Section::Binary {
+                        old_description,
+                        new_description,
+                        ..
+                    } => {
+                        writeln!(
+                            output,
+                            "Binary file changed: {} -> {}",
+                            old_description.as_deref().unwrap_or("(absent)"),
+                            new_description.as_deref().unwrap_or("(absent)")
+                        )?;
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the `@@` header and the lines of the text `hunk` including context.
+fn text_hunk_lines<'a>(file: &'a scm_record::File, hunk: &Hunk) -> (String, Vec<HunkLine<'a>>) {
+    let sections = &file.sections;
+    // Number of left and right lines preceding the hunk
+    let (mut left_start, mut right_start) = (0, 0);
+    for section in &sections[..hunk.sections.start] {
+        match section {
+            scm_record::Section::Unchanged { lines } => {
+                left_start += lines.len();
+                right_start += lines.len();
+            }
+            scm_record::Section::Changed { lines } => {
+                for line in lines {
+                    match line.change_type {
+                        scm_record::ChangeType::Removed => left_start += 1,
+                        scm_record::ChangeType::Added => right_start += 1,
+                    }
+                }
+            }
+            scm_record::Section::FileMode { .. } | scm_record::Section::Binary { .. } => {}
+        }
+    }
+
+    let mut hunk_lines = vec![];
+    if let Some(scm_record::Section::Unchanged { lines }) = hunk
+        .sections
+        .start
+        .checked_sub(1)
+        .and_then(|index| sections.get(index))
+    {
+        let context = &lines[lines.len().saturating_sub(NUM_CONTEXT_LINES)..];
+        left_start -= context.len();
+        right_start -= context.len();
+        hunk_lines.extend(context.iter().map(|line| HunkLine::Context(line)));
+    }
+    for (index, section) in sections[hunk.sections.clone()].iter().enumerate() {
+        match section {
+            scm_record::Section::Unchanged { lines } => {
+                hunk_lines.extend(lines.iter().map(|line| HunkLine::Context(line)));
+            }
+            scm_record::Section::Changed { lines } => {
+                hunk_lines.extend((0..lines.len()).map(|line| HunkLine::Changed {
+                    section: hunk.sections.start + index,
+                    line,
+                }));
+            }
+            scm_record::Section::FileMode { .. } | scm_record::Section::Binary { .. } => {}
+        }
+    }
+    if let Some(scm_record::Section::Unchanged { lines }) = sections.get(hunk.sections.end) {
+        let context = &lines[..lines.len().min(NUM_CONTEXT_LINES)];
+        hunk_lines.extend(context.iter().map(|line| HunkLine::Context(line)));
+    }
+
+    let (mut left_count, mut right_count) = (0, 0);
+    for line in &hunk_lines {
+        match line_change_type(file, *line) {
+            None => {
+                left_count += 1;
+                right_count += 1;
+            }
+            Some(scm_record::ChangeType::Removed) => left_count += 1,
+            Some(scm_record::ChangeType::Added) => right_count += 1,
+        }
+    }
+    let header = format!(
+        "@@ -{} +{} @@",
+        format_line_range(left_start, left_count),
+        format_line_range(right_start, right_count)
+    );
+    (header, hunk_lines)
+}
+
+fn format_line_range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{start},0"),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{count}", start + 1),
+    }
+}
+
+fn line_change_type(file: &scm_record::File, line: HunkLine) -> Option<scm_record::ChangeType> {
+    match line {
+        HunkLine::Context(_) => None,
+        HunkLine::Changed { section, line } => match &file.sections[section] {
+            scm_record::Section::Changed { lines } => Some(lines[line].change_type),
+            _ => panic!("hunk line should point to changed section"),
+        },
+    }
+}
+
+fn line_text<'a>(file: &'a scm_record::File, line: HunkLine<'a>) -> &'a str {
+    match line {
+        HunkLine::Context(text) => text,
+        HunkLine::Changed { section, line } => match &file.sections[section] {
+            scm_record::Section::Changed { lines } => &lines[line].line,
+            _ => panic!("hunk line should point to changed section"),
+        },
+    }
+}
+
+fn format_hunk_line(file: &scm_record::File, line: HunkLine) -> String {
+    let prefix = match line_change_type(file, line) {
+        None => ' ',
+        Some(scm_record::ChangeType::Removed) => '-',
+        Some(scm_record::ChangeType::Added) => '+',
+    };
+    let text = line_text(file, line);
+    if text.ends_with('\n') {
+        format!("{prefix}{text}")
+    } else {
+        format!("{prefix}{text}\n\\ No newline at end of file\n")
+    }
+}
+
+/// Matches the `edited` hunk text against the original `lines`, and returns
+/// the selection state of each changed line. Returns `None` if the edited
+/// text doesn't correspond to the original hunk.
+fn parse_edited_hunk(
+    file: &scm_record::File,
+    lines: &[HunkLine],
+    edited: &str,
+) -> Option<Vec<((usize, usize), bool)>> {
+    let mut edited_lines = edited
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.starts_with('\\'))
+        .skip_while(|line| line.starts_with("@@"))
+        // Editors may strip the trailing whitespace of empty context lines.
+        .map(|line| if line.is_empty() { " " } else { line })
+        .peekable();
+    let mut selection = vec![];
+    for &line in lines {
+        let text = line_text(file, line);
+        let text = text.strip_suffix('\n').unwrap_or(text);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        let next = edited_lines
+            .peek()
+            .and_then(|edited| edited.get(..1).zip(edited.get(1..)))
+            .filter(|(_, edited_text)| *edited_text == text);
+        match (line, line_change_type(file, line), next) {
+            (HunkLine::Context(_), _, Some((" ", _))) => {}
+            (
+                HunkLine::Changed { section, line },
+                Some(scm_record::ChangeType::Removed),
+                Some((prefix @ ("-" | " "), _)),
+            ) => {
+                selection.push(((section, line), prefix == "-"));
+            }
+            (
+                HunkLine::Changed { section, line },
+                Some(scm_record::ChangeType::Added),
+                Some(("+", _)),
+            ) => {
+                selection.push(((section, line), true));
+            }
+            (HunkLine::Changed { section, line }, Some(scm_record::ChangeType::Added), _) => {
+                selection.push(((section, line), false));
+                continue;
+            }
+            _ => return None,
+        }
+        edited_lines.next();
+    }
+    edited_lines.next().is_none().then_some(selection)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::sync::Arc;
+
+    use jj_lib::matchers::EverythingMatcher;
+    use jj_lib::repo::Repo as _;
+    use jj_lib::store::Store;
+    use testutils::repo_path;
+    use testutils::TestRepo;
+
+    use super::*;
+
+    /// Reads answers from a string, and records the output.
+    struct TestPromptIo<'a> {
+        answers: RefCell<std::str::Lines<'a>>,
+        output: RefCell<Vec<u8>>,
+    }
+
+    struct TestOutput<'a>(&'a RefCell<Vec<u8>>);
+
+    impl Write for TestOutput<'_> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl PromptIo for TestPromptIo<'_> {
+        fn output(&self) -> Box<dyn Write + '_> {
+            Box::new(TestOutput(&self.output))
+        }
+
+        fn prompt_choice(&self, prompt: &str, choices: &[&str]) -> io::Result<usize> {
+            loop {
+                write!(self.output(), "{prompt}: ")?;
+                let Some(answer) = self.answers.borrow_mut().next() else {
+                    return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+                };
+                match choices.iter().position(|choice| *choice == answer) {
+                    Some(index) => return Ok(index),
+                    None => writeln!(self.output(), "unrecognized response")?,
+                }
+            }
+        }
+    }
+
+    fn select_with_prompt(
+        store: &Arc<Store>,
+        left_tree: &MergedTree,
+        right_tree: &MergedTree,
+        answers: &str,
+        edit_hunk: impl FnMut(&str) -> Result<String, TempTextEditError>,
+    ) -> (MergedTreeId, String) {
+        let copy_records = CopyRecords::default();
+        let tree_diff =
+            left_tree.diff_stream_with_copies(right_tree, &EverythingMatcher, &copy_records);
+        let (changed_files, mut files) =
+            make_diff_files(store, tree_diff, ConflictMarkerStyle::Diff)
+                .block_on()
+                .unwrap();
+        let io = TestPromptIo {
+            answers: RefCell::new(answers.lines()),
+            output: RefCell::new(vec![]),
+        };
+        let mut prompter = HunkPrompter { io: &io, edit_hunk };
+        prompter.select_files(&mut files).unwrap();
+        let tree_id = apply_diff_builtin(
+            store,
+            left_tree,
+            right_tree,
+            changed_files,
+            &files,
+            ConflictMarkerStyle::Diff,
+        )
+        .unwrap();
+        (tree_id, String::from_utf8(io.output.into_inner()).unwrap())
+    }
+
+    fn no_edit(_: &str) -> Result<String, TempTextEditError> {
+        panic!("unexpected edit");
+    }
+
+    #[test]
+    fn test_edit_diff_prompt() {
+        let test_repo = TestRepo::init();
+        let store = test_repo.repo.store();
+
+        let changed_path = repo_path("changed");
+        let added_path = repo_path("added");
+        let left_tree = testutils::create_tree(
+            &test_repo.repo,
+            &[(changed_path, "a\nb\nc\nd\ne\nf\ng\nh\ni\n")],
+        );
+        let right_tree = testutils::create_tree(
+            &test_repo.repo,
+            &[
+                (changed_path, "A\nb\nc\nd\ne\nf\ng\nh\nI\n"),
+                (added_path, "added\n"),
+            ],
+        );
+
+        let (tree_id, output) = select_with_prompt(store, &left_tree, &right_tree, "y\n", no_edit);
+        insta::assert_snapshot!(output, @r"
+        --- /dev/null
+        +++ b/added
+        new file mode 100644
+        +added
+        (1/1) Select creation of this file [y,n,a,d,q,?]: --- a/changed
+        +++ b/changed
+        @@ -1,4 +1,4 @@
+        -a
+        +A
+         b
+         c
+         d
+        (1/2) Select this hunk [y,n,a,d,e,q,?]:
+        ");
+        let expected_tree = testutils::create_tree(
+            &test_repo.repo,
+            &[
+                (changed_path, "a\nb\nc\nd\ne\nf\ng\nh\ni\n"),
+                (added_path, "added\n"),
+            ],
+        );
+        assert_eq!(tree_id, expected_tree.id());
+
+        let (tree_id, output) =
+            select_with_prompt(store, &left_tree, &right_tree, "n\n?\nn\ny\n", no_edit);
+        insta::assert_snapshot!(output, @r"
+        --- /dev/null
+        +++ b/added
+        new file mode 100644
+        +added
+        (1/1) Select creation of this file [y,n,a,d,q,?]: --- a/changed
+        +++ b/changed
+        @@ -1,4 +1,4 @@
+        -a
+        +A
+         b
+         c
+         d
+        (1/2) Select this hunk [y,n,a,d,e,q,?]: y - select this hunk
+        n - do not select this hunk
+        a - select this hunk and all later hunks in the file
+        d - do not select this hunk or any of the later hunks in the file
+        e - manually edit the current hunk
+        q - quit; do not select this hunk or any of the remaining ones
+        ? - print help
+        @@ -1,4 +1,4 @@
+        -a
+        +A
+         b
+         c
+         d
+        (1/2) Select this hunk [y,n,a,d,e,q,?]: @@ -6,4 +6,4 @@
+         f
+         g
+         h
+        -i
+        +I
+        (2/2) Select this hunk [y,n,a,d,e,q,?]:
+        ");
+        let expected_tree = testutils::create_tree(
+            &test_repo.repo,
+            &[(changed_path, "a\nb\nc\nd\ne\nf\ng\nh\nI\n")],
+        );
+        assert_eq!(tree_id, expected_tree.id());
+    }
+
+    #[test]
+    fn test_edit_diff_prompt_split_hunk() {
+        let test_repo = TestRepo::init();
+        let store = test_repo.repo.store();
+
+        let path = repo_path("file");
+        let left_tree = testutils::create_tree(&test_repo.repo, &[(path, "a\nb\nc\nd\n")]);
+        let right_tree = testutils::create_tree(&test_repo.repo, &[(path, "a\nB\nc\nD\n")]);
+
+        let (tree_id, output) =
+            select_with_prompt(store, &left_tree, &right_tree, "s\nn\ny\n", no_edit);
+        insta::assert_snapshot!(output, @r"
+        --- a/file
+        +++ b/file
+        @@ -1,4 +1,4 @@
+         a
+        -b
+        +B
+         c
+        -d
+        +D
+        (1/1) Select this hunk [y,n,a,d,s,e,q,?]: Split into 2 hunks.
+        @@ -1,3 +1,3 @@
+         a
+        -b
+        +B
+         c
+        (1/2) Select this hunk [y,n,a,d,e,q,?]: @@ -3,2 +3,2 @@
+         c
+        -d
+        +D
+        (2/2) Select this hunk [y,n,a,d,e,q,?]:
+        ");
+        let expected_tree = testutils::create_tree(&test_repo.repo, &[(path, "a\nb\nc\nD\n")]);
+        assert_eq!(tree_id, expected_tree.id());
+    }
+
+    #[test]
+    fn test_edit_diff_prompt_edit_hunk() {
+        let test_repo = TestRepo::init();
+        let store = test_repo.repo.store();
+
+        let path = repo_path("file");
+        let left_tree = testutils::create_tree(&test_repo.repo, &[(path, "a\nb\nc\n")]);
+        let right_tree = testutils::create_tree(&test_repo.repo, &[(path, "a\nB1\nB2\nc\n")]);
+
+        // Keep the removed line, and add only the first line
+        let mut edited_text = None;
+        let (tree_id, output) = select_with_prompt(store, &left_tree, &right_tree, "e\n", |text| {
+            edited_text = Some(text.to_owned());
+            Ok(text.replace("-b\n", " b\n").replace("+B2\n", ""))
+        });
+        insta::assert_snapshot!(edited_text.unwrap(), @r"
+        # Manual hunk edit mode -- see bottom for a quick guide.
+        @@ -1,3 +1,4 @@
+         a
+        -b
+        +B1
+        +B2
+         c
+        # ---
+        # To remove '-' lines, make them ' ' lines (context).
+        # To remove '+' lines, delete them.
+        # Lines starting with # will be removed.
+        # Other modifications are not supported.
+        ");
+        insta::assert_snapshot!(output, @r"
+        --- a/file
+        +++ b/file
+        @@ -1,3 +1,4 @@
+         a
+        -b
+        +B1
+        +B2
+         c
+        (1/1) Select this hunk [y,n,a,d,e,q,?]:
+        ");
+        let expected_tree = testutils::create_tree(&test_repo.repo, &[(path, "a\nb\nB1\nc\n")]);
+        assert_eq!(tree_id, expected_tree.id());
+
+        // Modified lines can't be applied
+        let (tree_id, output) =
+            select_with_prompt(store, &left_tree, &right_tree, "e\nq\n", |text| {
+                Ok(text.replace("+B1\n", "+X\n"))
+            });
+        insta::assert_snapshot!(output, @r"
+        --- a/file
+        +++ b/file
+        @@ -1,3 +1,4 @@
+         a
+        -b
+        +B1
+        +B2
+         c
+        (1/1) Select this hunk [y,n,a,d,e,q,?]: Your edited hunk does not match the original hunk; the edit was ignored.
+        @@ -1,3 +1,4 @@
+         a
+        -b
+        +B1
+        +B2
+         c
+        (1/1) Select this hunk [y,n,a,d,e,q,?]:
+        ");
+        assert_eq!(tree_id, left_tree.id());
+    }
+}
@@ -15,6 +15,7 @@
 use std::borrow::Cow;
 use std::cmp;
 use std::io;
+use std::ops::Range;
 
 use bstr::ByteSlice as _;
 use unicode_width::UnicodeWidthChar as _;
@@ -501,6 +502,21 @@ pub fn wrap_bytes(text: &[u8], width: usize) -> Vec<&[u8]> {
     split_lines
 }
 
+/// Wraps lines at the given width, returns a vector of byte ranges of the lines
+/// (excluding "\n".)
+///
+/// This is similar to `wrap_bytes()`, but the lines are represented as ranges
+/// in the given text.
+pub fn wrap_bytes_to_ranges(text: &[u8], width: usize) -> Vec<Range<usize>> {
+    wrap_bytes(text, width)
+        .into_iter()
+        .map(|line| {
+            let start = byte_offset_from(text, line);
+            start..start + line.len()
+        })
+        .collect()
+}
+
 /// Wraps lines at the given width preserving labels.
 ///
 /// `textwrap::wrap()` can also process text containing ANSI escape sequences.
@@ -513,13 +529,7 @@ pub fn write_wrapped(
     width: usize,
 ) -> io::Result<()> {
     let data = recorded_content.data();
-    let mut line_ranges = wrap_bytes(data, width)
-        .into_iter()
-        .map(|line| {
-            let start = byte_offset_from(data, line);
-            start..start + line.len()
-        })
-        .peekable();
+    let mut line_ranges = wrap_bytes_to_ranges(data, width).into_iter().peekable();
     // The recorded data ranges are contiguous, and the line ranges are increasing
     // sequence (with some holes.) Both ranges should start from data[0].
     recorded_content.replay_with(formatter, |formatter, data_range| {
@@ -1401,6 +1411,15 @@ mod tests {
         assert_eq!(lines[4].as_ptr(), text[14..].as_ptr());
     }
 
+    #[test]
+    fn test_wrap_bytes_to_ranges() {
+        assert_eq!(
+            wrap_bytes_to_ranges(b"\nfoo\n\nbar baz\n", 10),
+            [0..0, 1..4, 5..5, 6..13, 14..14]
+        );
+        assert_eq!(wrap_bytes_to_ranges(b"foo bar baz", 7), [0..7, 8..11]);
+    }
+
     #[test]
     fn test_write_wrapped() {
         // Split single label chunk
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use crate::common::create_commit;
+use crate::common::create_commit_with_files;
 use crate::common::CommandOutput;
 use crate::common::TestEnvironment;
 use crate::common::TestWorkDir;
@@ -523,6 +524,54 @@ fn test_abandon_tracking_bookmarks() {
     ");
 }
 
+#[test]
+fn test_abandon_landed() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "a", &[], &[("file", "1\n2\n3\n")]);
+    create_commit_with_files(&work_dir, "b", &["a"], &[("file", "1\ntwo\n3\n")]);
+    create_commit(&work_dir, "c", &["b"]);
+    // Cherry-picked with different whitespace
+    create_commit_with_files(&work_dir, "upstream", &["a"], &[("file", "1\n two\n3\n")]);
+    // Test the setup
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    @  [vru] upstream
+    │ ○  [roy] c
+    │ ○  [zsu] b
+    ├─╯
+    ○  [rlv] a
+    ◆  [zzz]
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["abandon", "--landed=upstream", "a::"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Abandoned 1 commits:
+      zsuskuln 454e1fa2 b | b
+    Deleted bookmarks: b
+    Rebased 1 descendant commits onto parents of abandoned commits
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    @  [vru] upstream
+    │ ○  [roy] c
+    ├─╯
+    ○  [rlv] a
+    ◆  [zzz]
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["abandon", "--landed=upstream", "a::"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    No revisions to abandon.
+    [EOF]
+    ");
+}
+
 #[must_use]
 fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
     let template = r#"separate(" ", "[" ++ change_id.short(3) ++ "]", bookmarks)"#;
@@ -59,9 +59,18 @@ fn describe_new_cmd(work_dir: &TestWorkDir, commit_message: &str) {
     work_dir.run_jj(["new"]).success();
 }
 
+// Implements CommitFn using the `jj split` command.
+fn split_cmd(work_dir: &TestWorkDir, commit_message: &str) {
+    work_dir.write_file(commit_message, "");
+    work_dir
+        .run_jj(["split", "-m", commit_message, commit_message])
+        .success();
+}
+
 // Check that enabling and disabling advance-bookmarks works as expected.
 #[test_case(commit_cmd ; "commit")]
 #[test_case(describe_new_cmd; "new")]
+#[test_case(split_cmd; "split")]
 fn test_advance_bookmarks_enabled(make_commit: CommitFn) {
     let test_env = TestEnvironment::default();
     test_env.run_jj_in(".", ["git", "init", "repo"]).success();
@@ -110,6 +119,8 @@ fn test_advance_bookmarks_enabled(make_commit: CommitFn) {
 
 // Check that only a bookmark pointing to @- advances. Branches pointing to @
 // are not advanced.
+// `jj split` isn't tested here because bookmarks pointing to the split commit
+// move to the second commit, which becomes @.
 #[test_case(commit_cmd ; "commit")]
 #[test_case(describe_new_cmd; "new")]
 fn test_advance_bookmarks_at_minus(make_commit: CommitFn) {
@@ -161,6 +172,7 @@ fn test_advance_bookmarks_at_minus(make_commit: CommitFn) {
 // experimental-advance-bookmarks.enabled.
 #[test_case(commit_cmd ; "commit")]
 #[test_case(describe_new_cmd; "new")]
+#[test_case(split_cmd; "split")]
 fn test_advance_bookmarks_overrides(make_commit: CommitFn) {
     let test_env = TestEnvironment::default();
     test_env.run_jj_in(".", ["git", "init", "repo"]).success();
@@ -276,6 +288,7 @@ fn test_advance_bookmarks_overrides(make_commit: CommitFn) {
 // If multiple eligible bookmarks point to @-, all of them will be advanced.
 #[test_case(commit_cmd ; "commit")]
 #[test_case(describe_new_cmd; "new")]
+#[test_case(split_cmd; "split")]
 fn test_advance_bookmarks_multiple_bookmarks(make_commit: CommitFn) {
     let test_env = TestEnvironment::default();
     test_env.run_jj_in(".", ["git", "init", "repo"]).success();
@@ -474,3 +487,76 @@ fn test_new_advance_bookmarks_merge_children() {
     [EOF]
     ");
 }
+
+// Squashing the working-copy commit into its parent moves the working copy
+// onto the rewritten parent, which advances bookmarks like `jj new` would.
+#[test]
+fn test_squash_advance_bookmarks() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    set_advance_bookmarks(&test_env, true);
+    work_dir.run_jj(["commit", "-m", "first"]).success();
+    work_dir.run_jj(["commit", "-m", "second"]).success();
+    work_dir
+        .run_jj(["bookmark", "set", "-r", "@--", "test_bookmark"])
+        .success();
+    work_dir.write_file("file", "");
+    work_dir.run_jj(["squash"]).success();
+    insta::assert_snapshot!(get_log_output_with_bookmarks(&work_dir), @r"
+    @  bookmarks{} desc:
+    ○  bookmarks{test_bookmark} desc: second
+    ○  bookmarks{} desc: first
+    ◆  bookmarks{} desc:
+    [EOF]
+    ");
+}
+
+// Rebasing the working-copy commit onto another commit advances bookmarks like
+// `jj new` would, but rebasing it along with its parent doesn't.
+#[test]
+fn test_rebase_advance_bookmarks() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    set_advance_bookmarks(&test_env, true);
+    work_dir.run_jj(["commit", "-m", "first"]).success();
+    work_dir.run_jj(["commit", "-m", "second"]).success();
+    work_dir.run_jj(["new", "description(first)"]).success();
+    work_dir
+        .run_jj(["bookmark", "set", "-r", "@-", "test_bookmark"])
+        .success();
+    insta::assert_snapshot!(get_log_output_with_bookmarks(&work_dir), @r"
+    @  bookmarks{} desc:
+    │ ○  bookmarks{} desc: second
+    ├─╯
+    ○  bookmarks{test_bookmark} desc: first
+    ◆  bookmarks{} desc:
+    [EOF]
+    ");
+
+    work_dir
+        .run_jj(["rebase", "-r", "@", "-d", "description(second)"])
+        .success();
+    insta::assert_snapshot!(get_log_output_with_bookmarks(&work_dir), @r"
+    @  bookmarks{} desc:
+    ○  bookmarks{test_bookmark} desc: second
+    ○  bookmarks{} desc: first
+    ◆  bookmarks{} desc:
+    [EOF]
+    ");
+
+    work_dir
+        .run_jj(["rebase", "-s", "description(second)", "-d", "root()"])
+        .success();
+    insta::assert_snapshot!(get_log_output_with_bookmarks(&work_dir), @r"
+    @  bookmarks{} desc:
+    ○  bookmarks{test_bookmark} desc: second
+    │ ○  bookmarks{} desc: first
+    ├─╯
+    ◆  bookmarks{} desc:
+    [EOF]
+    ");
+}
@@ -2378,6 +2378,226 @@ fn test_bookmark_list_sort_overriding_config() {
     ");
 }
 
+#[test]
+fn test_bookmark_describe() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let template = r#"
+    separate(" ",
+      name,
+      "desc=" ++ description.escape_json(),
+      "owner=" ++ owner.escape_json(),
+      "base=" ++ if(base, base, "none"),
+    ) ++ "\n"
+    "#;
+
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "foo", "bar"])
+        .success();
+    let output = work_dir.run_jj([
+        "bookmark",
+        "describe",
+        "foo",
+        "-m",
+        "Feature foo",
+        "--owner",
+        "alice",
+        "--base",
+        "bar",
+    ]);
+    insta::assert_snapshot!(output, @"");
+    let output = work_dir.run_jj(["bookmark", "list", "-T", template]);
+    insta::assert_snapshot!(output, @r#"
+    bar desc="" owner="" base=none
+    foo desc="Feature foo" owner="alice" base=bar
+    [EOF]
+    "#);
+
+    // Unspecified fields are preserved
+    work_dir
+        .run_jj(["bookmark", "describe", "foo", "--owner", "bob"])
+        .success();
+    let output = work_dir.run_jj(["bookmark", "list", "-T", template]);
+    insta::assert_snapshot!(output, @r#"
+    bar desc="" owner="" base=none
+    foo desc="Feature foo" owner="bob" base=bar
+    [EOF]
+    "#);
+
+    // Invalid arguments
+    let output = work_dir.run_jj(["bookmark", "describe", "baz", "-m", "x"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No such bookmark: baz
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["bookmark", "describe", "foo", "--base", "baz"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No such bookmark: baz
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["bookmark", "describe", "foo", "--base", "foo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Bookmark foo cannot be based on itself
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Renaming the base updates the bookmarks based on it, and renaming a
+    // bookmark carries its metadata
+    work_dir
+        .run_jj(["bookmark", "rename", "bar", "main"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "rename", "foo", "feature"])
+        .success();
+    let output = work_dir.run_jj(["bookmark", "list", "-T", template]);
+    insta::assert_snapshot!(output, @r#"
+    feature desc="Feature foo" owner="bob" base=main
+    main desc="" owner="" base=none
+    [EOF]
+    "#);
+
+    // Clearing the base
+    work_dir
+        .run_jj(["bookmark", "describe", "feature", "--no-base"])
+        .success();
+    let output = work_dir.run_jj(["bookmark", "list", "-T", template]);
+    insta::assert_snapshot!(output, @r#"
+    feature desc="Feature foo" owner="bob" base=none
+    main desc="" owner="" base=none
+    [EOF]
+    "#);
+
+    // Deleting the bookmark discards its metadata
+    work_dir.run_jj(["bookmark", "delete", "feature"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "feature"])
+        .success();
+    let output = work_dir.run_jj(["bookmark", "list", "-T", template]);
+    insta::assert_snapshot!(output, @r#"
+    feature desc="" owner="" base=none
+    main desc="" owner="" base=none
+    [EOF]
+    "#);
+}
+
+#[test]
+fn test_bookmark_status() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-bookmark = true");
+
+    // Initialize remote refs
+    test_env.run_jj_in(".", ["git", "init", "remote"]).success();
+    let remote_dir = test_env.work_dir("remote");
+    for bookmark in [
+        "synced",
+        "ahead",
+        "behind",
+        "diverged",
+        "rewritten",
+        "deleted",
+    ] {
+        remote_dir
+            .run_jj(["new", "root()", "-m", bookmark])
+            .success();
+        remote_dir
+            .run_jj(["bookmark", "create", "-r@", bookmark])
+            .success();
+    }
+    remote_dir
+        .run_jj(["new", "behind", "-m", "behind 2"])
+        .success();
+    remote_dir
+        .run_jj(["bookmark", "set", "-r@", "behind"])
+        .success();
+    remote_dir.run_jj(["new"]).success();
+    remote_dir.run_jj(["git", "export"]).success();
+
+    // Initialize local refs
+    let mut remote_git_path = remote_dir.root().to_owned();
+    remote_git_path.extend([".jj", "repo", "store", "git"]);
+    test_env
+        .run_jj_in(
+            ".",
+            ["git", "clone", remote_git_path.to_str().unwrap(), "local"],
+        )
+        .success();
+    let local_dir = test_env.work_dir("local");
+
+    // Mutate refs in local repository
+    local_dir
+        .run_jj(["new", "ahead", "-m", "ahead 2"])
+        .success();
+    local_dir
+        .run_jj(["bookmark", "set", "-r@", "ahead"])
+        .success();
+    local_dir
+        .run_jj([
+            "bookmark",
+            "set",
+            "--allow-backwards",
+            "-r",
+            "behind-",
+            "behind",
+        ])
+        .success();
+    local_dir.run_jj(["new", "root()", "-m", "other"]).success();
+    local_dir
+        .run_jj(["bookmark", "set", "--allow-backwards", "-r@", "diverged"])
+        .success();
+    local_dir
+        .run_jj(["describe", "rewritten", "-m", "rewritten 2"])
+        .success();
+    local_dir
+        .run_jj(["bookmark", "delete", "deleted"])
+        .success();
+
+    let output = local_dir.run_jj(["bookmark", "status"]);
+    insta::assert_snapshot!(output, @r"
+    ahead@origin: ahead (1 ahead)
+      jj git push --bookmark ahead --remote origin
+    behind@origin: behind (1 behind)
+      jj bookmark set behind -r behind@origin
+    deleted@origin: deleted
+      jj git push --deleted --remote origin
+    diverged@origin: diverged (1 ahead, 1 behind)
+      jj rebase -b diverged -d diverged@origin
+    rewritten@origin: diverged (1 ahead, 1 behind, rewritten)
+      jj git push --bookmark rewritten --remote origin
+    synced@origin: synced
+    [EOF]
+    ");
+
+    let output = local_dir.run_jj(["bookmark", "status", "glob:d*", "--remote=origin"]);
+    insta::assert_snapshot!(output, @r"
+    deleted@origin: deleted
+      jj git push --deleted --remote origin
+    diverged@origin: diverged (1 ahead, 1 behind)
+      jj rebase -b diverged -d diverged@origin
+    [EOF]
+    ");
+
+    let template = r#"
+    "{" ++ separate(", ",
+      '"name": ' ++ name.escape_json(),
+      '"state": ' ++ state.escape_json(),
+      '"ahead": ' ++ ahead_count.lower(),
+      '"rewritten": ' ++ if(rewritten, "true", "false"),
+    ) ++ "}\n"
+    "#;
+    let output = local_dir.run_jj(["bookmark", "status", "-T", template, "rewritten"]);
+    insta::assert_snapshot!(output, @r#"
+    {"name": "rewritten", "state": "diverged", "ahead": 1, "rewritten": true}
+    [EOF]
+    "#);
+}
+
 #[test]
 fn test_bookmark_create_with_default_target_revision() {
     let test_env = TestEnvironment::default();
@@ -929,59 +929,36 @@ fn test_operations() {
         .unwrap();
     insta::assert_snapshot!(add_workspace_id, @"12f7cbba4278");
 
-    let output = work_dir.complete_fish(["op", "show", "8"]);
+    let output = work_dir.complete_fish(["op", "show", "6"]);
     insta::assert_snapshot!(output, @r"
-    8ed8c16786e6	(2001-02-03 08:05:11) describe commit 3725536d0ae06d69e46911258cee591dbdb66478
-    8f47435a3990	(2001-02-03 08:05:07) add workspace 'default'
+    6f2b22ecf26f	(2001-02-03 08:05:13) describe commit aa0b3230e3787076f232a08c8b1c7f54948a2d7a
     [EOF]
     ");
     // make sure global --at-op flag is respected
-    let output = work_dir.complete_fish(["--at-op", "8ed8c16786e6", "op", "show", "8"]);
+    let output = work_dir.complete_fish(["--at-op", "7a89ef1b18dc", "op", "show", "6"]);
     insta::assert_snapshot!(output, @r"
-    8ed8c16786e6	(2001-02-03 08:05:11) describe commit 3725536d0ae06d69e46911258cee591dbdb66478
-    8f47435a3990	(2001-02-03 08:05:07) add workspace 'default'
+    6f2b22ecf26f	(2001-02-03 08:05:13) describe commit aa0b3230e3787076f232a08c8b1c7f54948a2d7a
     [EOF]
     ");
 
-    let output = work_dir.complete_fish(["--at-op", "8e"]);
-    insta::assert_snapshot!(output, @r"
-    8ed8c16786e6	(2001-02-03 08:05:11) describe commit 3725536d0ae06d69e46911258cee591dbdb66478
-    [EOF]
-    ");
+    let output = work_dir.complete_fish(["--at-op", "7a"]);
+    insta::assert_snapshot!(output, @"");
 
-    let output = work_dir.complete_fish(["op", "abandon", "8e"]);
-    insta::assert_snapshot!(output, @r"
-    8ed8c16786e6	(2001-02-03 08:05:11) describe commit 3725536d0ae06d69e46911258cee591dbdb66478
-    [EOF]
-    ");
+    let output = work_dir.complete_fish(["op", "abandon", "7a"]);
+    insta::assert_snapshot!(output, @"");
 
-    let output = work_dir.complete_fish(["op", "diff", "--op", "8e"]);
-    insta::assert_snapshot!(output, @r"
-    8ed8c16786e6	(2001-02-03 08:05:11) describe commit 3725536d0ae06d69e46911258cee591dbdb66478
-    [EOF]
-    ");
-    let output = work_dir.complete_fish(["op", "diff", "--from", "8e"]);
-    insta::assert_snapshot!(output, @r"
-    8ed8c16786e6	(2001-02-03 08:05:11) describe commit 3725536d0ae06d69e46911258cee591dbdb66478
-    [EOF]
-    ");
-    let output = work_dir.complete_fish(["op", "diff", "--to", "8e"]);
-    insta::assert_snapshot!(output, @r"
-    8ed8c16786e6	(2001-02-03 08:05:11) describe commit 3725536d0ae06d69e46911258cee591dbdb66478
-    [EOF]
-    ");
+    let output = work_dir.complete_fish(["op", "diff", "--op", "7a"]);
+    insta::assert_snapshot!(output, @"");
+    let output = work_dir.complete_fish(["op", "diff", "--from", "7a"]);
+    insta::assert_snapshot!(output, @"");
+    let output = work_dir.complete_fish(["op", "diff", "--to", "7a"]);
+    insta::assert_snapshot!(output, @"");
 
-    let output = work_dir.complete_fish(["op", "restore", "8e"]);
-    insta::assert_snapshot!(output, @r"
-    8ed8c16786e6	(2001-02-03 08:05:11) describe commit 3725536d0ae06d69e46911258cee591dbdb66478
-    [EOF]
-    ");
+    let output = work_dir.complete_fish(["op", "restore", "7a"]);
+    insta::assert_snapshot!(output, @"");
 
-    let output = work_dir.complete_fish(["op", "undo", "8e"]);
-    insta::assert_snapshot!(output, @r"
-    8ed8c16786e6	(2001-02-03 08:05:11) describe commit 3725536d0ae06d69e46911258cee591dbdb66478
-    [EOF]
-    ");
+    let output = work_dir.complete_fish(["op", "undo", "7a"]);
+    insta::assert_snapshot!(output, @"");
 }
 
 #[test]
@@ -1041,6 +1018,7 @@ fn test_config() {
     ui.conflict-marker-style=diff
     ui.conflict-marker-style=snapshot
     ui.conflict-marker-style=git
+    ui.conflict-marker-style=minimal
     [EOF]
     ");
 
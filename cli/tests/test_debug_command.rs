@@ -242,6 +242,34 @@ fn test_debug_operation_id() {
     ");
 }
 
+#[test]
+fn test_debug_locks() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let output = work_dir.run_jj(["debug", "locks"]);
+    assert_snapshot!(output, @r"
+    ------- stderr -------
+    No locks are held
+    [EOF]
+    ");
+
+    // Lock held by a process on another host. The start time is out of range
+    // so that the output doesn't depend on the local time zone.
+    work_dir.write_file(
+        ".jj/repo/op_heads/heads/lock",
+        r#"{"pid":1,"hostname":"other-host","command":"jj describe","start_time":9223372036854775807}"#,
+    );
+    // Lock file written by an older version of jj
+    work_dir.write_file(".jj/working_copy/working_copy.lock", "");
+    let output = work_dir.run_jj(["debug", "locks"]);
+    assert_snapshot!(output.normalize_backslash(), @r"
+    .jj/repo/op_heads/heads/lock: held by process 1 on other-host running `jj describe`
+    .jj/working_copy/working_copy.lock: unknown holder
+    [EOF]
+    ");
+}
+
 fn filter_index_stats(output: CommandOutput) -> CommandOutput {
     let regex = Regex::new(r"    Name: [0-9a-z]+").unwrap();
     output.normalize_stdout_with(|text| regex.replace_all(&text, "    Name: [hash]").into_owned())
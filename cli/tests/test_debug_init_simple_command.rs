@@ -58,3 +58,32 @@ fn test_init_local() {
     [exit status: 2]
     ");
 }
+
+#[test]
+fn test_init_chunked() {
+    let test_env = TestEnvironment::default();
+    let output = test_env.run_jj_in(
+        ".",
+        ["debug", "init-simple", "--store-type=chunked", "repo"],
+    );
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Initialized repo in "repo"
+    [EOF]
+    "#);
+
+    let workspace_root = test_env.env_root().join("repo");
+    let store_path = workspace_root.join(".jj").join("repo").join("store");
+    assert_eq!(
+        std::fs::read_to_string(store_path.join("type")).unwrap(),
+        "chunked"
+    );
+    assert!(store_path.join("chunks").is_dir());
+
+    std::fs::write(workspace_root.join("file"), "contents\n").unwrap();
+    let output = test_env.run_jj_in(&workspace_root, ["file", "show", "file"]);
+    insta::assert_snapshot!(output, @r"
+    contents
+    [EOF]
+    ");
+}
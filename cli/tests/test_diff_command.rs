@@ -1923,6 +1923,124 @@ fn test_diff_ignore_whitespace() {
     ");
 }
 
+#[test]
+fn test_diff_color_moved() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file(
+        "file1",
+        "fn foo() {\n    do_something();\n    do_something_else();\n}\n\nfn bar() {\n}\n",
+    );
+    work_dir.write_file("file2", "fn baz() {\n}\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file1", "fn bar() {\n}\n");
+    work_dir.write_file(
+        "file2",
+        "fn baz() {\n}\n\nfn foo() {\n    do_something();\n    do_something_else();\n}\n",
+    );
+
+    // The function moved from file1 to file2 is highlighted
+    let output = work_dir.run_jj(["diff", "--git", "--color-moved", "--color=debug"]);
+    insta::assert_snapshot!(output, @r"
+    [1m<<diff file_header::diff --git a/file1 b/file1>>[0m
+    [1m<<diff file_header::index a8bcb66fa5..64e4020e12 100644>>[0m
+    [1m<<diff file_header::--- a/file1>>[0m
+    [1m<<diff file_header::+++ b/file1>>[0m
+    [38;5;6m<<diff hunk_header::@@ -1,7 +1,2 @@>>[39m
+    [38;5;5m<<diff moved removed::->>[4m<<diff moved removed token::fn foo() {>>[24m[39m
+    [38;5;5m<<diff moved removed::->>[4m<<diff moved removed token::    do_something();>>[24m[39m
+    [38;5;5m<<diff moved removed::->>[4m<<diff moved removed token::    do_something_else();>>[24m[39m
+    [38;5;5m<<diff moved removed::->>[4m<<diff moved removed token::}>>[24m[39m
+    [38;5;5m<<diff moved removed::->>[4m<<diff moved removed token::>>[24m[39m
+    <<diff context:: fn bar() {>>
+    <<diff context:: }>>
+    [1m<<diff file_header::diff --git a/file2 b/file2>>[0m
+    [1m<<diff file_header::index e49b1aeac8..5b3a88d420 100644>>[0m
+    [1m<<diff file_header::--- a/file2>>[0m
+    [1m<<diff file_header::+++ b/file2>>[0m
+    [38;5;6m<<diff hunk_header::@@ -1,2 +1,7 @@>>[39m
+    <<diff context:: fn baz() {>>
+    <<diff context:: }>>
+    [38;5;6m<<diff moved added::+>>[4m<<diff moved added token::>>[24m[39m
+    [38;5;6m<<diff moved added::+>>[4m<<diff moved added token::fn foo() {>>[24m[39m
+    [38;5;6m<<diff moved added::+>>[4m<<diff moved added token::    do_something();>>[24m[39m
+    [38;5;6m<<diff moved added::+>>[4m<<diff moved added token::    do_something_else();>>[24m[39m
+    [38;5;6m<<diff moved added::+>>[4m<<diff moved added token::}>>[24m[39m
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["diff", "--color-moved", "--color=debug"]);
+    insta::assert_snapshot!(output, @r"
+    [38;5;3m<<diff header::Modified regular file file1:>>[39m
+    [38;5;5m<<diff moved removed line_number::   1>>[39m<<diff moved::     : >>[4m[38;5;5m<<diff moved removed token::fn foo() {>>[24m[39m
+    [38;5;5m<<diff moved removed line_number::   2>>[39m<<diff moved::     : >>[4m[38;5;5m<<diff moved removed token::    do_something();>>[24m[39m
+    [38;5;5m<<diff moved removed line_number::   3>>[39m<<diff moved::     : >>[4m[38;5;5m<<diff moved removed token::    do_something_else();>>[24m[39m
+    [38;5;5m<<diff moved removed line_number::   4>>[39m<<diff moved::     : >>[4m[38;5;5m<<diff moved removed token::}>>[24m[39m
+    [38;5;5m<<diff moved removed line_number::   5>>[39m<<diff moved::     : >>[4m[38;5;5m<<diff moved removed token::>>[24m[39m
+    [38;5;1m<<diff removed line_number::   6>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   1>>[39m<<diff::: fn bar() {>>
+    [38;5;1m<<diff removed line_number::   7>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   2>>[39m<<diff::: }>>
+    [38;5;3m<<diff header::Modified regular file file2:>>[39m
+    [38;5;1m<<diff removed line_number::   1>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   1>>[39m<<diff::: fn baz() {>>
+    [38;5;1m<<diff removed line_number::   2>>[39m<<diff:: >>[38;5;2m<<diff added line_number::   2>>[39m<<diff::: }>>
+    <<diff moved::     >>[38;5;6m<<diff moved added line_number::   3>>[39m<<diff moved::: >>[4m[38;5;6m<<diff moved added token::>>[24m[39m
+    <<diff moved::     >>[38;5;6m<<diff moved added line_number::   4>>[39m<<diff moved::: >>[4m[38;5;6m<<diff moved added token::fn foo() {>>[24m[39m
+    <<diff moved::     >>[38;5;6m<<diff moved added line_number::   5>>[39m<<diff moved::: >>[4m[38;5;6m<<diff moved added token::    do_something();>>[24m[39m
+    <<diff moved::     >>[38;5;6m<<diff moved added line_number::   6>>[39m<<diff moved::: >>[4m[38;5;6m<<diff moved added token::    do_something_else();>>[24m[39m
+    <<diff moved::     >>[38;5;6m<<diff moved added line_number::   7>>[39m<<diff moved::: >>[4m[38;5;6m<<diff moved added token::}>>[24m[39m
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_diff_side_by_side() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "1\n2\n3\n4\n5\n6\n7\n8\nfoo bar\n");
+    work_dir.write_file("file2", "removed\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file1", "1\n2\n3\n4\n5\n6\n7\n8\nfoo baz qux\n");
+    work_dir.remove_file("file2");
+    work_dir.write_file(
+        "file3",
+        "  a long line which is wrapped at the column width because the terminal is narrow\n",
+    );
+
+    let output = work_dir.run_jj(["diff", "--side-by-side"]);
+    insta::assert_snapshot!(output, @r"
+    Modified regular file file1:
+        ...
+       6 6                                           |    6 6
+       7 7                                           |    7 7
+       8 8                                           |    8 8
+       9 foo bar                                     |    9 foo baz qux
+    Removed regular file file2:
+       1 removed                                     |
+    Added regular file file3:
+                                                     |    1   a long line which is wrapped at the
+                                                     |      column width because the terminal is
+                                                     |      narrow
+    [EOF]
+    ");
+
+    // Long lines are wrapped within the columns
+    let output = work_dir.run_jj_with(|cmd| {
+        cmd.args(["diff", "--config=ui.diff-formatter=:side-by-side", "file3"])
+            .env("COLUMNS", "60")
+    });
+    insta::assert_snapshot!(output, @r"
+    Added regular file file3:
+                                 |    1   a long line which is
+                                 |      wrapped at the column
+                                 |      width because the
+                                 |      terminal is narrow
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_diff_skipped_context() {
     let test_env = TestEnvironment::default();
@@ -183,6 +183,89 @@ fn test_git_clone_bad_source() {
     "#);
 }
 
+#[test]
+fn test_git_clone_with_submodule() {
+    let test_env = TestEnvironment::default();
+    let root_dir = test_env.work_dir("");
+    let sub_repo = git::init(test_env.env_root().join("sub"));
+    let sub_commit1 = git::add_commit(&sub_repo, "refs/heads/main", "file", b"1\n", "1", &[]);
+    let sub_commit2 = git::add_commit(
+        &sub_repo,
+        "refs/heads/next",
+        "file",
+        b"2\n",
+        "2",
+        &[sub_commit1.commit_id],
+    );
+    let git_repo = git::init(test_env.env_root().join("source"));
+    let gitmodules = "[submodule \"sub\"]\n\tpath = sub\n\turl = ../sub\n";
+    let blob_id = git_repo.write_blob(gitmodules).unwrap().detach();
+    let mut tree_editor = git_repo
+        .edit_tree(gix::ObjectId::empty_tree(git_repo.object_hash()))
+        .unwrap();
+    tree_editor
+        .upsert(".gitmodules", gix::object::tree::EntryKind::Blob, blob_id)
+        .unwrap();
+    tree_editor
+        .upsert(
+            "sub",
+            gix::object::tree::EntryKind::Commit,
+            sub_commit1.commit_id,
+        )
+        .unwrap();
+    let tree_id = tree_editor.write().unwrap().detach();
+    git::write_commit(&git_repo, "refs/heads/main", tree_id, "add submodule", &[]);
+    git::set_symbolic_reference(&git_repo, "HEAD", "refs/heads/main");
+
+    // Submodules aren't updated by default
+    let output = root_dir.run_jj(["git", "clone", "source", "clone-default"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Fetching into new repo in "$TEST_ENV/clone-default"
+    bookmark: main@origin [new] tracked
+    Setting the revset alias `trunk()` to `main@origin`
+    Working copy  (@) now at: sqpuoqvx ce4c85c2 (empty) (no description set)
+    Parent commit (@-)      : upwluzus 8f0b1221 main | add submodule
+    Added 2 files, modified 0 files, removed 0 files
+    [EOF]
+    "#);
+    assert!(!test_env.env_root().join("clone-default/sub/file").exists());
+
+    // The submodule is fetched from the URL relative to the source repo and
+    // checked out
+    let output = root_dir.run_jj([
+        "git",
+        "clone",
+        "--config=git.update-submodules=true",
+        "source",
+        "clone",
+    ]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Fetching into new repo in "$TEST_ENV/clone"
+    bookmark: main@origin [new] tracked
+    Fetched 1 submodules
+    Setting the revset alias `trunk()` to `main@origin`
+    Working copy  (@) now at: uuqppmxq 32177512 (empty) (no description set)
+    Parent commit (@-)      : upwluzus 8f0b1221 main | add submodule
+    Added 2 files, modified 0 files, removed 0 files
+    [EOF]
+    "#);
+    let clone_dir = test_env.work_dir("clone");
+    assert_eq!(clone_dir.read_file("sub/file"), b"1\n");
+
+    // Checking out another commit in the submodule updates the working copy
+    git::checkout_detached(
+        clone_dir.root().join("sub"),
+        &sub_commit2.commit_id.to_string(),
+    );
+    let output = clone_dir.run_jj(["diff", "--summary"]);
+    insta::assert_snapshot!(output, @r"
+    M sub
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_git_clone_colocate() {
     let test_env = TestEnvironment::default();
@@ -962,7 +962,7 @@ fn test_git_colocated_undo_head_move() {
     let output = work_dir.run_jj(["undo"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Undid operation: f349e313234e (2001-02-03 08:05:13) new empty commit
+    Undid operation: 455d6da90cc1 (2001-02-03 08:05:13) new empty commit
     Working copy  (@) now at: royxmykx e7d0d5fd (empty) (no description set)
     Parent commit (@-)      : qpvuntsm e8849ae1 (empty) (no description set)
     [EOF]
@@ -1632,6 +1632,65 @@ fn test_git_colocated_operation_cleanup() {
     insta::assert_snapshot!(String::from_utf8(output.stdout).unwrap(), @"");
 }
 
+#[test]
+fn test_git_colocated_workspaces() {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("file", "base");
+    work_dir.run_jj(["commit", "-mbase"]).success();
+    work_dir
+        .run_jj(["workspace", "add", "--name=second", "../second"])
+        .success();
+    let second_dir = test_env.work_dir("second");
+
+    // The secondary workspace is backed by a linked Git worktree
+    assert!(second_dir.root().join(".git").is_file());
+    assert!(work_dir.root().join(".git/worktrees/second").is_dir());
+    let git_repo = git::open(work_dir.root());
+    let second_git_repo = git::open(second_dir.root());
+    insta::assert_snapshot!(
+        second_git_repo.head_id().unwrap().to_string(),
+        @"639167ef6cb7ea27315913b7a8121e5e31afec70"
+    );
+
+    // Committing in the secondary workspace moves only its own Git HEAD
+    second_dir.write_file("file", "second");
+    second_dir.run_jj(["commit", "-msecond"]).success();
+    insta::assert_snapshot!(
+        second_git_repo.head_id().unwrap().to_string(),
+        @"eea61effb47d0588bffa7d320769174979c628a2"
+    );
+    insta::assert_snapshot!(git_repo.head_id().unwrap().to_string(), @"639167ef6cb7ea27315913b7a8121e5e31afec70");
+
+    // Moving the worktree HEAD in Git is imported into the secondary workspace
+    let base_id = git_repo.head_id().unwrap().detach();
+    git::set_head_to_id(&second_git_repo, base_id);
+    let output = second_dir.run_jj(["log", "-r=@-", "-T=description"]);
+    insta::assert_snapshot!(output, @r"
+    ○  base
+    │
+    ~
+    [EOF]
+    ------- stderr -------
+    Reset the working copy parent to the new Git HEAD.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-r=@-", "-T=description"]);
+    insta::assert_snapshot!(output, @r"
+    ○  base
+    │
+    ~
+    [EOF]
+    ");
+
+    // Forgetting the workspace removes the worktree
+    work_dir.run_jj(["workspace", "forget", "second"]).success();
+    assert!(!work_dir.root().join(".git/worktrees/second").exists());
+}
+
 #[must_use]
 fn get_bookmark_output(work_dir: &TestWorkDir) -> CommandOutput {
     // --quiet to suppress deleted bookmarks hint
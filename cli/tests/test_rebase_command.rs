@@ -31,14 +31,20 @@ fn test_rebase_invalid() {
     let output = work_dir.run_jj(["rebase"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    error: the following required arguments were not provided:
-      <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
-
-    Usage: jj rebase <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
+    Error: No destination specified and no base bookmark found
+    Hint: Use --destination to specify where to rebase the revisions to, or set a base bookmark with `jj bookmark describe --base`.
+    [EOF]
+    [exit status: 1]
+    ");
 
-    For more information, try '--help'.
+    // Missing destination with -r
+    let output = work_dir.run_jj(["rebase", "-r", "a"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No destination specified
+    Hint: Use --destination, --insert-after, or --insert-before to specify where to rebase the revisions to.
     [EOF]
-    [exit status: 2]
+    [exit status: 1]
     ");
 
     // Both -r and -s
@@ -47,7 +53,7 @@ fn test_rebase_invalid() {
     ------- stderr -------
     error: the argument '--revisions <REVSETS>' cannot be used with '--source <REVSETS>'
 
-    Usage: jj rebase --revisions <REVSETS> <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
+    Usage: jj rebase --revisions <REVSETS> --destination <REVSETS>
 
     For more information, try '--help'.
     [EOF]
@@ -60,7 +66,7 @@ fn test_rebase_invalid() {
     ------- stderr -------
     error: the argument '--branch <REVSETS>' cannot be used with '--source <REVSETS>'
 
-    Usage: jj rebase --branch <REVSETS> <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
+    Usage: jj rebase --branch <REVSETS> --destination <REVSETS>
 
     For more information, try '--help'.
     [EOF]
@@ -73,7 +79,7 @@ fn test_rebase_invalid() {
     ------- stderr -------
     error: the argument '--destination <REVSETS>' cannot be used with '--insert-after <REVSETS>'
 
-    Usage: jj rebase --revisions <REVSETS> <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
+    Usage: jj rebase --revisions <REVSETS> --destination <REVSETS>
 
     For more information, try '--help'.
     [EOF]
@@ -86,7 +92,7 @@ fn test_rebase_invalid() {
     ------- stderr -------
     error: the argument '--destination <REVSETS>' cannot be used with '--insert-before <REVSETS>'
 
-    Usage: jj rebase --revisions <REVSETS> <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
+    Usage: jj rebase --revisions <REVSETS> --destination <REVSETS>
 
     For more information, try '--help'.
     [EOF]
@@ -332,6 +338,67 @@ fn test_rebase_bookmark_with_merge() {
     ");
 }
 
+#[test]
+fn test_rebase_bookmark_base() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit(&work_dir, "a", &[]);
+    create_commit(&work_dir, "b", &["a"]);
+    create_commit(&work_dir, "c", &["b"]);
+    create_commit(&work_dir, "d", &["b"]);
+    create_commit(&work_dir, "e", &["a"]);
+
+    // No base bookmark in the stack
+    let output = work_dir.run_jj(["rebase", "-b", "c"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No destination specified and no base bookmark found
+    Hint: Use --destination to specify where to rebase the revisions to, or set a base bookmark with `jj bookmark describe --base`.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // The base of "c" is followed through "b", which is in the same stack
+    work_dir
+        .run_jj(["bookmark", "describe", "c", "--base", "b"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "describe", "b", "--base", "e"])
+        .success();
+    let output = work_dir.run_jj(["rebase", "-b", "c"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Rebased 3 commits to destination
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    ○  d: b
+    │ ○  c: b
+    ├─╯
+    ○  b: e
+    @  e: a
+    ○  a
+    ◆
+    [EOF]
+    ");
+
+    // Bookmarks in the stack disagree on the base
+    work_dir.run_jj(["undo"]).success();
+    work_dir
+        .run_jj(["bookmark", "describe", "c", "--base", "a"])
+        .success();
+    let output = work_dir.run_jj(["rebase", "-b", "c"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Multiple base bookmarks found: a, e
+    Hint: Use --destination to specify where to rebase the revisions to.
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_rebase_single_revision() {
     let test_env = TestEnvironment::default();
@@ -2728,8 +2795,9 @@ fn test_rebase_skip_emptied() {
     let output = work_dir.run_jj(["rebase", "-d=b", "--skip-emptied"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
+    Abandoned 1 commits that were already present in the destination:
+      royxmykx dc725230 will become empty
     Rebased 2 commits to destination
-    Abandoned 1 newly emptied commits
     Working copy  (@) now at: yostqsxw f2f45f17 (empty) also already empty
     Parent commit (@-)      : vruxwmqv fe22a09e (empty) already empty
     [EOF]
@@ -2767,8 +2835,9 @@ fn test_rebase_skip_emptied() {
     ]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
+    Abandoned 1 commits that were already present in the destination:
+      royxmykx dc725230 will become empty
     Rebased 2 descendant commits
-    Abandoned 1 newly emptied commits
     Working copy  (@) now at: yostqsxw a9d31081 (empty) also already empty
     Parent commit (@-)      : vruxwmqv 39722460 (empty) already empty
     Added 0 files, modified 0 files, removed 1 files
@@ -2843,6 +2912,45 @@ fn test_rebase_skip_emptied_descendants() {
     ");
 }
 
+#[test]
+fn test_rebase_skip_emptied_landed() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "a", &[], &[("file", "1\n2\n3\n")]);
+    create_commit_with_files(&work_dir, "b", &["a"], &[("file", "1\ntwo\n3\n")]);
+    create_commit(&work_dir, "c", &["b"]);
+    // Cherry-picked with different whitespace, so rebasing "b" would conflict
+    create_commit_with_files(&work_dir, "upstream", &["a"], &[("file", "1\n two\n3\n")]);
+    // Test the setup
+    insta::assert_snapshot!(work_dir.run_jj(["log", "-T", "description"]), @r"
+    @  upstream
+    │ ○  c
+    │ ○  b
+    ├─╯
+    ○  a
+    ◆
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["rebase", "-s=b", "-d=upstream", "--skip-emptied"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Abandoned 1 commits that were already present in the destination:
+      zsuskuln 454e1fa2 b | b
+    Rebased 1 commits to destination
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["log", "-T", "description"]), @r"
+    ○  c
+    @  upstream
+    ○  a
+    ◆
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_rebase_skip_if_on_destination() {
     let test_env = TestEnvironment::default();
@@ -14,6 +14,8 @@
 
 use std::io::Write as _;
 
+use indoc::indoc;
+
 use crate::common::TestEnvironment;
 
 #[test]
@@ -66,11 +68,18 @@ fn test_sparse_manage_patterns() {
     let output = sub_dir.run_jj(["sparse", "set", "--add=../file2"]);
     insta::assert_snapshot!(output, @r#"
     ------- stderr -------
-    error: invalid value '../file2' for '--add <ADD>': Invalid component ".." in repo-relative path "../file2"
-
-    For more information, try '--help'.
+    Error: Failed to parse fileset: Invalid file pattern
+    Caused by:
+    1:  --> 1:1
+      |
+    1 | ../file2
+      | ^------^
+      |
+      = Invalid file pattern
+    2: Path "../file2" is not in the repo "."
+    3: Invalid component ".." in repo-relative path "../file2"
     [EOF]
-    [exit status: 2]
+    [exit status: 1]
     "#);
 
     // Can `--add` a few files
@@ -182,6 +191,177 @@ fn test_sparse_manage_patterns() {
     ");
 }
 
+#[test]
+fn test_sparse_fileset_patterns() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("README.md", "contents");
+    work_dir.create_dir("docs");
+    work_dir.write_file("docs/guide.md", "contents");
+    work_dir.write_file("docs/logo.png", "contents");
+    work_dir.create_dir("src");
+    work_dir.write_file("src/main.rs", "contents");
+
+    // Patterns are fileset expressions relative to the workspace root
+    let sub_dir = work_dir.create_dir("sub");
+    let output = sub_dir.run_jj([
+        "sparse",
+        "set",
+        "--clear",
+        "--add",
+        "glob:'**/*.md'",
+        "--add",
+        "src",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Added 0 files, modified 0 files, removed 1 files
+    [EOF]
+    ");
+    let output = sub_dir.run_jj(["sparse", "list"]);
+    insta::assert_snapshot!(output, @r"
+    src
+    glob:'**/*.md'
+    [EOF]
+    ");
+    assert!(work_dir.root().join("README.md").exists());
+    assert!(work_dir.root().join("docs/guide.md").exists());
+    assert!(!work_dir.root().join("docs/logo.png").exists());
+    assert!(work_dir.root().join("src/main.rs").exists());
+
+    // Patterns can be removed as written
+    let output = sub_dir.run_jj(["sparse", "set", "--remove", "glob:'**/*.md'"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Added 0 files, modified 0 files, removed 2 files
+    [EOF]
+    ");
+    assert!(!work_dir.root().join("README.md").exists());
+    assert!(work_dir.root().join("src/main.rs").exists());
+
+    // Invalid patterns are rejected
+    let output = sub_dir.run_jj(["sparse", "set", "--add", "glob:("]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to parse fileset: Syntax error
+    Caused by:  --> 1:6
+      |
+    1 | glob:(
+      |      ^---
+      |
+      = expected <identifier>, <bare_string>, <string_literal>, or <raw_string_literal>
+    Hint: See https://jj-vcs.github.io/jj/latest/filesets/ or use `jj help -k filesets` for filesets syntax and how to match file paths.
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_sparse_use_profile() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.create_dir(".jj-sparse");
+    work_dir.write_file(
+        ".jj-sparse/backend.toml",
+        indoc! {r#"
+            include = ["common"]
+            patterns = ["backend"]
+        "#},
+    );
+    work_dir.write_file(".jj-sparse/common.toml", r#"patterns = ["build"]"#);
+    work_dir.write_file(".jj-sparse/cycle.toml", r#"include = ["cycle"]"#);
+    for dir in ["backend", "build", "frontend"] {
+        work_dir.create_dir(dir);
+        work_dir.write_file(format!("{dir}/file"), "contents");
+    }
+    work_dir
+        .run_jj(["commit", "-m", "backend profile"])
+        .success();
+
+    // Profiles are resolved in the working-copy commit
+    let output = work_dir.run_jj(["sparse", "use", "backend"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Added 0 files, modified 0 files, removed 1 files
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["sparse", "list"]);
+    insta::assert_snapshot!(output, @r"
+    .jj-sparse
+    backend
+    build
+    [EOF]
+    ------- stderr -------
+    Using sparse profile backend
+    [EOF]
+    ");
+    assert!(work_dir.root().join("backend/file").exists());
+    assert!(work_dir.root().join("build/file").exists());
+    assert!(!work_dir.root().join("frontend/file").exists());
+
+    // Checking out a commit with a different version of the profile updates
+    // the patterns
+    work_dir.write_file(".jj-sparse/common.toml", r#"patterns = ["frontend"]"#);
+    work_dir
+        .run_jj(["commit", "-m", "add frontend to profile"])
+        .success();
+    work_dir
+        .run_jj(["new", "description('backend profile')"])
+        .success();
+    assert!(work_dir.root().join("build/file").exists());
+    assert!(!work_dir.root().join("frontend/file").exists());
+    let output = work_dir.run_jj(["new", "description('add frontend')"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: yqosqzyt f93b8bfa (empty) (no description set)
+    Parent commit (@-)      : rlvkpnrz 700f4953 add frontend to profile
+    Added 1 files, modified 1 files, removed 1 files
+    [EOF]
+    ");
+    assert!(work_dir.root().join("backend/file").exists());
+    assert!(!work_dir.root().join("build/file").exists());
+    assert!(work_dir.root().join("frontend/file").exists());
+
+    // Setting patterns stops using the profile
+    work_dir
+        .run_jj(["sparse", "set", "--remove", "frontend"])
+        .success();
+    let output = work_dir.run_jj(["new", "description('backend profile')"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: yostqsxw 660dcbb8 (empty) (no description set)
+    Parent commit (@-)      : qpvuntsm c5abdc90 backend profile
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["sparse", "list"]);
+    insta::assert_snapshot!(output, @r"
+    .jj-sparse
+    backend
+    [EOF]
+    ");
+
+    // Invalid profiles are rejected
+    let output = work_dir.run_jj(["sparse", "use", "missing"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: Sparse profile "missing" doesn't exist
+    [EOF]
+    [exit status: 1]
+    "#);
+    let output = work_dir.run_jj(["sparse", "use", "cycle"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: Sparse profile "cycle" includes itself
+    [EOF]
+    [exit status: 1]
+    "#);
+}
+
 #[test]
 fn test_sparse_editor_avoids_unc() {
     use std::path::PathBuf;
@@ -14,8 +14,10 @@
 
 use std::path::PathBuf;
 
+use indoc::indoc;
 use test_case::test_case;
 
+use crate::common::force_interactive;
 use crate::common::CommandOutput;
 use crate::common::TestEnvironment;
 use crate::common::TestWorkDir;
@@ -835,6 +837,150 @@ fn test_split_interactive() {
     ");
 }
 
+#[test]
+fn test_split_patch() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "a\nb\nc\nd\ne\nf\ng\nh\ni\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file1", "A\nb\nc\nd\ne\nf\ng\nh\nI\n");
+    work_dir.write_file("file2", "new\n");
+
+    // Select only the second hunk of file1, and quit before file2
+    let output = work_dir.run_jj_with(|cmd| {
+        force_interactive(cmd)
+            .args(["split", "--patch", "-m", "first"])
+            .write_stdin("n\ny\nq\n")
+    });
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    --- a/file1
+    +++ b/file1
+    @@ -1,4 +1,4 @@
+    -a
+    +A
+     b
+     c
+     d
+    (1/2) Select this hunk [y,n,a,d,e,q,?]: @@ -6,4 +6,4 @@
+     f
+     g
+     h
+    -i
+    +I
+    (2/2) Select this hunk [y,n,a,d,e,q,?]: --- /dev/null
+    +++ b/file2
+    new file mode 100644
+    +new
+    (1/1) Select creation of this file [y,n,a,d,q,?]: Selected changes : rlvkpnrz 18c8f71e first
+    Remaining changes: kkmpptxz 73e7ef14 (no description set)
+    Working copy  (@) now at: kkmpptxz 73e7ef14 (no description set)
+    Parent commit (@-)      : rlvkpnrz 18c8f71e first
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["log", "--summary"]);
+    insta::assert_snapshot!(output, @r"
+    @  kkmpptxz test.user@example.com 2001-02-03 08:05:09 73e7ef14
+    │  (no description set)
+    │  M file1
+    │  A file2
+    ○  rlvkpnrz test.user@example.com 2001-02-03 08:05:09 18c8f71e
+    │  first
+    │  M file1
+    ○  qpvuntsm test.user@example.com 2001-02-03 08:05:08 d7ccac4b
+    │  (no description set)
+    │  A file1
+    ◆  zzzzzzzz root() 00000000
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_split_select() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "a\nb\nc\nd\ne\nf\ng\nh\ni\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file1", "A\nb\nc\nd\ne\nf\ng\nh\nI\n");
+    work_dir.write_file("file2", "new\n");
+
+    // Select the second hunk of file1 by line number, and file2 by patch
+    let patch = indoc! {"
+        diff --git a/file2 b/file2
+        new file mode 100644
+        --- /dev/null
+        +++ b/file2
+        @@ -0,0 +1,1 @@
+        +new
+    "};
+    let output = work_dir.run_jj_with(|cmd| {
+        cmd.args([
+            "split",
+            "--select=file1:8-9",
+            "--select-patch=-",
+            "-m",
+            "first",
+        ])
+        .write_stdin(patch)
+    });
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Selected changes : rlvkpnrz 3f6f5e86 first
+    Remaining changes: kkmpptxz 83d0b203 (no description set)
+    Working copy  (@) now at: kkmpptxz 83d0b203 (no description set)
+    Parent commit (@-)      : rlvkpnrz 3f6f5e86 first
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["diff", "--git", "-r@-"]);
+    insta::assert_snapshot!(output, @r"
+    diff --git a/file1 b/file1
+    index b236ae5a2d..492f771c08 100644
+    --- a/file1
+    +++ b/file1
+    @@ -6,4 +6,4 @@
+     f
+     g
+     h
+    -i
+    +I
+    diff --git a/file2 b/file2
+    new file mode 100644
+    index 0000000000..3e757656cf
+    --- /dev/null
+    +++ b/file2
+    @@ -0,0 +1,1 @@
+    +new
+    [EOF]
+    ");
+
+    // Invalid line range
+    let output = work_dir.run_jj(["split", "--select=file1:2-1"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Invalid line range: 2-1
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Malformed patch
+    let output = work_dir.run_jj_with(|cmd| {
+        cmd.args(["split", "--select-patch=-"])
+            .write_stdin("diff --git a/file1 b/file1\n@@ bad @@\n")
+    });
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to parse patch at line 2: Invalid hunk header
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_split_interactive_with_paths() {
     let mut test_env = TestEnvironment::default();
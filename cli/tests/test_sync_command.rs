@@ -0,0 +1,171 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::CommandOutput;
+use crate::common::TestEnvironment;
+use crate::common::TestWorkDir;
+
+#[must_use]
+fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
+    let template = r#"
+    separate(" ",
+      description.first_line(),
+      bookmarks,
+      if(immutable, "immutable"),
+      if(conflict, "conflict"),
+    ) ++ "\n"
+    "#;
+    work_dir.run_jj(["log", "-T", template])
+}
+
+fn create_commit_with_file(work_dir: &TestWorkDir, parent: &str, name: &str, content: &str) {
+    work_dir.run_jj(["new", parent, "-m", name]).success();
+    work_dir.write_file(name, content);
+}
+
+#[test]
+fn test_sync() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "remote"]).success();
+    let remote_dir = test_env.work_dir("remote");
+    create_commit_with_file(&remote_dir, "root()", "base", "base");
+    remote_dir
+        .run_jj(["bookmark", "create", "-r@", "main"])
+        .success();
+    remote_dir.run_jj(["new"]).success();
+    remote_dir.run_jj(["git", "export"]).success();
+
+    let mut remote_git_path = remote_dir.root().to_owned();
+    remote_git_path.extend([".jj", "repo", "store", "git"]);
+    test_env
+        .run_jj_in(
+            ".",
+            ["git", "clone", remote_git_path.to_str().unwrap(), "local"],
+        )
+        .success();
+    let local_dir = test_env.work_dir("local");
+
+    // A commit that will be rebased upstream, keeping its change ID
+    create_commit_with_file(&local_dir, "main@origin", "landed", "landed");
+    local_dir
+        .run_jj(["bookmark", "create", "-r@", "landed"])
+        .success();
+    local_dir
+        .run_jj(["git", "push", "--bookmark", "landed", "--allow-new"])
+        .success();
+    // Untracked so that fetching doesn't abandon the local commit
+    local_dir
+        .run_jj(["bookmark", "untrack", "landed@origin"])
+        .success();
+    create_commit_with_file(&local_dir, "landed", "on landed", "on landed");
+    // A stack that will be squash-merged upstream
+    create_commit_with_file(&local_dir, "main@origin", "squashed", "squashed");
+    // A stack that will conflict with upstream
+    create_commit_with_file(&local_dir, "main@origin", "conflicting", "local");
+    local_dir.run_jj(["file", "track", "conflicting"]).success();
+    // A stack based on a commit that isn't in trunk()
+    create_commit_with_file(&local_dir, "root()", "unrelated", "unrelated");
+    local_dir.run_jj(["new", "main@origin"]).success();
+
+    // Land the changes upstream
+    remote_dir.run_jj(["git", "import"]).success();
+    create_commit_with_file(&remote_dir, "main", "conflicting", "upstream");
+    remote_dir
+        .run_jj(["rebase", "-r", "landed", "-d", "@"])
+        .success();
+    create_commit_with_file(&remote_dir, "landed", "squashed", "squashed");
+    remote_dir
+        .run_jj(["bookmark", "set", "-r@", "main"])
+        .success();
+    remote_dir.run_jj(["new"]).success();
+    remote_dir.run_jj(["git", "export"]).success();
+
+    insta::assert_snapshot!(get_log_output(&local_dir), @r"
+    @
+    │ ○  conflicting
+    ├─╯
+    │ ○  squashed
+    ├─╯
+    │ ○  on landed
+    │ ◆  landed landed landed@origin immutable
+    ├─╯
+    ◆  base main@origin immutable
+    │ ○  unrelated
+    ├─╯
+    ◆  immutable
+    [EOF]
+    ");
+    let output = local_dir.run_jj(["sync"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    bookmark: landed@origin [updated] untracked
+    bookmark: main@origin   [updated] untracked
+    Rebased 4 stacks onto trunk()
+    Abandoned 2 commits that landed upstream:
+      yqosqzyt 6176c3a9 landed landed@origin | landed
+      kmkuslsw 82077af7 squashed
+    Warning: 1 stacks have conflicts after the rebase:
+      wqnwkozp 66211ea0 (conflict) conflicting
+    Working copy  (@) now at: nkmrtpmo 04d6380a (empty) (no description set)
+    Parent commit (@-)      : nmzmmopx c3bcea12 landed main@origin | squashed
+    Added 3 files, modified 0 files, removed 0 files
+    New conflicts appeared in 1 commits:
+      wqnwkozp 66211ea0 (conflict) conflicting
+    Hint: To resolve the conflicts, start by creating a commit on top of
+    the conflicted commit:
+      jj new wqnwkozp
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you can inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&local_dir), @r"
+    @
+    │ ×  conflicting conflict
+    ├─╯
+    │ ○  on landed
+    ├─╯
+    ◆  squashed landed main@origin immutable
+    ~  (elided revisions)
+    │ ○  unrelated
+    ├─╯
+    ◆  immutable
+    [EOF]
+    ");
+
+    // Nothing to do
+    let output = local_dir.run_jj(["sync"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    All stacks are already based on trunk()
+    Nothing changed.
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_sync_no_remotes() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let output = work_dir.run_jj(["sync"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Warning: No git remotes matching 'origin'
+    Error: No git remotes to push
+    [EOF]
+    [exit status: 1]
+    ");
+}
@@ -48,6 +48,41 @@ fn test_undo_merge_operation() {
     ------- stderr -------
     Concurrent modification detected, resolving automatically.
     Error: Cannot undo a merge operation
+    Hint: Use `jj op undo --parent` to choose the parent operation to revert to
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Revert to one of the parents of the merge operation
+    let output = work_dir
+        .run_jj([
+            "op",
+            "log",
+            "--no-graph",
+            r#"-T=id.short() ++ "\n""#,
+            "-n=3",
+        ])
+        .success();
+    let op_ids = output.stdout.raw().lines().collect::<Vec<_>>();
+    let output = work_dir.run_jj(["op", "undo", "--parent", op_ids[1]]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Undid operation: 5c6572d3dd58 (2001-02-03 08:05:10) reconcile divergent operations
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-T=commit_id.short()"]);
+    insta::assert_snapshot!(output, @r"
+    @  43444d88b009
+    ○  e8849ae12c70
+    ◆  000000000000
+    [EOF]
+    ");
+
+    // The parent must be a parent of the operation being undone
+    let output = work_dir.run_jj(["op", "undo", "--parent", op_ids[2]]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Operation b6c93bcb296f is not a parent of operation 265b3ade3e84
     [EOF]
     [exit status: 1]
     ");
@@ -440,7 +475,7 @@ fn test_bookmark_track_untrack_undo() {
     [EOF]
     ");
 
-    work_dir.run_jj(["undo"]).success();
+    work_dir.run_jj(["op", "undo"]).success();
     insta::assert_snapshot!(get_bookmark_output(&work_dir), @r"
     feature1: qpvuntsm bab5b5ef (empty) commit
     feature1@origin: qpvuntsm bab5b5ef (empty) commit
@@ -467,6 +502,86 @@ fn test_bookmark_track_untrack_undo() {
     ");
 }
 
+#[test]
+fn test_op_restore_selected_portions() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-m=one"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "foo", "bar"])
+        .success();
+    work_dir.run_jj(["new", "-m=two"]).success();
+    let output = work_dir
+        .run_jj(["log", "--no-graph", "-r@", "-T=commit_id"])
+        .success();
+    let commit_id = output.stdout.into_raw();
+    let output = work_dir
+        .run_jj(["op", "log", "--no-graph", "-n=1", "-T=id.short()"])
+        .success();
+    let op_id = output.stdout.into_raw();
+
+    work_dir
+        .run_jj(["bookmark", "delete", "foo", "bar"])
+        .success();
+    work_dir.run_jj(["abandon"]).success();
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    @
+    ○  one
+    ◆
+    [EOF]
+    ");
+
+    // Only the selected bookmark is restored
+    let output = work_dir.run_jj(["op", "restore", &op_id, "--bookmark=foo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Restored to operation: 46f79d1cf624 (2001-02-03 08:05:10) new empty commit
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    @
+    ○  one foo
+    ◆
+    [EOF]
+    ");
+
+    // Only the visibility of the selected commit is restored
+    let output = work_dir.run_jj(["op", "restore", &op_id, "--revision", &commit_id]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Restored to operation: 46f79d1cf624 (2001-02-03 08:05:10) new empty commit
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    @
+    │ ○  two
+    ├─╯
+    ○  one foo
+    ◆
+    [EOF]
+    ");
+
+    // Only the working-copy commit of the selected workspace is restored
+    let output = work_dir.run_jj(["op", "restore", &op_id, "--workspace=default"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Restored to operation: 46f79d1cf624 (2001-02-03 08:05:10) new empty commit
+    Working copy  (@) now at: zsuskuln d472c8ab (empty) two
+    Parent commit (@-)      : qpvuntsm 401ea16f foo | (empty) one
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    @  two
+    │ ○
+    ├─╯
+    ○  one foo
+    ◆
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_undo_latest_undo_implicitly() {
     let test_env = TestEnvironment::default();
@@ -476,10 +591,10 @@ fn test_undo_latest_undo_implicitly() {
     // Double-undo creation of child
     work_dir.run_jj(["new"]).success();
     work_dir.run_jj(["undo"]).success();
-    let output = work_dir.run_jj(["undo"]);
+    let output = work_dir.run_jj(["op", "undo"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Undid operation: 6de77c1b46a3 (2001-02-03 08:05:09) undo operation dbcb2561b6fee72ea6de79511b6b62f1fff2424f79d16dd30339f94621100f77c86ca7450f7b1ec1bd95d4d56b7a54fe3f3e612353e62cedc682366211b4144e
+    Undid operation: 9f6bb7b5d643 (2001-02-03 08:05:09) undo operation dbcb2561b6fee72ea6de79511b6b62f1fff2424f79d16dd30339f94621100f77c86ca7450f7b1ec1bd95d4d56b7a54fe3f3e612353e62cedc682366211b4144e
     Working copy  (@) now at: rlvkpnrz 43444d88 (empty) (no description set)
     Parent commit (@-)      : qpvuntsm e8849ae1 (empty) (no description set)
     Warning: The second-last `jj undo` was reverted by the latest `jj undo`. The repo is now in the same state as it was before the second-last `jj undo`.
@@ -490,10 +605,10 @@ fn test_undo_latest_undo_implicitly() {
     // Double-undo creation of sibling
     work_dir.run_jj(["new", "@-"]).success();
     work_dir.run_jj(["undo"]).success();
-    let output = work_dir.run_jj(["undo"]);
+    let output = work_dir.run_jj(["op", "undo"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Undid operation: b77c991c5a2f (2001-02-03 08:05:12) undo operation 58f57841c00da755413d291ed9e1a1d9a58dd4311b5000a8703f1bf93339dd12cdbc2c6e1c8cd5f43cb584cabddcf8366153a007c244d89ee80a4e42e513058d
+    Undid operation: d4af6dbf3dff (2001-02-03 08:05:12) undo operation 987981cfe3fe927dacf54a6133406e41a226abc9fefaf3f7cc590e4b5702e7afbfe0f3f2409c17512cef8b51ddfce0d07b88310686d598efe66419bdf5128838
     Working copy  (@) now at: mzvwutvl 8afc18ff (empty) (no description set)
     Parent commit (@-)      : qpvuntsm e8849ae1 (empty) (no description set)
     Warning: The second-last `jj undo` was reverted by the latest `jj undo`. The repo is now in the same state as it was before the second-last `jj undo`.
@@ -517,7 +632,7 @@ fn test_undo_latest_undo_explicitly() {
     let output = work_dir.run_jj(["undo", op_id_hex]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Undid operation: 6de77c1b46a3 (2001-02-03 08:05:09) undo operation dbcb2561b6fee72ea6de79511b6b62f1fff2424f79d16dd30339f94621100f77c86ca7450f7b1ec1bd95d4d56b7a54fe3f3e612353e62cedc682366211b4144e
+    Undid operation: 9f6bb7b5d643 (2001-02-03 08:05:09) undo operation dbcb2561b6fee72ea6de79511b6b62f1fff2424f79d16dd30339f94621100f77c86ca7450f7b1ec1bd95d4d56b7a54fe3f3e612353e62cedc682366211b4144e
     Working copy  (@) now at: rlvkpnrz 43444d88 (empty) (no description set)
     Parent commit (@-)      : qpvuntsm e8849ae1 (empty) (no description set)
     [EOF]
@@ -528,7 +643,7 @@ fn test_undo_latest_undo_explicitly() {
     let output = work_dir.run_jj(["undo", "@"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Undid operation: c99ea8473832 (2001-02-03 08:05:13) undo operation 09cbd5c994ee9e437950a49fa4400d3c6e9d5c1e44ea55b38806a9692317716647578328c53b887a758a40270ad8ab6d5b67c1675d088281879cc7c74a2da6cc
+    Undid operation: 206ccbe62f2b (2001-02-03 08:05:13) undo operation 95a8675036acf60a69755ffcb0fd75a6d1201b6323088e11675ee201e87decb699aeb940601d96e8b17f15dfedd9d19749626796eefa7fa46249ba896982584b
     Working copy  (@) now at: royxmykx ba0e5dca (empty) (no description set)
     Parent commit (@-)      : rlvkpnrz 43444d88 (empty) (no description set)
     Warning: The second-last `jj undo` was reverted by the latest `jj undo`. The repo is now in the same state as it was before the second-last `jj undo`.
@@ -554,7 +669,7 @@ fn test_undo_an_older_undo() {
     let output = work_dir.run_jj(["undo", op_id_hex]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Undid operation: 6de77c1b46a3 (2001-02-03 08:05:09) undo operation dbcb2561b6fee72ea6de79511b6b62f1fff2424f79d16dd30339f94621100f77c86ca7450f7b1ec1bd95d4d56b7a54fe3f3e612353e62cedc682366211b4144e
+    Undid operation: 9f6bb7b5d643 (2001-02-03 08:05:09) undo operation dbcb2561b6fee72ea6de79511b6b62f1fff2424f79d16dd30339f94621100f77c86ca7450f7b1ec1bd95d4d56b7a54fe3f3e612353e62cedc682366211b4144e
     [EOF]
     ");
 
@@ -565,7 +680,7 @@ fn test_undo_an_older_undo() {
     let output = work_dir.run_jj(["undo", "@-"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Undid operation: ffea82d2bf89 (2001-02-03 08:05:14) undo operation 2b3480d4ece49d59f5da51e1284b742773e6b858935f122bebab625430137f9aae1aaebf7a8c081e0715e9cd24deee9d0d039743fbb1dd61804c633c9c7f17a2
+    Undid operation: 83071c59aa84 (2001-02-03 08:05:14) undo operation c94c9a05916c3cec3c5f41168f4363eae3fa4a30cff524a1b2930c0b79a9f30128b5b544e29577ea74b1cde9ae54798b77bb6b8731a87c7b298707b2dfbaeea8
     [EOF]
     ");
 }
@@ -578,20 +693,20 @@ fn test_undo_an_undo_multiple_times() {
 
     work_dir.run_jj(["new"]).success();
     work_dir.run_jj(["undo"]).success();
-    let output = work_dir.run_jj(["undo"]);
+    let output = work_dir.run_jj(["op", "undo"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Undid operation: 6de77c1b46a3 (2001-02-03 08:05:09) undo operation dbcb2561b6fee72ea6de79511b6b62f1fff2424f79d16dd30339f94621100f77c86ca7450f7b1ec1bd95d4d56b7a54fe3f3e612353e62cedc682366211b4144e
+    Undid operation: 9f6bb7b5d643 (2001-02-03 08:05:09) undo operation dbcb2561b6fee72ea6de79511b6b62f1fff2424f79d16dd30339f94621100f77c86ca7450f7b1ec1bd95d4d56b7a54fe3f3e612353e62cedc682366211b4144e
     Working copy  (@) now at: rlvkpnrz 43444d88 (empty) (no description set)
     Parent commit (@-)      : qpvuntsm e8849ae1 (empty) (no description set)
     Warning: The second-last `jj undo` was reverted by the latest `jj undo`. The repo is now in the same state as it was before the second-last `jj undo`.
     Hint: To undo multiple operations, use `jj op log` to see past states and `jj op restore` to restore one of these states.
     [EOF]
     ");
-    let output = work_dir.run_jj(["undo"]);
+    let output = work_dir.run_jj(["op", "undo"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Undid operation: f17b43a603fd (2001-02-03 08:05:10) undo operation 6de77c1b46a39115938845d2fffb5780a49a4991d1f8f99b60509705faa38d496b06cdbb427a497ff22d1f2b81613ac80a8dd6b97ca2fa35d7cc66d2a33059e4
+    Undid operation: d263105bca1c (2001-02-03 08:05:10) undo operation 9f6bb7b5d6439373f113d226a724d9314a788be57beea822c011cc795ef4de0c6d4af203638110d8fd40aed5fbe6ba3b2957c1bc3e5ddf5cc9fa722b9af99042
     Working copy  (@) now at: qpvuntsm e8849ae1 (empty) (no description set)
     Parent commit (@-)      : zzzzzzzz 00000000 (empty) (no description set)
     Warning: The second-last `jj undo` was reverted by the latest `jj undo`. The repo is now in the same state as it was before the second-last `jj undo`.
@@ -601,20 +716,20 @@ fn test_undo_an_undo_multiple_times() {
 
     work_dir.run_jj(["new"]).success();
     work_dir.run_jj(["undo"]).success();
-    let output = work_dir.run_jj(["undo"]);
+    let output = work_dir.run_jj(["op", "undo"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Undid operation: 1eee1feca2d3 (2001-02-03 08:05:13) undo operation 18ee4719e7c7a2da3fbeec66678dfb03c4f3deebbe887dbc46270102c86894615a07a223713981d12b3ddc757e5104c7e9f7b391202747cd1868e0d2ac01f35d
+    Undid operation: ec749fed6314 (2001-02-03 08:05:13) undo operation 04cb43f2aac98c258dd9da52f05fde70097a8a0448919b9a5647d157f52a75c0d896aa519fd36796970a5c21e95b281278c9a5d20706354fdcbc727c7e00e2d0
     Working copy  (@) now at: royxmykx e7d0d5fd (empty) (no description set)
     Parent commit (@-)      : qpvuntsm e8849ae1 (empty) (no description set)
     Warning: The second-last `jj undo` was reverted by the latest `jj undo`. The repo is now in the same state as it was before the second-last `jj undo`.
     Hint: To undo multiple operations, use `jj op log` to see past states and `jj op restore` to restore one of these states.
     [EOF]
     ");
-    let output = work_dir.run_jj(["undo", "@"]);
+    let output = work_dir.run_jj(["op", "undo", "@"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Undid operation: 079cb7121929 (2001-02-03 08:05:14) undo operation 1eee1feca2d334e1c7c956048eec1e54a35d58b39aaec88c8a48ce7ce216b30e287f49c0305c51ad86292c6a2e11dd98ed97d8ef0a497e7114cf2aea99d4e71d
+    Undid operation: 0028db1ceefa (2001-02-03 08:05:14) undo operation ec749fed6314550aa9f16a36b8379d4427d55a051dfcfbad60696b4075ded82bd9f2ae44a067f6a4fb9294ca489a22615a2bc870af6771d5ea66af6f4f847e4f
     Working copy  (@) now at: qpvuntsm e8849ae1 (empty) (no description set)
     Parent commit (@-)      : zzzzzzzz 00000000 (empty) (no description set)
     Warning: The second-last `jj undo` was reverted by the latest `jj undo`. The repo is now in the same state as it was before the second-last `jj undo`.
@@ -641,6 +756,211 @@ fn test_undo_bookmark_deletion() {
     ");
 }
 
+#[test]
+fn test_undo_redo_multiple_operations() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-m=one"]).success();
+    work_dir.run_jj(["new", "-m=two"]).success();
+    work_dir.run_jj(["new", "-m=three"]).success();
+
+    // Repeated undo walks back through the operation log
+    let output = work_dir.run_jj(["undo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Undid operation: de6d47709bcc (2001-02-03 08:05:10) new empty commit
+    Working copy  (@) now at: kkmpptxz 2356a492 (empty) two
+    Parent commit (@-)      : qpvuntsm 401ea16f (empty) one
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["undo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Undid operation: 4d522c2ffb33 (2001-02-03 08:05:09) new empty commit
+    Working copy  (@) now at: qpvuntsm 401ea16f (empty) one
+    Parent commit (@-)      : zzzzzzzz 00000000 (empty) (no description set)
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    @  one
+    ◆
+    [EOF]
+    ");
+
+    // Redo re-applies the undone operations in reverse order
+    let output = work_dir.run_jj(["redo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Redid operation: 4d522c2ffb33 (2001-02-03 08:05:09) new empty commit
+    Working copy  (@) now at: kkmpptxz 2356a492 (empty) two
+    Parent commit (@-)      : qpvuntsm 401ea16f (empty) one
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["redo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Redid operation: de6d47709bcc (2001-02-03 08:05:10) new empty commit
+    Working copy  (@) now at: zsuskuln 3bea56cc (empty) three
+    Parent commit (@-)      : kkmpptxz 2356a492 (empty) two
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    @  three
+    ○  two
+    ○  one
+    ◆
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["redo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Nothing to redo
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["op", "log", "-n2"]);
+    insta::assert_snapshot!(output, @r"
+    @  8cb03451de58 test-username@host.example.com 2001-02-03 04:05:15.000 +07:00 - 2001-02-03 04:05:15.000 +07:00
+    │  redo operation de6d47709bcc6db34ab28217e66f664ebd5bd8af480257fe568b379e695877c335dfefe92cdad36083d67981c63b1ae69e311eb31ad4231b477ef3ebd5d812fe
+    │  args: jj redo
+    │  undo-depth: 0
+    ○  c335ae4e75aa test-username@host.example.com 2001-02-03 04:05:14.000 +07:00 - 2001-02-03 04:05:14.000 +07:00
+    │  redo operation 4d522c2ffb3399c2987dbf421669bb3a766de19dc997ddc4f0269fd0bd3c20a44b0b79c960542c8a76e0b7e959709ff9bb67af9a344040ebed05e5c0de89e063
+    │  args: jj redo
+    │  undo-depth: 1
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_undo_redo_new_operation_clears_redo() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-m=one"]).success();
+    work_dir.run_jj(["new", "-m=two"]).success();
+    work_dir.run_jj(["undo"]).success();
+    work_dir.run_jj(["describe", "-m=three"]).success();
+    let output = work_dir.run_jj(["redo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Nothing to redo
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // The next undo starts from the latest operation
+    work_dir.run_jj(["undo"]).success();
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    @  one
+    ◆
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_undo_redo_with_snapshot() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-m=one"]).success();
+    work_dir.run_jj(["new", "-m=two"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@-", "foo"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@-", "bar"])
+        .success();
+    work_dir.run_jj(["undo"]).success();
+
+    // A snapshot of the working copy doesn't reset the undo stack, and the
+    // snapshotted changes are kept
+    work_dir.write_file("file", "contents\n");
+    let output = work_dir.run_jj(["undo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Undid operation: b040b51c68d3 (2001-02-03 08:05:10) create bookmark foo pointing to commit 401ea16fc3fe9927b7c6e535480a94015dfe6206
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    @  two A file
+    ○  one
+    ◆
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["redo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Redid operation: b040b51c68d3 (2001-02-03 08:05:10) create bookmark foo pointing to commit 401ea16fc3fe9927b7c6e535480a94015dfe6206
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    @  two A file
+    ○  one foo
+    ◆
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_undo_redo_what() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(r#"revset-aliases."immutable_heads()" = "none()""#);
+    let git_repo_path = test_env.env_root().join("git-repo");
+    git::init_bare(git_repo_path);
+    test_env
+        .run_jj_in(".", ["git", "clone", "git-repo", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "main"])
+        .success();
+    work_dir.run_jj(["describe", "-m", "AA"]).success();
+    work_dir.run_jj(["git", "push", "--allow-new"]).success();
+    work_dir.run_jj(["describe", "-m", "BB"]).success();
+    work_dir.run_jj(["git", "push"]).success();
+
+    // Undo the push, but only for the remote-tracking bookmarks
+    work_dir
+        .run_jj(["undo", "--what=remote-tracking"])
+        .success();
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @r"
+    main: qpvuntsm b9558b25 (empty) BB
+      @origin (ahead by 1 commits, behind by 1 commits): qpvuntsm hidden 4c7ca6b3 (empty) AA
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["op", "log", "-n1", "-T", "tags"]);
+    insta::assert_snapshot!(output, @r"
+    @  args: jj undo '--what=remote-tracking'
+    │  undo-depth: 1
+    │  undo-what: remote-tracking
+    [EOF]
+    ");
+
+    // Redo restores the same portions
+    work_dir.run_jj(["redo"]).success();
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @r"
+    main: qpvuntsm b9558b25 (empty) BB
+      @origin: qpvuntsm b9558b25 (empty) BB
+    [EOF]
+    ");
+}
+
+#[must_use]
+fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
+    work_dir.run_jj([
+        "log",
+        "-T",
+        r#"separate(" ", description.first_line(), bookmarks, diff.summary())"#,
+    ])
+}
+
 #[must_use]
 fn get_bookmark_output(work_dir: &TestWorkDir) -> CommandOutput {
     // --quiet to suppress deleted bookmarks hint
@@ -62,6 +62,118 @@ fn test_gc_args() {
     [EOF]
     [exit status: 1]
     ");
+
+    let output = work_dir.run_jj(["util", "gc", "--dry-run"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Dry-run garbage collection is not supported by the Git backend
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_gc_simple_backend() {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["debug", "init-simple", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+
+    // Recent objects are kept
+    work_dir.write_file("file", "a change\n");
+    work_dir.run_jj(["abandon"]).success();
+    let output = work_dir.run_jj(["util", "gc", "--dry-run"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Would remove 0 unreachable objects (0.0B)
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["util", "gc"]);
+    insta::assert_snapshot!(output, @"");
+
+    // The abandoned commit is unreachable once the operations referring to it
+    // are removed
+    work_dir.run_jj(["operation", "abandon", "..@-"]).success();
+    let output = work_dir.run_jj(["util", "gc", "--dry-run", "--expire=now"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Would remove 4 unreachable objects (649.0B)
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["util", "gc", "--expire=now"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Removed 4 unreachable objects (649.0B)
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["util", "gc", "--dry-run", "--expire=now"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Would remove 0 unreachable objects (0.0B)
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_fsck() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let output = work_dir.run_jj(["util", "fsck"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Checked 2 operations, 2 commits, and 1 objects
+    No problems found
+    [EOF]
+    ");
+
+    // Operation head pointing to a missing operation
+    let bogus_op_id = "ab".repeat(64);
+    work_dir.write_file(format!(".jj/repo/op_heads/heads/{bogus_op_id}"), "");
+    // Strip OS-dependent error messages
+    let strip_io_errors = |s: String| {
+        s.lines()
+            .filter(|line| !line.starts_with("2: ") && !line.starts_with("3: "))
+            .map(|line| format!("{line}\n"))
+            .collect()
+    };
+    let output = work_dir
+        .run_jj(["util", "fsck"])
+        .normalize_stderr_with(strip_io_errors);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Warning: Operation head abababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababab cannot be read
+    Caused by:
+    1: Object abababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababab of type operation not found
+    Checked 2 operations, 2 commits, and 1 objects
+    Error: Found 1 problems
+    Hint: Run `jj util fsck --repair` to repair 1 of them.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir
+        .run_jj(["util", "fsck", "--repair"])
+        .normalize_stderr_with(strip_io_errors);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Warning: Operation head abababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababab cannot be read
+    Caused by:
+    1: Object abababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababab of type operation not found
+    Checked 2 operations, 2 commits, and 1 objects
+    Repaired 1 of 1 problems
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["util", "fsck"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Checked 2 operations, 2 commits, and 1 objects
+    No problems found
+    [EOF]
+    ");
 }
 
 #[test]
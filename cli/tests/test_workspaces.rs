@@ -1370,6 +1370,148 @@ fn test_workspaces_rename_workspace() {
     ");
 }
 
+#[test]
+fn test_workspaces_add_temporary() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "main"]).success();
+    let main_dir = test_env.work_dir("main");
+    main_dir
+        .run_jj(["workspace", "add", "--temporary", "../scratch"])
+        .success();
+    let scratch_dir = test_env.work_dir("scratch");
+    scratch_dir.write_file("file", "contents");
+    scratch_dir.run_jj(["describe", "-m", "scratch"]).success();
+
+    // Rewriting the working-copy commit doesn't delete the workspace
+    main_dir
+        .run_jj(["describe", "scratch@", "-m", "edited"])
+        .success();
+    assert!(scratch_dir.root().exists());
+
+    // Abandoning it does
+    let output = main_dir.run_jj(["abandon", "scratch@"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Abandoned 1 commits:
+      uuqppmxq 4250b1c7 edited
+    Deleted temporary workspace scratch in "../scratch"
+    [EOF]
+    "#);
+    assert!(!scratch_dir.root().exists());
+    let output = main_dir.run_jj(["workspace", "list"]);
+    insta::assert_snapshot!(output, @r"
+    default: qpvuntsm e8849ae1 (empty) (no description set)
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_workspaces_add_temporary_with_changes() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "main"]).success();
+    let main_dir = test_env.work_dir("main");
+    main_dir
+        .run_jj(["workspace", "add", "--temporary", "../scratch"])
+        .success();
+    let scratch_dir = test_env.work_dir("scratch");
+    scratch_dir.write_file("file", "contents");
+
+    // Changes that weren't snapshotted keep the directory from being deleted
+    let output = main_dir.run_jj(["abandon", "scratch@"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Abandoned 1 commits:
+      uuqppmxq 94f41578 (empty) (no description set)
+    Warning: Temporary workspace scratch in "../scratch" has changes that weren't snapshotted, so it was forgotten but not deleted
+    [EOF]
+    "#);
+    assert!(scratch_dir.root().join("file").exists());
+    let output = main_dir.run_jj(["workspace", "list"]);
+    insta::assert_snapshot!(output, @r"
+    default: qpvuntsm e8849ae1 (empty) (no description set)
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_workspaces_prune() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "main"]).success();
+    let main_dir = test_env.work_dir("main");
+    main_dir.run_jj(["workspace", "add", "../second"]).success();
+    main_dir.run_jj(["workspace", "add", "../third"]).success();
+
+    let output = main_dir.run_jj(["workspace", "prune"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    No workspaces to prune.
+    [EOF]
+    ");
+
+    std::fs::remove_dir_all(test_env.env_root().join("second")).unwrap();
+    let output = main_dir.run_jj(["workspace", "prune"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Forgot 1 workspaces whose directories no longer exist:
+      second: "../second"
+    [EOF]
+    "#);
+    let output = main_dir.run_jj(["workspace", "list"]);
+    insta::assert_snapshot!(output, @r"
+    default: qpvuntsm e8849ae1 (empty) (no description set)
+    third: pmmvwywv 337ba39f (empty) (no description set)
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_workspaces_move() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "main"]).success();
+    let main_dir = test_env.work_dir("main");
+    main_dir.run_jj(["workspace", "add", "../second"]).success();
+
+    // Move a secondary workspace from the default workspace
+    let output = main_dir.run_jj(["workspace", "move", "--workspace", "second", "../moved"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Moved workspace second to "../moved"
+    [EOF]
+    "#);
+    let moved_dir = test_env.work_dir("moved");
+    moved_dir.run_jj(["status"]).success();
+
+    // The destination must not exist
+    let output = moved_dir.run_jj(["workspace", "move", "../main"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Destination already exists
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Moving the workspace that contains the repo updates the other workspaces
+    let output = main_dir.run_jj(["workspace", "move", "../main2"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Moved workspace default to "../main2"
+    Hint: The current directory was moved. Run `cd` to go to the new location.
+    [EOF]
+    "#);
+    let output = moved_dir.run_jj(["workspace", "list"]);
+    insta::assert_snapshot!(output, @r"
+    default: qpvuntsm e8849ae1 (empty) (no description set)
+    second: uuqppmxq 94f41578 (empty) (no description set)
+    [EOF]
+    ");
+    let output = moved_dir.run_jj(["workspace", "prune"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    No workspaces to prune.
+    [EOF]
+    ");
+}
+
 #[must_use]
 fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
     let template = r#"
@@ -137,7 +137,10 @@ pub async fn split_hunks_to_trees(
             .compact_line_ranges()
             .filter_map(|(commit_id, range)| Some((commit_id.ok()?, range)))
             .collect_vec();
-        let diff = Diff::by_line([&left_text, &right_text]);
+        let diff = Diff::by_line_with_algorithm(
+            [&left_text, &right_text],
+            repo.store().merge_options().diff_algorithm,
+        );
         let selected_ranges = split_file_hunks(&annotation_ranges, &diff);
         // Build trees containing parent (= left) contents + selected hunks
         for (&commit_id, ranges) in &selected_ranges {
@@ -247,7 +250,11 @@ fn split_file_hunks<'a>(
 
 /// Constructs new text by replacing `text1` range with `text2` range for each
 /// selected `(range1, range2)` pairs.
-fn combine_texts(text1: &[u8], text2: &[u8], selected_ranges: &[SelectedRange]) -> BString {
+pub(crate) fn combine_texts(
+    text1: &[u8],
+    text2: &[u8],
+    selected_ranges: &[SelectedRange],
+) -> BString {
     itertools::chain!(
         [(0..0, 0..0)],
         selected_ranges.iter().cloned(),
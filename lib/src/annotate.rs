@@ -20,12 +20,14 @@
 
 use std::collections::hash_map;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::iter;
 use std::ops::Range;
 use std::rc::Rc;
 
 use bstr::BStr;
 use bstr::BString;
+use futures::StreamExt as _;
 use itertools::Itertools as _;
 use pollster::FutureExt as _;
 
@@ -124,8 +126,6 @@ impl FileAnnotation {
 /// Annotation process for a specific file.
 #[derive(Clone, Debug)]
 pub struct FileAnnotator {
-    // If we add copy-tracing support, file_path might be tracked by state.
-    file_path: RepoPathBuf,
     original_text: BString,
     state: AnnotationState,
 }
@@ -136,7 +136,7 @@ impl FileAnnotator {
     /// If the file is not found, the result would be empty.
     pub fn from_commit(starting_commit: &Commit, file_path: &RepoPath) -> BackendResult<Self> {
         let source = Source::load(starting_commit, file_path)?;
-        Ok(Self::with_source(starting_commit.id(), file_path, source))
+        Ok(Self::with_source(starting_commit.id(), source))
     }
 
     /// Initializes annotator for a specific file path starting with the given
@@ -150,24 +150,19 @@ impl FileAnnotator {
         file_path: &RepoPath,
         starting_text: impl Into<Vec<u8>>,
     ) -> Self {
-        let source = Source::new(BString::new(starting_text.into()));
-        Self::with_source(starting_commit_id, file_path, source)
+        let source = Source::new(file_path.to_owned(), BString::new(starting_text.into()));
+        Self::with_source(starting_commit_id, source)
     }
 
-    fn with_source(
-        starting_commit_id: &CommitId,
-        file_path: &RepoPath,
-        mut source: Source,
-    ) -> Self {
+    fn with_source(starting_commit_id: &CommitId, mut source: Source) -> Self {
         source.fill_line_map();
         let original_text = source.text.clone();
         let state = AnnotationState {
             original_line_map: vec![Err(starting_commit_id.clone()); source.line_map.len()],
             commit_source_map: HashMap::from([(starting_commit_id.clone(), source)]),
-            num_unresolved_roots: 0,
+            unresolved_roots: HashSet::new(),
         };
         FileAnnotator {
-            file_path: file_path.to_owned(),
             original_text,
             state,
         }
@@ -178,12 +173,15 @@ impl FileAnnotator {
     /// The `domain` expression narrows the range of ancestors to search. It
     /// will be intersected as `domain & ::pending_commits & files(file_path)`.
     /// The `pending_commits` is assumed to be included in the `domain`.
+    ///
+    /// If the file was renamed or copied from another file, the annotation
+    /// continues from the source file.
     pub fn compute(
         &mut self,
         repo: &dyn Repo,
         domain: &Rc<ResolvedRevsetExpression>,
     ) -> Result<(), RevsetEvaluationError> {
-        process_commits(repo, &mut self.state, domain, &self.file_path)
+        process_commits(repo, &mut self.state, domain)
     }
 
     /// Remaining commit ids to visit from.
@@ -209,13 +207,15 @@ struct AnnotationState {
     original_line_map: OriginalLineMap,
     /// Commits to file line mappings and contents.
     commit_source_map: HashMap<CommitId, Source>,
-    /// Number of unresolved root commits in `commit_source_map`.
-    num_unresolved_roots: usize,
+    /// Unresolved root commits in `commit_source_map`.
+    unresolved_roots: HashSet<CommitId>,
 }
 
 /// Line mapping and file content at a certain commit.
 #[derive(Clone, Debug)]
 struct Source {
+    /// Path to the file at the current commit.
+    path: RepoPathBuf,
     /// Mapping of line numbers in the file at the current commit to the
     /// original file, sorted by the line numbers at the current commit.
     line_map: Vec<(usize, usize)>,
@@ -224,8 +224,9 @@ struct Source {
 }
 
 impl Source {
-    fn new(text: BString) -> Self {
+    fn new(path: RepoPathBuf, text: BString) -> Self {
         Source {
+            path,
             line_map: Vec::new(),
             text,
         }
@@ -234,7 +235,7 @@ impl Source {
     fn load(commit: &Commit, file_path: &RepoPath) -> Result<Self, BackendError> {
         let tree = commit.tree()?;
         let text = get_file_contents(commit.store(), file_path, &tree).block_on()?;
-        Ok(Self::new(text))
+        Ok(Self::new(file_path.to_owned(), text))
     }
 
     fn fill_line_map(&mut self) {
@@ -253,52 +254,97 @@ fn process_commits(
     repo: &dyn Repo,
     state: &mut AnnotationState,
     domain: &Rc<ResolvedRevsetExpression>,
-    file_name: &RepoPath,
 ) -> Result<(), RevsetEvaluationError> {
-    let predicate = RevsetFilterPredicate::File(FilesetExpression::file_path(file_name.to_owned()));
+    state.unresolved_roots.clear();
+    // The history of renamed files continues at other paths, which have to be
+    // queried again.
+    while process_commits_at_paths(repo, state, domain)? {}
+    Ok(())
+}
+
+/// Processes the commits which changed the current paths of the file. Returns
+/// true if a rename was followed, and the ancestors have to be queried again.
+fn process_commits_at_paths(
+    repo: &dyn Repo,
+    state: &mut AnnotationState,
+    domain: &Rc<ResolvedRevsetExpression>,
+) -> Result<bool, RevsetEvaluationError> {
+    let paths = state
+        .commit_source_map
+        .values()
+        .map(|source| &source.path)
+        .unique()
+        .map(|path| FilesetExpression::file_path(path.clone()))
+        .collect_vec();
+    let predicate = RevsetFilterPredicate::File(FilesetExpression::union_all(paths));
     // TODO: If the domain isn't a contiguous range, changes masked out by it
     // might not be caught by the closest ancestor revision. For example,
     // domain=merges() would pick up almost nothing because merge revisions
     // are usually empty. Perhaps, we want to query `files(file_path,
     // within_sub_graph=domain)`, not `domain & files(file_path)`.
-    let heads = RevsetExpression::commits(state.commit_source_map.keys().cloned().collect());
+    let heads = RevsetExpression::commits(
+        state
+            .commit_source_map
+            .keys()
+            .filter(|id| !state.unresolved_roots.contains(*id))
+            .cloned()
+            .collect(),
+    );
     let revset = heads
         .union(&domain.intersection(&heads.ancestors()).filtered(predicate))
         .evaluate(repo)?;
 
-    state.num_unresolved_roots = 0;
     for node in revset.iter_graph() {
         let (commit_id, edge_list) = node?;
-        process_commit(repo, file_name, state, &commit_id, &edge_list)?;
-        if state.commit_source_map.len() == state.num_unresolved_roots {
+        let followed_rename = process_commit(repo, state, &commit_id, &edge_list)?;
+        if state.commit_source_map.len() == state.unresolved_roots.len() {
             // No more lines to propagate to ancestors.
-            break;
+            return Ok(false);
+        }
+        if followed_rename {
+            return Ok(true);
         }
     }
-    Ok(())
+    Ok(false)
 }
 
 /// For a given commit, for each parent, we compare the version in the parent
 /// tree with the current version, updating the mappings for any lines in
 /// common. If the parent doesn't have the file, we skip it.
+///
+/// If the file was added by renaming or copying another file, we compare it
+/// with the source file in the direct parents instead. Returns true in that
+/// case.
 fn process_commit(
     repo: &dyn Repo,
-    file_name: &RepoPath,
     state: &mut AnnotationState,
     current_commit_id: &CommitId,
     edges: &[GraphEdge<CommitId>],
-) -> Result<(), BackendError> {
+) -> Result<bool, BackendError> {
     let Some(mut current_source) = state.commit_source_map.remove(current_commit_id) else {
-        return Ok(());
+        return Ok(false);
     };
 
-    for parent_edge in edges {
+    let copy_sources = find_copy_sources(repo, current_commit_id, &current_source.path)?;
+    let followed_rename = !copy_sources.is_empty();
+    let parent_edges = if followed_rename {
+        copy_sources
+            .into_iter()
+            .map(|(parent_id, path)| (GraphEdge::direct(parent_id), path))
+            .collect_vec()
+    } else {
+        edges
+            .iter()
+            .map(|edge| (edge.clone(), current_source.path.clone()))
+            .collect_vec()
+    };
+    for (parent_edge, parent_path) in &parent_edges {
         let parent_commit_id = &parent_edge.target;
         let parent_source = match state.commit_source_map.entry(parent_commit_id.clone()) {
             hash_map::Entry::Occupied(entry) => entry.into_mut(),
             hash_map::Entry::Vacant(entry) => {
                 let commit = repo.store().get_commit(entry.key())?;
-                entry.insert(Source::load(&commit, file_name)?)
+                entry.insert(Source::load(&commit, parent_path)?)
             }
         };
 
@@ -343,7 +389,7 @@ fn process_commit(
             for &(_, original_line_number) in &parent_source.line_map {
                 state.original_line_map[original_line_number] = Err(current_commit_id.clone());
             }
-            state.num_unresolved_roots += 1;
+            state.unresolved_roots.insert(parent_commit_id.clone());
         }
     }
 
@@ -354,7 +400,39 @@ fn process_commit(
         state.original_line_map[original_line_number] = Ok(current_commit_id.clone());
     }
 
-    Ok(())
+    Ok(followed_rename)
+}
+
+/// If the file at `path` was added in the commit, returns the parents and the
+/// paths it was renamed or copied from.
+fn find_copy_sources(
+    repo: &dyn Repo,
+    commit_id: &CommitId,
+    path: &RepoPath,
+) -> BackendResult<Vec<(CommitId, RepoPathBuf)>> {
+    let store = repo.store();
+    let commit = store.get_commit(commit_id)?;
+    let parents: Vec<Commit> = commit.parents().try_collect()?;
+    for parent in &parents {
+        if parent.tree()?.path_value(path)?.is_present() {
+            return Ok(vec![]);
+        }
+    }
+    let mut sources = vec![];
+    for parent in &parents {
+        let records: Vec<_> = store
+            .get_copy_records(Some(&[path.to_owned()]), parent.id(), commit_id)?
+            .collect()
+            .block_on();
+        for record in records {
+            let record = record?;
+            if record.target.as_ref() == path {
+                sources.push((parent.id().clone(), record.source));
+                break;
+            }
+        }
+    }
+    Ok(sources)
 }
 
 /// For two files, calls `copy(current_start, parent_start, count)` for each
@@ -196,6 +196,16 @@ pub struct CopyRecord {
     pub source_commit: CommitId,
 }
 
+/// Statistics of a garbage collection run by [`Backend::gc()`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GcStats {
+    /// Number of removed objects, or objects that would be removed in dry-run
+    /// mode.
+    pub removed_objects: usize,
+    /// Total size of the removed objects in bytes.
+    pub removed_bytes: u64,
+}
+
 /// Error that may occur during backend initialization.
 #[derive(Debug, Error)]
 #[error(transparent)]
@@ -495,5 +505,14 @@ pub trait Backend: Send + Sync + Debug {
     /// All commits found in the `index` won't be removed. In addition to that,
     /// objects created after `keep_newer` will be preserved. This mitigates a
     /// risk of deleting new commits created concurrently by another process.
-    fn gc(&self, index: &dyn Index, keep_newer: SystemTime) -> BackendResult<()>;
+    ///
+    /// If `dry_run` is true, nothing is removed, but the returned stats tell
+    /// how many objects would be removed. Backends which can't calculate that
+    /// should return [`BackendError::Unsupported`].
+    fn gc(
+        &self,
+        index: &dyn Index,
+        keep_newer: SystemTime,
+        dry_run: bool,
+    ) -> BackendResult<GcStats>;
 }
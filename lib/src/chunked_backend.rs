@@ -0,0 +1,541 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Backend which splits file contents into content-defined chunks.
+//!
+//! Large files which are modified in place (such as game assets) typically
+//! share most of their content between versions. By splitting the files at
+//! positions determined by the content, unchanged regions are stored only once.
+
+use std::any::Any;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use blake2::Blake2b512;
+use blake2::Digest as _;
+use futures::stream::BoxStream;
+use prost::Message as _;
+use tempfile::NamedTempFile;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt as _;
+use tokio::io::ReadBuf;
+
+use crate::backend::Backend;
+use crate::backend::BackendError;
+use crate::backend::BackendResult;
+use crate::backend::ChangeId;
+use crate::backend::Commit;
+use crate::backend::CommitId;
+use crate::backend::Conflict;
+use crate::backend::ConflictId;
+use crate::backend::CopyRecord;
+use crate::backend::FileId;
+use crate::backend::GcStats;
+use crate::backend::SigningFn;
+use crate::backend::SymlinkId;
+use crate::backend::Tree;
+use crate::backend::TreeId;
+use crate::file_util::persist_content_addressed_temp_file;
+use crate::index::Index;
+use crate::object_id::ObjectId as _;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::simple_backend::list_stored_objects;
+use crate::simple_backend::map_not_found_err;
+use crate::simple_backend::remove_garbage;
+use crate::simple_backend::to_other_err;
+use crate::simple_backend::SimpleBackend;
+
+/// Chunks are never cut shorter than this, except at the end of file.
+const MIN_CHUNK_SIZE: usize = 16 * 1024;
+/// Chunks are always cut at this size.
+const MAX_CHUNK_SIZE: usize = 256 * 1024;
+/// Chunk boundary is more likely to be found after the average (64KiB) size.
+/// These masks select the high bits of the rolling hash, which depend on the
+/// last 64 bytes.
+const MASK_BEFORE_AVERAGE: u64 = !(u64::MAX >> 18);
+const MASK_AFTER_AVERAGE: u64 = !(u64::MAX >> 14);
+const AVERAGE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Random values for the gear rolling hash.
+const GEAR_TABLE: [u64; 256] = make_gear_table();
+
+const fn make_gear_table() -> [u64; 256] {
+    // splitmix64
+    let mut table = [0; 256];
+    let mut state: u64 = 0x6a09_e667_f3bc_c908;
+    let mut i = 0;
+    while i < table.len() {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+}
+
+/// Returns the length of the first chunk of `data`.
+///
+/// The boundary is determined by the content within `MAX_CHUNK_SIZE` bytes,
+/// so `data` should contain at least that many bytes unless it's the end of
+/// the file.
+fn find_chunk_boundary(data: &[u8]) -> usize {
+    if data.len() <= MIN_CHUNK_SIZE {
+        return data.len();
+    }
+    let end = data.len().min(MAX_CHUNK_SIZE);
+    let average = end.min(AVERAGE_CHUNK_SIZE);
+    let mut hash: u64 = 0;
+    for (i, &byte) in data.iter().enumerate().take(end).skip(MIN_CHUNK_SIZE) {
+        hash = (hash << 1).wrapping_add(GEAR_TABLE[usize::from(byte)]);
+        let mask = if i < average {
+            MASK_BEFORE_AVERAGE
+        } else {
+            MASK_AFTER_AVERAGE
+        };
+        if hash & mask == 0 {
+            return i + 1;
+        }
+    }
+    end
+}
+
+/// A commit backend which stores file contents in content-defined chunks,
+/// and everything else in the same format as [`SimpleBackend`].
+///
+/// A file is stored as a list of chunk ids, and the chunks are shared between
+/// files with the same content regions. File ids are the same as the
+/// `SimpleBackend`'s.
+#[derive(Debug)]
+pub struct ChunkedBackend {
+    path: PathBuf,
+    inner: SimpleBackend,
+}
+
+impl ChunkedBackend {
+    /// "chunked"
+    pub fn name() -> &'static str {
+        "chunked"
+    }
+
+    /// Creates a new backend at the given path.
+    pub fn init(store_path: &Path) -> Self {
+        let inner = SimpleBackend::init(store_path);
+        fs::create_dir(store_path.join("chunks")).unwrap();
+        ChunkedBackend {
+            path: store_path.to_path_buf(),
+            inner,
+        }
+    }
+
+    /// Loads the backend from the given path.
+    pub fn load(store_path: &Path) -> Self {
+        ChunkedBackend {
+            path: store_path.to_path_buf(),
+            inner: SimpleBackend::load(store_path),
+        }
+    }
+
+    fn files_dir(&self) -> PathBuf {
+        self.path.join("files")
+    }
+
+    fn chunks_dir(&self) -> PathBuf {
+        self.path.join("chunks")
+    }
+
+    fn read_chunked_file(
+        &self,
+        path: &Path,
+    ) -> io::Result<crate::protos::simple_store::ChunkedFile> {
+        let buf = fs::read(path)?;
+        crate::protos::simple_store::ChunkedFile::decode(&*buf)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Writes a chunk unless the same chunk already exists.
+    fn write_chunk(&self, data: &[u8]) -> BackendResult<Vec<u8>> {
+        let id = Blake2b512::digest(data).to_vec();
+        let path = self.chunks_dir().join(hex::encode(&id));
+        // Renew the modification time of the existing chunk so it won't be
+        // garbage-collected while the file referencing it is being written.
+        match fs::File::options().append(true).open(&path) {
+            Ok(file) => {
+                file.set_modified(SystemTime::now()).map_err(to_other_err)?;
+                return Ok(id);
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(to_other_err(err)),
+        }
+        let mut temp_file = NamedTempFile::new_in(&self.path).map_err(to_other_err)?;
+        temp_file.write_all(data).map_err(to_other_err)?;
+        persist_content_addressed_temp_file(temp_file, &path).map_err(to_other_err)?;
+        Ok(id)
+    }
+}
+
+#[async_trait]
+impl Backend for ChunkedBackend {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        Self::name()
+    }
+
+    fn commit_id_length(&self) -> usize {
+        self.inner.commit_id_length()
+    }
+
+    fn change_id_length(&self) -> usize {
+        self.inner.change_id_length()
+    }
+
+    fn root_commit_id(&self) -> &CommitId {
+        self.inner.root_commit_id()
+    }
+
+    fn root_change_id(&self) -> &ChangeId {
+        self.inner.root_change_id()
+    }
+
+    fn empty_tree_id(&self) -> &TreeId {
+        self.inner.empty_tree_id()
+    }
+
+    fn concurrency(&self) -> usize {
+        1
+    }
+
+    async fn read_file(
+        &self,
+        path: &RepoPath,
+        id: &FileId,
+    ) -> BackendResult<Pin<Box<dyn AsyncRead>>> {
+        let chunked_file = self
+            .read_chunked_file(&self.files_dir().join(id.hex()))
+            .map_err(|err| map_not_found_err(err, id))?;
+        let chunks_dir = self.chunks_dir();
+        let pending_chunks = chunked_file
+            .chunks
+            .into_iter()
+            .map(|chunk| (chunks_dir.join(hex::encode(chunk.id)), chunk.size))
+            .collect();
+        Ok(Box::pin(ChunkedFileReader {
+            path: path.to_owned(),
+            id: id.clone(),
+            pending_chunks,
+            current_chunk: vec![],
+            position: 0,
+        }))
+    }
+
+    async fn write_file(
+        &self,
+        _path: &RepoPath,
+        contents: &mut (dyn AsyncRead + Send + Unpin),
+    ) -> BackendResult<FileId> {
+        let mut chunked_file = crate::protos::simple_store::ChunkedFile::default();
+        let mut hasher = Blake2b512::new();
+        let mut buf: Vec<u8> = Vec::with_capacity(MAX_CHUNK_SIZE * 2);
+        let mut read_buf: Vec<u8> = vec![0; 1 << 14];
+        let mut eof = false;
+        while !eof || !buf.is_empty() {
+            while !eof && buf.len() < MAX_CHUNK_SIZE {
+                let bytes_read = contents.read(&mut read_buf).await.map_err(to_other_err)?;
+                eof = bytes_read == 0;
+                buf.extend_from_slice(&read_buf[..bytes_read]);
+            }
+            if buf.is_empty() {
+                break;
+            }
+            let len = find_chunk_boundary(&buf);
+            let chunk = &buf[..len];
+            hasher.update(chunk);
+            let chunk_id = self.write_chunk(chunk)?;
+            chunked_file
+                .chunks
+                .push(crate::protos::simple_store::chunked_file::Chunk {
+                    id: chunk_id,
+                    size: len as u64,
+                });
+            buf.drain(..len);
+        }
+        let id = FileId::new(hasher.finalize().to_vec());
+
+        let temp_file = NamedTempFile::new_in(&self.path).map_err(to_other_err)?;
+        temp_file
+            .as_file()
+            .write_all(&chunked_file.encode_to_vec())
+            .map_err(to_other_err)?;
+        persist_content_addressed_temp_file(temp_file, self.files_dir().join(id.hex()))
+            .map_err(to_other_err)?;
+        Ok(id)
+    }
+
+    async fn read_symlink(&self, path: &RepoPath, id: &SymlinkId) -> BackendResult<String> {
+        self.inner.read_symlink(path, id).await
+    }
+
+    async fn write_symlink(&self, path: &RepoPath, target: &str) -> BackendResult<SymlinkId> {
+        self.inner.write_symlink(path, target).await
+    }
+
+    async fn read_tree(&self, path: &RepoPath, id: &TreeId) -> BackendResult<Tree> {
+        self.inner.read_tree(path, id).await
+    }
+
+    async fn write_tree(&self, path: &RepoPath, contents: &Tree) -> BackendResult<TreeId> {
+        self.inner.write_tree(path, contents).await
+    }
+
+    fn read_conflict(&self, path: &RepoPath, id: &ConflictId) -> BackendResult<Conflict> {
+        self.inner.read_conflict(path, id)
+    }
+
+    fn write_conflict(&self, path: &RepoPath, contents: &Conflict) -> BackendResult<ConflictId> {
+        self.inner.write_conflict(path, contents)
+    }
+
+    async fn read_commit(&self, id: &CommitId) -> BackendResult<Commit> {
+        self.inner.read_commit(id).await
+    }
+
+    async fn write_commit(
+        &self,
+        contents: Commit,
+        sign_with: Option<&mut SigningFn>,
+    ) -> BackendResult<(CommitId, Commit)> {
+        self.inner.write_commit(contents, sign_with).await
+    }
+
+    fn get_copy_records(
+        &self,
+        paths: Option<&[RepoPathBuf]>,
+        root: &CommitId,
+        head: &CommitId,
+    ) -> BackendResult<BoxStream<'_, BackendResult<CopyRecord>>> {
+        self.inner.get_copy_records(paths, root, head)
+    }
+
+    #[tracing::instrument(skip(self, index))]
+    fn gc(
+        &self,
+        index: &dyn Index,
+        keep_newer: SystemTime,
+        dry_run: bool,
+    ) -> BackendResult<GcStats> {
+        let garbage = self.inner.find_garbage(index, keep_newer)?;
+        let garbage_file_ids: HashSet<&[u8]> = garbage["files"]
+            .iter()
+            .map(|object| object.id.as_slice())
+            .collect();
+        let mut referenced_chunk_ids = HashSet::new();
+        for object in list_stored_objects(&self.files_dir(), keep_newer)? {
+            if garbage_file_ids.contains(object.id.as_slice()) {
+                continue;
+            }
+            match self.read_chunked_file(&object.path) {
+                Ok(chunked_file) => {
+                    referenced_chunk_ids.extend(chunked_file.chunks.into_iter().map(|c| c.id));
+                }
+                // Removed by concurrent gc
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(to_other_err(err)),
+            }
+        }
+        let mut garbage_chunks = list_stored_objects(&self.chunks_dir(), keep_newer)?;
+        garbage_chunks
+            .retain(|object| !object.is_new && !referenced_chunk_ids.contains(&object.id));
+        tracing::info!(
+            referenced_chunk_count = referenced_chunk_ids.len(),
+            garbage_chunk_count = garbage_chunks.len(),
+            "collected referenced chunks"
+        );
+        remove_garbage(garbage.values().flatten().chain(&garbage_chunks), dry_run)
+    }
+}
+
+/// Reads chunks of a file one by one.
+struct ChunkedFileReader {
+    path: RepoPathBuf,
+    id: FileId,
+    pending_chunks: VecDeque<(PathBuf, u64)>,
+    current_chunk: Vec<u8>,
+    position: usize,
+}
+
+impl ChunkedFileReader {
+    fn load_chunk(&self, path: &Path, size: u64) -> io::Result<Vec<u8>> {
+        let data = fs::read(path)?;
+        if data.len() as u64 != size {
+            let source = BackendError::ReadFile {
+                path: self.path.clone(),
+                id: self.id.clone(),
+                source: format!("Chunk {} has unexpected size", path.display()).into(),
+            };
+            return Err(io::Error::new(io::ErrorKind::InvalidData, source));
+        }
+        Ok(data)
+    }
+}
+
+impl AsyncRead for ChunkedFileReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        while this.position == this.current_chunk.len() {
+            let Some((path, size)) = this.pending_chunks.pop_front() else {
+                return Poll::Ready(Ok(())); // EOF
+            };
+            this.current_chunk = this.load_chunk(&path, size)?;
+            this.position = 0;
+        }
+        let remaining = &this.current_chunk[this.position..];
+        let len = remaining.len().min(buf.remaining());
+        buf.put_slice(&remaining[..len]);
+        this.position += len;
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pollster::FutureExt as _;
+
+    use super::*;
+    use crate::tests::new_temp_dir;
+
+    fn random_bytes(seed: u64, len: usize) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (state >> 56) as u8
+            })
+            .collect()
+    }
+
+    fn write_file(backend: &ChunkedBackend, contents: &[u8]) -> FileId {
+        let path = RepoPath::from_internal_string("file").unwrap();
+        let mut reader = contents;
+        backend.write_file(path, &mut reader).block_on().unwrap()
+    }
+
+    fn read_file(backend: &ChunkedBackend, id: &FileId) -> Vec<u8> {
+        let path = RepoPath::from_internal_string("file").unwrap();
+        let mut reader = backend.read_file(path, id).block_on().unwrap();
+        let mut contents = vec![];
+        reader.read_to_end(&mut contents).block_on().unwrap();
+        contents
+    }
+
+    fn count_chunks(backend: &ChunkedBackend) -> usize {
+        fs::read_dir(backend.chunks_dir()).unwrap().count()
+    }
+
+    #[test]
+    fn test_find_chunk_boundary() {
+        assert_eq!(find_chunk_boundary(&[]), 0);
+        assert_eq!(find_chunk_boundary(&[0; 100]), 100);
+        // Constant data never matches the mask
+        assert_eq!(
+            find_chunk_boundary(&[0; MAX_CHUNK_SIZE + 1]),
+            MAX_CHUNK_SIZE
+        );
+
+        let data = random_bytes(1, MAX_CHUNK_SIZE * 4);
+        let len = find_chunk_boundary(&data);
+        assert!((MIN_CHUNK_SIZE..=MAX_CHUNK_SIZE).contains(&len));
+        // The boundary doesn't depend on the data after the maximum size
+        assert_eq!(find_chunk_boundary(&data[..MAX_CHUNK_SIZE]), len);
+    }
+
+    #[test]
+    fn test_read_write_file() {
+        let temp_dir = new_temp_dir();
+        let backend = ChunkedBackend::init(temp_dir.path());
+
+        for contents in [vec![], b"abc".to_vec(), random_bytes(2, MAX_CHUNK_SIZE * 5)] {
+            let id = write_file(&backend, &contents);
+            assert_eq!(read_file(&backend, &id), contents);
+        }
+    }
+
+    #[test]
+    fn test_file_id_matches_simple_backend() {
+        let temp_dir = new_temp_dir();
+        let backend = ChunkedBackend::init(temp_dir.path());
+        let simple_temp_dir = new_temp_dir();
+        let simple_backend = SimpleBackend::init(simple_temp_dir.path());
+
+        let path = RepoPath::from_internal_string("file").unwrap();
+        let contents = random_bytes(3, MAX_CHUNK_SIZE * 2);
+        let simple_id = simple_backend
+            .write_file(path, &mut contents.as_slice())
+            .block_on()
+            .unwrap();
+        assert_eq!(write_file(&backend, &contents), simple_id);
+    }
+
+    #[test]
+    fn test_chunks_are_shared() {
+        let temp_dir = new_temp_dir();
+        let backend = ChunkedBackend::init(temp_dir.path());
+
+        let original = random_bytes(4, MAX_CHUNK_SIZE * 8);
+        write_file(&backend, &original);
+        let original_chunk_count = count_chunks(&backend);
+        assert!(original_chunk_count > 8);
+
+        // Insert a few bytes in the middle. Only the chunks around the edit
+        // should be added.
+        let mut modified = original.clone();
+        modified.splice(MAX_CHUNK_SIZE * 4..MAX_CHUNK_SIZE * 4, *b"inserted");
+        let id = write_file(&backend, &modified);
+        assert_eq!(read_file(&backend, &id), modified);
+        let added_chunk_count = count_chunks(&backend) - original_chunk_count;
+        assert!(added_chunk_count <= 2, "{added_chunk_count} chunks added");
+    }
+
+    #[test]
+    fn test_read_missing_file() {
+        let temp_dir = new_temp_dir();
+        let backend = ChunkedBackend::init(temp_dir.path());
+        let path = RepoPath::from_internal_string("file").unwrap();
+        let id = FileId::from_hex("abcd");
+        assert!(matches!(
+            backend.read_file(path, &id).block_on(),
+            Err(BackendError::ObjectNotFound { .. })
+        ));
+    }
+}
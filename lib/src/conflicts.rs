@@ -43,6 +43,7 @@ use crate::diff::Diff;
 use crate::diff::DiffHunk;
 use crate::diff::DiffHunkKind;
 use crate::files;
+use crate::files::MergeOptions;
 use crate::files::MergeResult;
 use crate::merge::Merge;
 use crate::merge::MergedTreeValue;
@@ -284,6 +285,10 @@ pub enum ConflictMarkerStyle {
     Snapshot,
     /// Style which replicates Git's "diff3" style to support external tools.
     Git,
+    /// Like `Git`, but conflicting hunks are refined by word, and only the
+    /// lines with conflicting words are shown between the markers.
+    /// Conflicts with more than two sides are shown as snapshots.
+    Minimal,
 }
 
 /// Characters which can be repeated to form a conflict marker line when
@@ -390,7 +395,7 @@ pub fn materialize_merge_result<T: AsRef<[u8]>>(
     conflict_marker_style: ConflictMarkerStyle,
     output: &mut dyn Write,
 ) -> io::Result<()> {
-    let merge_result = files::merge_hunks(single_hunk);
+    let merge_result = files::merge_hunks(single_hunk, &MergeOptions::default());
     match &merge_result {
         MergeResult::Resolved(content) => output.write_all(content),
         MergeResult::Conflict(hunks) => {
@@ -406,7 +411,7 @@ pub fn materialize_merge_result_with_marker_len<T: AsRef<[u8]>>(
     conflict_marker_len: usize,
     output: &mut dyn Write,
 ) -> io::Result<()> {
-    let merge_result = files::merge_hunks(single_hunk);
+    let merge_result = files::merge_hunks(single_hunk, &MergeOptions::default());
     match &merge_result {
         MergeResult::Resolved(content) => output.write_all(content),
         MergeResult::Conflict(hunks) => {
@@ -419,7 +424,7 @@ pub fn materialize_merge_result_to_bytes<T: AsRef<[u8]>>(
     single_hunk: &Merge<T>,
     conflict_marker_style: ConflictMarkerStyle,
 ) -> BString {
-    let merge_result = files::merge_hunks(single_hunk);
+    let merge_result = files::merge_hunks(single_hunk, &MergeOptions::default());
     match merge_result {
         MergeResult::Resolved(content) => content,
         MergeResult::Conflict(hunks) => {
@@ -442,7 +447,7 @@ pub fn materialize_merge_result_to_bytes_with_marker_len<T: AsRef<[u8]>>(
     conflict_marker_style: ConflictMarkerStyle,
     conflict_marker_len: usize,
 ) -> BString {
-    let merge_result = files::merge_hunks(single_hunk);
+    let merge_result = files::merge_hunks(single_hunk, &MergeOptions::default());
     match merge_result {
         MergeResult::Resolved(content) => content,
         MergeResult::Conflict(hunks) => {
@@ -465,6 +470,13 @@ fn materialize_conflict_hunks(
     conflict_marker_len: usize,
     output: &mut dyn Write,
 ) -> io::Result<()> {
+    let refined_hunks;
+    let hunks = if conflict_marker_style == ConflictMarkerStyle::Minimal {
+        refined_hunks = refine_conflict_hunks(hunks);
+        &refined_hunks
+    } else {
+        hunks
+    };
     let num_conflicts = hunks
         .iter()
         .filter(|hunk| hunk.as_resolved().is_none())
@@ -479,7 +491,7 @@ fn materialize_conflict_hunks(
 
             match (conflict_marker_style, hunk.as_slice()) {
                 // 2-sided conflicts can use Git-style conflict markers
-                (ConflictMarkerStyle::Git, [left, base, right]) => {
+                (ConflictMarkerStyle::Git | ConflictMarkerStyle::Minimal, [left, base, right]) => {
                     materialize_git_style_conflict(
                         left,
                         base,
@@ -504,6 +516,33 @@ fn materialize_conflict_hunks(
     Ok(())
 }
 
+/// Applies the non-conflicting word changes in each conflicting hunk to all
+/// terms, and splits it into smaller line hunks.
+fn refine_conflict_hunks(hunks: &[Merge<BString>]) -> Vec<Merge<BString>> {
+    let mut refined_hunks: Vec<Merge<BString>> = vec![];
+    // Adjacent resolved hunks are concatenated.
+    let mut push_hunk = |hunk: Merge<BString>| match refined_hunks.last_mut() {
+        Some(last) if last.is_resolved() && hunk.is_resolved() => {
+            for buf in last.iter_mut() {
+                buf.extend_from_slice(hunk.first());
+            }
+        }
+        _ => refined_hunks.push(hunk),
+    };
+    for hunk in hunks {
+        if hunk.is_resolved() {
+            push_hunk(hunk.clone());
+            continue;
+        }
+        let merged_words = files::merge_words(hunk);
+        match files::merge_hunks(&merged_words, &MergeOptions::default()) {
+            MergeResult::Resolved(content) => push_hunk(Merge::resolved(content)),
+            MergeResult::Conflict(line_hunks) => line_hunks.into_iter().for_each(&mut push_hunk),
+        }
+    }
+    refined_hunks
+}
+
 fn materialize_git_style_conflict(
     left: &[u8],
     base: &[u8],
@@ -14,21 +14,39 @@
 
 //! Code for working with copies and renames.
 
+use std::cmp::max;
+use std::cmp::min;
+use std::cmp::Reverse;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::pin::Pin;
 use std::task::ready;
 use std::task::Context;
 use std::task::Poll;
 
 use futures::Stream;
+use futures::StreamExt as _;
+use tokio::io::AsyncReadExt as _;
 
+use crate::backend::BackendError;
 use crate::backend::BackendResult;
+use crate::backend::CommitId;
 use crate::backend::CopyRecord;
+use crate::backend::FileId;
+use crate::backend::TreeValue;
+use crate::config::ConfigGetError;
+use crate::diff::Diff;
+use crate::diff::DiffHunkKind;
+use crate::matchers::EverythingMatcher;
+use crate::matchers::Matcher;
 use crate::merge::MergedTreeValue;
 use crate::merged_tree::MergedTree;
+use crate::merged_tree::TreeDiffEntry;
 use crate::merged_tree::TreeDiffStream;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
+use crate::settings::UserSettings;
+use crate::store::Store;
 
 /// A collection of CopyRecords.
 #[derive(Default, Debug)]
@@ -90,6 +108,202 @@ impl CopyRecords {
     }
 }
 
+/// Options for detecting renames and copies by comparing file contents.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CopyDetectionOptions {
+    /// Whether to detect renames and copies by comparing file contents in
+    /// addition to the copies recorded by the backend.
+    pub compare_contents: bool,
+    /// Minimum percentage of the contents which must be preserved for a file
+    /// to be considered renamed or copied. 100 only detects exact renames.
+    pub similarity_threshold: u32,
+    /// Whether to detect copies of modified files in addition to renames of
+    /// removed files.
+    pub copies: bool,
+    /// Maximum number of added or source files to compare. Detection is
+    /// skipped if there are more. 0 disables the detection.
+    pub limit: usize,
+}
+
+impl CopyDetectionOptions {
+    /// Loads the options from the `diff.renames` settings.
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        Ok(CopyDetectionOptions {
+            compare_contents: settings.get("diff.renames.compare-contents")?,
+            similarity_threshold: settings.get("diff.renames.similarity-threshold")?,
+            copies: settings.get("diff.renames.copies")?,
+            limit: settings.get("diff.renames.limit")?,
+        })
+    }
+}
+
+impl Default for CopyDetectionOptions {
+    fn default() -> Self {
+        CopyDetectionOptions {
+            compare_contents: false,
+            similarity_threshold: 50,
+            copies: false,
+            limit: 1000,
+        }
+    }
+}
+
+/// Files larger than this are only matched if they're identical.
+const MAX_COMPARED_FILE_SIZE: u64 = 1 << 20;
+
+/// Files with a NUL byte within this many leading bytes are considered binary,
+/// and are only matched if they're identical.
+const BINARY_DETECTION_SIZE: usize = 8000;
+
+/// Detects files renamed or copied from `source_tree` to `target_tree` by
+/// comparing the contents of the added files with the removed (and, if
+/// enabled, modified) files.
+///
+/// Only added files matching `matcher` are considered as targets. Each source
+/// file is matched with at most one target, preferring the most similar ones.
+/// Empty files and conflicts are ignored. Binary and large files are only
+/// matched if they're identical.
+///
+/// `options.compare_contents` isn't checked here. It's up to the caller.
+pub async fn detect_copies(
+    source_tree: &MergedTree,
+    target_tree: &MergedTree,
+    source_commit: &CommitId,
+    target_commit: &CommitId,
+    matcher: &dyn Matcher,
+    options: &CopyDetectionOptions,
+) -> BackendResult<Vec<CopyRecord>> {
+    if options.limit == 0 {
+        return Ok(vec![]);
+    }
+    let mut targets = vec![];
+    let mut sources = vec![];
+    let mut diff_stream = source_tree.diff_stream(target_tree, &EverythingMatcher);
+    while let Some(TreeDiffEntry { path, values }) = diff_stream.next().await {
+        let (before, after) = values?;
+        match (as_file_id(&before), as_file_id(&after)) {
+            (None, Some(id)) if before.is_absent() && matcher.matches(&path) => {
+                targets.push((path, id.clone()));
+            }
+            (Some(id), None) if after.is_absent() => sources.push((path, id.clone())),
+            (Some(id), Some(_)) if options.copies => sources.push((path, id.clone())),
+            _ => {}
+        }
+    }
+    if targets.is_empty()
+        || sources.is_empty()
+        || targets.len() > options.limit
+        || sources.len() > options.limit
+    {
+        return Ok(vec![]);
+    }
+
+    let store = source_tree.store();
+    let target_contents = read_comparable_contents(store, &targets).await?;
+    let source_contents = read_comparable_contents(store, &sources).await?;
+
+    let mut candidates = vec![];
+    for (target_index, target_content) in target_contents.iter().enumerate() {
+        for (source_index, source_content) in source_contents.iter().enumerate() {
+            let score = if targets[target_index].1 == sources[source_index].1 {
+                // Binary and large files are never empty.
+                if target_content.as_ref().is_some_and(|text| text.is_empty()) {
+                    continue;
+                }
+                100
+            } else if let (Some(source_text), Some(target_text)) = (source_content, target_content)
+            {
+                similarity(source_text, target_text, options.similarity_threshold)
+            } else {
+                continue;
+            };
+            if score >= options.similarity_threshold {
+                candidates.push((score, target_index, source_index));
+            }
+        }
+    }
+    // Prefer the most similar pairs. Ties are broken by the order of the
+    // paths so the result is deterministic.
+    candidates.sort_by_key(|&(score, target_index, source_index)| {
+        (Reverse(score), target_index, source_index)
+    });
+    let mut used_targets = HashSet::new();
+    let mut used_sources = HashSet::new();
+    let mut records = vec![];
+    for (_, target_index, source_index) in candidates {
+        if used_targets.contains(&target_index) || used_sources.contains(&source_index) {
+            continue;
+        }
+        used_targets.insert(target_index);
+        used_sources.insert(source_index);
+        let (target, _) = &targets[target_index];
+        let (source, source_file) = &sources[source_index];
+        records.push(CopyRecord {
+            target: target.clone(),
+            target_commit: target_commit.clone(),
+            source: source.clone(),
+            source_file: source_file.clone(),
+            source_commit: source_commit.clone(),
+        });
+    }
+    records.sort_by(|a, b| a.target.cmp(&b.target));
+    Ok(records)
+}
+
+/// Reads the contents of the files which can be compared by lines. Binary and
+/// large files are `None`.
+async fn read_comparable_contents(
+    store: &Store,
+    files: &[(RepoPathBuf, FileId)],
+) -> BackendResult<Vec<Option<Vec<u8>>>> {
+    let mut contents = Vec::with_capacity(files.len());
+    for (path, id) in files {
+        let mut content = vec![];
+        let reader = store.read_file(path, id).await?;
+        reader
+            .take(MAX_COMPARED_FILE_SIZE + 1)
+            .read_to_end(&mut content)
+            .await
+            .map_err(|err| BackendError::ReadFile {
+                path: path.clone(),
+                id: id.clone(),
+                source: err.into(),
+            })?;
+        let is_large = content.len() as u64 > MAX_COMPARED_FILE_SIZE;
+        let is_binary = content.iter().take(BINARY_DETECTION_SIZE).any(|&b| b == 0);
+        contents.push((!is_large && !is_binary).then_some(content));
+    }
+    Ok(contents)
+}
+
+fn as_file_id(value: &MergedTreeValue) -> Option<&FileId> {
+    match value.as_resolved() {
+        Some(Some(TreeValue::File { id, .. })) => Some(id),
+        _ => None,
+    }
+}
+
+/// Returns the percentage of the larger file which is preserved in the other
+/// file, or 0 if it can't reach the `threshold`.
+fn similarity(a: &[u8], b: &[u8], threshold: u32) -> u32 {
+    let max_len = max(a.len(), b.len());
+    let min_len = min(a.len(), b.len());
+    if min_len == 0 || percentage(min_len, max_len) < threshold {
+        return 0;
+    }
+    let diff = Diff::by_line([a, b]);
+    let common_len = diff
+        .hunks()
+        .filter(|hunk| hunk.kind == DiffHunkKind::Matching)
+        .map(|hunk| hunk.contents[0].len())
+        .sum();
+    percentage(common_len, max_len)
+}
+
+fn percentage(part: usize, total: usize) -> u32 {
+    (part as u64 * 100 / total as u64) as u32
+}
+
 /// Whether or not the source path was deleted.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum CopyOperation {
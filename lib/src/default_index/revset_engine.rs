@@ -19,6 +19,7 @@ use std::cmp::Ordering;
 use std::cmp::Reverse;
 use std::collections::BTreeSet;
 use std::collections::BinaryHeap;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt;
 use std::iter;
@@ -50,12 +51,15 @@ use crate::default_index::IndexPosition;
 use crate::diff::Diff;
 use crate::diff::DiffHunkKind;
 use crate::files;
+use crate::files::MergeOptions;
 use crate::graph::GraphNode;
 use crate::matchers::Matcher;
 use crate::matchers::Visit;
 use crate::merge::Merge;
 use crate::merged_tree::resolve_file_values;
 use crate::object_id::ObjectId as _;
+use crate::patch_id::compute_patch_id;
+use crate::patch_id::PatchId;
 use crate::repo_path::RepoPath;
 use crate::revset::ResolvedExpression;
 use crate::revset::ResolvedPredicateExpression;
@@ -978,6 +982,16 @@ impl EvaluationContext<'_> {
                 let candidate_set = self.evaluate(candidates)?;
                 Ok(Box::new(self.take_latest_revset(&*candidate_set, *count)?))
             }
+            ResolvedExpression::Landed {
+                candidates,
+                upstream,
+            } => {
+                let candidate_set = self.evaluate(candidates)?;
+                let upstream_set = self.evaluate(upstream)?;
+                Ok(Box::new(
+                    self.take_landed_revset(&*candidate_set, &*upstream_set)?,
+                ))
+            }
             ResolvedExpression::Coalesce(expression1, expression2) => {
                 let set1 = self.evaluate(expression1)?;
                 if set1.positions().attach(index).next().is_some() {
@@ -1061,6 +1075,53 @@ impl EvaluationContext<'_> {
         Ok(EagerRevset { positions })
     }
 
+    fn take_landed_revset(
+        &self,
+        candidate_set: &dyn InternalRevset,
+        upstream_set: &dyn InternalRevset,
+    ) -> Result<EagerRevset, RevsetEvaluationError> {
+        let patch_id_at = |pos| -> Result<_, RevsetEvaluationError> {
+            let entry = self.index.entry_by_pos(pos);
+            let commit = self.store.get_commit(&entry.commit_id())?;
+            let parents: Vec<_> = commit.parents().try_collect()?;
+            let from_tree = rewrite::merge_commit_trees_no_resolve_without_repo(
+                &self.store,
+                &self.index,
+                &parents,
+            )?;
+            let to_tree = commit.tree()?;
+            Ok(compute_patch_id(&self.store, &from_tree, &to_tree).block_on()?)
+        };
+
+        // Empty commits don't have a patch ID, so they never match.
+        let mut candidates_by_patch_id: HashMap<PatchId, Vec<IndexPosition>> = HashMap::new();
+        for pos in candidate_set.positions().attach(self.index) {
+            let pos = pos?;
+            if let Some(patch_id) = patch_id_at(pos)? {
+                candidates_by_patch_id
+                    .entry(patch_id)
+                    .or_default()
+                    .push(pos);
+            }
+        }
+
+        // Stop walking the upstream commits once all candidates are found.
+        let mut positions = vec![];
+        let mut upstream_iter = upstream_set.positions().attach(self.index);
+        while !candidates_by_patch_id.is_empty() {
+            let Some(pos) = upstream_iter.next() else {
+                break;
+            };
+            if let Some(patch_id) = patch_id_at(pos?)? {
+                if let Some(matched) = candidates_by_patch_id.remove(&patch_id) {
+                    positions.extend(matched);
+                }
+            }
+        }
+        positions.sort_unstable_by_key(|&pos| Reverse(pos));
+        Ok(EagerRevset { positions })
+    }
+
     fn take_latest_revset(
         &self,
         candidate_set: &dyn InternalRevset,
@@ -1330,7 +1391,12 @@ fn matches_diff_from_parent(
             let (left_value, right_value) = futures::try_join!(left_future, right_future)?;
             let left_contents = to_file_content(&entry.path, left_value).await?;
             let right_contents = to_file_content(&entry.path, right_value).await?;
-            if diff_match_lines(&left_contents, &right_contents, text_pattern)? {
+            if diff_match_lines(
+                &left_contents,
+                &right_contents,
+                text_pattern,
+                store.merge_options(),
+            )? {
                 return Ok(true);
             }
         }
@@ -1343,6 +1409,7 @@ fn diff_match_lines(
     lefts: &Merge<BString>,
     rights: &Merge<BString>,
     pattern: &StringPattern,
+    options: &MergeOptions,
 ) -> BackendResult<bool> {
     // Filter lines prior to comparison. This might produce inferior hunks due
     // to lack of contexts, but is way faster than full diff.
@@ -1353,8 +1420,8 @@ fn diff_match_lines(
     } else {
         let lefts: Merge<BString> = lefts.map(|text| match_lines(text, pattern).collect());
         let rights: Merge<BString> = rights.map(|text| match_lines(text, pattern).collect());
-        let lefts = files::merge(&lefts);
-        let rights = files::merge(&rights);
+        let lefts = files::merge(&lefts, options);
+        let rights = files::merge(&rights, options);
         let diff = Diff::by_line(lefts.iter().chain(rights.iter()));
         let different = files::conflict_diff_hunks(diff.hunks(), lefts.as_slice().len())
             .any(|hunk| hunk.kind == DiffHunkKind::Different);
@@ -1782,7 +1849,7 @@ mod tests {
         let left2 = Merge::resolved(conflict2.first().clone());
         let diff = |needle: &str| {
             let pattern = StringPattern::substring(needle);
-            diff_match_lines(&left1, &left2, &pattern).unwrap()
+            diff_match_lines(&left1, &left2, &pattern, &MergeOptions::default()).unwrap()
         };
 
         assert!(diff(""));
@@ -1803,7 +1870,7 @@ mod tests {
         let (conflict1, conflict2) = diff_match_lines_samples();
         let diff = |needle: &str| {
             let pattern = StringPattern::substring(needle);
-            diff_match_lines(&conflict1, &conflict2, &pattern).unwrap()
+            diff_match_lines(&conflict1, &conflict2, &pattern, &MergeOptions::default()).unwrap()
         };
 
         assert!(diff(""));
@@ -1828,7 +1895,7 @@ mod tests {
         let base = Merge::resolved(conflict2.get_remove(0).unwrap().clone());
         let diff = |needle: &str| {
             let pattern = StringPattern::substring(needle);
-            diff_match_lines(&base, &conflict2, &pattern).unwrap()
+            diff_match_lines(&base, &conflict2, &pattern, &MergeOptions::default()).unwrap()
         };
 
         assert!(diff(""));
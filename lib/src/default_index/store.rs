@@ -168,6 +168,30 @@ impl DefaultIndexStore {
         .map_err(DefaultIndexStoreError::LoadIndex)
     }
 
+    /// Loads the index associated with the given `operation` without
+    /// rebuilding it.
+    ///
+    /// Returns `Ok(None)` if the operation hasn't been indexed yet.
+    pub fn load_index_at_operation(
+        &self,
+        operation: &Operation,
+        store: &Arc<Store>,
+    ) -> Result<Option<DefaultReadonlyIndex>, DefaultIndexStoreError> {
+        match self.load_index_segments_at_operation(
+            operation.id(),
+            store.commit_id_length(),
+            store.change_id_length(),
+        ) {
+            Ok(index_segment) => Ok(Some(DefaultReadonlyIndex::from_segment(index_segment))),
+            Err(DefaultIndexStoreError::LoadAssociation(err))
+                if err.kind() == io::ErrorKind::NotFound =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
     /// Rebuilds index for the given `operation`.
     ///
     /// The index to be built will be calculated from one of the ancestor
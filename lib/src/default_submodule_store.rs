@@ -21,7 +21,6 @@ use crate::submodule_store::SubmoduleStore;
 
 #[derive(Debug)]
 pub struct DefaultSubmoduleStore {
-    #[expect(dead_code)]
     path: PathBuf,
 }
 
@@ -48,4 +47,10 @@ impl SubmoduleStore for DefaultSubmoduleStore {
     fn name(&self) -> &str {
         Self::name()
     }
+
+    fn git_dir(&self, submodule_name: &str) -> PathBuf {
+        // Submodule names may contain path separators and "..", so they can't
+        // be used as file names as is.
+        self.path.join("repos").join(hex::encode(submodule_name))
+    }
 }
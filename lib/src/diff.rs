@@ -20,8 +20,11 @@ use std::hash::Hash;
 use std::hash::Hasher;
 use std::hash::RandomState;
 use std::iter;
+use std::ops::Index;
+use std::ops::IndexMut;
 use std::ops::Range;
 use std::slice;
+use std::str::FromStr;
 
 use bstr::BStr;
 use hashbrown::HashTable;
@@ -183,6 +186,34 @@ impl CompareBytes for CompareBytesIgnoreWhitespaceAmount {
     }
 }
 
+/// Algorithm to find the unchanged words (or lines) between inputs.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiffAlgorithm {
+    /// Recursively matches the least frequent words. Usually produces the
+    /// most readable diffs.
+    #[default]
+    Histogram,
+    /// Finds the minimal diff by Myers' algorithm, like `git diff --minimal`.
+    Myers,
+    /// Recursively matches the words which are unique in both inputs, and
+    /// falls back to Myers' algorithm if there are none.
+    Patience,
+}
+
+impl FromStr for DiffAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "histogram" => Ok(DiffAlgorithm::Histogram),
+            "myers" => Ok(DiffAlgorithm::Myers),
+            "patience" => Ok(DiffAlgorithm::Patience),
+            _ => Err(format!("Unknown diff algorithm: {s}")),
+        }
+    }
+}
+
 // Not implementing Eq because the text should be compared by WordComparator.
 #[derive(Clone, Copy, Debug)]
 struct HashedWord<'input> {
@@ -424,11 +455,28 @@ fn collect_unchanged_words<C: CompareBytes, S: BuildHasher>(
     left: &LocalDiffSource,
     right: &LocalDiffSource,
     comp: &WordComparator<C, S>,
+    algorithm: DiffAlgorithm,
 ) {
     if left.ranges.is_empty() || right.ranges.is_empty() {
         return;
     }
+    match algorithm {
+        DiffAlgorithm::Histogram => {
+            collect_unchanged_words_histogram(found_positions, left, right, comp);
+        }
+        DiffAlgorithm::Myers => collect_unchanged_words_myers(found_positions, left, right, comp),
+        DiffAlgorithm::Patience => {
+            collect_unchanged_words_patience(found_positions, left, right, comp);
+        }
+    }
+}
 
+fn collect_unchanged_words_histogram<C: CompareBytes, S: BuildHasher>(
+    found_positions: &mut Vec<(WordPosition, WordPosition)>,
+    left: &LocalDiffSource,
+    right: &LocalDiffSource,
+    comp: &WordComparator<C, S>,
+) {
     // Prioritize LCS-based algorithm than leading/trailing matches
     let old_len = found_positions.len();
     collect_unchanged_words_lcs(found_positions, left, right, comp);
@@ -497,13 +545,34 @@ fn collect_unchanged_words_lcs<C: CompareBytes, S: BuildHasher>(
         return;
     };
 
-    // [(index into ranges, serial to identify {word, occurrence #})]
-    let (mut left_positions, mut right_positions): (Vec<_>, Vec<_>) =
+    collect_unchanged_words_around_anchors(
+        found_positions,
+        left,
+        right,
+        comp,
+        DiffAlgorithm::Histogram,
         uncommon_shared_word_positions
             .flat_map(|(lefts, rights)| iter::zip(lefts, rights))
-            .enumerate()
-            .map(|(serial, (&left_pos, &right_pos))| ((left_pos, serial), (right_pos, serial)))
-            .unzip();
+            .map(|(&left_pos, &right_pos)| (left_pos, right_pos)),
+    );
+}
+
+/// Finds the longest common subsequence of the `anchors`, which are pairs of
+/// positions of the same words, and recurses into the words between them.
+fn collect_unchanged_words_around_anchors<C: CompareBytes, S: BuildHasher>(
+    found_positions: &mut Vec<(WordPosition, WordPosition)>,
+    left: &LocalDiffSource,
+    right: &LocalDiffSource,
+    comp: &WordComparator<C, S>,
+    algorithm: DiffAlgorithm,
+    anchors: impl IntoIterator<Item = (LocalWordPosition, LocalWordPosition)>,
+) {
+    // [(index into ranges, serial to identify {word, occurrence #})]
+    let (mut left_positions, mut right_positions): (Vec<_>, Vec<_>) = anchors
+        .into_iter()
+        .enumerate()
+        .map(|(serial, (left_pos, right_pos))| ((left_pos, serial), (right_pos, serial)))
+        .unzip();
     left_positions.sort_unstable_by_key(|&(pos, _serial)| pos);
     right_positions.sort_unstable_by_key(|&(pos, _serial)| pos);
     let left_index_by_right_index: Vec<usize> = {
@@ -531,6 +600,7 @@ fn collect_unchanged_words_lcs<C: CompareBytes, S: BuildHasher>(
             &left.narrowed(previous_left_position..left_position),
             &right.narrowed(previous_right_position..right_position),
             comp,
+            algorithm,
         );
         found_positions.push((
             left.map_to_global(left_position),
@@ -545,9 +615,277 @@ fn collect_unchanged_words_lcs<C: CompareBytes, S: BuildHasher>(
         &left.narrowed(previous_left_position..LocalWordPosition(left.ranges.len())),
         &right.narrowed(previous_right_position..LocalWordPosition(right.ranges.len())),
         comp,
+        algorithm,
     );
 }
 
+fn collect_unchanged_words_patience<C: CompareBytes, S: BuildHasher>(
+    found_positions: &mut Vec<(WordPosition, WordPosition)>,
+    left: &LocalDiffSource,
+    right: &LocalDiffSource,
+    comp: &WordComparator<C, S>,
+) {
+    let left_words = left.hashed_words().collect_vec();
+    let right_words = right.hashed_words().collect_vec();
+    let leading_len = common_leading_len(&left_words, &right_words, comp);
+    let trailing_len = common_trailing_len(
+        &left_words[leading_len..],
+        &right_words[leading_len..],
+        comp,
+    );
+    found_positions.extend((0..leading_len).map(|i| {
+        (
+            left.map_to_global(LocalWordPosition(i)),
+            right.map_to_global(LocalWordPosition(i)),
+        )
+    }));
+
+    let left_middle = left.narrowed(
+        LocalWordPosition(leading_len)..LocalWordPosition(left_words.len() - trailing_len),
+    );
+    let right_middle = right.narrowed(
+        LocalWordPosition(leading_len)..LocalWordPosition(right_words.len() - trailing_len),
+    );
+    if !left_middle.ranges.is_empty() && !right_middle.ranges.is_empty() {
+        let left_histogram = Histogram::calculate(&left_middle, comp, 1);
+        let right_histogram = Histogram::calculate(&right_middle, comp, 1);
+        let unique_shared_word_positions = left_histogram
+            .word_to_positions
+            .iter()
+            .filter_map(|(word, left_positions)| {
+                let right_positions = right_histogram.positions_by_word(*word, comp)?;
+                match (&left_positions[..], right_positions) {
+                    ([left_pos], [right_pos]) => Some((*left_pos, *right_pos)),
+                    _ => None,
+                }
+            })
+            .collect_vec();
+        if unique_shared_word_positions.is_empty() {
+            collect_unchanged_words_myers(found_positions, &left_middle, &right_middle, comp);
+        } else {
+            collect_unchanged_words_around_anchors(
+                found_positions,
+                &left_middle,
+                &right_middle,
+                comp,
+                DiffAlgorithm::Patience,
+                unique_shared_word_positions,
+            );
+        }
+    }
+
+    found_positions.extend((1..=trailing_len).rev().map(|i| {
+        (
+            left.map_to_global(LocalWordPosition(left_words.len() - i)),
+            right.map_to_global(LocalWordPosition(right_words.len() - i)),
+        )
+    }));
+}
+
+fn collect_unchanged_words_myers<C: CompareBytes, S: BuildHasher>(
+    found_positions: &mut Vec<(WordPosition, WordPosition)>,
+    left: &LocalDiffSource,
+    right: &LocalDiffSource,
+    comp: &WordComparator<C, S>,
+) {
+    let left_words = left.hashed_words().collect_vec();
+    let right_words = right.hashed_words().collect_vec();
+    let max_d = myers_max_d(left_words.len(), right_words.len());
+    let mut vf = MyersV::new(max_d);
+    let mut vb = MyersV::new(max_d);
+    let mut positions = vec![];
+    myers_conquer(
+        &mut positions,
+        &left_words,
+        0..left_words.len(),
+        &right_words,
+        0..right_words.len(),
+        &mut vf,
+        &mut vb,
+        comp,
+    );
+    found_positions.extend(positions.into_iter().map(|(left_pos, right_pos)| {
+        (
+            left.map_to_global(LocalWordPosition(left_pos)),
+            right.map_to_global(LocalWordPosition(right_pos)),
+        )
+    }));
+}
+
+fn common_leading_len<C: CompareBytes, S: BuildHasher>(
+    left: &[HashedWord],
+    right: &[HashedWord],
+    comp: &WordComparator<C, S>,
+) -> usize {
+    iter::zip(left, right)
+        .take_while(|&(&l, &r)| comp.eq_hashed(l, r))
+        .count()
+}
+
+fn common_trailing_len<C: CompareBytes, S: BuildHasher>(
+    left: &[HashedWord],
+    right: &[HashedWord],
+    comp: &WordComparator<C, S>,
+) -> usize {
+    iter::zip(left.iter().rev(), right.iter().rev())
+        .take_while(|&(&l, &r)| comp.eq_hashed(l, r))
+        .count()
+}
+
+fn myers_max_d(left_len: usize, right_len: usize) -> usize {
+    (left_len + right_len).div_ceil(2) + 1
+}
+
+/// Furthest reaching x positions indexed by diagonal `k = x - y`, which may be
+/// negative.
+struct MyersV {
+    offset: isize,
+    v: Vec<usize>,
+}
+
+impl MyersV {
+    fn new(max_d: usize) -> Self {
+        MyersV {
+            offset: max_d as isize + 1,
+            v: vec![0; 2 * max_d + 3],
+        }
+    }
+}
+
+impl Index<isize> for MyersV {
+    type Output = usize;
+
+    fn index(&self, k: isize) -> &usize {
+        &self.v[(k + self.offset) as usize]
+    }
+}
+
+impl IndexMut<isize> for MyersV {
+    fn index_mut(&mut self, k: isize) -> &mut usize {
+        &mut self.v[(k + self.offset) as usize]
+    }
+}
+
+/// Finds the start of the middle snake of the shortest edit script between
+/// the `left` and `right` ranges by searching forward and backward at the same
+/// time. This takes linear space.
+fn myers_find_middle_snake<C: CompareBytes, S: BuildHasher>(
+    left: &[HashedWord],
+    left_range: Range<usize>,
+    right: &[HashedWord],
+    right_range: Range<usize>,
+    vf: &mut MyersV,
+    vb: &mut MyersV,
+    comp: &WordComparator<C, S>,
+) -> Option<(usize, usize)> {
+    let n = left_range.len();
+    let m = right_range.len();
+    let left = &left[left_range.clone()];
+    let right = &right[right_range.clone()];
+    let delta = n as isize - m as isize;
+    let odd = delta & 1 == 1;
+    vf[1] = 0;
+    vb[1] = 0;
+    let d_max = myers_max_d(n, m) as isize;
+    for d in 0..d_max {
+        // Forward path
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = if k == -d || (k != d && vf[k - 1] < vf[k + 1]) {
+                vf[k + 1]
+            } else {
+                vf[k - 1] + 1
+            };
+            let y = (x as isize - k) as usize;
+            let (x0, y0) = (x, y);
+            if x < n && y < m {
+                x += common_leading_len(&left[x..], &right[y..], comp);
+            }
+            vf[k] = x;
+            if odd && (k - delta).abs() < d && vf[k] + vb[-(k - delta)] >= n {
+                return Some((x0 + left_range.start, y0 + right_range.start));
+            }
+        }
+        // Backward path
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = if k == -d || (k != d && vb[k - 1] < vb[k + 1]) {
+                vb[k + 1]
+            } else {
+                vb[k - 1] + 1
+            };
+            let mut y = (x as isize - k) as usize;
+            if x < n && y < m {
+                let advance = common_trailing_len(&left[..n - x], &right[..m - y], comp);
+                x += advance;
+                y += advance;
+            }
+            vb[k] = x;
+            if !odd && (k - delta).abs() <= d && vb[k] + vf[-(k - delta)] >= n {
+                return Some((n - x + left_range.start, m - y + right_range.start));
+            }
+        }
+    }
+    None
+}
+
+/// Collects the unchanged positions between the `left` and `right` ranges by
+/// recursively splitting them at the middle snake.
+#[expect(clippy::too_many_arguments)]
+fn myers_conquer<C: CompareBytes, S: BuildHasher>(
+    positions: &mut Vec<(usize, usize)>,
+    left: &[HashedWord],
+    mut left_range: Range<usize>,
+    right: &[HashedWord],
+    mut right_range: Range<usize>,
+    vf: &mut MyersV,
+    vb: &mut MyersV,
+    comp: &WordComparator<C, S>,
+) {
+    let leading_len =
+        common_leading_len(&left[left_range.clone()], &right[right_range.clone()], comp);
+    positions.extend((0..leading_len).map(|i| (left_range.start + i, right_range.start + i)));
+    left_range.start += leading_len;
+    right_range.start += leading_len;
+    let trailing_len =
+        common_trailing_len(&left[left_range.clone()], &right[right_range.clone()], comp);
+    left_range.end -= trailing_len;
+    right_range.end -= trailing_len;
+
+    if !left_range.is_empty() && !right_range.is_empty() {
+        if let Some((x, y)) = myers_find_middle_snake(
+            left,
+            left_range.clone(),
+            right,
+            right_range.clone(),
+            vf,
+            vb,
+            comp,
+        ) {
+            myers_conquer(
+                positions,
+                left,
+                left_range.start..x,
+                right,
+                right_range.start..y,
+                vf,
+                vb,
+                comp,
+            );
+            myers_conquer(
+                positions,
+                left,
+                x..left_range.end,
+                right,
+                y..right_range.end,
+                vf,
+                vb,
+                comp,
+            );
+        }
+    }
+
+    positions.extend((0..trailing_len).map(|i| (left_range.end + i, right_range.end + i)));
+}
+
 /// Intersects two sorted sequences of `(base, other)` word positions by
 /// `base`. `base` positions should refer to the same source text.
 fn intersect_unchanged_words(
@@ -613,6 +951,17 @@ impl<'input> Diff<'input> {
         inputs: impl IntoIterator<Item = &'input T>,
         tokenizer: impl Fn(&[u8]) -> Vec<Range<usize>>,
         compare: impl CompareBytes,
+    ) -> Self {
+        Self::for_tokenizer_with_algorithm(inputs, tokenizer, compare, DiffAlgorithm::default())
+    }
+
+    /// Like `for_tokenizer()`, but finds the unchanged tokens by the given
+    /// `algorithm`.
+    pub fn for_tokenizer_with_algorithm<T: AsRef<[u8]> + ?Sized + 'input>(
+        inputs: impl IntoIterator<Item = &'input T>,
+        tokenizer: impl Fn(&[u8]) -> Vec<Range<usize>>,
+        compare: impl CompareBytes,
+        algorithm: DiffAlgorithm,
     ) -> Self {
         let mut inputs = inputs.into_iter().map(BStr::new);
         let base_input = inputs.next().expect("inputs must not be empty");
@@ -640,6 +989,7 @@ impl<'input> Diff<'input> {
             &base_token_ranges,
             &other_token_ranges,
             compare,
+            algorithm,
         )
     }
 
@@ -649,6 +999,7 @@ impl<'input> Diff<'input> {
         base_token_ranges: &[Range<usize>],
         other_token_ranges: &[Vec<Range<usize>>],
         compare: impl CompareBytes,
+        algorithm: DiffAlgorithm,
     ) -> Self {
         assert_eq!(other_inputs.len(), other_token_ranges.len());
         let comp = WordComparator::new(compare);
@@ -681,6 +1032,7 @@ impl<'input> Diff<'input> {
                     &base_source.local(),
                     &first_other_source.local(),
                     &comp,
+                    algorithm,
                 );
                 if tail_other_sources.is_empty() {
                     unchanged_regions.extend(first_positions.iter().map(
@@ -707,6 +1059,7 @@ impl<'input> Diff<'input> {
                                 &base_source.local(),
                                 &other_source.local(),
                                 &comp,
+                                algorithm,
                             );
                             intersect_unchanged_words(current_positions, &new_positions)
                         },
@@ -756,6 +1109,14 @@ impl<'input> Diff<'input> {
         Diff::for_tokenizer(inputs, find_line_ranges, CompareBytesExactly)
     }
 
+    /// Compares `inputs` line by line using the given `algorithm`.
+    pub fn by_line_with_algorithm<T: AsRef<[u8]> + ?Sized + 'input>(
+        inputs: impl IntoIterator<Item = &'input T>,
+        algorithm: DiffAlgorithm,
+    ) -> Self {
+        Diff::for_tokenizer_with_algorithm(inputs, find_line_ranges, CompareBytesExactly, algorithm)
+    }
+
     /// Compares `inputs` word by word.
     ///
     /// The `inputs` is usually a changed hunk (e.g. a `DiffHunk::Different`)
@@ -1201,7 +1562,13 @@ mod tests {
         let left = DiffSource::new(left_text, left_ranges, &comp);
         let right = DiffSource::new(right_text, right_ranges, &comp);
         let mut positions = Vec::new();
-        collect_unchanged_words(&mut positions, &left.local(), &right.local(), &comp);
+        collect_unchanged_words(
+            &mut positions,
+            &left.local(),
+            &right.local(),
+            &comp,
+            DiffAlgorithm::Histogram,
+        );
         positions
             .into_iter()
             .map(|(left_pos, right_pos)| (left.range_at(left_pos), right.range_at(right_pos)))
@@ -1487,6 +1854,113 @@ mod tests {
         );
     }
 
+    fn unchanged_words_with_algorithm(
+        left_text: &str,
+        right_text: &str,
+        algorithm: DiffAlgorithm,
+    ) -> Vec<(usize, usize)> {
+        let comp = WordComparator::new(CompareBytesExactly);
+        let left_ranges = find_word_ranges(left_text.as_bytes());
+        let right_ranges = find_word_ranges(right_text.as_bytes());
+        let left = DiffSource::new(left_text, &left_ranges, &comp);
+        let right = DiffSource::new(right_text, &right_ranges, &comp);
+        let mut positions = Vec::new();
+        collect_unchanged_words(
+            &mut positions,
+            &left.local(),
+            &right.local(),
+            &comp,
+            algorithm,
+        );
+        positions
+            .into_iter()
+            .map(|(left_pos, right_pos)| (left_pos.0, right_pos.0))
+            .collect()
+    }
+
+    fn lcs_len(left: &[&str], right: &[&str]) -> usize {
+        let mut table = vec![vec![0; right.len() + 1]; left.len() + 1];
+        for (i, l) in left.iter().enumerate() {
+            for (j, r) in right.iter().enumerate() {
+                table[i + 1][j + 1] = if l == r {
+                    table[i][j] + 1
+                } else {
+                    table[i][j + 1].max(table[i + 1][j])
+                };
+            }
+        }
+        table[left.len()][right.len()]
+    }
+
+    #[test]
+    fn test_unchanged_words_algorithms_are_valid() {
+        // Pseudo-random inputs with many repeated words
+        let mut seed: u32 = 1;
+        let mut random_text = |len: usize| {
+            (0..len)
+                .map(|_| {
+                    seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                    ["a", "b", "c", "d"][(seed >> 16) as usize % 4]
+                })
+                .join(" ")
+        };
+        for i in 0..200 {
+            let left_text = random_text(i % 13);
+            let right_text = random_text(i % 7 + i % 5);
+            let left_words = left_text.split(' ').filter(|w| !w.is_empty()).collect_vec();
+            let right_words = right_text
+                .split(' ')
+                .filter(|w| !w.is_empty())
+                .collect_vec();
+            for algorithm in [
+                DiffAlgorithm::Histogram,
+                DiffAlgorithm::Myers,
+                DiffAlgorithm::Patience,
+            ] {
+                let positions = unchanged_words_with_algorithm(&left_text, &right_text, algorithm);
+                for &(left_pos, right_pos) in &positions {
+                    assert_eq!(left_words[left_pos], right_words[right_pos]);
+                }
+                assert!(positions
+                    .iter()
+                    .tuple_windows()
+                    .all(|(a, b)| a.0 < b.0 && a.1 < b.1));
+                if algorithm == DiffAlgorithm::Myers {
+                    // Myers' diff is minimal
+                    assert_eq!(positions.len(), lcs_len(&left_words, &right_words));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_diff_algorithms_by_line() {
+        let diff = |algorithm| {
+            let left = "a\n}\nb\n}\n";
+            let right = "a\n}\nx\n}\nb\n}\n";
+            Diff::by_line_with_algorithm([left, right], algorithm)
+                .hunks()
+                .collect_vec()
+        };
+        // All algorithms agree on the simple insertion
+        let expected = vec![
+            DiffHunk::matching(["a\n}\n"].repeat(2)),
+            DiffHunk::different(["", "x\n}\n"]),
+            DiffHunk::matching(["b\n}\n"].repeat(2)),
+        ];
+        assert_eq!(diff(DiffAlgorithm::Histogram), expected);
+        assert_eq!(diff(DiffAlgorithm::Myers), expected);
+        assert_eq!(diff(DiffAlgorithm::Patience), expected);
+    }
+
+    #[test]
+    fn test_diff_algorithm_from_str() {
+        assert_eq!("myers".parse(), Ok(DiffAlgorithm::Myers));
+        assert_eq!("patience".parse(), Ok(DiffAlgorithm::Patience));
+        assert_eq!("histogram".parse(), Ok(DiffAlgorithm::Histogram));
+        assert!("minimal".parse::<DiffAlgorithm>().is_err());
+    }
+
     #[test]
     fn test_diff_ignore_all_whitespace() {
         fn diff(inputs: [&str; 2]) -> Vec<DiffHunk<'_>> {
@@ -15,6 +15,7 @@
 #![allow(missing_docs)]
 
 use std::borrow::Borrow;
+use std::borrow::Cow;
 use std::collections::VecDeque;
 use std::iter;
 use std::mem;
@@ -23,10 +24,13 @@ use bstr::BStr;
 use bstr::BString;
 use itertools::Itertools as _;
 
+use crate::config::ConfigGetError;
 use crate::diff::Diff;
+use crate::diff::DiffAlgorithm;
 use crate::diff::DiffHunk;
 use crate::diff::DiffHunkKind;
 use crate::merge::Merge;
+use crate::settings::UserSettings;
 
 /// A diff line which may contain small hunks originating from both sides.
 #[derive(PartialEq, Eq, Clone, Debug)]
@@ -269,11 +273,43 @@ pub enum MergeResult {
     Conflict(Vec<Merge<BString>>),
 }
 
+/// Granularity of hunks when merging files.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FileMergeHunkLevel {
+    /// Splits into line hunks.
+    #[default]
+    Line,
+    /// Splits into line hunks first, then refines conflicting hunks into word
+    /// hunks. The line hunk is resolved if all of its word hunks can be
+    /// resolved.
+    Word,
+}
+
+/// Options for file-level merging.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MergeOptions {
+    /// Granularity of hunks when merging files.
+    pub hunk_level: FileMergeHunkLevel,
+    /// Algorithm used to match up lines of the inputs.
+    pub diff_algorithm: DiffAlgorithm,
+}
+
+impl MergeOptions {
+    /// Loads merge options from `settings`.
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        Ok(MergeOptions {
+            hunk_level: settings.get("merge.hunk-level")?,
+            diff_algorithm: settings.get("diff.algorithm")?,
+        })
+    }
+}
+
 /// Splits `inputs` into hunks, resolves trivial merge conflicts for each.
 ///
 /// Returns either fully-resolved content or list of partially-resolved hunks.
-pub fn merge_hunks<T: AsRef<[u8]>>(inputs: &Merge<T>) -> MergeResult {
-    merge_inner(inputs)
+pub fn merge_hunks<T: AsRef<[u8]>>(inputs: &Merge<T>, options: &MergeOptions) -> MergeResult {
+    merge_inner(inputs, options)
 }
 
 /// Splits `inputs` into hunks, resolves trivial merge conflicts for each, then
@@ -281,65 +317,97 @@ pub fn merge_hunks<T: AsRef<[u8]>>(inputs: &Merge<T>) -> MergeResult {
 ///
 /// The returned merge object is either fully resolved or conflict having the
 /// same number of terms as the `inputs`.
-pub fn merge<T: AsRef<[u8]>>(inputs: &Merge<T>) -> Merge<BString> {
-    merge_inner(inputs)
+pub fn merge<T: AsRef<[u8]>>(inputs: &Merge<T>, options: &MergeOptions) -> Merge<BString> {
+    merge_inner(inputs, options)
 }
 
 /// Splits `inputs` into hunks, attempts to resolve trivial merge conflicts for
 /// each.
 ///
 /// If all input hunks can be merged successfully, returns the merged content.
-pub fn try_merge<T: AsRef<[u8]>>(inputs: &Merge<T>) -> Option<BString> {
-    merge_inner(inputs)
+pub fn try_merge<T: AsRef<[u8]>>(inputs: &Merge<T>, options: &MergeOptions) -> Option<BString> {
+    merge_inner(inputs, options)
 }
 
-fn merge_inner<'input, T: AsRef<[u8]>, B: FromMergeHunks<'input>>(inputs: &'input Merge<T>) -> B {
+fn merge_inner<T: AsRef<[u8]>, B: FromMergeHunks>(inputs: &Merge<T>, options: &MergeOptions) -> B {
     // TODO: Using the first remove as base (first in the inputs) is how it's
     // usually done for 3-way conflicts. Are there better heuristics when there are
     // more than 3 parts?
     let num_diffs = inputs.removes().len();
-    let diff = Diff::by_line(inputs.removes().chain(inputs.adds()));
+    let diff = Diff::by_line_with_algorithm(
+        inputs.removes().chain(inputs.adds()),
+        options.diff_algorithm,
+    );
     let hunks = resolve_diff_hunks(&diff, num_diffs);
-    B::from_hunks(hunks)
+    match options.hunk_level {
+        FileMergeHunkLevel::Line => B::from_hunks(hunks),
+        FileMergeHunkLevel::Word => B::from_hunks(hunks.map(resolve_word_hunks)),
+    }
+}
+
+/// Refines the conflicting line `hunk` into word hunks, and concatenates them
+/// if all of them can be resolved.
+fn resolve_word_hunks<'input>(hunk: Merge<&'input BStr>) -> Merge<Cow<'input, [u8]>> {
+    if hunk.is_resolved() {
+        return hunk.map(|&s| Cow::Borrowed(s.as_ref()));
+    }
+    let num_diffs = hunk.removes().len();
+    let diff = Diff::by_word(hunk.removes().chain(hunk.adds()));
+    match collect_resolved(resolve_diff_hunks(&diff, num_diffs)) {
+        Some(content) => Merge::resolved(Cow::Owned(content.into())),
+        None => hunk.map(|&s| Cow::Borrowed(s.as_ref())),
+    }
+}
+
+/// Refines the conflicting `hunk` into word hunks, and applies the resolved
+/// word hunks to all terms.
+///
+/// The returned merge object has the same number of terms as the `hunk`. It
+/// may still have conflicting lines, but they only differ in the words which
+/// couldn't be resolved.
+pub fn merge_words<T: AsRef<[u8]>>(hunk: &Merge<T>) -> Merge<BString> {
+    let num_diffs = hunk.removes().len();
+    let diff = Diff::by_word(hunk.removes().chain(hunk.adds()));
+    collect_merged(resolve_diff_hunks(&diff, num_diffs))
 }
 
 /// `FromIterator` for merge result.
-trait FromMergeHunks<'input>: Sized {
-    fn from_hunks<I: IntoIterator<Item = Merge<&'input BStr>>>(hunks: I) -> Self;
+trait FromMergeHunks: Sized {
+    fn from_hunks<H: AsRef<[u8]>, I: IntoIterator<Item = Merge<H>>>(hunks: I) -> Self;
 }
 
-impl<'input> FromMergeHunks<'input> for MergeResult {
-    fn from_hunks<I: IntoIterator<Item = Merge<&'input BStr>>>(hunks: I) -> Self {
+impl FromMergeHunks for MergeResult {
+    fn from_hunks<H: AsRef<[u8]>, I: IntoIterator<Item = Merge<H>>>(hunks: I) -> Self {
         collect_hunks(hunks)
     }
 }
 
-impl<'input> FromMergeHunks<'input> for Merge<BString> {
-    fn from_hunks<I: IntoIterator<Item = Merge<&'input BStr>>>(hunks: I) -> Self {
+impl FromMergeHunks for Merge<BString> {
+    fn from_hunks<H: AsRef<[u8]>, I: IntoIterator<Item = Merge<H>>>(hunks: I) -> Self {
         collect_merged(hunks)
     }
 }
 
-impl<'input> FromMergeHunks<'input> for Option<BString> {
-    fn from_hunks<I: IntoIterator<Item = Merge<&'input BStr>>>(hunks: I) -> Self {
+impl FromMergeHunks for Option<BString> {
+    fn from_hunks<H: AsRef<[u8]>, I: IntoIterator<Item = Merge<H>>>(hunks: I) -> Self {
         collect_resolved(hunks)
     }
 }
 
 /// Collects merged hunks into either fully-resolved content or list of
 /// partially-resolved hunks.
-fn collect_hunks<'input>(hunks: impl IntoIterator<Item = Merge<&'input BStr>>) -> MergeResult {
+fn collect_hunks<H: AsRef<[u8]>>(hunks: impl IntoIterator<Item = Merge<H>>) -> MergeResult {
     let mut resolved_hunk = BString::new(vec![]);
     let mut merge_hunks: Vec<Merge<BString>> = vec![];
     for hunk in hunks {
-        if let Some(&content) = hunk.as_resolved() {
-            resolved_hunk.extend_from_slice(content);
+        if let Some(content) = hunk.as_resolved() {
+            resolved_hunk.extend_from_slice(content.as_ref());
         } else {
             if !resolved_hunk.is_empty() {
                 merge_hunks.push(Merge::resolved(resolved_hunk));
                 resolved_hunk = BString::new(vec![]);
             }
-            merge_hunks.push(hunk.map(|&s| s.to_owned()));
+            merge_hunks.push(hunk.map(|s| s.as_ref().into()));
         }
     }
 
@@ -355,12 +423,12 @@ fn collect_hunks<'input>(hunks: impl IntoIterator<Item = Merge<&'input BStr>>) -
 
 /// Collects merged hunks back to single `Merge` object, duplicating resolved
 /// hunks to all positive and negative terms.
-fn collect_merged<'input>(hunks: impl IntoIterator<Item = Merge<&'input BStr>>) -> Merge<BString> {
+fn collect_merged<H: AsRef<[u8]>>(hunks: impl IntoIterator<Item = Merge<H>>) -> Merge<BString> {
     let mut maybe_resolved = Merge::resolved(BString::default());
     for hunk in hunks {
-        if let Some(&content) = hunk.as_resolved() {
+        if let Some(content) = hunk.as_resolved() {
             for buf in maybe_resolved.iter_mut() {
-                buf.extend_from_slice(content);
+                buf.extend_from_slice(content.as_ref());
             }
         } else {
             maybe_resolved = match maybe_resolved.into_resolved() {
@@ -369,7 +437,7 @@ fn collect_merged<'input>(hunks: impl IntoIterator<Item = Merge<&'input BStr>>)
             };
             assert_eq!(maybe_resolved.as_slice().len(), hunk.as_slice().len());
             for (buf, s) in iter::zip(maybe_resolved.iter_mut(), hunk) {
-                buf.extend_from_slice(s);
+                buf.extend_from_slice(s.as_ref());
             }
         }
     }
@@ -377,13 +445,12 @@ fn collect_merged<'input>(hunks: impl IntoIterator<Item = Merge<&'input BStr>>)
 }
 
 /// Collects resolved merge hunks. Short-circuits on unresolved hunk.
-fn collect_resolved<'input>(
-    hunks: impl IntoIterator<Item = Merge<&'input BStr>>,
-) -> Option<BString> {
-    hunks
-        .into_iter()
-        .map(|hunk| hunk.into_resolved().ok())
-        .collect()
+fn collect_resolved<H: AsRef<[u8]>>(hunks: impl IntoIterator<Item = Merge<H>>) -> Option<BString> {
+    let mut resolved = BString::default();
+    for hunk in hunks {
+        resolved.extend_from_slice(hunk.into_resolved().ok()?.as_ref());
+    }
+    Some(resolved)
 }
 
 /// Iterator that attempts to resolve trivial merge conflict for each hunk.
@@ -415,6 +482,15 @@ mod tests {
 
     use super::*;
 
+    const LINE: &MergeOptions = &MergeOptions {
+        hunk_level: FileMergeHunkLevel::Line,
+        diff_algorithm: DiffAlgorithm::Histogram,
+    };
+    const WORD: &MergeOptions = &MergeOptions {
+        hunk_level: FileMergeHunkLevel::Word,
+        diff_algorithm: DiffAlgorithm::Histogram,
+    };
+
     fn conflict<const N: usize>(values: [&[u8]; N]) -> Merge<BString> {
         Merge::from_vec(values.map(hunk).to_vec())
     }
@@ -776,109 +852,112 @@ mod tests {
     fn test_merge_single_hunk() {
         // Unchanged and empty on all sides
         assert_eq!(
-            merge_hunks(&conflict([b"", b"", b""])),
+            merge_hunks(&conflict([b"", b"", b""]), LINE),
             MergeResult::Resolved(hunk(b""))
         );
         // Unchanged on all sides
         assert_eq!(
-            merge_hunks(&conflict([b"a", b"a", b"a"])),
+            merge_hunks(&conflict([b"a", b"a", b"a"]), LINE),
             MergeResult::Resolved(hunk(b"a"))
         );
         // One side removed, one side unchanged
         assert_eq!(
-            merge_hunks(&conflict([b"", b"a\n", b"a\n"])),
+            merge_hunks(&conflict([b"", b"a\n", b"a\n"]), LINE),
             MergeResult::Resolved(hunk(b""))
         );
         // One side unchanged, one side removed
         assert_eq!(
-            merge_hunks(&conflict([b"a\n", b"a\n", b""])),
+            merge_hunks(&conflict([b"a\n", b"a\n", b""]), LINE),
             MergeResult::Resolved(hunk(b""))
         );
         // Both sides removed same line
         assert_eq!(
-            merge_hunks(&conflict([b"", b"a\n", b""])),
+            merge_hunks(&conflict([b"", b"a\n", b""]), LINE),
             MergeResult::Resolved(hunk(b""))
         );
         // One side modified, one side unchanged
         assert_eq!(
-            merge_hunks(&conflict([b"a b", b"a", b"a"])),
+            merge_hunks(&conflict([b"a b", b"a", b"a"]), LINE),
             MergeResult::Resolved(hunk(b"a b"))
         );
         // One side unchanged, one side modified
         assert_eq!(
-            merge_hunks(&conflict([b"a", b"a", b"a b"])),
+            merge_hunks(&conflict([b"a", b"a", b"a b"]), LINE),
             MergeResult::Resolved(hunk(b"a b"))
         );
         // All sides added same content
         assert_eq!(
-            merge_hunks(&conflict([b"a\n", b"", b"a\n", b"", b"a\n"])),
+            merge_hunks(&conflict([b"a\n", b"", b"a\n", b"", b"a\n"]), LINE),
             MergeResult::Resolved(hunk(b"a\n"))
         );
         // One side modified, two sides added
         assert_eq!(
-            merge_hunks(&conflict([b"b", b"a", b"b", b"", b"b"])),
+            merge_hunks(&conflict([b"b", b"a", b"b", b"", b"b"]), LINE),
             MergeResult::Conflict(vec![conflict([b"b", b"a", b"b", b"", b"b"])])
         );
         // All sides removed same content
         assert_eq!(
-            merge_hunks(&conflict([b"", b"a\n", b"", b"a\n", b"", b"a\n", b""])),
+            merge_hunks(
+                &conflict([b"", b"a\n", b"", b"a\n", b"", b"a\n", b""]),
+                LINE
+            ),
             MergeResult::Resolved(hunk(b""))
         );
         // One side modified, two sides removed
         assert_eq!(
-            merge_hunks(&conflict([b"b\n", b"a\n", b"", b"a\n", b""])),
+            merge_hunks(&conflict([b"b\n", b"a\n", b"", b"a\n", b""]), LINE),
             MergeResult::Conflict(vec![conflict([b"b\n", b"a\n", b"", b"a\n", b""])])
         );
         // Three sides made the same change
         assert_eq!(
-            merge_hunks(&conflict([b"b", b"a", b"b", b"a", b"b"])),
+            merge_hunks(&conflict([b"b", b"a", b"b", b"a", b"b"]), LINE),
             MergeResult::Resolved(hunk(b"b"))
         );
         // One side removed, one side modified
         assert_eq!(
-            merge_hunks(&conflict([b"", b"a\n", b"b\n"])),
+            merge_hunks(&conflict([b"", b"a\n", b"b\n"]), LINE),
             MergeResult::Conflict(vec![conflict([b"", b"a\n", b"b\n"])])
         );
         // One side modified, one side removed
         assert_eq!(
-            merge_hunks(&conflict([b"b\n", b"a\n", b""])),
+            merge_hunks(&conflict([b"b\n", b"a\n", b""]), LINE),
             MergeResult::Conflict(vec![conflict([b"b\n", b"a\n", b""])])
         );
         // Two sides modified in different ways
         assert_eq!(
-            merge_hunks(&conflict([b"b", b"a", b"c"])),
+            merge_hunks(&conflict([b"b", b"a", b"c"]), LINE),
             MergeResult::Conflict(vec![conflict([b"b", b"a", b"c"])])
         );
         // Two of three sides don't change, third side changes
         assert_eq!(
-            merge_hunks(&conflict([b"a", b"a", b"", b"a", b"a"])),
+            merge_hunks(&conflict([b"a", b"a", b"", b"a", b"a"]), LINE),
             MergeResult::Resolved(hunk(b""))
         );
         // One side unchanged, two other sides make the same change
         assert_eq!(
-            merge_hunks(&conflict([b"b", b"a", b"a", b"a", b"b"])),
+            merge_hunks(&conflict([b"b", b"a", b"a", b"a", b"b"]), LINE),
             MergeResult::Resolved(hunk(b"b"))
         );
         // One side unchanged, two other sides make the different change
         assert_eq!(
-            merge_hunks(&conflict([b"b", b"a", b"a", b"a", b"c"])),
+            merge_hunks(&conflict([b"b", b"a", b"a", b"a", b"c"]), LINE),
             MergeResult::Conflict(vec![conflict([b"b", b"a", b"a", b"a", b"c"])])
         );
         // Merge of an unresolved conflict and another branch, where the other branch
         // undid the change from one of the inputs to the unresolved conflict in the
         // first.
         assert_eq!(
-            merge_hunks(&conflict([b"b", b"a", b"a", b"b", b"c"])),
+            merge_hunks(&conflict([b"b", b"a", b"a", b"b", b"c"]), LINE),
             MergeResult::Resolved(hunk(b"c"))
         );
         // Merge of an unresolved conflict and another branch.
         assert_eq!(
-            merge_hunks(&conflict([b"c", b"a", b"d", b"b", b"e"])),
+            merge_hunks(&conflict([b"c", b"a", b"d", b"b", b"e"]), LINE),
             MergeResult::Conflict(vec![conflict([b"c", b"a", b"d", b"b", b"e"])])
         );
         // Two sides made the same change, third side made a different change
         assert_eq!(
-            merge_hunks(&conflict([b"c", b"a", b"c", b"b", b"c"])),
+            merge_hunks(&conflict([b"c", b"a", b"c", b"b", b"c"]), LINE),
             MergeResult::Conflict(vec![conflict([b"c", b"a", b"c", b"b", b"c"])])
         );
     }
@@ -888,25 +967,28 @@ mod tests {
         // Two sides left one line unchanged, and added conflicting additional lines
         let inputs = conflict([b"a\nb\n", b"a\n", b"a\nc\n"]);
         assert_eq!(
-            merge_hunks(&inputs),
+            merge_hunks(&inputs, LINE),
             MergeResult::Conflict(vec![resolved(b"a\n"), conflict([b"b\n", b"", b"c\n"])])
         );
-        assert_eq!(merge(&inputs), conflict([b"a\nb\n", b"a\n", b"a\nc\n"]));
-        assert_eq!(try_merge(&inputs), None);
+        assert_eq!(
+            merge(&inputs, LINE),
+            conflict([b"a\nb\n", b"a\n", b"a\nc\n"])
+        );
+        assert_eq!(try_merge(&inputs, LINE), None);
 
         // Two sides changed different lines: no conflict
         let inputs = conflict([b"a2\nb\nc\n", b"a\nb\nc\n", b"a\nb\nc2\n"]);
         assert_eq!(
-            merge_hunks(&inputs),
+            merge_hunks(&inputs, LINE),
             MergeResult::Resolved(hunk(b"a2\nb\nc2\n"))
         );
-        assert_eq!(merge(&inputs), resolved(b"a2\nb\nc2\n"));
-        assert_eq!(try_merge(&inputs), Some(hunk(b"a2\nb\nc2\n")));
+        assert_eq!(merge(&inputs, LINE), resolved(b"a2\nb\nc2\n"));
+        assert_eq!(try_merge(&inputs, LINE), Some(hunk(b"a2\nb\nc2\n")));
 
         // Conflict with non-conflicting lines around
         let inputs = conflict([b"a\nb1\nc\n", b"a\nb\nc\n", b"a\nb2\nc\n"]);
         assert_eq!(
-            merge_hunks(&inputs),
+            merge_hunks(&inputs, LINE),
             MergeResult::Conflict(vec![
                 resolved(b"a\n"),
                 conflict([b"b1\n", b"b\n", b"b2\n"]),
@@ -914,25 +996,25 @@ mod tests {
             ])
         );
         assert_eq!(
-            merge(&inputs),
+            merge(&inputs, LINE),
             conflict([b"a\nb1\nc\n", b"a\nb\nc\n", b"a\nb2\nc\n"])
         );
-        assert_eq!(try_merge(&inputs), None);
+        assert_eq!(try_merge(&inputs, LINE), None);
 
         // Two conflict hunks, one can be resolved
         let inputs = conflict([b"a\nb\nc\n", b"a1\nb\nc\n", b"a2\nb\nc2\n"]);
         assert_eq!(
-            merge_hunks(&inputs),
+            merge_hunks(&inputs, LINE),
             MergeResult::Conflict(vec![
                 conflict([b"a\n", b"a1\n", b"a2\n"]),
                 resolved(b"b\nc2\n"),
             ])
         );
         assert_eq!(
-            merge(&inputs),
+            merge(&inputs, LINE),
             conflict([b"a\nb\nc2\n", b"a1\nb\nc2\n", b"a2\nb\nc2\n"])
         );
-        assert_eq!(try_merge(&inputs), None);
+        assert_eq!(try_merge(&inputs, LINE), None);
 
         // One side changes a line and adds a block after. The other side just adds the
         // same block. You might expect the last block would be deduplicated. However,
@@ -985,6 +1067,56 @@ mod tests {
                 x
             }
         "};
-        assert_eq!(merge(&conflict([left, base, right])), resolved(merged));
+        assert_eq!(
+            merge(&conflict([left, base, right]), LINE),
+            resolved(merged)
+        );
+    }
+
+    #[test]
+    fn test_merge_word_level() {
+        // Two sides changed different words in the same line
+        let inputs = conflict([b"a1 b c\n", b"a b c\n", b"a b c2\n"]);
+        assert_eq!(
+            merge_hunks(&inputs, LINE),
+            MergeResult::Conflict(vec![conflict([b"a1 b c\n", b"a b c\n", b"a b c2\n"])])
+        );
+        assert_eq!(
+            merge_hunks(&inputs, WORD),
+            MergeResult::Resolved(hunk(b"a1 b c2\n"))
+        );
+        assert_eq!(merge(&inputs, WORD), resolved(b"a1 b c2\n"));
+        assert_eq!(try_merge(&inputs, WORD), Some(hunk(b"a1 b c2\n")));
+
+        // Two sides changed the same word: the whole line conflicts
+        let inputs = conflict([b"x\na1 b c1\n", b"x\na b c\n", b"x\na b c2\n"]);
+        assert_eq!(
+            merge_hunks(&inputs, WORD),
+            MergeResult::Conflict(vec![
+                resolved(b"x\n"),
+                conflict([b"a1 b c1\n", b"a b c\n", b"a b c2\n"]),
+            ])
+        );
+        assert_eq!(try_merge(&inputs, WORD), None);
+
+        // Resolved line hunks aren't affected
+        let inputs = conflict([b"a\nb1\n", b"a\nb\n", b"a2\nb\n"]);
+        assert_eq!(
+            merge_hunks(&inputs, WORD),
+            MergeResult::Resolved(hunk(b"a2\nb1\n"))
+        );
+    }
+
+    #[test]
+    fn test_merge_words() {
+        // The non-conflicting word changes are applied to all terms
+        let inputs = conflict([b"a1 b c1\n", b"a b c\n", b"a b2 c2\n"]);
+        assert_eq!(
+            merge_words(&inputs),
+            conflict([b"a1 b2 c1\n", b"a1 b2 c\n", b"a1 b2 c2\n"])
+        );
+
+        let inputs = conflict([b"a1 b c\n", b"a b c\n", b"a b c2\n"]);
+        assert_eq!(merge_words(&inputs), resolved(b"a1 b c2\n"));
     }
 }
@@ -0,0 +1,562 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Repository integrity checks.
+//!
+//! Unlike the usual repo loading path, which may silently rebuild the index or
+//! fail on the first unreadable object, the functions in this module visit
+//! everything reachable from the operation heads and report every problem
+//! found.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use itertools::Itertools as _;
+use pollster::FutureExt as _;
+use thiserror::Error;
+use tokio::io::AsyncReadExt as _;
+
+use crate::backend::BackendError;
+use crate::backend::ChangeId;
+use crate::backend::CommitId;
+use crate::backend::ConflictId;
+use crate::backend::FileId;
+use crate::backend::SymlinkId;
+use crate::backend::TreeId;
+use crate::backend::TreeValue;
+use crate::default_index::AsCompositeIndex as _;
+use crate::default_index::DefaultIndexStore;
+use crate::default_index::DefaultIndexStoreError;
+use crate::default_index::DefaultIndexStoreInitError;
+use crate::index::Index as _;
+use crate::object_id::ObjectId as _;
+use crate::op_heads_store::OpHeadsStoreError;
+use crate::op_store::OpStoreError;
+use crate::op_store::OperationId;
+use crate::op_store::ViewId;
+use crate::operation::Operation;
+use crate::repo::RepoLoader;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::view::View;
+use crate::working_copy::WorkingCopy;
+use crate::working_copy::WorkingCopyStateError;
+
+/// Problem found by the integrity checks.
+#[derive(Debug, Error)]
+pub enum FsckProblem {
+    /// Operation head points to an operation which cannot be read.
+    #[error("Operation head {op_id} cannot be read")]
+    DanglingOpHead {
+        /// Operation id recorded as a head.
+        op_id: OperationId,
+        /// Underlying error.
+        source: OpStoreError,
+    },
+    /// Operation cannot be read.
+    #[error("Operation {op_id} cannot be read")]
+    UnreadableOperation {
+        /// Operation id.
+        op_id: OperationId,
+        /// Underlying error.
+        source: OpStoreError,
+    },
+    /// View of an operation cannot be read.
+    #[error("View {view_id} of operation {op_id} cannot be read")]
+    UnreadableView {
+        /// Operation id.
+        op_id: OperationId,
+        /// View id.
+        view_id: ViewId,
+        /// Underlying error.
+        source: OpStoreError,
+    },
+    /// Commit cannot be read.
+    #[error("Commit {commit_id} cannot be read")]
+    UnreadableCommit {
+        /// Commit id.
+        commit_id: CommitId,
+        /// Underlying error.
+        source: BackendError,
+    },
+    /// Tree, file, symlink, or conflict object cannot be read.
+    #[error(
+        "Object {hash} at path {path} cannot be read",
+        path = path.as_internal_file_string()
+    )]
+    UnreadableObject {
+        /// Path of the object in the tree.
+        path: RepoPathBuf,
+        /// Object id in hex.
+        hash: String,
+        /// Underlying error.
+        source: BackendError,
+    },
+    /// Commit index associated with an operation cannot be loaded.
+    #[error("Commit index at operation {op_id} cannot be loaded")]
+    UnreadableIndex {
+        /// Operation id.
+        op_id: OperationId,
+        /// Underlying error.
+        source: DefaultIndexStoreError,
+    },
+    /// Commit index doesn't match the commits in the backend.
+    #[error("Commit index at operation {op_id} is inconsistent: {message}")]
+    InconsistentIndex {
+        /// Operation id.
+        op_id: OperationId,
+        /// Description of the inconsistency.
+        message: String,
+    },
+    /// Working copy state cannot be read.
+    #[error("Working copy state cannot be read")]
+    UnreadableWorkingCopy {
+        /// Underlying error.
+        source: WorkingCopyStateError,
+    },
+    /// Working copy was last updated to an operation which cannot be read.
+    #[error("Working copy refers to operation {op_id} which cannot be read")]
+    WorkingCopyOperationNotFound {
+        /// Operation id recorded in the working copy state.
+        op_id: OperationId,
+        /// Underlying error.
+        source: OpStoreError,
+    },
+}
+
+impl FsckProblem {
+    /// Returns true if this problem can be fixed by [`repair()`].
+    pub fn is_repairable(&self) -> bool {
+        matches!(
+            self,
+            FsckProblem::DanglingOpHead { .. }
+                | FsckProblem::UnreadableIndex { .. }
+                | FsckProblem::InconsistentIndex { .. }
+        )
+    }
+}
+
+/// Result of the integrity checks.
+#[derive(Debug, Default)]
+pub struct FsckReport {
+    /// Number of operations visited.
+    pub num_operations: usize,
+    /// Number of commits visited.
+    pub num_commits: usize,
+    /// Number of tree, file, symlink, and conflict objects visited.
+    pub num_objects: usize,
+    /// Problems found.
+    pub problems: Vec<FsckProblem>,
+}
+
+/// Error that may occur during [`repair()`].
+#[derive(Debug, Error)]
+pub enum FsckRepairError {
+    /// Failed to update operation heads.
+    #[error(transparent)]
+    OpHeadsStore(#[from] OpHeadsStoreError),
+    /// Failed to reset the commit index.
+    #[error(transparent)]
+    IndexStoreInit(#[from] DefaultIndexStoreInitError),
+    /// Failed to rebuild the commit index.
+    #[error(transparent)]
+    IndexStore(#[from] DefaultIndexStoreError),
+}
+
+/// Checks the operation log, views, commits, trees, and commit index of the
+/// repo.
+///
+/// All objects reachable from the current operation heads are read. Commit
+/// predecessors are visited if they exist, but missing predecessors aren't
+/// reported since they may have been garbage-collected.
+pub fn check_repo(repo_loader: &RepoLoader) -> Result<FsckReport, OpHeadsStoreError> {
+    let mut checker = RepoChecker {
+        repo_loader,
+        report: FsckReport::default(),
+        visited_objects: HashSet::new(),
+    };
+    let op_heads = checker.check_op_heads()?;
+    let (op_views, commit_ids) = checker.check_operations(&op_heads);
+    let commits = checker.check_commits(commit_ids);
+    checker.check_index(&op_heads, &op_views, &commits);
+    Ok(checker.report)
+}
+
+/// Checks that the working-copy state can be read and that its operation and
+/// tree exist.
+pub fn check_working_copy(
+    repo_loader: &RepoLoader,
+    working_copy: &dyn WorkingCopy,
+) -> Vec<FsckProblem> {
+    let mut checker = RepoChecker {
+        repo_loader,
+        report: FsckReport::default(),
+        visited_objects: HashSet::new(),
+    };
+    let op_id = working_copy.operation_id();
+    if let Err(source) = repo_loader.op_store().read_operation(op_id) {
+        checker
+            .report
+            .problems
+            .push(FsckProblem::WorkingCopyOperationNotFound {
+                op_id: op_id.clone(),
+                source,
+            });
+    }
+    match working_copy.tree_id() {
+        Ok(tree_id) => {
+            for tree_id in tree_id.to_merge().iter() {
+                checker.check_tree(RepoPath::root(), tree_id);
+            }
+        }
+        Err(source) => {
+            checker
+                .report
+                .problems
+                .push(FsckProblem::UnreadableWorkingCopy { source });
+        }
+    }
+    checker.report.problems
+}
+
+/// Repairs the given problems if possible. Returns the number of problems
+/// repaired.
+///
+/// Dangling operation heads are removed if there are other readable heads.
+/// The commit index is rebuilt from scratch if it's corrupt or inconsistent.
+pub fn repair(
+    repo_loader: &RepoLoader,
+    problems: &[FsckProblem],
+) -> Result<usize, FsckRepairError> {
+    let op_heads_store = repo_loader.op_heads_store();
+    let mut num_repaired = 0;
+
+    let dangling_op_ids = problems
+        .iter()
+        .filter_map(|problem| match problem {
+            FsckProblem::DanglingOpHead { op_id, .. } => Some(op_id.clone()),
+            _ => None,
+        })
+        .collect_vec();
+    let live_op_ids = op_heads_store
+        .get_op_heads()?
+        .into_iter()
+        .filter(|id| !dangling_op_ids.contains(id))
+        .collect_vec();
+    if let Some(new_id) = live_op_ids.first() {
+        if !dangling_op_ids.is_empty() {
+            op_heads_store.update_op_heads(&dangling_op_ids, new_id)?;
+            num_repaired += dangling_op_ids.len();
+        }
+    }
+
+    let num_index_problems = problems
+        .iter()
+        .filter(|problem| {
+            matches!(
+                problem,
+                FsckProblem::UnreadableIndex { .. } | FsckProblem::InconsistentIndex { .. }
+            )
+        })
+        .count();
+    let index_store = repo_loader.index_store();
+    if let Some(default_index_store) = index_store.as_any().downcast_ref::<DefaultIndexStore>() {
+        if num_index_problems > 0 {
+            default_index_store.reinit()?;
+            for op_id in &live_op_ids {
+                // Unreadable operations have been reported separately
+                let Ok(op) = repo_loader.load_operation(op_id) else {
+                    continue;
+                };
+                default_index_store.build_index_at_operation(&op, repo_loader.store())?;
+            }
+            num_repaired += num_index_problems;
+        }
+    }
+    Ok(num_repaired)
+}
+
+struct RepoChecker<'a> {
+    repo_loader: &'a RepoLoader,
+    report: FsckReport,
+    visited_objects: HashSet<(RepoPathBuf, String)>,
+}
+
+impl RepoChecker<'_> {
+    fn problem(&mut self, problem: FsckProblem) {
+        self.report.problems.push(problem);
+    }
+
+    /// Returns the readable operation heads.
+    fn check_op_heads(&mut self) -> Result<Vec<Operation>, OpHeadsStoreError> {
+        let op_store = self.repo_loader.op_store();
+        let mut op_heads = vec![];
+        for op_id in self.repo_loader.op_heads_store().get_op_heads()? {
+            match op_store.read_operation(&op_id) {
+                Ok(data) => op_heads.push(Operation::new(op_store.clone(), op_id, data)),
+                Err(source) => self.problem(FsckProblem::DanglingOpHead { op_id, source }),
+            }
+        }
+        Ok(op_heads)
+    }
+
+    /// Visits all ancestors of the `op_heads`. Returns the readable views
+    /// keyed by operation id, and the commits referenced by them.
+    fn check_operations(
+        &mut self,
+        op_heads: &[Operation],
+    ) -> (HashMap<OperationId, View>, Vec<CommitId>) {
+        let op_store = self.repo_loader.op_store();
+        let mut visited: HashSet<OperationId> = HashSet::new();
+        let mut pending: Vec<Operation> = op_heads.to_vec();
+        let mut op_views = HashMap::new();
+        let mut commit_ids = vec![];
+        let mut seen_commit_ids = HashSet::new();
+        while let Some(op) = pending.pop() {
+            if !visited.insert(op.id().clone()) {
+                continue;
+            }
+            self.report.num_operations += 1;
+            match op_store.read_view(op.view_id()) {
+                Ok(data) => {
+                    let view = View::new(data);
+                    for id in view.all_referenced_commit_ids() {
+                        if seen_commit_ids.insert(id.clone()) {
+                            commit_ids.push(id.clone());
+                        }
+                    }
+                    op_views.insert(op.id().clone(), view);
+                }
+                Err(source) => self.problem(FsckProblem::UnreadableView {
+                    op_id: op.id().clone(),
+                    view_id: op.view_id().clone(),
+                    source,
+                }),
+            }
+            for parent_id in op.parent_ids() {
+                if visited.contains(parent_id) {
+                    continue;
+                }
+                match op_store.read_operation(parent_id) {
+                    Ok(data) => {
+                        pending.push(Operation::new(op_store.clone(), parent_id.clone(), data));
+                    }
+                    Err(source) => {
+                        visited.insert(parent_id.clone());
+                        self.problem(FsckProblem::UnreadableOperation {
+                            op_id: parent_id.clone(),
+                            source,
+                        });
+                    }
+                }
+            }
+        }
+        (op_views, commit_ids)
+    }
+
+    /// Visits all ancestors and predecessors of the given commits. Returns
+    /// the parent ids and change id of the readable commits.
+    fn check_commits(
+        &mut self,
+        head_ids: Vec<CommitId>,
+    ) -> HashMap<CommitId, (Vec<CommitId>, ChangeId)> {
+        let backend = self.repo_loader.store().backend();
+        let mut commits = HashMap::new();
+        let mut visited: HashSet<CommitId> = head_ids.iter().cloned().collect();
+        // (commit_id, required)
+        let mut pending = head_ids.into_iter().map(|id| (id, true)).collect_vec();
+        while let Some((commit_id, required)) = pending.pop() {
+            let commit = match backend.read_commit(&commit_id).block_on() {
+                Ok(commit) => commit,
+                Err(BackendError::ObjectNotFound { .. }) if !required => continue,
+                Err(source) => {
+                    self.problem(FsckProblem::UnreadableCommit { commit_id, source });
+                    continue;
+                }
+            };
+            self.report.num_commits += 1;
+            for tree_id in commit.root_tree.to_merge().iter() {
+                self.check_tree(RepoPath::root(), tree_id);
+            }
+            for parent_id in &commit.parents {
+                if visited.insert(parent_id.clone()) {
+                    pending.push((parent_id.clone(), true));
+                }
+            }
+            for predecessor_id in &commit.predecessors {
+                if visited.insert(predecessor_id.clone()) {
+                    pending.push((predecessor_id.clone(), false));
+                }
+            }
+            commits.insert(commit_id, (commit.parents, commit.change_id));
+        }
+        commits
+    }
+
+    /// Returns false if the object has already been visited.
+    fn visit_object(&mut self, path: &RepoPath, hash: String) -> bool {
+        if !self.visited_objects.insert((path.to_owned(), hash)) {
+            return false;
+        }
+        self.report.num_objects += 1;
+        true
+    }
+
+    fn object_problem(&mut self, path: &RepoPath, hash: String, source: BackendError) {
+        self.problem(FsckProblem::UnreadableObject {
+            path: path.to_owned(),
+            hash,
+            source,
+        });
+    }
+
+    fn check_tree(&mut self, path: &RepoPath, id: &TreeId) {
+        if !self.visit_object(path, id.hex()) {
+            return;
+        }
+        let backend = self.repo_loader.store().backend();
+        let tree = match backend.read_tree(path, id).block_on() {
+            Ok(tree) => tree,
+            Err(source) => return self.object_problem(path, id.hex(), source),
+        };
+        for entry in tree.entries() {
+            let entry_path = path.join(entry.name());
+            self.check_value(&entry_path, entry.value());
+        }
+    }
+
+    fn check_value(&mut self, path: &RepoPath, value: &TreeValue) {
+        match value {
+            TreeValue::File { id, .. } => self.check_file(path, id),
+            TreeValue::Symlink(id) => self.check_symlink(path, id),
+            TreeValue::Tree(id) => self.check_tree(path, id),
+            TreeValue::GitSubmodule(_) => {}
+            TreeValue::Conflict(id) => self.check_conflict(path, id),
+        }
+    }
+
+    fn check_file(&mut self, path: &RepoPath, id: &FileId) {
+        if !self.visit_object(path, id.hex()) {
+            return;
+        }
+        let backend = self.repo_loader.store().backend();
+        let result = async {
+            let mut reader = backend.read_file(path, id).await?;
+            let mut content = vec![];
+            reader
+                .read_to_end(&mut content)
+                .await
+                .map_err(|err| BackendError::ReadFile {
+                    path: path.to_owned(),
+                    id: id.clone(),
+                    source: err.into(),
+                })?;
+            Ok(())
+        }
+        .block_on();
+        if let Err(source) = result {
+            self.object_problem(path, id.hex(), source);
+        }
+    }
+
+    fn check_symlink(&mut self, path: &RepoPath, id: &SymlinkId) {
+        if !self.visit_object(path, id.hex()) {
+            return;
+        }
+        let backend = self.repo_loader.store().backend();
+        if let Err(source) = backend.read_symlink(path, id).block_on() {
+            self.object_problem(path, id.hex(), source);
+        }
+    }
+
+    fn check_conflict(&mut self, path: &RepoPath, id: &ConflictId) {
+        if !self.visit_object(path, id.hex()) {
+            return;
+        }
+        let backend = self.repo_loader.store().backend();
+        match backend.read_conflict(path, id) {
+            Ok(conflict) => {
+                for term in conflict.removes.iter().chain(&conflict.adds) {
+                    self.check_value(path, &term.value);
+                }
+            }
+            Err(source) => self.object_problem(path, id.hex(), source),
+        }
+    }
+
+    /// Checks that the index at each operation head contains the commits
+    /// referenced by the view, and that the indexed parents and change ids
+    /// match the commits in the backend.
+    fn check_index(
+        &mut self,
+        op_heads: &[Operation],
+        op_views: &HashMap<OperationId, View>,
+        commits: &HashMap<CommitId, (Vec<CommitId>, ChangeId)>,
+    ) {
+        let index_store = self.repo_loader.index_store();
+        let Some(default_index_store) = index_store.as_any().downcast_ref::<DefaultIndexStore>()
+        else {
+            return;
+        };
+        for op in op_heads {
+            let index =
+                match default_index_store.load_index_at_operation(op, self.repo_loader.store()) {
+                    Ok(Some(index)) => index,
+                    // Not indexed yet. It will be indexed when the repo is loaded.
+                    Ok(None) => continue,
+                    Err(source) => {
+                        self.problem(FsckProblem::UnreadableIndex {
+                            op_id: op.id().clone(),
+                            source,
+                        });
+                        continue;
+                    }
+                };
+            let index = index.as_composite();
+            let mut inconsistent = |message: String| {
+                self.report.problems.push(FsckProblem::InconsistentIndex {
+                    op_id: op.id().clone(),
+                    message,
+                });
+            };
+            if let Some(view) = op_views.get(op.id()) {
+                let missing_ids = view
+                    .all_referenced_commit_ids()
+                    .filter(|id| !index.has_id(id))
+                    .collect_vec();
+                if !missing_ids.is_empty() {
+                    inconsistent(format!(
+                        "commits referenced by the view are not indexed: {}",
+                        missing_ids.iter().map(|id| id.hex()).join(", ")
+                    ));
+                }
+            }
+            for (commit_id, (parent_ids, change_id)) in commits {
+                let Some(entry) = index.entry_by_id(commit_id) else {
+                    continue;
+                };
+                let indexed_parent_ids = entry.parents().map(|entry| entry.commit_id());
+                if !indexed_parent_ids.eq(parent_ids.iter().cloned()) {
+                    inconsistent(format!("parents of commit {} don't match", commit_id.hex()));
+                }
+                if entry.change_id() != *change_id {
+                    inconsistent(format!(
+                        "change id of commit {} doesn't match",
+                        commit_id.hex()
+                    ));
+                }
+            }
+        }
+    }
+}
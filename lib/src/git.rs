@@ -19,8 +19,10 @@ use std::borrow::Cow;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::default::Default;
+use std::fs;
 use std::fs::File;
 use std::num::NonZeroU32;
+use std::path::Path;
 use std::path::PathBuf;
 use std::str;
 use std::sync::Arc;
@@ -58,6 +60,8 @@ use crate::ref_name::RemoteName;
 use crate::ref_name::RemoteNameBuf;
 use crate::ref_name::RemoteRefSymbol;
 use crate::ref_name::RemoteRefSymbolBuf;
+use crate::ref_name::WorkspaceName;
+use crate::ref_name::WorkspaceNameBuf;
 use crate::refs::BookmarkPushUpdate;
 use crate::repo::MutableRepo;
 use crate::repo::Repo;
@@ -772,19 +776,230 @@ fn remotely_pinned_commit_ids(view: &View) -> Vec<CommitId> {
         .collect()
 }
 
-/// Imports HEAD from the underlying Git repo.
+/// Git worktree which the working copy of a colocated workspace is shared
+/// with.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GitWorktree {
+    /// The main worktree of the Git repo. Its HEAD is recorded as
+    /// `View::git_head`.
+    Main,
+    /// The linked worktree in `.git/worktrees/<name>` named after the
+    /// workspace. Its HEAD is recorded per workspace in the view.
+    Linked(WorkspaceNameBuf),
+}
+
+impl GitWorktree {
+    /// Returns HEAD of this worktree recorded in the `view`.
+    pub fn git_head<'v>(&self, view: &'v View) -> &'v RefTarget {
+        match self {
+            GitWorktree::Main => view.git_head(),
+            GitWorktree::Linked(name) => view.get_git_worktree_head(name),
+        }
+    }
+
+    fn set_head_target(&self, mut_repo: &mut MutableRepo, target: RefTarget) {
+        match self {
+            GitWorktree::Main => mut_repo.set_git_head_target(target),
+            GitWorktree::Linked(name) => mut_repo.set_git_worktree_head_target(name, target),
+        }
+    }
+
+    /// Opens the Git repo with the HEAD and index of this worktree.
+    fn open_repo(&self, git_repo: gix::Repository) -> Result<gix::Repository, GitWorktreeError> {
+        let GitWorktree::Linked(name) = self else {
+            return Ok(git_repo);
+        };
+        let proxy = find_worktree(&git_repo, name)?
+            .ok_or_else(|| GitWorktreeError::NotFound(name.to_owned()))?;
+        proxy
+            .into_repo_with_possibly_inaccessible_worktree()
+            .map_err(GitWorktreeError::from_git)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GitWorktreeError {
+    #[error("Workspace name {} cannot be used as Git worktree name", .0.as_symbol())]
+    InvalidName(WorkspaceNameBuf),
+    #[error("Git worktree {} already exists", .0.as_symbol())]
+    AlreadyExists(WorkspaceNameBuf),
+    #[error("Git worktree {} doesn't exist", .0.as_symbol())]
+    NotFound(WorkspaceNameBuf),
+    #[error(transparent)]
+    Io(#[from] PathError),
+    #[error(transparent)]
+    Git(Box<dyn std::error::Error + Send + Sync>),
+    #[error(transparent)]
+    UnexpectedBackend(#[from] UnexpectedGitBackendError),
+}
+
+impl GitWorktreeError {
+    fn from_git(source: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        GitWorktreeError::Git(source.into())
+    }
+}
+
+fn find_worktree<'repo>(
+    git_repo: &'repo gix::Repository,
+    name: &WorkspaceName,
+) -> Result<Option<gix::worktree::Proxy<'repo>>, GitWorktreeError> {
+    let worktrees = git_repo.worktrees().map_err(GitWorktreeError::from_git)?;
+    Ok(worktrees
+        .into_iter()
+        .find(|proxy| proxy.id() == name.as_str()))
+}
+
+/// Returns the directory of the linked worktree in the Git repo which would
+/// be named after the workspace.
+fn worktree_git_dir(
+    git_repo: &gix::Repository,
+    name: &WorkspaceName,
+) -> Result<PathBuf, GitWorktreeError> {
+    // The name is used as a directory name, and it shouldn't be confused with
+    // the special directories.
+    let is_valid = !name.as_str().is_empty()
+        && !name.as_str().starts_with('.')
+        && !name.as_str().contains(std::path::is_separator);
+    if !is_valid {
+        return Err(GitWorktreeError::InvalidName(name.to_owned()));
+    }
+    Ok(git_repo.common_dir().join("worktrees").join(name.as_str()))
+}
+
+fn write_git_file(path: &Path, contents: &[u8]) -> Result<(), GitWorktreeError> {
+    fs::write(path, contents).context(path)?;
+    Ok(())
+}
+
+fn path_to_git_file_line(prefix: &str, path: &Path) -> Vec<u8> {
+    let path = gix::path::into_bstr(path);
+    let path = gix::path::to_unix_separators_on_windows(path);
+    [prefix.as_bytes(), path.as_ref(), b"\n"].concat()
+}
+
+/// Creates a linked Git worktree for the workspace at `workspace_root` so that
+/// Git commands can be run in the workspace. The worktree is named after the
+/// workspace.
+///
+/// HEAD of the new worktree is unborn. It will be updated by `reset_head()`
+/// when a commit is checked out in the workspace.
+pub fn add_worktree(
+    store: &Store,
+    name: &WorkspaceName,
+    workspace_root: &Path,
+) -> Result<(), GitWorktreeError> {
+    let git_repo = get_git_repo(store)?;
+    let worktree_dir = worktree_git_dir(&git_repo, name)?;
+    if worktree_dir.exists() {
+        return Err(GitWorktreeError::AlreadyExists(name.to_owned()));
+    }
+    fs::create_dir_all(&worktree_dir).context(&worktree_dir)?;
+    let worktree_dir = dunce::canonicalize(&worktree_dir).context(&worktree_dir)?;
+    let dot_git_path = workspace_root.join(".git");
+    write_git_file(
+        &worktree_dir.join("gitdir"),
+        &path_to_git_file_line("", &dot_git_path),
+    )?;
+    write_git_file(&worktree_dir.join("commondir"), b"../..\n")?;
+    write_git_file(
+        &worktree_dir.join("HEAD"),
+        format!("ref: {UNBORN_ROOT_REF_NAME}\n").as_bytes(),
+    )?;
+    write_git_file(
+        &dot_git_path,
+        &path_to_git_file_line("gitdir: ", &worktree_dir),
+    )?;
+    Ok(())
+}
+
+/// Removes the linked Git worktree named after the workspace, if any. The
+/// files in the workspace aren't touched.
+pub fn remove_worktree(store: &Store, name: &WorkspaceName) -> Result<(), GitWorktreeError> {
+    let git_repo = get_git_repo(store)?;
+    let Some(proxy) = find_worktree(&git_repo, name)? else {
+        return Ok(());
+    };
+    let worktree_dir = proxy.git_dir().to_owned();
+    fs::remove_dir_all(&worktree_dir).context(&worktree_dir)?;
+    Ok(())
+}
+
+/// Renames the linked Git worktree of the workspace at `workspace_root` to
+/// follow the new workspace name. Does nothing if there's no such worktree.
+pub fn rename_worktree(
+    store: &Store,
+    old_name: &WorkspaceName,
+    new_name: &WorkspaceName,
+    workspace_root: &Path,
+) -> Result<(), GitWorktreeError> {
+    let git_repo = get_git_repo(store)?;
+    let Some(proxy) = find_worktree(&git_repo, old_name)? else {
+        return Ok(());
+    };
+    let old_worktree_dir = proxy.git_dir().to_owned();
+    let new_worktree_dir = worktree_git_dir(&git_repo, new_name)?;
+    if new_worktree_dir.exists() {
+        return Err(GitWorktreeError::AlreadyExists(new_name.to_owned()));
+    }
+    fs::rename(&old_worktree_dir, &new_worktree_dir).context(&old_worktree_dir)?;
+    let new_worktree_dir = dunce::canonicalize(&new_worktree_dir).context(&new_worktree_dir)?;
+    write_git_file(
+        &workspace_root.join(".git"),
+        &path_to_git_file_line("gitdir: ", &new_worktree_dir),
+    )?;
+    Ok(())
+}
+
+/// Updates the linked Git worktree named after the workspace to point to the
+/// new location of the workspace. Does nothing if there's no such worktree.
+pub fn relocate_worktree(
+    store: &Store,
+    name: &WorkspaceName,
+    new_workspace_root: &Path,
+) -> Result<(), GitWorktreeError> {
+    let git_repo = get_git_repo(store)?;
+    let Some(proxy) = find_worktree(&git_repo, name)? else {
+        return Ok(());
+    };
+    write_git_file(
+        &proxy.git_dir().join("gitdir"),
+        &path_to_git_file_line("", &new_workspace_root.join(".git")),
+    )?;
+    Ok(())
+}
+
+/// Returns the root directory of the linked Git worktree named after the
+/// workspace, if any.
+pub fn linked_worktree_root(
+    store: &Store,
+    name: &WorkspaceName,
+) -> Result<Option<PathBuf>, GitWorktreeError> {
+    let git_repo = get_git_repo(store)?;
+    let Some(proxy) = find_worktree(&git_repo, name)? else {
+        return Ok(None);
+    };
+    let root = proxy.base().map_err(GitWorktreeError::from_git)?;
+    Ok(Some(root))
+}
+
+/// Imports HEAD of the `worktree` from the underlying Git repo.
 ///
 /// Unlike `import_refs()`, the old HEAD branch is not abandoned because HEAD
 /// move doesn't always mean the old HEAD branch has been rewritten.
 ///
 /// Unlike `reset_head()`, this function doesn't move the working-copy commit to
 /// the child of the new HEAD revision.
-pub fn import_head(mut_repo: &mut MutableRepo) -> Result<(), GitImportError> {
+pub fn import_head(
+    mut_repo: &mut MutableRepo,
+    worktree: &GitWorktree,
+) -> Result<(), GitImportError> {
     let store = mut_repo.store();
     let git_backend = get_git_backend(store)?;
-    let git_repo = git_backend.git_repo();
+    let git_repo = worktree
+        .open_repo(git_backend.git_repo())
+        .map_err(GitImportError::from_git)?;
 
-    let old_git_head = mut_repo.view().git_head();
+    let old_git_head = worktree.git_head(mut_repo.view());
     let new_git_head_id = if let Ok(oid) = git_repo.head_id() {
         Some(CommitId::from_bytes(oid.as_bytes()))
     } else {
@@ -813,7 +1028,7 @@ pub fn import_head(mut_repo: &mut MutableRepo) -> Result<(), GitImportError> {
             .map_err(GitImportError::Backend)?;
     }
 
-    mut_repo.set_git_head_target(RefTarget::resolved(new_git_head_id));
+    worktree.set_head_target(mut_repo, RefTarget::resolved(new_git_head_id));
     Ok(())
 }
 
@@ -1245,10 +1460,16 @@ impl GitResetHeadError {
     }
 }
 
-/// Sets Git HEAD to the parent of the given working-copy commit and resets
-/// the Git index.
-pub fn reset_head(mut_repo: &mut MutableRepo, wc_commit: &Commit) -> Result<(), GitResetHeadError> {
-    let git_repo = get_git_repo(mut_repo.store())?;
+/// Sets HEAD of the `worktree` to the parent of the given working-copy commit
+/// and resets the Git index of the worktree.
+pub fn reset_head(
+    mut_repo: &mut MutableRepo,
+    wc_commit: &Commit,
+    worktree: &GitWorktree,
+) -> Result<(), GitResetHeadError> {
+    let git_repo = worktree
+        .open_repo(get_git_repo(mut_repo.store())?)
+        .map_err(GitResetHeadError::from_git)?;
 
     let first_parent_id = &wc_commit.parent_ids()[0];
     let new_head_target = if first_parent_id != mut_repo.store().root_commit_id() {
@@ -1258,7 +1479,7 @@ pub fn reset_head(mut_repo: &mut MutableRepo, wc_commit: &Commit) -> Result<(),
     };
 
     // If the first parent of the working copy has changed, reset the Git HEAD.
-    let old_head_target = mut_repo.git_head();
+    let old_head_target = worktree.git_head(mut_repo.view()).clone();
     if old_head_target != new_head_target {
         let expected_ref = if let Some(id) = old_head_target.as_normal() {
             // We have to check the actual HEAD state because we don't record a
@@ -1281,7 +1502,7 @@ pub fn reset_head(mut_repo: &mut MutableRepo, wc_commit: &Commit) -> Result<(),
             .map(|id| gix::ObjectId::from_bytes_or_panic(id.as_bytes()));
         update_git_head(&git_repo, expected_ref, new_oid)
             .map_err(|err| GitResetHeadError::UpdateHeadRef(err.into()))?;
-        mut_repo.set_git_head_target(new_head_target);
+        worktree.set_head_target(mut_repo, new_head_target);
     }
 
     // If there is an ongoing operation (merge, rebase, etc.), we need to clean it
@@ -1484,8 +1705,8 @@ fn build_index_from_merged_tree(
 }
 
 /// Diff `old_tree` to `new_tree` and mark added files as intent-to-add in the
-/// Git index. Also removes current intent-to-add entries in the index if they
-/// were removed in the diff.
+/// Git index of the `worktree`. Also removes current intent-to-add entries in
+/// the index if they were removed in the diff.
 ///
 /// Should be called when the diff between the working-copy commit and its
 /// parent(s) has changed.
@@ -1493,8 +1714,11 @@ pub fn update_intent_to_add(
     repo: &dyn Repo,
     old_tree: &MergedTree,
     new_tree: &MergedTree,
+    worktree: &GitWorktree,
 ) -> Result<(), GitResetHeadError> {
-    let git_repo = get_git_repo(repo.store())?;
+    let git_repo = worktree
+        .open_repo(get_git_repo(repo.store())?)
+        .map_err(GitResetHeadError::from_git)?;
     let mut index = git_repo
         .index_or_empty()
         .map_err(GitResetHeadError::from_git)?;
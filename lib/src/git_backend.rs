@@ -62,6 +62,7 @@ use crate::backend::ConflictId;
 use crate::backend::ConflictTerm;
 use crate::backend::CopyRecord;
 use crate::backend::FileId;
+use crate::backend::GcStats;
 use crate::backend::MergedTreeId;
 use crate::backend::MillisSinceEpoch;
 use crate::backend::SecureSig;
@@ -1418,7 +1419,17 @@ impl Backend for GitBackend {
     }
 
     #[tracing::instrument(skip(self, index))]
-    fn gc(&self, index: &dyn Index, keep_newer: SystemTime) -> BackendResult<()> {
+    fn gc(
+        &self,
+        index: &dyn Index,
+        keep_newer: SystemTime,
+        dry_run: bool,
+    ) -> BackendResult<GcStats> {
+        if dry_run {
+            return Err(BackendError::Unsupported(
+                "Dry-run garbage collection is not supported by the Git backend".to_owned(),
+            ));
+        }
         let git_repo = self.lock_git_repo();
         let new_heads = index
             .all_heads_for_gc()
@@ -1435,7 +1446,8 @@ impl Backend for GitBackend {
         // Since "git gc" will move loose refs into packed refs, in-memory
         // packed-refs cache should be invalidated without relying on mtime.
         git_repo.refs.force_refresh_packed_buffer().ok();
-        Ok(())
+        // "git gc" doesn't report the removed objects.
+        Ok(GcStats::default())
     }
 }
 
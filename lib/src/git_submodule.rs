@@ -0,0 +1,458 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fetching and checking out Git submodules.
+//!
+//! The commit of a submodule is recorded in the tree as
+//! [`TreeValue::GitSubmodule`], and its name and URL are read from the
+//! `.gitmodules` file of the same tree. The objects of each submodule are
+//! stored in a nested Git repo in the [`SubmoduleStore`], which is shared by
+//! all workspaces. The working tree of a submodule is a linked worktree of
+//! that repo, so Git commands run in the submodule work as usual.
+//!
+//! [`SubmoduleStore`]: crate::submodule_store::SubmoduleStore
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use bstr::ByteSlice as _;
+use pollster::FutureExt as _;
+use thiserror::Error;
+use tokio::io::AsyncReadExt as _;
+
+use crate::backend::BackendError;
+use crate::backend::CommitId;
+use crate::backend::TreeValue;
+use crate::git;
+use crate::git_subprocess::GitSubprocessContext;
+use crate::git_subprocess::GitSubprocessError;
+use crate::local_working_copy::git_common_dir;
+use crate::local_working_copy::read_git_submodule_head;
+use crate::local_working_copy::resolve_dot_git;
+use crate::matchers::Matcher;
+use crate::merged_tree::MergedTree;
+use crate::object_id::ObjectId as _;
+use crate::ref_name::RemoteName;
+use crate::repo::Repo;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::settings::GitSettings;
+
+const GITMODULES_PATH: &str = ".gitmodules";
+
+/// Error while fetching or checking out a submodule.
+#[allow(missing_docs)]
+#[derive(Debug, Error)]
+pub enum GitSubmoduleError {
+    #[error("Failed to parse .gitmodules")]
+    InvalidConfig(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    #[error("Submodule {name} has no URL to fetch from")]
+    MissingUrl { name: String },
+    #[error("Commit {commit_id} doesn't exist in submodule {name}")]
+    MissingCommit { name: String, commit_id: CommitId },
+    #[error("Failed to access {path}", path = path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error(transparent)]
+    Subprocess(#[from] GitSubprocessError),
+}
+
+/// Submodule declared in `.gitmodules`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubmoduleConfig {
+    /// Name of the submodule, which identifies its nested repo.
+    pub name: String,
+    /// Path to the submodule in the tree.
+    pub path: RepoPathBuf,
+    /// URL to fetch the submodule from, which may be relative to the URL of
+    /// the parent repo.
+    pub url: Option<String>,
+}
+
+/// Result of fetching or checking out submodules.
+#[derive(Debug, Default)]
+pub struct SubmoduleStats {
+    /// Paths to submodules which were fetched or checked out.
+    pub updated_paths: Vec<RepoPathBuf>,
+    /// Submodules which couldn't be fetched or checked out.
+    pub failed: Vec<(RepoPathBuf, GitSubmoduleError)>,
+}
+
+/// Parses the content of a `.gitmodules` file.
+///
+/// Submodules without a valid relative path are skipped.
+pub fn parse_gitmodules(content: &[u8]) -> Result<Vec<SubmoduleConfig>, GitSubmoduleError> {
+    let file = gix::submodule::File::from_bytes(content, None, &Default::default())
+        .map_err(|err| GitSubmoduleError::InvalidConfig(err.into()))?;
+    let configs = file
+        .names()
+        .filter_map(|name| {
+            let path = file.path(name).ok()?;
+            let path = RepoPathBuf::from_internal_string(path.to_str().ok()?.trim_end_matches('/'))
+                .ok()?;
+            let url = file
+                .config()
+                .string(format!("submodule.{name}.url"))
+                .and_then(|url| url.to_str().ok().map(|url| url.to_owned()))
+                .filter(|url| !url.is_empty());
+            Some(SubmoduleConfig {
+                name: name.to_str_lossy().into_owned(),
+                path,
+                url,
+            })
+        })
+        .collect();
+    Ok(configs)
+}
+
+/// Reads the submodules declared in the `.gitmodules` file of the `tree`.
+///
+/// Returns an empty list if there's no such file or if it's conflicted.
+pub fn read_gitmodules(tree: &MergedTree) -> Result<Vec<SubmoduleConfig>, GitSubmoduleError> {
+    let path = RepoPath::from_internal_string(GITMODULES_PATH).unwrap();
+    let value = tree.path_value(path)?;
+    let Some(TreeValue::File { id, .. }) = value.as_normal() else {
+        return Ok(vec![]);
+    };
+    let mut content = vec![];
+    async {
+        let mut reader = tree.store().read_file(path, id).await?;
+        reader
+            .read_to_end(&mut content)
+            .await
+            .map_err(|err| BackendError::ReadFile {
+                path: path.to_owned(),
+                id: id.clone(),
+                source: err.into(),
+            })
+    }
+    .block_on()?;
+    parse_gitmodules(&content)
+}
+
+/// Fetches the commits of the submodules in the `tree` which aren't in their
+/// nested repos yet.
+///
+/// Relative submodule URLs are resolved against the URL of `remote_name` in
+/// the parent repo.
+pub fn fetch_submodules(
+    repo: &dyn Repo,
+    tree: &MergedTree,
+    remote_name: Option<&RemoteName>,
+    git_settings: &GitSettings,
+) -> Result<SubmoduleStats, GitSubmoduleError> {
+    let parent_url = remote_name.and_then(|name| get_remote_url(repo, name));
+    let mut stats = SubmoduleStats::default();
+    for config in read_gitmodules(tree)? {
+        let value = tree.path_value(&config.path)?;
+        let Some(TreeValue::GitSubmodule(commit_id)) = value.as_normal() else {
+            continue;
+        };
+        let git_dir = repo.submodule_store().git_dir(&config.name);
+        let git_ctx = GitSubprocessContext::new(&git_dir, &git_settings.executable_path);
+        match fetch_commit(
+            &git_ctx,
+            &git_dir,
+            &config,
+            commit_id,
+            parent_url.as_deref(),
+        ) {
+            Ok(true) => stats.updated_paths.push(config.path),
+            Ok(false) => {}
+            Err(err) => stats.failed.push((config.path, err)),
+        }
+    }
+    Ok(stats)
+}
+
+/// Checks out the submodules which changed between `old_tree` and `new_tree`
+/// in the working copy at `workspace_root`.
+///
+/// Submodules which aren't checked out yet are checked out even if they
+/// didn't change. Missing commits are fetched first. The working trees of
+/// removed submodules are deleted. A submodule with local changes which would
+/// be overwritten or deleted is left alone and reported as failed.
+pub fn check_out_submodules(
+    repo: &dyn Repo,
+    old_tree: Option<&MergedTree>,
+    new_tree: &MergedTree,
+    matcher: &dyn Matcher,
+    workspace_root: &Path,
+    remote_name: Option<&RemoteName>,
+    git_settings: &GitSettings,
+) -> Result<SubmoduleStats, GitSubmoduleError> {
+    let configs = read_gitmodules(new_tree)?;
+    let parent_url = remote_name.and_then(|name| get_remote_url(repo, name));
+    let to_disk_path = |path: &RepoPath| {
+        path.to_fs_path(workspace_root)
+            .map_err(|err| GitSubmoduleError::Io {
+                path: workspace_root.to_owned(),
+                source: io::Error::other(err),
+            })
+    };
+    let mut stats = SubmoduleStats::default();
+    if let Some(old_tree) = old_tree {
+        for config in read_gitmodules(old_tree)? {
+            if !matcher.matches(&config.path)
+                || !matches!(
+                    old_tree.path_value(&config.path)?.as_normal(),
+                    Some(TreeValue::GitSubmodule(_))
+                )
+                || matches!(
+                    new_tree.path_value(&config.path)?.as_normal(),
+                    Some(TreeValue::GitSubmodule(_))
+                )
+            {
+                continue;
+            }
+            match remove_submodule(repo, &config, &to_disk_path(&config.path)?, git_settings) {
+                Ok(true) => stats.updated_paths.push(config.path),
+                Ok(false) => {}
+                Err(err) => stats.failed.push((config.path, err)),
+            }
+        }
+    }
+    for config in configs {
+        if !matcher.matches(&config.path) {
+            continue;
+        }
+        let new_value = new_tree.path_value(&config.path)?;
+        let Some(TreeValue::GitSubmodule(commit_id)) = new_value.as_normal() else {
+            continue;
+        };
+        let disk_path = to_disk_path(&config.path)?;
+        let head = read_git_submodule_head(&disk_path);
+        let changed = match old_tree {
+            Some(old_tree) => old_tree.path_value(&config.path)? != new_value,
+            None => true,
+        };
+        if head.as_ref() == Some(commit_id) || (!changed && head.is_some()) {
+            continue;
+        }
+        match check_out_submodule(
+            repo,
+            &config,
+            commit_id,
+            &disk_path,
+            parent_url.as_deref(),
+            git_settings,
+        ) {
+            Ok(()) => stats.updated_paths.push(config.path),
+            Err(err) => stats.failed.push((config.path, err)),
+        }
+    }
+    Ok(stats)
+}
+
+/// Removes the working tree of a submodule which was removed from the tree.
+///
+/// Only worktrees of nested repos in the submodule store are removed, and only
+/// if they have no local changes. Returns `false` if there was nothing to
+/// remove.
+fn remove_submodule(
+    repo: &dyn Repo,
+    config: &SubmoduleConfig,
+    disk_path: &Path,
+    git_settings: &GitSettings,
+) -> Result<bool, GitSubmoduleError> {
+    let git_dir = repo.submodule_store().git_dir(&config.name);
+    let Some(worktree_git_dir) = resolve_dot_git(&disk_path.join(".git")) else {
+        return Ok(false);
+    };
+    let is_same_dir = |a: &Path, b: &Path| {
+        matches!(
+            (dunce::canonicalize(a), dunce::canonicalize(b)),
+            (Ok(a), Ok(b)) if a == b
+        )
+    };
+    if !is_same_dir(&git_common_dir(&worktree_git_dir), &git_dir) {
+        return Ok(false);
+    }
+    let git_ctx = GitSubprocessContext::new(&git_dir, &git_settings.executable_path);
+    git_ctx.spawn_worktree_remove(disk_path)?;
+    Ok(true)
+}
+
+fn check_out_submodule(
+    repo: &dyn Repo,
+    config: &SubmoduleConfig,
+    commit_id: &CommitId,
+    disk_path: &Path,
+    parent_url: Option<&str>,
+    git_settings: &GitSettings,
+) -> Result<(), GitSubmoduleError> {
+    // Use the repo the submodule already points to, if any. It might have
+    // been set up by Git.
+    if let Some(git_dir) = resolve_dot_git(&disk_path.join(".git")) {
+        let git_ctx = GitSubprocessContext::new(&git_dir, &git_settings.executable_path);
+        fetch_commit(&git_ctx, &git_dir, config, commit_id, parent_url)?;
+        git_ctx.spawn_checkout(disk_path, commit_id)?;
+    } else {
+        // Each workspace gets its own linked worktree so that it has its own
+        // HEAD and index.
+        let git_dir = repo.submodule_store().git_dir(&config.name);
+        let git_ctx = GitSubprocessContext::new(&git_dir, &git_settings.executable_path);
+        fetch_commit(&git_ctx, &git_dir, config, commit_id, parent_url)?;
+        git_ctx.spawn_worktree_add(disk_path, commit_id)?;
+    }
+    Ok(())
+}
+
+/// Fetches the `commit_id` into the submodule repo at `git_dir`, creating the
+/// repo if needed. Returns `false` if the commit already exists.
+fn fetch_commit(
+    git_ctx: &GitSubprocessContext,
+    git_dir: &Path,
+    config: &SubmoduleConfig,
+    commit_id: &CommitId,
+    parent_url: Option<&str>,
+) -> Result<bool, GitSubmoduleError> {
+    if git_dir.exists() && git_ctx.spawn_has_commit(commit_id)? {
+        return Ok(false);
+    }
+    let url = config
+        .url
+        .as_deref()
+        .map(|url| resolve_url(url, parent_url))
+        .ok_or_else(|| GitSubmoduleError::MissingUrl {
+            name: config.name.clone(),
+        })?;
+    if !git_dir.exists() {
+        fs::create_dir_all(git_dir).map_err(|source| GitSubmoduleError::Io {
+            path: git_dir.to_owned(),
+            source,
+        })?;
+        git_ctx.spawn_init()?;
+    }
+    git_ctx.spawn_fetch_url(&url, &["+refs/heads/*:refs/remotes/origin/*"])?;
+    // The commit may not be reachable from any branch. Most servers allow
+    // fetching such commits by id.
+    if !git_ctx.spawn_has_commit(commit_id)? {
+        let fetched = git_ctx.spawn_fetch_url(&url, &[&commit_id.hex()]).is_ok()
+            && git_ctx.spawn_has_commit(commit_id)?;
+        if !fetched {
+            return Err(GitSubmoduleError::MissingCommit {
+                name: config.name.clone(),
+                commit_id: commit_id.clone(),
+            });
+        }
+    }
+    Ok(true)
+}
+
+fn get_remote_url(repo: &dyn Repo, remote_name: &RemoteName) -> Option<String> {
+    let git_repo = git::get_git_repo(repo.store()).ok()?;
+    let remote = git_repo.try_find_remote(remote_name.as_str())?.ok()?;
+    let url = remote.url(gix::remote::Direction::Fetch)?;
+    Some(url.to_bstring().to_str().ok()?.to_owned())
+}
+
+/// Resolves a submodule URL relative to the parent repo's URL as Git does.
+fn resolve_url(url: &str, parent_url: Option<&str>) -> String {
+    let (Some(parent_url), true) = (parent_url, url.starts_with("./") || url.starts_with("../"))
+    else {
+        return url.to_owned();
+    };
+    let mut base = parent_url.trim_end_matches('/').to_owned();
+    let mut separator = '/';
+    let mut rest = url;
+    loop {
+        if let Some(tail) = rest.strip_prefix("./") {
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("../") {
+            rest = tail;
+            // "host:path" URLs lose the path component before the host.
+            match base.rfind(['/', ':']) {
+                Some(pos) => {
+                    if base[pos..].starts_with(':') {
+                        separator = ':';
+                    }
+                    base.truncate(pos);
+                }
+                None => base.clear(),
+            }
+        } else {
+            break;
+        }
+    }
+    if base.is_empty() {
+        rest.to_owned()
+    } else {
+        format!("{base}{separator}{rest}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gitmodules() {
+        let content = br#"
+[submodule "lib"]
+    path = vendor/lib
+    url = https://example.com/lib.git
+[submodule "no-url"]
+    path = no-url/
+[submodule "no-path"]
+    url = ../no-path
+"#;
+        let configs = parse_gitmodules(content).unwrap();
+        assert_eq!(
+            configs,
+            vec![
+                SubmoduleConfig {
+                    name: "lib".to_owned(),
+                    path: RepoPathBuf::from_internal_string("vendor/lib").unwrap(),
+                    url: Some("https://example.com/lib.git".to_owned()),
+                },
+                SubmoduleConfig {
+                    name: "no-url".to_owned(),
+                    path: RepoPathBuf::from_internal_string("no-url").unwrap(),
+                    url: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_url() {
+        let parent = Some("https://example.com/org/repo.git");
+        assert_eq!(
+            resolve_url("https://example.com/lib", parent),
+            "https://example.com/lib"
+        );
+        assert_eq!(
+            resolve_url("../lib.git", parent),
+            "https://example.com/org/lib.git"
+        );
+        assert_eq!(
+            resolve_url("./lib.git", parent),
+            "https://example.com/org/repo.git/lib.git"
+        );
+        assert_eq!(
+            resolve_url("../../lib.git", Some("git@example.com:org/repo")),
+            "git@example.com:lib.git"
+        );
+        assert_eq!(resolve_url("../lib", Some("/path/to/repo")), "/path/to/lib");
+        assert_eq!(resolve_url("../lib", None), "../lib");
+    }
+}
@@ -28,12 +28,14 @@ use bstr::ByteSlice as _;
 use itertools::Itertools as _;
 use thiserror::Error;
 
+use crate::backend::CommitId;
 use crate::git::GitPushStats;
 use crate::git::Progress;
 use crate::git::RefSpec;
 use crate::git::RefToPush;
 use crate::git::RemoteCallbacks;
 use crate::git_backend::GitBackend;
+use crate::object_id::ObjectId as _;
 use crate::ref_name::GitRefNameBuf;
 use crate::ref_name::RefNameBuf;
 use crate::ref_name::RemoteName;
@@ -271,6 +273,92 @@ impl<'a> GitSubprocessContext<'a> {
 
         parse_git_push_output(output)
     }
+
+    /// Create a bare repo at the Git directory
+    pub(crate) fn spawn_init(&self) -> Result<(), GitSubprocessError> {
+        let mut command = self.create_command();
+        command.stdout(Stdio::null());
+        command.args(["init", "--quiet"]);
+        parse_git_simple_output(wait_with_output(self.spawn_cmd(command)?)?)
+    }
+
+    /// Fetch from a URL rather than a configured remote
+    ///
+    /// Branches are stored as `refs/remotes/origin/*` so that the fetched
+    /// commits stay reachable.
+    pub(crate) fn spawn_fetch_url(
+        &self,
+        url: &str,
+        refspecs: &[&str],
+    ) -> Result<(), GitSubprocessError> {
+        let mut command = self.create_command();
+        command.stdout(Stdio::null());
+        command.args(["fetch", "--quiet", "--no-write-fetch-head", "--"]);
+        command.arg(url);
+        command.args(refspecs);
+        parse_git_simple_output(wait_with_output(self.spawn_cmd(command)?)?)
+    }
+
+    /// Check whether the commit exists in the repo
+    pub(crate) fn spawn_has_commit(
+        &self,
+        commit_id: &CommitId,
+    ) -> Result<bool, GitSubprocessError> {
+        let mut command = self.create_command();
+        command.stdout(Stdio::null());
+        command.args(["cat-file", "-e"]);
+        command.arg(format!("{}^{{commit}}", commit_id.hex()));
+        let output = wait_with_output(self.spawn_cmd(command)?)?;
+        Ok(output.status.success())
+    }
+
+    /// Check out the commit in `work_tree`, detaching `HEAD`
+    ///
+    /// Like `git checkout`, this fails rather than overwriting local changes.
+    pub(crate) fn spawn_checkout(
+        &self,
+        work_tree: &Path,
+        commit_id: &CommitId,
+    ) -> Result<(), GitSubprocessError> {
+        let mut command = self.create_command();
+        command.stdout(Stdio::null());
+        command.arg("--work-tree").arg(work_tree);
+        command.args(["checkout", "--quiet", "--detach"]);
+        command.arg(commit_id.hex());
+        parse_git_simple_output(wait_with_output(self.spawn_cmd(command)?)?)
+    }
+
+    /// Add a linked worktree at `path` with the commit checked out
+    ///
+    /// Registrations of worktrees which no longer exist are pruned first so
+    /// that a deleted worktree can be added again.
+    pub(crate) fn spawn_worktree_add(
+        &self,
+        path: &Path,
+        commit_id: &CommitId,
+    ) -> Result<(), GitSubprocessError> {
+        let mut command = self.create_command();
+        command.stdout(Stdio::null());
+        command.args(["worktree", "prune"]);
+        parse_git_simple_output(wait_with_output(self.spawn_cmd(command)?)?)?;
+
+        let mut command = self.create_command();
+        command.stdout(Stdio::null());
+        command.args(["worktree", "add", "--quiet", "--detach", "--"]);
+        command.arg(path).arg(commit_id.hex());
+        parse_git_simple_output(wait_with_output(self.spawn_cmd(command)?)?)
+    }
+
+    /// Remove the linked worktree at `path`
+    ///
+    /// This fails if the worktree has local changes or untracked files.
+    pub(crate) fn spawn_worktree_remove(&self, path: &Path) -> Result<(), GitSubprocessError> {
+        let mut command = self.create_command();
+        command.stdout(Stdio::null());
+        command.args(["worktree", "remove", "--"]);
+        command.arg(path);
+        parse_git_simple_output(wait_with_output(self.spawn_cmd(command)?)?)
+    }
 }
 
 /// Generate a GitSubprocessError::ExternalGitError if the stderr output was not
@@ -405,6 +493,18 @@ fn parse_git_branch_prune_output(output: Output) -> Result<(), GitSubprocessErro
     Err(external_git_error(&output.stderr))
 }
 
+fn parse_git_simple_output(output: Output) -> Result<(), GitSubprocessError> {
+    if output.status.success() {
+        return Ok(());
+    }
+
+    if let Some(option) = parse_unknown_option(&output.stderr) {
+        return Err(GitSubprocessError::UnsupportedGitOption(option));
+    }
+
+    Err(external_git_error(&output.stderr))
+}
+
 fn parse_git_remote_show_output(output: Output) -> Result<Output, GitSubprocessError> {
     if output.status.success() {
         return Ok(output);
@@ -0,0 +1,517 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Non-interactive selection of changes by line ranges or patch hunks.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use bstr::ByteSlice as _;
+use futures::StreamExt as _;
+use itertools::Itertools as _;
+use thiserror::Error;
+
+use crate::absorb::combine_texts;
+use crate::backend::BackendResult;
+use crate::backend::MergedTreeId;
+use crate::backend::TreeValue;
+use crate::conflicts::materialized_diff_stream;
+use crate::conflicts::MaterializedTreeValue;
+use crate::copies::CopyRecords;
+use crate::diff::Diff;
+use crate::diff::DiffHunkKind;
+use crate::matchers::FilesMatcher;
+use crate::matchers::IntersectionMatcher;
+use crate::matchers::Matcher;
+use crate::merge::Merge;
+use crate::merged_tree::MergedTree;
+use crate::merged_tree::MergedTreeBuilder;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+
+/// Changes to be selected from a single file.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FileSelection {
+    /// Selects all changes to the file.
+    pub all: bool,
+    /// Selects the change of the executable bit.
+    pub mode: bool,
+    /// Selects hunks overlapping these 0-based line ranges of the new file
+    /// content.
+    pub line_ranges: Vec<Range<usize>>,
+}
+
+/// Changes to be selected, keyed by file path.
+#[derive(Clone, Debug, Default)]
+pub struct HunkSelection {
+    files: HashMap<RepoPathBuf, FileSelection>,
+}
+
+/// Error occurred while parsing patch.
+#[derive(Debug, Error)]
+#[error("Failed to parse patch at line {line_number}: {message}")]
+pub struct PatchParseError {
+    /// 1-based line number.
+    pub line_number: usize,
+    /// Description of the error.
+    pub message: String,
+}
+
+impl HunkSelection {
+    /// Creates an empty selection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if no changes are selected.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Returns the changes to be selected from the file at `path`.
+    pub fn get(&self, path: &RepoPath) -> Option<&FileSelection> {
+        self.files.get(path)
+    }
+
+    /// Selects all changes to the file at `path`.
+    pub fn select_file(&mut self, path: RepoPathBuf) {
+        self.files.entry(path).or_default().all = true;
+    }
+
+    /// Selects hunks overlapping the 0-based `lines` of the new file content.
+    pub fn select_lines(&mut self, path: RepoPathBuf, lines: Range<usize>) {
+        self.files.entry(path).or_default().line_ranges.push(lines);
+    }
+
+    /// Parses unified diff (such as the output of `jj diff --git`) and
+    /// selects the hunks included in it.
+    ///
+    /// Hunks are identified by the line ranges of the new content, so the
+    /// patch should be generated from the same changes. Files without text
+    /// hunks (e.g. binary files) are selected as a whole.
+    pub fn from_patch(patch: &[u8]) -> Result<Self, PatchParseError> {
+        let mut selection = HunkSelection::new();
+        let mut current: Option<PatchFile> = None;
+        let mut lines = patch.lines().enumerate();
+        while let Some((index, line)) = lines.next() {
+            let error = |message: &str| PatchParseError {
+                line_number: index + 1,
+                message: message.to_owned(),
+            };
+            if let Some(rest) = line.strip_prefix(b"diff --git ") {
+                selection.add_patch_file(current.take(), index)?;
+                let header = rest.to_str().map_err(|_| error("Invalid UTF-8 path"))?;
+                let (old_path, new_path) = header
+                    .rsplit_once(" b/")
+                    .ok_or_else(|| error("Invalid diff header"))?;
+                current = Some(PatchFile {
+                    old_path: Some(old_path.to_owned()),
+                    new_path: Some(format!("b/{new_path}")),
+                    ..Default::default()
+                });
+            } else if let Some(path) = line.strip_prefix(b"--- ") {
+                let path = path.to_str().map_err(|_| error("Invalid UTF-8 path"))?;
+                if current.as_ref().is_none_or(|file| file.has_hunks) {
+                    selection.add_patch_file(current.take(), index)?;
+                    current = Some(PatchFile::default());
+                }
+                current.as_mut().unwrap().old_path = Some(path.to_owned());
+            } else if let Some(path) = line.strip_prefix(b"+++ ") {
+                let path = path.to_str().map_err(|_| error("Invalid UTF-8 path"))?;
+                let file = current
+                    .as_mut()
+                    .ok_or_else(|| error("Unexpected +++ line"))?;
+                file.new_path = Some(path.to_owned());
+            } else if let Some(header) = line.strip_prefix(b"@@ ") {
+                let file = current.as_mut().ok_or_else(|| error("Unexpected hunk"))?;
+                let (old_count, new_range) =
+                    parse_hunk_header(header).ok_or_else(|| error("Invalid hunk header"))?;
+                file.has_hunks = true;
+                file.selection.line_ranges.push(new_range.clone());
+                // Skip hunk body, which may contain lines looking like headers
+                let (mut old_remaining, mut new_remaining) = (old_count, new_range.len());
+                while old_remaining > 0 || new_remaining > 0 {
+                    let Some((_, line)) = lines.next() else {
+                        return Err(error("Unexpected end of hunk"));
+                    };
+                    match line.first() {
+                        Some(b' ') | None => {
+                            old_remaining = old_remaining.saturating_sub(1);
+                            new_remaining = new_remaining.saturating_sub(1);
+                        }
+                        Some(b'-') => old_remaining = old_remaining.saturating_sub(1),
+                        Some(b'+') => new_remaining = new_remaining.saturating_sub(1),
+                        Some(b'\\') => {}
+                        Some(_) => return Err(error("Unexpected line in hunk")),
+                    }
+                }
+            } else if let Some(file) = current.as_mut() {
+                if line.starts_with(b"new file mode ")
+                    || line.starts_with(b"deleted file mode ")
+                    || line.starts_with(b"Binary files ")
+                    || line.starts_with(b"rename from ")
+                    || line.starts_with(b"copy from ")
+                {
+                    file.selection.all = true;
+                } else if line.starts_with(b"new mode ") {
+                    file.selection.mode = true;
+                }
+            }
+        }
+        selection.add_patch_file(current, patch.lines().count())?;
+        Ok(selection)
+    }
+
+    fn add_patch_file(
+        &mut self,
+        file: Option<PatchFile>,
+        line_index: usize,
+    ) -> Result<(), PatchParseError> {
+        let Some(mut file) = file else {
+            return Ok(());
+        };
+        let to_repo_path = |path: &str| {
+            let path = path
+                .strip_prefix("a/")
+                .or_else(|| path.strip_prefix("b/"))
+                .unwrap_or(path);
+            RepoPathBuf::from_internal_string(path).map_err(|err| PatchParseError {
+                line_number: line_index,
+                message: err.to_string(),
+            })
+        };
+        let old_path = file.old_path.filter(|path| path != "/dev/null");
+        let new_path = file.new_path.filter(|path| path != "/dev/null");
+        if !file.has_hunks && !file.selection.mode {
+            file.selection.all = true;
+        }
+        match (old_path, new_path) {
+            (Some(old_path), Some(new_path)) => {
+                let old_path = to_repo_path(&old_path)?;
+                let new_path = to_repo_path(&new_path)?;
+                if old_path != new_path {
+                    // Renames are selected as deletion and addition.
+                    self.select_file(old_path);
+                    self.select_file(new_path);
+                } else {
+                    self.add_file_selection(new_path, file.selection);
+                }
+            }
+            (Some(path), None) | (None, Some(path)) => {
+                // Addition or deletion
+                file.selection.all = true;
+                self.add_file_selection(to_repo_path(&path)?, file.selection);
+            }
+            (None, None) => {
+                return Err(PatchParseError {
+                    line_number: line_index,
+                    message: "Missing file path".to_owned(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn add_file_selection(&mut self, path: RepoPathBuf, selection: FileSelection) {
+        let entry = self.files.entry(path).or_default();
+        entry.all |= selection.all;
+        entry.mode |= selection.mode;
+        entry.line_ranges.extend(selection.line_ranges);
+    }
+}
+
+#[derive(Debug, Default)]
+struct PatchFile {
+    old_path: Option<String>,
+    new_path: Option<String>,
+    has_hunks: bool,
+    selection: FileSelection,
+}
+
+/// Parses `-l,s +l,s @@` part of hunk header. Returns the number of old lines
+/// and the 0-based range of new lines.
+fn parse_hunk_header(header: &[u8]) -> Option<(usize, Range<usize>)> {
+    let header = header.to_str().ok()?;
+    let mut words = header.split(' ');
+    let old = words.next()?.strip_prefix('-')?;
+    let new = words.next()?.strip_prefix('+')?;
+    let parse_range = |range: &str| -> Option<(usize, usize)> {
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (_, old_count) = parse_range(old)?;
+    let (new_start, new_count) = parse_range(new)?;
+    // Empty range starts after the given line
+    let new_start = if new_count == 0 {
+        new_start
+    } else {
+        new_start.checked_sub(1)?
+    };
+    Some((old_count, new_start..new_start + new_count))
+}
+
+/// Builds a tree containing the `left_tree` contents plus the changes from the
+/// `right_tree` selected by `selection`. Only the paths matching the `matcher`
+/// are considered.
+pub async fn select_hunks(
+    left_tree: &MergedTree,
+    right_tree: &MergedTree,
+    matcher: &dyn Matcher,
+    selection: &HunkSelection,
+) -> BackendResult<MergedTreeId> {
+    let store = left_tree.store();
+    let files_matcher = FilesMatcher::new(selection.files.keys());
+    let matcher = IntersectionMatcher::new(matcher, &files_matcher);
+    let mut tree_builder = MergedTreeBuilder::new(left_tree.id().clone());
+    let copy_records = CopyRecords::default();
+    let tree_diff = left_tree.diff_stream_with_copies(right_tree, &matcher, &copy_records);
+    let mut diff_stream = materialized_diff_stream(store, tree_diff);
+    while let Some(entry) = diff_stream.next().await {
+        let path = entry.path.target();
+        let file_selection = &selection.files[path];
+        let (left_value, right_value) = entry.values?;
+        if file_selection.all {
+            tree_builder.set_or_remove(path.to_owned(), right_tree.path_value(path)?);
+            continue;
+        }
+        let (left_text, left_executable) = match left_value {
+            MaterializedTreeValue::Absent => (None, None),
+            MaterializedTreeValue::File(mut file) => {
+                (Some(file.read_all(path).await?), Some(file.executable))
+            }
+            // Other kinds of changes can only be selected as a whole.
+            _ => continue,
+        };
+        let (right_text, right_executable) = match right_value {
+            MaterializedTreeValue::Absent => (None, None),
+            MaterializedTreeValue::File(mut file) => {
+                (Some(file.read_all(path).await?), Some(file.executable))
+            }
+            _ => continue,
+        };
+        let left_content = left_text.as_deref().unwrap_or_default();
+        let right_content = right_text.as_deref().unwrap_or_default();
+        let diff = Diff::by_line_with_algorithm(
+            [left_content, right_content],
+            store.merge_options().diff_algorithm,
+        );
+        let selected_ranges = diff
+            .hunk_ranges()
+            .filter(|hunk| hunk.kind == DiffHunkKind::Different)
+            .map(|hunk| {
+                let [left_range, right_range]: &[_; 2] = hunk.ranges[..].try_into().unwrap();
+                (left_range.clone(), right_range.clone())
+            })
+            .filter(|(_, right_range)| {
+                let lines = to_line_range(right_content, right_range.clone());
+                file_selection
+                    .line_ranges
+                    .iter()
+                    .any(|selected| ranges_overlap(selected, &lines))
+            })
+            .collect_vec();
+        let mode_selected = file_selection.mode
+            && left_executable.is_some()
+            && right_executable.is_some()
+            && left_executable != right_executable;
+        if selected_ranges.is_empty() && !mode_selected {
+            continue;
+        }
+        let executable = if mode_selected {
+            right_executable
+        } else {
+            left_executable.or(right_executable)
+        };
+        let new_text = combine_texts(left_content, right_content, &selected_ranges);
+        let new_value = if new_text.is_empty() && right_text.is_none() {
+            Merge::absent()
+        } else {
+            let id = store.write_file(path, &mut new_text.as_slice()).await?;
+            Merge::normal(TreeValue::File {
+                id,
+                executable: executable.unwrap(),
+            })
+        };
+        tree_builder.set_or_remove(path.to_owned(), new_value);
+    }
+    tree_builder.write_tree(store)
+}
+
+/// Converts the byte `range` of the `text` to 0-based line range.
+fn to_line_range(text: &[u8], range: Range<usize>) -> Range<usize> {
+    let start = text[..range.start].lines_with_terminator().count();
+    let len = text[range].lines_with_terminator().count();
+    start..start + len
+}
+
+/// Returns true if the `selected` lines overlap with the `lines` of a hunk.
+/// Empty `lines`, which represents deletion, overlaps if it is adjacent to or
+/// within the `selected` range.
+fn ranges_overlap(selected: &Range<usize>, lines: &Range<usize>) -> bool {
+    if lines.is_empty() {
+        selected.start <= lines.start && lines.start <= selected.end
+    } else {
+        selected.start < lines.end && lines.start < selected.end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo_path_buf(value: &str) -> RepoPathBuf {
+        RepoPathBuf::from_internal_string(value).unwrap()
+    }
+
+    #[test]
+    fn test_parse_hunk_header() {
+        assert_eq!(parse_hunk_header(b"-1,3 +1,4 @@"), Some((3, 0..4)));
+        assert_eq!(parse_hunk_header(b"-5 +6 @@ fn foo()"), Some((1, 5..6)));
+        assert_eq!(parse_hunk_header(b"-0,0 +1,2 @@"), Some((0, 0..2)));
+        assert_eq!(parse_hunk_header(b"-3,2 +2,0 @@"), Some((2, 2..2)));
+        assert_eq!(parse_hunk_header(b"-1,3 +0,1 @@"), None);
+        assert_eq!(parse_hunk_header(b"+1,3 -1,3 @@"), None);
+    }
+
+    #[test]
+    fn test_ranges_overlap() {
+        assert!(ranges_overlap(&(2..5), &(4..6)));
+        assert!(ranges_overlap(&(2..5), &(0..3)));
+        assert!(!ranges_overlap(&(2..5), &(5..6)));
+        assert!(!ranges_overlap(&(2..5), &(0..2)));
+        // deletion
+        assert!(ranges_overlap(&(2..5), &(2..2)));
+        assert!(ranges_overlap(&(2..5), &(5..5)));
+        assert!(!ranges_overlap(&(2..5), &(6..6)));
+    }
+
+    #[test]
+    fn test_to_line_range() {
+        let text = b"a\nbb\nccc\ndd";
+        assert_eq!(to_line_range(text, 0..0), 0..0);
+        assert_eq!(to_line_range(text, 0..2), 0..1);
+        assert_eq!(to_line_range(text, 2..9), 1..3);
+        assert_eq!(to_line_range(text, 9..11), 3..4);
+        assert_eq!(to_line_range(text, 11..11), 4..4);
+    }
+
+    #[test]
+    fn test_from_patch() {
+        let patch = indoc::indoc! {b"
+            diff --git a/file1 b/file1
+            index 0123456789..abcdef0123 100644
+            --- a/file1
+            +++ b/file1
+            @@ -1,3 +1,3 @@
+             a
+            -b
+            +B
+             c
+            @@ -10,2 +10,3 @@ context
+             j
+            +--- looks like a header
+             k
+            diff --git a/added b/added
+            new file mode 100644
+            index 0000000000..abcdef0123
+            --- /dev/null
+            +++ b/added
+            @@ -0,0 +1,1 @@
+            +added
+            diff --git a/exec b/exec
+            old mode 100644
+            new mode 100755
+            diff --git a/image.png b/image.png
+            index 0123456789..abcdef0123 100644
+            Binary files a/image.png and b/image.png differ
+            diff --git a/old b/new
+            rename from old
+            rename to new
+        "};
+        let selection = HunkSelection::from_patch(patch).unwrap();
+        assert_eq!(
+            selection.get(&repo_path_buf("file1")),
+            Some(&FileSelection {
+                all: false,
+                mode: false,
+                line_ranges: vec![0..3, 9..12],
+            })
+        );
+        let select_all = FileSelection {
+            all: true,
+            ..Default::default()
+        };
+        assert!(selection.get(&repo_path_buf("added")).unwrap().all);
+        assert_eq!(
+            selection.get(&repo_path_buf("exec")),
+            Some(&FileSelection {
+                mode: true,
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            selection.get(&repo_path_buf("image.png")),
+            Some(&select_all)
+        );
+        assert_eq!(selection.get(&repo_path_buf("old")), Some(&select_all));
+        assert_eq!(selection.get(&repo_path_buf("new")), Some(&select_all));
+        assert_eq!(selection.get(&repo_path_buf("unknown")), None);
+    }
+
+    #[test]
+    fn test_from_patch_plain_diff() {
+        let patch = indoc::indoc! {b"
+            --- file1
+            +++ file1
+            @@ -2 +2 @@
+            -b
+            +B
+            --- a/file2
+            +++ b/file2
+            @@ -1,2 +1 @@
+             a
+            -b
+        "};
+        let selection = HunkSelection::from_patch(patch).unwrap();
+        assert_eq!(
+            selection.get(&repo_path_buf("file1")).unwrap().line_ranges,
+            vec![1..2]
+        );
+        assert_eq!(
+            selection.get(&repo_path_buf("file2")).unwrap().line_ranges,
+            vec![0..1]
+        );
+    }
+
+    #[test]
+    fn test_from_patch_error() {
+        let err = HunkSelection::from_patch(b"@@ -1 +1 @@\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to parse patch at line 1: Unexpected hunk"
+        );
+        let err = HunkSelection::from_patch(b"--- a/f\n+++ b/f\n@@ -1 +1 @@\n-a\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to parse patch at line 3: Unexpected end of hunk"
+        );
+        let err = HunkSelection::from_patch(b"--- a/f\n+++ b/f\n@@ -x +1 @@\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to parse patch at line 3: Invalid hunk header"
+        );
+    }
+}
@@ -31,6 +31,7 @@ pub mod content_hash;
 pub mod absorb;
 pub mod annotate;
 pub mod backend;
+pub mod chunked_backend;
 pub mod commit;
 pub mod commit_builder;
 pub mod config;
@@ -50,6 +51,7 @@ pub mod fileset;
 mod fileset_parser;
 pub mod fix;
 pub mod fmt_util;
+pub mod fsck;
 pub mod fsmonitor;
 #[cfg(feature = "git")]
 pub mod git;
@@ -69,23 +71,28 @@ pub mod git {
 #[cfg(feature = "git")]
 pub mod git_backend;
 #[cfg(feature = "git")]
+pub mod git_submodule;
+#[cfg(feature = "git")]
 mod git_subprocess;
 pub mod gitignore;
 pub mod gpg_signing;
 pub mod graph;
 pub mod hex_util;
+pub mod hunk_selection;
 pub mod id_prefix;
 pub mod index;
 pub mod local_working_copy;
 pub mod lock;
 pub mod matchers;
 pub mod merge;
+pub mod merge_driver;
 pub mod merged_tree;
 pub mod object_id;
 pub mod op_heads_store;
 pub mod op_store;
 pub mod op_walk;
 pub mod operation;
+pub mod patch_id;
 #[expect(missing_docs)]
 pub mod protos;
 pub mod ref_name;
@@ -105,6 +112,7 @@ pub mod signing;
 pub mod simple_backend;
 pub mod simple_op_heads_store;
 pub mod simple_op_store;
+pub mod sparse;
 pub mod ssh_signing;
 pub mod stacked_table;
 pub mod store;
@@ -121,6 +129,7 @@ pub mod union_find;
 pub mod view;
 pub mod working_copy;
 pub mod workspace;
+pub mod workspace_store;
 
 #[cfg(test)]
 mod tests {
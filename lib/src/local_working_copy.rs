@@ -39,6 +39,7 @@ use std::sync::mpsc::channel;
 use std::sync::mpsc::Sender;
 use std::sync::Arc;
 use std::sync::OnceLock;
+use std::time::Duration;
 use std::time::UNIX_EPOCH;
 
 use either::Either;
@@ -59,6 +60,7 @@ use tracing::trace_span;
 
 use crate::backend::BackendError;
 use crate::backend::BackendResult;
+use crate::backend::CommitId;
 use crate::backend::FileId;
 use crate::backend::MergedTreeId;
 use crate::backend::MillisSinceEpoch;
@@ -77,6 +79,7 @@ use crate::file_util::check_symlink_support;
 use crate::file_util::copy_async_to_sync;
 use crate::file_util::try_symlink;
 use crate::file_util::BlockingAsyncReader;
+use crate::fileset::FilesetExpression;
 #[cfg(feature = "watchman")]
 use crate::fsmonitor::watchman;
 use crate::fsmonitor::FsmonitorSettings;
@@ -89,7 +92,6 @@ use crate::matchers::EverythingMatcher;
 use crate::matchers::FilesMatcher;
 use crate::matchers::IntersectionMatcher;
 use crate::matchers::Matcher;
-use crate::matchers::PrefixMatcher;
 use crate::merge::Merge;
 use crate::merge::MergeBuilder;
 use crate::merge::MergedTreeValue;
@@ -103,6 +105,9 @@ use crate::ref_name::WorkspaceNameBuf;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
 use crate::repo_path::RepoPathComponent;
+use crate::sparse::resolve_sparse_profile;
+use crate::sparse::sparse_patterns_to_expression;
+use crate::sparse::SparsePattern;
 use crate::store::Store;
 use crate::tree::Tree;
 use crate::working_copy::CheckoutError;
@@ -166,6 +171,9 @@ pub struct FileState {
     pub mtime: MillisSinceEpoch,
     pub size: u64,
     pub materialized_conflict_data: Option<MaterializedConflictData>,
+    /// Commit checked out in the Git submodule when the working copy was last
+    /// updated or snapshotted. Set only for `FileType::GitSubmodule`.
+    pub submodule_head: Option<CommitId>,
     /* TODO: What else do we need here? Git stores a lot of fields.
      * TODO: Could possibly handle case-insensitive file systems keeping an
      *       Option<PathBuf> with the actual path here. */
@@ -189,6 +197,7 @@ impl FileState {
             mtime: MillisSinceEpoch(0),
             size: 0,
             materialized_conflict_data: None,
+            submodule_head: None,
         }
     }
 
@@ -204,6 +213,7 @@ impl FileState {
             mtime: mtime_from_metadata(metadata),
             size,
             materialized_conflict_data,
+            submodule_head: None,
         }
     }
 
@@ -216,15 +226,17 @@ impl FileState {
             mtime: mtime_from_metadata(metadata),
             size: metadata.len(),
             materialized_conflict_data: None,
+            submodule_head: None,
         }
     }
 
-    fn for_gitsubmodule() -> Self {
+    fn for_gitsubmodule(submodule_head: Option<CommitId>) -> Self {
         FileState {
             file_type: FileType::GitSubmodule,
             mtime: MillisSinceEpoch(0),
             size: 0,
             materialized_conflict_data: None,
+            submodule_head,
         }
     }
 }
@@ -443,8 +455,14 @@ pub struct TreeState {
     state_path: PathBuf,
     tree_id: MergedTreeId,
     file_states: FileStatesMap,
-    // Currently only path prefixes
-    sparse_patterns: Vec<RepoPathBuf>,
+    sparse_patterns: Vec<SparsePattern>,
+    // Matches the paths of `sparse_patterns`.
+    sparse_expression: FilesetExpression,
+    // The profile `sparse_patterns` were resolved from, if any. The patterns
+    // are resolved again when a tree with different profile is checked out.
+    sparse_profile: Option<String>,
+    // Why `sparse_patterns` are ignored, if they couldn't be parsed.
+    sparse_patterns_error: Option<String>,
     own_mtime: MillisSinceEpoch,
     symlink_support: bool,
 
@@ -479,6 +497,8 @@ fn file_state_from_proto(proto: &crate::protos::working_copy::FileState) -> File
                 conflict_marker_len: data.conflict_marker_len,
             }
         }),
+        submodule_head: (!proto.submodule_head.is_empty())
+            .then(|| CommitId::new(proto.submodule_head.clone())),
     }
 }
 
@@ -503,6 +523,10 @@ fn file_state_to_proto(file_state: &FileState) -> crate::protos::working_copy::F
             conflict_marker_len: data.conflict_marker_len,
         }
     });
+    proto.submodule_head = file_state
+        .submodule_head
+        .as_ref()
+        .map_or_else(Vec::new, |id| id.to_bytes());
     proto
 }
 
@@ -534,18 +558,91 @@ fn is_file_state_entries_proto_unique_and_sorted(
 
 fn sparse_patterns_from_proto(
     proto: Option<&crate::protos::working_copy::SparsePatterns>,
-) -> Vec<RepoPathBuf> {
+) -> (Vec<SparsePattern>, Option<String>) {
     let mut sparse_patterns = vec![];
+    let mut sparse_profile = None;
     if let Some(proto_sparse_patterns) = proto {
         for prefix in &proto_sparse_patterns.prefixes {
-            sparse_patterns.push(RepoPathBuf::from_internal_string(prefix).unwrap());
+            let path = RepoPathBuf::from_internal_string(prefix).unwrap();
+            sparse_patterns.push(SparsePattern::Prefix(path));
+        }
+        for fileset in &proto_sparse_patterns.filesets {
+            sparse_patterns.push(SparsePattern::Fileset(fileset.clone()));
+        }
+        if !proto_sparse_patterns.profile.is_empty() {
+            sparse_profile = Some(proto_sparse_patterns.profile.clone());
         }
     } else {
         // For compatibility with old working copies.
         // TODO: Delete this is late 2022 or so.
-        sparse_patterns.push(RepoPathBuf::root());
+        sparse_patterns.push(SparsePattern::Prefix(RepoPathBuf::root()));
+    }
+    (sparse_patterns, sparse_profile)
+}
+
+fn sparse_patterns_to_proto(
+    sparse_patterns: &[SparsePattern],
+    sparse_profile: Option<&str>,
+) -> crate::protos::working_copy::SparsePatterns {
+    let mut proto = crate::protos::working_copy::SparsePatterns::default();
+    for pattern in sparse_patterns {
+        match pattern {
+            SparsePattern::Prefix(path) => proto
+                .prefixes
+                .push(path.as_internal_file_string().to_owned()),
+            SparsePattern::Fileset(text) => proto.filesets.push(text.clone()),
+        }
+    }
+    proto.profile = sparse_profile.unwrap_or_default().to_owned();
+    proto
+}
+
+/// Returns the Git directory the `.git` file or directory at `dot_git_path`
+/// points to.
+pub(crate) fn resolve_dot_git(dot_git_path: &Path) -> Option<PathBuf> {
+    let metadata = dot_git_path.symlink_metadata().ok()?;
+    if metadata.is_dir() {
+        return Some(dot_git_path.to_owned());
+    }
+    let content = fs::read_to_string(dot_git_path).ok()?;
+    let git_dir = content.strip_prefix("gitdir:")?.trim();
+    Some(dot_git_path.parent()?.join(git_dir))
+}
+
+/// Returns the directory of the refs and objects shared by the linked
+/// worktrees of the repo, which is the `git_dir` itself for the main worktree.
+pub(crate) fn git_common_dir(git_dir: &Path) -> PathBuf {
+    match fs::read_to_string(git_dir.join("commondir")) {
+        Ok(common_dir) => git_dir.join(common_dir.trim()),
+        Err(_) => git_dir.to_owned(),
+    }
+}
+
+/// Reads the commit checked out in the Git submodule at `disk_path`.
+///
+/// Returns `None` if the submodule isn't checked out or its `HEAD` can't be
+/// resolved.
+pub(crate) fn read_git_submodule_head(disk_path: &Path) -> Option<CommitId> {
+    let git_dir = resolve_dot_git(&disk_path.join(".git"))?;
+    let common_dir = git_common_dir(&git_dir);
+    let mut target = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    // Follow symbolic refs, but not forever.
+    for _ in 0..5 {
+        let Some(ref_name) = target.trim().strip_prefix("ref: ").map(str::to_owned) else {
+            return CommitId::try_from_hex(target.trim()).ok();
+        };
+        target = match fs::read_to_string(common_dir.join(&ref_name)) {
+            Ok(target) => target,
+            Err(_) => {
+                let packed_refs = fs::read_to_string(common_dir.join("packed-refs")).ok()?;
+                packed_refs.lines().find_map(|line| {
+                    let (id, name) = line.split_once(' ')?;
+                    (name == ref_name).then(|| id.to_owned())
+                })?
+            }
+        };
     }
-    sparse_patterns
+    None
 }
 
 /// Creates intermediate directories from the `working_copy_path` to the
@@ -676,6 +773,10 @@ fn can_create_new_file(disk_path: &Path) -> Result<bool, CheckoutError> {
 
 const RESERVED_DIR_NAMES: &[&str] = &[".git", ".jj"];
 
+/// How long to wait for the working-copy lock held by another process. The
+/// lock may be held while an editor is open, so don't wait indefinitely.
+const WORKING_COPY_LOCK_TIMEOUT: Duration = Duration::from_secs(60);
+
 /// Suppose the `disk_path` exists, checks if the last component points to
 /// ".git" or ".jj" in the same parent directory.
 fn reject_reserved_existing_path(disk_path: &Path) -> Result<(), CheckoutError> {
@@ -742,6 +843,7 @@ fn file_state(metadata: &Metadata) -> Option<FileState> {
             mtime,
             size,
             materialized_conflict_data: None,
+            submodule_head: None,
         }
     })
 }
@@ -781,12 +883,16 @@ impl TreeState {
         self.file_states.all()
     }
 
-    pub fn sparse_patterns(&self) -> &Vec<RepoPathBuf> {
+    pub fn sparse_patterns(&self) -> &Vec<SparsePattern> {
         &self.sparse_patterns
     }
 
+    pub fn sparse_profile(&self) -> Option<&str> {
+        self.sparse_profile.as_deref()
+    }
+
     fn sparse_matcher(&self) -> Box<dyn Matcher> {
-        Box::new(PrefixMatcher::new(&self.sparse_patterns))
+        self.sparse_expression.to_matcher()
     }
 
     pub fn init(
@@ -807,7 +913,10 @@ impl TreeState {
             state_path,
             tree_id,
             file_states: FileStatesMap::new(),
-            sparse_patterns: vec![RepoPathBuf::root()],
+            sparse_patterns: vec![SparsePattern::Prefix(RepoPathBuf::root())],
+            sparse_expression: FilesetExpression::all(),
+            sparse_profile: None,
+            sparse_patterns_error: None,
             own_mtime: MillisSinceEpoch(0),
             symlink_support: check_symlink_support().unwrap_or(false),
             watchman_clock: None,
@@ -872,7 +981,25 @@ impl TreeState {
         }
         self.file_states =
             FileStatesMap::from_proto(proto.file_states, proto.is_file_states_sorted);
-        self.sparse_patterns = sparse_patterns_from_proto(proto.sparse_patterns.as_ref());
+        (self.sparse_patterns, self.sparse_profile) =
+            sparse_patterns_from_proto(proto.sparse_patterns.as_ref());
+        // Patterns that can't be parsed (e.g. because the fileset syntax
+        // changed) shouldn't make the working copy unusable, so all files are
+        // matched until the patterns are replaced.
+        match sparse_patterns_to_expression(&self.sparse_patterns, &self.working_copy_path) {
+            Ok(expression) => {
+                self.sparse_expression = expression;
+                self.sparse_patterns_error = None;
+            }
+            Err(err) => {
+                tracing::warn!(?err, "failed to parse sparse patterns");
+                self.sparse_expression = FilesetExpression::all();
+                self.sparse_patterns_error = Some(format!(
+                    "Failed to parse the sparse patterns, so the working copy is treated as a \
+                     full checkout: {err}"
+                ));
+            }
+        }
         self.watchman_clock = proto.watchman_clock;
         Ok(())
     }
@@ -892,13 +1019,10 @@ impl TreeState {
         proto.file_states = self.file_states.data.clone();
         // `FileStatesMap` is guaranteed to be sorted.
         proto.is_file_states_sorted = true;
-        let mut sparse_patterns = crate::protos::working_copy::SparsePatterns::default();
-        for path in &self.sparse_patterns {
-            sparse_patterns
-                .prefixes
-                .push(path.as_internal_file_string().to_owned());
-        }
-        proto.sparse_patterns = Some(sparse_patterns);
+        proto.sparse_patterns = Some(sparse_patterns_to_proto(
+            &self.sparse_patterns,
+            self.sparse_profile.as_deref(),
+        ));
         proto.watchman_clock = self.watchman_clock.clone();
 
         let mut temp_file = NamedTempFile::new_in(&self.state_path).unwrap();
@@ -1265,6 +1389,9 @@ impl FileSnapshotter<'_> {
         let maybe_current_file_state = file_states.get_at(dir, name);
         if let Some(file_state) = &maybe_current_file_state {
             if file_state.file_type == FileType::GitSubmodule {
+                if file_type.is_dir() && self.matcher.matches(&path) {
+                    self.visit_submodule(&path, &entry.path(), file_state)?;
+                }
                 return Ok(None);
             }
         }
@@ -1341,6 +1468,40 @@ impl FileSnapshotter<'_> {
         }
     }
 
+    /// Records the commit checked out in the submodule if it was moved since
+    /// the working copy was last updated, and if it differs from the one in the
+    /// current tree.
+    fn visit_submodule(
+        &self,
+        path: &RepoPath,
+        disk_path: &Path,
+        current_file_state: &FileState,
+    ) -> Result<(), SnapshotError> {
+        let Some(new_id) = read_git_submodule_head(disk_path) else {
+            return Ok(());
+        };
+        // If the submodule HEAD hasn't moved, the commit in the tree wins even
+        // if it isn't checked out (e.g. because updating the submodule failed.)
+        if current_file_state.submodule_head.as_ref() == Some(&new_id) {
+            return Ok(());
+        }
+        let current_tree_values = self.current_tree.path_value(path)?;
+        // Conflicted submodules have to be resolved explicitly.
+        if let Some(TreeValue::GitSubmodule(old_id)) = current_tree_values.as_normal() {
+            if *old_id != new_id {
+                let new_tree_values = Merge::normal(TreeValue::GitSubmodule(new_id.clone()));
+                self.tree_entries_tx
+                    .send((path.to_owned(), new_tree_values))
+                    .ok();
+            }
+        }
+        let new_file_state = FileState::for_gitsubmodule(Some(new_id));
+        self.file_states_tx
+            .send((path.to_owned(), new_file_state))
+            .ok();
+        Ok(())
+    }
+
     /// Visits only paths we're already tracking.
     fn visit_tracked_files(&self, file_states: FileStates<'_>) -> Result<(), SnapshotError> {
         for (tracked_path, current_file_state) in file_states {
@@ -1722,7 +1883,7 @@ impl TreeState {
             },
             other => CheckoutError::InternalBackendError(other),
         })?;
-        let stats = self
+        let mut stats = self
             .update(
                 &old_tree,
                 new_tree,
@@ -1731,12 +1892,51 @@ impl TreeState {
             )
             .block_on()?;
         self.tree_id = new_tree.id();
+        if let Some(name) = &self.sparse_profile {
+            // Keep the current patterns if the profile can't be resolved in the
+            // new tree. A broken profile shouldn't prevent checking out.
+            match resolve_sparse_profile(new_tree, name, &self.working_copy_path) {
+                Ok(sparse_patterns) if sparse_patterns != self.sparse_patterns => {
+                    let sparse_stats =
+                        self.apply_sparse_patterns(new_tree, sparse_patterns, options)?;
+                    stats.added_files += sparse_stats.added_files;
+                    stats.removed_files += sparse_stats.removed_files;
+                    stats.skipped_files += sparse_stats.skipped_files;
+                }
+                Ok(_) => {}
+                Err(err) => stats
+                    .sparse_warnings
+                    .push(format!("{err}, so the sparse patterns weren't updated")),
+            }
+        }
+        stats
+            .sparse_warnings
+            .extend(self.sparse_patterns_error.clone());
         Ok(stats)
     }
 
     pub fn set_sparse_patterns(
         &mut self,
-        sparse_patterns: Vec<RepoPathBuf>,
+        sparse_patterns: Vec<SparsePattern>,
+        options: &CheckoutOptions,
+    ) -> Result<CheckoutStats, CheckoutError> {
+        let tree = self.current_tree().map_err(|err| match err {
+            err @ BackendError::ObjectNotFound { .. } => CheckoutError::SourceNotFound {
+                source: Box::new(err),
+            },
+            other => CheckoutError::InternalBackendError(other),
+        })?;
+        let stats = self.apply_sparse_patterns(&tree, sparse_patterns, options)?;
+        self.sparse_profile = None;
+        Ok(stats)
+    }
+
+    /// Sets the sparse patterns to the patterns of the profile `name` in the
+    /// current tree. The patterns will follow the profile in the trees checked
+    /// out later.
+    pub fn set_sparse_profile(
+        &mut self,
+        name: String,
         options: &CheckoutOptions,
     ) -> Result<CheckoutStats, CheckoutError> {
         let tree = self.current_tree().map_err(|err| match err {
@@ -1745,28 +1945,49 @@ impl TreeState {
             },
             other => CheckoutError::InternalBackendError(other),
         })?;
-        let old_matcher = PrefixMatcher::new(&self.sparse_patterns);
-        let new_matcher = PrefixMatcher::new(&sparse_patterns);
-        let added_matcher = DifferenceMatcher::new(&new_matcher, &old_matcher);
-        let removed_matcher = DifferenceMatcher::new(&old_matcher, &new_matcher);
+        let sparse_patterns = resolve_sparse_profile(&tree, &name, &self.working_copy_path)?;
+        let stats = self.apply_sparse_patterns(&tree, sparse_patterns, options)?;
+        self.sparse_profile = Some(name);
+        Ok(stats)
+    }
+
+    fn apply_sparse_patterns(
+        &mut self,
+        tree: &MergedTree,
+        sparse_patterns: Vec<SparsePattern>,
+        options: &CheckoutOptions,
+    ) -> Result<CheckoutStats, CheckoutError> {
+        let sparse_expression =
+            sparse_patterns_to_expression(&sparse_patterns, &self.working_copy_path).map_err(
+                |err| CheckoutError::Other {
+                    message: "Invalid sparse patterns".to_string(),
+                    err: err.into(),
+                },
+            )?;
+        let old_matcher = self.sparse_matcher();
+        let new_matcher = sparse_expression.to_matcher();
+        let added_matcher = DifferenceMatcher::new(new_matcher.as_ref(), old_matcher.as_ref());
+        let removed_matcher = DifferenceMatcher::new(old_matcher.as_ref(), new_matcher.as_ref());
         let empty_tree = MergedTree::resolved(Tree::empty(self.store.clone(), RepoPathBuf::root()));
         let added_stats = self
             .update(
                 &empty_tree,
-                &tree,
+                tree,
                 &added_matcher,
                 options.conflict_marker_style,
             )
             .block_on()?;
         let removed_stats = self
             .update(
-                &tree,
+                tree,
                 &empty_tree,
                 &removed_matcher,
                 options.conflict_marker_style,
             )
             .block_on()?;
         self.sparse_patterns = sparse_patterns;
+        self.sparse_expression = sparse_expression;
+        self.sparse_patterns_error = None;
         assert_eq!(added_stats.updated_files, 0);
         assert_eq!(added_stats.removed_files, 0);
         assert_eq!(removed_stats.updated_files, 0);
@@ -1777,6 +1998,7 @@ impl TreeState {
             added_files: added_stats.added_files,
             removed_files: removed_stats.removed_files,
             skipped_files: added_stats.skipped_files,
+            sparse_warnings: vec![],
         })
     }
 
@@ -1794,6 +2016,7 @@ impl TreeState {
             added_files: 0,
             removed_files: 0,
             skipped_files: 0,
+            sparse_warnings: vec![],
         };
         let mut changed_file_states = Vec::new();
         let mut deleted_files = HashSet::new();
@@ -1820,7 +2043,8 @@ impl TreeState {
             }
 
             // Existing Git submodule can be a non-empty directory on disk. We
-            // shouldn't attempt to manage it as a tracked path.
+            // shouldn't attempt to manage it as a tracked path. The commit
+            // recorded in the tree is checked out in the submodule separately.
             //
             // TODO: It might be better to add general support for paths not
             // tracked by jj than processing submodules specially. For example,
@@ -1829,9 +2053,16 @@ impl TreeState {
             if matches!(before.as_normal(), Some(TreeValue::GitSubmodule(_)))
                 && matches!(after, MaterializedTreeValue::GitSubmodule(_))
             {
-                eprintln!("ignoring git submodule at {path:?}");
-                // Not updating the file state as if there were no diffs. Leave
-                // the state type as FileType::GitSubmodule if it was before.
+                // Record the submodule HEAD before it's updated so that the
+                // next snapshot can tell whether it was moved by the user.
+                let disk_path = path.to_fs_path(&self.working_copy_path)?;
+                let file_state = FileState::for_gitsubmodule(read_git_submodule_head(&disk_path));
+                changed_file_states.push((path, file_state));
+                continue;
+            }
+            if matches!(before.as_normal(), Some(TreeValue::GitSubmodule(_))) && after.is_absent() {
+                // The working tree of the submodule is removed separately.
+                deleted_files.insert(path);
                 continue;
             }
 
@@ -1875,8 +2106,7 @@ impl TreeState {
                     }
                 }
                 MaterializedTreeValue::GitSubmodule(_) => {
-                    eprintln!("ignoring git submodule at {path:?}");
-                    FileState::for_gitsubmodule()
+                    FileState::for_gitsubmodule(read_git_submodule_head(&disk_path))
                 }
                 MaterializedTreeValue::Tree(_) => {
                     panic!("unexpected tree entry in diff at {path:?}");
@@ -1961,6 +2191,7 @@ impl TreeState {
                     mtime: MillisSinceEpoch(0),
                     size: 0,
                     materialized_conflict_data: None,
+                    submodule_head: None,
                 };
                 changed_file_states.push((path, file_state));
             }
@@ -2021,16 +2252,23 @@ impl WorkingCopy for LocalWorkingCopy {
         Ok(self.tree_state()?.current_tree_id())
     }
 
-    fn sparse_patterns(&self) -> Result<&[RepoPathBuf], WorkingCopyStateError> {
+    fn sparse_patterns(&self) -> Result<&[SparsePattern], WorkingCopyStateError> {
         Ok(self.tree_state()?.sparse_patterns())
     }
 
+    fn sparse_profile(&self) -> Result<Option<&str>, WorkingCopyStateError> {
+        Ok(self.tree_state()?.sparse_profile())
+    }
+
     fn start_mutation(&self) -> Result<Box<dyn LockedWorkingCopy>, WorkingCopyStateError> {
         let lock_path = self.state_path.join("working_copy.lock");
-        let lock = FileLock::lock(lock_path).map_err(|err| WorkingCopyStateError {
-            message: "Failed to lock working copy".to_owned(),
-            err: err.into(),
-        })?;
+        let lock =
+            FileLock::lock_with_timeout(lock_path, WORKING_COPY_LOCK_TIMEOUT).map_err(|err| {
+                WorkingCopyStateError {
+                    message: "Failed to lock working copy".to_owned(),
+                    err: err.into(),
+                }
+            })?;
 
         let wc = LocalWorkingCopy {
             store: self.store.clone(),
@@ -2342,13 +2580,13 @@ impl LockedWorkingCopy for LockedLocalWorkingCopy {
         Ok(())
     }
 
-    fn sparse_patterns(&self) -> Result<&[RepoPathBuf], WorkingCopyStateError> {
+    fn sparse_patterns(&self) -> Result<&[SparsePattern], WorkingCopyStateError> {
         self.wc.sparse_patterns()
     }
 
     fn set_sparse_patterns(
         &mut self,
-        new_sparse_patterns: Vec<RepoPathBuf>,
+        new_sparse_patterns: Vec<SparsePattern>,
         options: &CheckoutOptions,
     ) -> Result<CheckoutStats, CheckoutError> {
         // TODO: Write a "pending_checkout" file with new sparse patterns so we can
@@ -2365,6 +2603,23 @@ impl LockedWorkingCopy for LockedLocalWorkingCopy {
         Ok(stats)
     }
 
+    fn set_sparse_profile(
+        &mut self,
+        name: String,
+        options: &CheckoutOptions,
+    ) -> Result<CheckoutStats, CheckoutError> {
+        let stats = self
+            .wc
+            .tree_state_mut()
+            .map_err(|err| CheckoutError::Other {
+                message: "Failed to load the working copy state".to_string(),
+                err: err.into(),
+            })?
+            .set_sparse_profile(name, options)?;
+        self.tree_state_dirty = true;
+        Ok(stats)
+    }
+
     #[instrument(skip_all)]
     fn finish(
         mut self: Box<Self>,
@@ -2429,6 +2684,7 @@ mod tests {
             mtime: MillisSinceEpoch(0),
             size,
             materialized_conflict_data: None,
+            submodule_head: None,
         };
         let new_static_entry = |path: &'static str, size| (repo_path(path), new_state(size));
         let new_owned_entry = |path: &str, size| (repo_path(path).to_owned(), new_state(size));
@@ -2478,6 +2734,7 @@ mod tests {
             mtime: MillisSinceEpoch(0),
             size,
             materialized_conflict_data: None,
+            submodule_head: None,
         };
         let new_proto_entry = |path: &str, size| {
             file_state_entry_to_proto(repo_path(path).to_owned(), &new_state(size))
@@ -2543,6 +2800,7 @@ mod tests {
             mtime: MillisSinceEpoch(0),
             size,
             materialized_conflict_data: None,
+            submodule_head: None,
         };
         let new_proto_entry = |path: &str, size| {
             file_state_entry_to_proto(repo_path(path).to_owned(), &new_state(size))
@@ -14,70 +14,77 @@
 
 use std::fs::File;
 use std::fs::OpenOptions;
+use std::io;
 use std::path::PathBuf;
 use std::time::Duration;
 
 use tracing::instrument;
 
+use super::BackoffIterator;
 use super::FileLockError;
+use super::LockHolder;
 
 pub struct FileLock {
     path: PathBuf,
     _file: File,
 }
 
-struct BackoffIterator {
-    next_sleep_secs: f32,
-    elapsed_secs: f32,
-}
-
-impl BackoffIterator {
-    fn new() -> Self {
-        Self {
-            next_sleep_secs: 0.001,
-            elapsed_secs: 0.0,
-        }
-    }
-}
-
-impl Iterator for BackoffIterator {
-    type Item = Duration;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.elapsed_secs >= 10.0 {
-            None
-        } else {
-            let current_sleep = self.next_sleep_secs * (rand::random::<f32>() + 0.5);
-            self.next_sleep_secs *= 1.5;
-            self.elapsed_secs += current_sleep;
-            Some(Duration::from_secs_f32(current_sleep))
-        }
-    }
-}
-
 // Suppress warning on platforms where specialized lock impl is available
 #[cfg_attr(unix, allow(dead_code))]
 impl FileLock {
     pub fn lock(path: PathBuf) -> Result<FileLock, FileLockError> {
+        Self::lock_impl(path, Some(Duration::from_secs(10)))
+    }
+
+    /// Takes the lock, waiting up to `timeout` if it's held by another
+    /// process.
+    pub fn lock_with_timeout(path: PathBuf, timeout: Duration) -> Result<FileLock, FileLockError> {
+        Self::lock_impl(path, Some(timeout))
+    }
+
+    fn lock_impl(path: PathBuf, timeout: Option<Duration>) -> Result<FileLock, FileLockError> {
         let mut options = OpenOptions::new();
         options.create_new(true);
         options.write(true);
-        let mut backoff_iterator = BackoffIterator::new();
+        // Let the OS delete the lock file when the handle is closed, which
+        // also happens if the holder crashes. A lock file can therefore never
+        // outlive its holder.
+        #[cfg(windows)]
+        {
+            use std::os::windows::fs::OpenOptionsExt as _;
+            const DELETE: u32 = 0x0001_0000;
+            const GENERIC_WRITE: u32 = 0x4000_0000;
+            const FILE_SHARE_READ: u32 = 0x0000_0001;
+            const FILE_SHARE_DELETE: u32 = 0x0000_0004;
+            const FILE_FLAG_DELETE_ON_CLOSE: u32 = 0x0400_0000;
+            options.access_mode(GENERIC_WRITE | DELETE);
+            options.share_mode(FILE_SHARE_READ | FILE_SHARE_DELETE);
+            options.custom_flags(FILE_FLAG_DELETE_ON_CLOSE);
+        }
+        let mut backoff_iterator = BackoffIterator::new(timeout);
         loop {
             match options.open(&path) {
                 Ok(file) => {
+                    // The holder information is informational only, so
+                    // failing to write it isn't fatal.
+                    LockHolder::current()
+                        .write_to(&file)
+                        .inspect_err(|err| {
+                            tracing::warn!(?err, ?path, "Failed to write lock holder");
+                        })
+                        .ok();
                     return Ok(FileLock { path, _file: file });
                 }
                 Err(err)
-                    if err.kind() == std::io::ErrorKind::AlreadyExists
-                        || (cfg!(windows)
-                            && err.kind() == std::io::ErrorKind::PermissionDenied) =>
+                    if err.kind() == io::ErrorKind::AlreadyExists
+                        || (cfg!(windows) && err.kind() == io::ErrorKind::PermissionDenied) =>
                 {
                     if let Some(duration) = backoff_iterator.next() {
                         std::thread::sleep(duration);
                     } else {
                         return Err(FileLockError {
-                            message: "Timed out while trying to create lock file",
+                            message: "Timed out while waiting for lock",
+                            holder: LockHolder::read(&path),
                             path,
                             err,
                         });
@@ -87,6 +94,7 @@ impl FileLock {
                     return Err(FileLockError {
                         message: "Failed to create lock file",
                         path,
+                        holder: None,
                         err,
                     })
                 }
@@ -98,6 +106,10 @@ impl FileLock {
 impl Drop for FileLock {
     #[instrument(skip_all)]
     fn drop(&mut self) {
+        // On Windows, the file is deleted when the handle is closed.
+        if cfg!(windows) {
+            return;
+        }
         std::fs::remove_file(&self.path)
             .inspect_err(|err| tracing::warn!(?err, ?self.path, "Failed to delete lock file"))
             .ok();
@@ -18,9 +18,19 @@ mod fallback;
 #[cfg(unix)]
 mod unix;
 
+use std::fmt;
+use std::fs;
+use std::fs::File;
 use std::io;
+use std::io::Write as _;
+use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
 
+use itertools::Itertools as _;
+use serde::Deserialize;
+use serde::Serialize;
 use thiserror::Error;
 
 #[cfg(not(unix))]
@@ -29,14 +39,155 @@ pub use self::fallback::FileLock;
 pub use self::unix::FileLock;
 
 #[derive(Debug, Error)]
-#[error("{message}: {path}")]
+#[error("{message}: {path}{holder}", holder = FormatHolder(holder.as_ref()))]
 pub struct FileLockError {
     pub message: &'static str,
     pub path: PathBuf,
+    /// Process holding the lock, if known.
+    pub holder: Option<LockHolder>,
     #[source]
     pub err: io::Error,
 }
 
+struct FormatHolder<'a>(Option<&'a LockHolder>);
+
+impl fmt::Display for FormatHolder<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(holder) => write!(f, " (held by {holder})"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Process which holds a lock. This is recorded in the lock file so that other
+/// processes waiting for the lock can tell who they are waiting for.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct LockHolder {
+    pub pid: u32,
+    pub hostname: String,
+    pub command: String,
+    /// Milliseconds since the Unix epoch.
+    pub start_time: i64,
+}
+
+impl LockHolder {
+    /// Returns the holder information of the current process.
+    pub fn current() -> Self {
+        let start_time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis().try_into().unwrap_or(i64::MAX));
+        LockHolder {
+            pid: std::process::id(),
+            hostname: local_hostname(),
+            command: std::env::args().join(" "),
+            start_time,
+        }
+    }
+
+    /// Reads the holder information from the lock file at `path`.
+    ///
+    /// Returns `None` if the lock file doesn't exist or doesn't contain valid
+    /// holder information, which can happen if the lock was created by an
+    /// older version of jj, or if the holder hasn't written it yet.
+    pub fn read(path: &Path) -> Option<Self> {
+        let data = fs::read(path).ok()?;
+        serde_json::from_slice(&data).ok()
+    }
+
+    fn write_to(&self, mut file: &File) -> io::Result<()> {
+        file.set_len(0)?;
+        file.write_all(&serde_json::to_vec(self).map_err(io::Error::other)?)?;
+        file.flush()
+    }
+
+    /// Returns true if the holder is a process on this host which no longer
+    /// exists.
+    pub fn is_stale(&self) -> bool {
+        self.hostname == local_hostname() && process_exists(self.pid) == Some(false)
+    }
+}
+
+impl fmt::Display for LockHolder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "process {} on {}", self.pid, self.hostname)?;
+        if !self.command.is_empty() {
+            write!(f, " running `{}`", self.command)?;
+        }
+        if let Some(time) = chrono::DateTime::from_timestamp_millis(self.start_time) {
+            let time = time.with_timezone(&chrono::Local);
+            write!(f, " since {}", time.format("%Y-%m-%d %H:%M:%S"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn local_hostname() -> String {
+    rustix::system::uname()
+        .nodename()
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(not(unix))]
+fn local_hostname() -> String {
+    std::env::var("COMPUTERNAME").unwrap_or_default()
+}
+
+/// Returns whether the process with the given `pid` exists, or `None` if
+/// unknown.
+#[cfg(unix)]
+fn process_exists(pid: u32) -> Option<bool> {
+    let pid = rustix::process::Pid::from_raw(pid.try_into().ok()?)?;
+    match rustix::process::test_kill_process(pid) {
+        Ok(()) => Some(true),
+        Err(rustix::io::Errno::SRCH) => Some(false),
+        // EPERM means the process exists but belongs to another user
+        Err(_) => Some(true),
+    }
+}
+
+#[cfg(not(unix))]
+fn process_exists(_pid: u32) -> Option<bool> {
+    None
+}
+
+/// Sleep durations between attempts to take a lock held by another process.
+struct BackoffIterator {
+    next_sleep_secs: f32,
+    elapsed_secs: f32,
+    timeout_secs: Option<f32>,
+}
+
+impl BackoffIterator {
+    fn new(timeout: Option<Duration>) -> Self {
+        Self {
+            next_sleep_secs: 0.001,
+            elapsed_secs: 0.0,
+            timeout_secs: timeout.map(|timeout| timeout.as_secs_f32()),
+        }
+    }
+}
+
+impl Iterator for BackoffIterator {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self
+            .timeout_secs
+            .is_some_and(|timeout_secs| self.elapsed_secs >= timeout_secs)
+        {
+            None
+        } else {
+            let current_sleep = self.next_sleep_secs * (rand::random::<f32>() + 0.5);
+            self.next_sleep_secs = (self.next_sleep_secs * 1.5).min(1.0);
+            self.elapsed_secs += current_sleep;
+            Some(Duration::from_secs_f32(current_sleep))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::cmp::max;
@@ -85,4 +236,55 @@ mod tests {
         let value = u32::from_le_bytes(data.try_into().unwrap());
         assert_eq!(value, num_threads as u32);
     }
+
+    #[test_case(FileLock::lock)]
+    #[cfg_attr(unix, test_case(fallback::FileLock::lock))]
+    fn lock_records_holder<T>(lock_fn: fn(PathBuf) -> Result<T, FileLockError>) {
+        let temp_dir = new_temp_dir();
+        let lock_path = temp_dir.path().join("test.lock");
+        let _lock = lock_fn(lock_path.clone()).unwrap();
+        let holder = LockHolder::read(&lock_path).unwrap();
+        assert_eq!(holder.pid, std::process::id());
+        assert_eq!(holder.hostname, local_hostname());
+        assert!(!holder.is_stale());
+    }
+
+    #[test_case(FileLock::lock_with_timeout)]
+    #[cfg_attr(unix, test_case(fallback::FileLock::lock_with_timeout))]
+    fn lock_timeout<T>(lock_fn: fn(PathBuf, Duration) -> Result<T, FileLockError>) {
+        let temp_dir = new_temp_dir();
+        let lock_path = temp_dir.path().join("test.lock");
+        let _lock = lock_fn(lock_path.clone(), Duration::ZERO).unwrap();
+        let Err(err) = lock_fn(lock_path.clone(), Duration::from_millis(10)) else {
+            panic!("lock should be held");
+        };
+        assert_eq!(err.message, "Timed out while waiting for lock");
+        let holder = err.holder.as_ref().unwrap();
+        assert_eq!(holder.pid, std::process::id());
+        assert!(err
+            .to_string()
+            .contains(&format!("(held by process {}", holder.pid)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn lock_ignores_stale_lock_file() {
+        let temp_dir = new_temp_dir();
+        let lock_path = temp_dir.path().join("test.lock");
+        // Lock file left by a process which no longer exists
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        child.wait().unwrap();
+        let stale_holder = LockHolder {
+            pid: child.id(),
+            ..LockHolder::current()
+        };
+        assert!(stale_holder.is_stale());
+        fs::write(&lock_path, serde_json::to_vec(&stale_holder).unwrap()).unwrap();
+
+        let _lock = FileLock::lock_with_timeout(lock_path.clone(), Duration::ZERO).unwrap();
+        assert_eq!(
+            LockHolder::read(&lock_path).unwrap().pid,
+            std::process::id()
+        );
+    }
 }
@@ -15,12 +15,16 @@
 #![allow(missing_docs)]
 
 use std::fs::File;
+use std::fs::OpenOptions;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use rustix::fs::FlockOperation;
 use tracing::instrument;
 
+use super::BackoffIterator;
 use super::FileLockError;
+use super::LockHolder;
 
 pub struct FileLock {
     path: PathBuf,
@@ -29,25 +33,68 @@ pub struct FileLock {
 
 impl FileLock {
     pub fn lock(path: PathBuf) -> Result<FileLock, FileLockError> {
+        Self::lock_impl(path, None)
+    }
+
+    /// Takes the lock, waiting up to `timeout` if it's held by another
+    /// process.
+    pub fn lock_with_timeout(path: PathBuf, timeout: Duration) -> Result<FileLock, FileLockError> {
+        Self::lock_impl(path, Some(timeout))
+    }
+
+    fn lock_impl(path: PathBuf, timeout: Option<Duration>) -> Result<FileLock, FileLockError> {
+        // Without timeout, block in flock() rather than polling.
+        let operation = if timeout.is_some() {
+            FlockOperation::NonBlockingLockExclusive
+        } else {
+            FlockOperation::LockExclusive
+        };
+        let mut backoff_iterator = BackoffIterator::new(timeout);
         loop {
-            // Create lockfile, or open pre-existing one
-            let file = File::create(&path).map_err(|err| FileLockError {
-                message: "Failed to open lock file",
-                path: path.clone(),
-                err,
-            })?;
-            // If the lock was already held, wait for it to be released
-            rustix::fs::flock(&file, FlockOperation::LockExclusive).map_err(|errno| {
-                FileLockError {
-                    message: "Failed to lock lock file",
+            // Create lockfile, or open pre-existing one. Don't truncate it
+            // since it may contain the current holder information.
+            let file = OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(&path)
+                .map_err(|err| FileLockError {
+                    message: "Failed to open lock file",
                     path: path.clone(),
-                    err: errno.into(),
+                    holder: None,
+                    err,
+                })?;
+            // If the lock was already held, wait for it to be released. The
+            // lock is released by the OS if the holder dies, so a lock file
+            // left by a dead process doesn't block us.
+            match rustix::fs::flock(&file, operation) {
+                Ok(()) => {}
+                Err(rustix::io::Errno::WOULDBLOCK) => {
+                    if let Some(duration) = backoff_iterator.next() {
+                        std::thread::sleep(duration);
+                        continue;
+                    }
+                    return Err(FileLockError {
+                        message: "Timed out while waiting for lock",
+                        holder: LockHolder::read(&path),
+                        path,
+                        err: rustix::io::Errno::WOULDBLOCK.into(),
+                    });
                 }
-            })?;
+                Err(errno) => {
+                    return Err(FileLockError {
+                        message: "Failed to lock lock file",
+                        path,
+                        holder: None,
+                        err: errno.into(),
+                    });
+                }
+            }
 
             let stat = rustix::fs::fstat(&file).map_err(|errno| FileLockError {
                 message: "failed to stat lock file",
                 path: path.clone(),
+                holder: None,
                 err: errno.into(),
             })?;
             if stat.st_nlink == 0 {
@@ -58,6 +105,12 @@ impl FileLock {
                 continue;
             }
 
+            // The holder information is informational only, so failing to
+            // write it isn't fatal.
+            LockHolder::current()
+                .write_to(&file)
+                .inspect_err(|err| tracing::warn!(?err, ?path, "Failed to write lock holder"))
+                .ok();
             return Ok(Self { path, file });
         }
     }
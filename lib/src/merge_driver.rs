@@ -0,0 +1,359 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Merge drivers resolve file conflicts which the line-based merge algorithm
+//! can't resolve, e.g. by merging the syntax trees of the file contents.
+//!
+//! Drivers run external programs, so they aren't invoked by every tree merge.
+//! Code rewriting commits calls [`MergeDrivers::resolve_conflicts()`] to
+//! resolve the conflicts in the new trees before they're recorded.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::process::Stdio;
+use std::sync::Arc;
+
+use bstr::BString;
+use itertools::Itertools as _;
+use once_cell::sync::Lazy;
+use pollster::FutureExt as _;
+use regex::Captures;
+use regex::Regex;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::backend::BackendResult;
+use crate::config::ConfigGetError;
+use crate::fileset;
+use crate::fileset::FilesetDiagnostics;
+use crate::fileset::FilesetExpression;
+use crate::fileset::FilesetParseError;
+use crate::matchers::Matcher;
+use crate::merge::Merge;
+use crate::merged_tree::MergedTree;
+use crate::merged_tree::MergedTreeBuilder;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathUiConverter;
+use crate::settings::UserSettings;
+use crate::store::Store;
+use crate::tree::try_resolve_file_conflict_with;
+
+/// Error from running a merge driver.
+#[derive(Debug, Error)]
+pub enum MergeDriverError {
+    /// Failed to write the inputs or read the output of the driver.
+    #[error("Failed to exchange files with merge driver {name}")]
+    Io {
+        /// The name of the driver.
+        name: String,
+        /// The underlying error.
+        source: io::Error,
+    },
+    /// Failed to start the driver.
+    #[error("Failed to run merge driver {name}")]
+    Spawn {
+        /// The name of the driver.
+        name: String,
+        /// The underlying error.
+        source: io::Error,
+    },
+}
+
+/// Error from loading the `merge-drivers` config.
+#[derive(Debug, Error)]
+pub enum MergeDriverConfigError {
+    /// The config table is invalid.
+    #[error(transparent)]
+    Config(#[from] ConfigGetError),
+    /// A file pattern is invalid.
+    #[error("Invalid file pattern in `merge-drivers.{name}`")]
+    Pattern {
+        /// The name of the driver.
+        name: String,
+        /// The underlying error.
+        source: FilesetParseError,
+    },
+}
+
+/// Resolves file conflicts automatically.
+pub trait MergeDriver: Debug + Send + Sync {
+    /// Name of the driver, used in error messages.
+    fn name(&self) -> &str;
+
+    /// Merges the contents of the conflicted file at `path`.
+    ///
+    /// Returns `None` if the driver couldn't resolve the conflict.
+    fn merge(
+        &self,
+        path: &RepoPath,
+        contents: &Merge<Vec<u8>>,
+    ) -> Result<Option<BString>, MergeDriverError>;
+}
+
+/// Merge driver which runs an external program on the base, left, and right
+/// contents of two-sided conflicts.
+///
+/// The program is expected to write the merged contents to the `$output` file
+/// and exit with status 0. Any other exit status means that the conflict
+/// couldn't be resolved.
+#[derive(Clone, Debug)]
+pub struct ExternalMergeDriver {
+    name: String,
+    program: String,
+    merge_args: Vec<String>,
+}
+
+impl ExternalMergeDriver {
+    /// Creates a driver which runs `program` with `merge_args`.
+    ///
+    /// The `$base`, `$left`, `$right`, and `$output` variables in the arguments
+    /// are substituted with the paths to the temporary files. `$path` is
+    /// substituted with the repository path of the conflicted file.
+    pub fn new(name: String, program: String, merge_args: Vec<String>) -> Self {
+        ExternalMergeDriver {
+            name,
+            program,
+            merge_args,
+        }
+    }
+}
+
+impl MergeDriver for ExternalMergeDriver {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn merge(
+        &self,
+        path: &RepoPath,
+        contents: &Merge<Vec<u8>>,
+    ) -> Result<Option<BString>, MergeDriverError> {
+        let [left, base, right] = contents.as_slice() else {
+            return Ok(None);
+        };
+        let io_error = |source| MergeDriverError::Io {
+            name: self.name.clone(),
+            source,
+        };
+        let temp_dir = tempfile::Builder::new()
+            .prefix("jj-merge-driver-")
+            .tempdir()
+            .map_err(io_error)?;
+        // Preserve the file extension, which drivers often use to detect the
+        // file type.
+        let suffix = path
+            .components()
+            .next_back()
+            .and_then(|name| name.as_internal_str().rsplit_once('.'))
+            .map_or(String::new(), |(_, ext)| format!(".{ext}"));
+        let write_input = |name: &str, content: &[u8]| {
+            let file_path = temp_dir.path().join(format!("{name}{suffix}"));
+            fs::write(&file_path, content)?;
+            io::Result::Ok(file_path)
+        };
+        let base_path = write_input("base", base).map_err(io_error)?;
+        let left_path = write_input("left", left).map_err(io_error)?;
+        let right_path = write_input("right", right).map_err(io_error)?;
+        let output_path = write_input("output", b"").map_err(io_error)?;
+
+        let path_str = path.as_internal_file_string();
+        let variables: HashMap<&str, &str> = [
+            ("base", path_to_str(&base_path)),
+            ("left", path_to_str(&left_path)),
+            ("right", path_to_str(&right_path)),
+            ("output", path_to_str(&output_path)),
+            ("path", path_str),
+        ]
+        .into_iter()
+        .collect();
+        let status = Command::new(&self.program)
+            .args(interpolate_variables(&self.merge_args, &variables))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .status()
+            .map_err(|source| MergeDriverError::Spawn {
+                name: self.name.clone(),
+                source,
+            })?;
+        if !status.success() {
+            tracing::info!(driver = self.name, ?path, ?status, "merge driver failed");
+            return Ok(None);
+        }
+        let merged = fs::read(&output_path).map_err(io_error)?;
+        Ok(Some(merged.into()))
+    }
+}
+
+fn path_to_str(path: &Path) -> &str {
+    // The temporary directory path is assumed to be valid UTF-8, as in
+    // external merge tools.
+    path.to_str().expect("temporary path should be valid UTF-8")
+}
+
+static VARIABLE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\$([a-z0-9_]+)\b").unwrap());
+
+fn interpolate_variables(args: &[String], variables: &HashMap<&str, &str>) -> Vec<String> {
+    args.iter()
+        .map(|arg| {
+            VARIABLE_REGEX
+                .replace_all(arg, |caps: &Captures| {
+                    let name = &caps[1];
+                    if let Some(subst) = variables.get(name) {
+                        (*subst).to_owned()
+                    } else {
+                        caps[0].to_owned()
+                    }
+                })
+                .into_owned()
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct RawMergeDriverConfig {
+    program: Option<String>,
+    merge_args: Vec<String>,
+    patterns: Vec<String>,
+}
+
+#[derive(Debug)]
+struct MergeDriverEntry {
+    matcher: Box<dyn Matcher>,
+    driver: Box<dyn MergeDriver>,
+}
+
+/// Set of merge drivers, each of which applies to the files matching its
+/// file patterns.
+#[derive(Debug, Default)]
+pub struct MergeDrivers {
+    entries: Vec<MergeDriverEntry>,
+}
+
+impl MergeDrivers {
+    /// Creates an empty set, which resolves no conflicts.
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Loads the drivers from the `merge-drivers` config table.
+    ///
+    /// Drivers are tried in the order of their names.
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, MergeDriverConfigError> {
+        let mut drivers = Self::empty();
+        // Sort keys so the order of drivers is deterministic.
+        for name in settings.table_keys("merge-drivers").sorted() {
+            let config: RawMergeDriverConfig = settings.get(["merge-drivers", name])?;
+            let path_converter = RepoPathUiConverter::Fs {
+                cwd: "".into(),
+                base: "".into(),
+            };
+            // Parse-time warnings can't be reported here, so they're ignored.
+            let mut diagnostics = FilesetDiagnostics::new();
+            let files = FilesetExpression::union_all(
+                config
+                    .patterns
+                    .iter()
+                    .map(|pattern| fileset::parse(&mut diagnostics, pattern, &path_converter))
+                    .try_collect()
+                    .map_err(|source| MergeDriverConfigError::Pattern {
+                        name: name.to_owned(),
+                        source,
+                    })?,
+            );
+            let driver = ExternalMergeDriver::new(
+                name.to_owned(),
+                config.program.unwrap_or_else(|| name.to_owned()),
+                config.merge_args,
+            );
+            drivers.add(files, Box::new(driver));
+        }
+        Ok(drivers)
+    }
+
+    /// Adds a driver for the files matching `files`. Drivers added earlier
+    /// take precedence.
+    pub fn add(&mut self, files: FilesetExpression, driver: Box<dyn MergeDriver>) {
+        let matcher = files.to_matcher();
+        self.entries.push(MergeDriverEntry { matcher, driver });
+    }
+
+    /// Returns true if there are no drivers.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Tries the drivers matching `path` in order until one of them resolves
+    /// the conflict.
+    ///
+    /// Drivers which fail to run are skipped, so the conflict is left
+    /// unresolved in the worst case.
+    pub fn try_merge(&self, path: &RepoPath, contents: &Merge<Vec<u8>>) -> Option<BString> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.matcher.matches(path))
+            .find_map(|entry| match entry.driver.merge(path, contents) {
+                Ok(merged) => merged,
+                Err(err) => {
+                    tracing::warn!(?err, ?path, "failed to run merge driver");
+                    None
+                }
+            })
+    }
+
+    /// Resolves the file conflicts in `tree` which the drivers can resolve,
+    /// and returns the new tree.
+    ///
+    /// Since this runs external programs, it should only be called when
+    /// rewriting commits, not when merging trees e.g. to display a diff.
+    pub fn resolve_conflicts(
+        &self,
+        store: &Arc<Store>,
+        tree: MergedTree,
+    ) -> BackendResult<MergedTree> {
+        if self.is_empty() || !tree.has_conflict() {
+            return Ok(tree);
+        }
+        let mut tree_builder = MergedTreeBuilder::new(tree.id());
+        let mut resolved_any = false;
+        for (path, values) in tree.conflicts() {
+            if !self
+                .entries
+                .iter()
+                .any(|entry| entry.matcher.matches(&path))
+            {
+                continue;
+            }
+            let values = values?;
+            let simplified = values.map(|value| value.as_ref()).simplify();
+            let resolved = try_resolve_file_conflict_with(store, &path, &simplified, |contents| {
+                self.try_merge(&path, contents)
+            })
+            .block_on()?;
+            if let Some(resolved) = resolved {
+                tree_builder.set_or_remove(path, Merge::normal(resolved));
+                resolved_any = true;
+            }
+        }
+        if !resolved_any {
+            return Ok(tree);
+        }
+        let tree_id = tree_builder.write_tree(store)?;
+        store.get_root_tree(&tree_id)
+    }
+}
@@ -325,6 +325,53 @@ impl MergedTree {
         self.merge_no_resolve(base, other).resolve()
     }
 
+    /// Like `merge()`, but changes to files renamed on one side are applied to
+    /// the renamed files.
+    ///
+    /// `self_copies` and `other_copies` are the copy records from `base` to
+    /// `self` and from `base` to `other` respectively. Copies which didn't
+    /// remove the source file, and renames which would overwrite existing
+    /// files, are ignored.
+    pub fn merge_with_copies(
+        &self,
+        base: &MergedTree,
+        other: &MergedTree,
+        self_copies: &CopyRecords,
+        other_copies: &CopyRecords,
+    ) -> BackendResult<MergedTree> {
+        let store = self.store();
+        let mut self_builder = MergedTreeBuilder::new(self.id());
+        let mut base_builder = MergedTreeBuilder::new(base.id());
+        let mut other_builder = MergedTreeBuilder::new(other.id());
+        // Move the files on the base and the unrenamed side to where the
+        // renamed side has them, so the contents are merged at the new path.
+        let moved_self = follow_renames(
+            base,
+            other,
+            self,
+            self_copies,
+            other_copies,
+            &mut base_builder,
+            &mut other_builder,
+        )?;
+        let moved_other = follow_renames(
+            base,
+            self,
+            other,
+            other_copies,
+            self_copies,
+            &mut base_builder,
+            &mut self_builder,
+        )?;
+        if !moved_self && !moved_other {
+            return self.merge(base, other);
+        }
+        let new_self = store.get_root_tree(&self_builder.write_tree(store)?)?;
+        let new_base = store.get_root_tree(&base_builder.write_tree(store)?)?;
+        let new_other = store.get_root_tree(&other_builder.write_tree(store)?)?;
+        new_self.merge(&new_base, &new_other)
+    }
+
     /// Merges this tree with `other`, using `base` as base, without attempting
     /// to resolve file conflicts.
     pub fn merge_no_resolve(&self, base: &MergedTree, other: &MergedTree) -> MergedTree {
@@ -352,6 +399,51 @@ pub struct TreeDiffEntry {
 /// ones) can fetch trees asynchronously.
 pub type TreeDiffStream<'matcher> = BoxStream<'matcher, TreeDiffEntry>;
 
+/// Moves the files which were renamed from `base` to `renamed` in the `base`
+/// and `unrenamed` trees if `unrenamed` changed them. Returns true if any
+/// files were moved.
+fn follow_renames(
+    base: &MergedTree,
+    unrenamed: &MergedTree,
+    renamed: &MergedTree,
+    renamed_copies: &CopyRecords,
+    unrenamed_copies: &CopyRecords,
+    base_builder: &mut MergedTreeBuilder,
+    unrenamed_builder: &mut MergedTreeBuilder,
+) -> BackendResult<bool> {
+    let mut moved = false;
+    for record in renamed_copies.iter() {
+        let (source, target) = (&record.source, &record.target);
+        // Ambiguous records, and files renamed on both sides, are left to the
+        // path-based merge.
+        if source == target
+            || renamed_copies.for_source(source) != Some(record)
+            || renamed_copies.for_target(target) != Some(record)
+            || unrenamed_copies.has_source(source)
+            || unrenamed_copies.has_target(target)
+        {
+            continue;
+        }
+        if renamed.path_value(source)?.is_present()
+            || base.path_value(target)?.is_present()
+            || unrenamed.path_value(target)?.is_present()
+        {
+            continue;
+        }
+        let base_value = base.path_value(source)?;
+        let unrenamed_value = unrenamed.path_value(source)?;
+        if unrenamed_value.is_absent() || unrenamed_value == base_value {
+            continue;
+        }
+        base_builder.set_or_remove(target.clone(), base_value);
+        base_builder.set_or_remove(source.clone(), Merge::absent());
+        unrenamed_builder.set_or_remove(target.clone(), unrenamed_value);
+        unrenamed_builder.set_or_remove(source.clone(), Merge::absent());
+        moved = true;
+    }
+    Ok(moved)
+}
+
 fn all_tree_basenames(trees: &Merge<Tree>) -> impl Iterator<Item = &RepoPathComponent> {
     trees
         .iter()
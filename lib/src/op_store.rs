@@ -30,6 +30,7 @@ use crate::backend::CommitId;
 use crate::backend::MillisSinceEpoch;
 use crate::backend::Timestamp;
 use crate::content_hash::ContentHash;
+use crate::content_hash::DigestUpdate;
 use crate::merge::Merge;
 use crate::object_id::id_type;
 use crate::object_id::HexPrefix;
@@ -194,6 +195,24 @@ pub enum RemoteRefState {
     Tracked,
 }
 
+/// User-provided metadata attached to a local bookmark.
+#[derive(ContentHash, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct BookmarkMetadata {
+    /// Free-form description, e.g. the title and body of a pull request.
+    pub description: String,
+    /// Person or team responsible for the bookmark.
+    pub owner: String,
+    /// Bookmark this bookmark is based on.
+    pub base: Option<RefNameBuf>,
+}
+
+impl BookmarkMetadata {
+    /// Returns true if no metadata is set.
+    pub fn is_empty(&self) -> bool {
+        self.description.is_empty() && self.owner.is_empty() && self.base.is_none()
+    }
+}
+
 /// Helper to strip redundant `Option<T>` from `RefTarget` lookup result.
 pub trait RefTargetOptionExt {
     type Value;
@@ -244,24 +263,59 @@ pub struct BookmarkTarget<'a> {
 
 /// Represents the way the repo looks at a given time, just like how a Tree
 /// object represents how the file system looks at a given time.
-#[derive(ContentHash, PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct View {
     /// All head commits
     pub head_ids: HashSet<CommitId>,
     pub local_bookmarks: BTreeMap<RefNameBuf, RefTarget>,
+    /// Metadata of local bookmarks. Empty metadata isn't stored.
+    pub bookmark_metadata: BTreeMap<RefNameBuf, BookmarkMetadata>,
     pub tags: BTreeMap<RefNameBuf, RefTarget>,
     pub remote_views: BTreeMap<RemoteNameBuf, RemoteView>,
     pub git_refs: BTreeMap<GitRefNameBuf, RefTarget>,
-    /// The commit the Git HEAD points to.
-    // TODO: Support multiple Git worktrees?
+    /// The commit the Git HEAD of the main worktree points to.
     // TODO: Do we want to store the current bookmark name too?
     pub git_head: RefTarget,
+    /// The commits the Git HEADs of the linked worktrees point to, keyed by
+    /// the workspace the worktree is shared with.
+    pub git_worktree_heads: BTreeMap<WorkspaceNameBuf, RefTarget>,
     // The commit that *should be* checked out in the workspace. Note that the working copy
     // (.jj/working_copy/) has the source of truth about which commit *is* checked out (to be
     // precise: the commit to which we most recently completed an update to).
     pub wc_commit_ids: BTreeMap<WorkspaceNameBuf, CommitId>,
 }
 
+impl ContentHash for View {
+    fn hash(&self, state: &mut impl DigestUpdate) {
+        let View {
+            head_ids,
+            local_bookmarks,
+            bookmark_metadata,
+            tags,
+            remote_views,
+            git_refs,
+            git_head,
+            git_worktree_heads,
+            wc_commit_ids,
+        } = self;
+        head_ids.hash(state);
+        local_bookmarks.hash(state);
+        tags.hash(state);
+        remote_views.hash(state);
+        git_refs.hash(state);
+        git_head.hash(state);
+        wc_commit_ids.hash(state);
+        // Fields added later are only hashed if set, so the ids of existing
+        // views don't change.
+        if !bookmark_metadata.is_empty() {
+            bookmark_metadata.hash(state);
+        }
+        if !git_worktree_heads.is_empty() {
+            git_worktree_heads.hash(state);
+        }
+    }
+}
+
 impl View {
     /// Creates new truly empty view.
     ///
@@ -271,10 +325,12 @@ impl View {
         View {
             head_ids: HashSet::new(),
             local_bookmarks: BTreeMap::new(),
+            bookmark_metadata: BTreeMap::new(),
             tags: BTreeMap::new(),
             remote_views: BTreeMap::new(),
             git_refs: BTreeMap::new(),
             git_head: RefTarget::absent(),
+            git_worktree_heads: BTreeMap::new(),
             wc_commit_ids: BTreeMap::new(),
         }
     }
@@ -284,10 +340,12 @@ impl View {
         View {
             head_ids: HashSet::from([root_commit_id]),
             local_bookmarks: BTreeMap::new(),
+            bookmark_metadata: BTreeMap::new(),
             tags: BTreeMap::new(),
             remote_views: BTreeMap::new(),
             git_refs: BTreeMap::new(),
             git_head: RefTarget::absent(),
+            git_worktree_heads: BTreeMap::new(),
             wc_commit_ids: BTreeMap::new(),
         }
     }
@@ -0,0 +1,183 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Patch IDs identify the changes made by a commit independently of its parents
+//! and metadata.
+//!
+//! A patch ID is similar to `git patch-id --stable`: two commits that make the
+//! same changes to the same files have the same patch ID, even if they are
+//! based on different commits. Whitespace within the changed lines and the
+//! order of the files are ignored. Unlike Git, the unchanged context lines
+//! aren't part of the patch ID, so a cherry-picked commit keeps its patch ID
+//! even if the lines around its changes were modified upstream.
+
+#![allow(missing_docs)]
+
+use std::sync::Arc;
+
+use blake2::Blake2b512;
+use blake2::Digest as _;
+use bstr::BString;
+use futures::StreamExt as _;
+use pollster::FutureExt as _;
+
+use crate::backend::BackendResult;
+use crate::commit::Commit;
+use crate::conflicts::materialize_merge_result_to_bytes;
+use crate::conflicts::materialize_tree_value;
+use crate::conflicts::ConflictMarkerStyle;
+use crate::conflicts::MaterializedTreeValue;
+use crate::content_hash::ContentHash;
+use crate::diff::Diff;
+use crate::diff::DiffHunkKind;
+use crate::matchers::EverythingMatcher;
+use crate::merged_tree::resolve_file_values;
+use crate::merged_tree::MergedTree;
+use crate::object_id::id_type;
+use crate::object_id::ObjectId as _;
+use crate::repo::Repo;
+use crate::repo_path::RepoPath;
+use crate::store::Store;
+
+id_type!(
+    /// Identifier for the changes made by a commit. Commits that make the same
+    /// changes have the same `PatchId` regardless of their parents.
+    pub PatchId { hex() }
+);
+
+/// Computes the patch ID of the changes from `from_tree` to `to_tree`.
+///
+/// Returns `None` if there are no changes between the trees.
+pub async fn compute_patch_id(
+    store: &Arc<Store>,
+    from_tree: &MergedTree,
+    to_tree: &MergedTree,
+) -> BackendResult<Option<PatchId>> {
+    // Per-file hashes are summed so the result doesn't depend on the order
+    // of the files, like `git patch-id --stable`.
+    let mut sum = [0u8; 64];
+    let mut has_changes = false;
+    // TODO: handle copy tracking
+    let mut tree_diff = from_tree.diff_stream(to_tree, &EverythingMatcher);
+    while let Some(entry) = tree_diff.next().await {
+        let (left_value, right_value) = entry.values?;
+        let left_value = resolve_file_values(store, &entry.path, left_value).await?;
+        if left_value == right_value {
+            continue;
+        }
+        let left_future = materialize_tree_value(store, &entry.path, left_value);
+        let right_future = materialize_tree_value(store, &entry.path, right_value);
+        let (left_value, right_value) = futures::try_join!(left_future, right_future)?;
+        let left_contents = to_file_content(&entry.path, left_value).await?;
+        let right_contents = to_file_content(&entry.path, right_value).await?;
+        let file_hash = hash_file_diff(&entry.path, &left_contents, &right_contents);
+        add_hash(&mut sum, &file_hash);
+        has_changes = true;
+    }
+    Ok(has_changes.then(|| PatchId::new(sum.to_vec())))
+}
+
+/// Computes the patch ID of the changes made by `commit` compared to its
+/// parents.
+///
+/// Returns `None` if the commit is empty.
+pub fn commit_patch_id(repo: &dyn Repo, commit: &Commit) -> BackendResult<Option<PatchId>> {
+    let from_tree = commit.parent_tree(repo)?;
+    let to_tree = commit.tree()?;
+    compute_patch_id(repo.store(), &from_tree, &to_tree).block_on()
+}
+
+fn hash_file_diff(path: &RepoPath, left: &[u8], right: &[u8]) -> [u8; 64] {
+    let mut hasher = Blake2b512::new();
+    hasher.update(path.as_internal_file_string());
+    hasher.update(b"\0");
+    let diff = Diff::by_line([left, right]);
+    for hunk in diff.hunks() {
+        if hunk.kind == DiffHunkKind::Matching {
+            continue;
+        }
+        hasher.update(b"@@\n");
+        for (prefix, text) in [(b"-", hunk.contents[0]), (b"+", hunk.contents[1])] {
+            for line in text.split_inclusive(|b| *b == b'\n') {
+                hasher.update(prefix);
+                hasher.update(
+                    line.iter()
+                        .copied()
+                        .filter(|b| !b.is_ascii_whitespace())
+                        .collect::<Vec<_>>(),
+                );
+                hasher.update(b"\n");
+            }
+        }
+    }
+    hasher.finalize().into()
+}
+
+fn add_hash(sum: &mut [u8; 64], hash: &[u8; 64]) {
+    let mut carry = 0;
+    for (sum_byte, hash_byte) in sum.iter_mut().zip(hash).rev() {
+        let value = u16::from(*sum_byte) + u16::from(*hash_byte) + carry;
+        *sum_byte = value as u8;
+        carry = value >> 8;
+    }
+}
+
+async fn to_file_content(path: &RepoPath, value: MaterializedTreeValue) -> BackendResult<BString> {
+    match value {
+        MaterializedTreeValue::Absent => Ok(BString::default()),
+        MaterializedTreeValue::AccessDenied(_) => Ok(BString::default()),
+        MaterializedTreeValue::File(mut file) => Ok(file.read_all(path).await?.into()),
+        MaterializedTreeValue::Symlink { id: _, target } => Ok(target.into()),
+        MaterializedTreeValue::GitSubmodule(id) => Ok(id.hex().into()),
+        MaterializedTreeValue::FileConflict(file) => Ok(materialize_merge_result_to_bytes(
+            &file.contents,
+            ConflictMarkerStyle::Diff,
+        )),
+        MaterializedTreeValue::OtherConflict { .. } => Ok(BString::default()),
+        MaterializedTreeValue::Tree(id) => {
+            panic!("Unexpected tree with id {id:?} in diff at path {path:?}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_file_diff_ignores_whitespace_and_context() {
+        let path = RepoPath::from_internal_string("file").unwrap();
+        let hash = hash_file_diff(path, b"a\nb\nc\n", b"a\nb  2\nc\n");
+        assert_eq!(hash_file_diff(path, b"x\nb\ny\n", b"x\nb2\ny\n"), hash);
+        assert_eq!(hash_file_diff(path, b"b\n", b" b 2\n"), hash);
+        assert_ne!(hash_file_diff(path, b"b\n", b"b3\n"), hash);
+        let other_path = RepoPath::from_internal_string("other").unwrap();
+        assert_ne!(hash_file_diff(other_path, b"b\n", b"b2\n"), hash);
+    }
+
+    #[test]
+    fn test_add_hash_is_commutative() {
+        let a = [0xff; 64];
+        let mut b = [0; 64];
+        b[63] = 1;
+        let mut sum1 = [0; 64];
+        add_hash(&mut sum1, &a);
+        add_hash(&mut sum1, &b);
+        let mut sum2 = [0; 64];
+        add_hash(&mut sum2, &b);
+        add_hash(&mut sum2, &a);
+        assert_eq!(sum1, sum2);
+        assert_eq!(sum1, [0; 64]);
+    }
+}
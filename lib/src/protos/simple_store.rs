@@ -97,3 +97,20 @@ pub mod conflict {
         pub content: ::core::option::Option<super::TreeValue>,
     }
 }
+/// File content split into chunks. Stored by the chunked backend in place of
+/// the file content.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChunkedFile {
+    #[prost(message, repeated, tag = "1")]
+    pub chunks: ::prost::alloc::vec::Vec<chunked_file::Chunk>,
+}
+/// Nested message and enum types in `ChunkedFile`.
+pub mod chunked_file {
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Chunk {
+        #[prost(bytes = "vec", tag = "1")]
+        pub id: ::prost::alloc::vec::Vec<u8>,
+        #[prost(uint64, tag = "2")]
+        pub size: u64,
+    }
+}
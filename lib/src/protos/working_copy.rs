@@ -19,6 +19,10 @@ pub struct FileState {
     pub conflict_id: ::prost::alloc::vec::Vec<u8>,
     #[prost(message, optional, tag = "5")]
     pub materialized_conflict_data: ::core::option::Option<MaterializedConflictData>,
+    /// Commit checked out in the Git submodule. Set only if file_type is
+    /// GitSubmodule
+    #[prost(bytes = "vec", tag = "6")]
+    pub submodule_head: ::prost::alloc::vec::Vec<u8>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FileStateEntry {
@@ -31,6 +35,12 @@ pub struct FileStateEntry {
 pub struct SparsePatterns {
     #[prost(string, repeated, tag = "1")]
     pub prefixes: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Fileset expressions relative to the workspace root.
+    #[prost(string, repeated, tag = "2")]
+    pub filesets: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Name of the sparse profile the patterns were resolved from, if any.
+    #[prost(string, tag = "3")]
+    pub profile: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TreeState {
@@ -20,6 +20,7 @@ use crate::backend::CommitId;
 use crate::index::Index;
 use crate::merge::trivial_merge;
 use crate::merge::Merge;
+use crate::op_store::BookmarkMetadata;
 use crate::op_store::RefTarget;
 use crate::op_store::RemoteRef;
 
@@ -86,6 +87,28 @@ pub fn diff_named_commit_ids<'a, 'b, K: Ord>(
     .filter(|(_, (target1, target2))| target1 != target2)
 }
 
+/// Compares `metadata1` and `metadata2` bookmark metadata, yields entry if
+/// they differ.
+///
+/// `metadata1` and `metadata2` must be sorted by `K`.
+pub fn diff_named_bookmark_metadata<'a, 'b, K: Ord>(
+    metadata1: impl IntoIterator<Item = (K, &'a BookmarkMetadata)>,
+    metadata2: impl IntoIterator<Item = (K, &'b BookmarkMetadata)>,
+) -> impl Iterator<
+    Item = (
+        K,
+        (Option<&'a BookmarkMetadata>, Option<&'b BookmarkMetadata>),
+    ),
+> {
+    iter_named_pairs(
+        metadata1.into_iter().map(|(k, v)| (k, Some(v))),
+        metadata2.into_iter().map(|(k, v)| (k, Some(v))),
+        || None,
+        || None,
+    )
+    .filter(|(_, (metadata1, metadata2))| metadata1 != metadata2)
+}
+
 fn iter_named_pairs<K: Ord, V1, V2>(
     refs1: impl IntoIterator<Item = (K, V1)>,
     refs2: impl IntoIterator<Item = (K, V2)>,
@@ -39,16 +39,20 @@ use crate::backend::BackendResult;
 use crate::backend::ChangeId;
 use crate::backend::CommitId;
 use crate::backend::MergedTreeId;
+use crate::chunked_backend::ChunkedBackend;
 use crate::commit::Commit;
 use crate::commit::CommitByCommitterTimestamp;
 use crate::commit_builder::CommitBuilder;
 use crate::commit_builder::DetachedCommitBuilder;
+use crate::config::ConfigGetError;
+use crate::copies::CopyDetectionOptions;
 use crate::dag_walk;
 use crate::default_index::DefaultIndexStore;
 use crate::default_index::DefaultMutableIndex;
 use crate::default_submodule_store::DefaultSubmoduleStore;
 use crate::file_util::IoResultExt as _;
 use crate::file_util::PathError;
+use crate::files::MergeOptions;
 use crate::index::ChangeIdIndex;
 use crate::index::Index;
 use crate::index::IndexReadError;
@@ -57,6 +61,8 @@ use crate::index::MutableIndex;
 use crate::index::ReadonlyIndex;
 use crate::merge::trivial_merge;
 use crate::merge::MergeBuilder;
+use crate::merge_driver::MergeDriverConfigError;
+use crate::merge_driver::MergeDrivers;
 use crate::object_id::HexPrefix;
 use crate::object_id::ObjectId as _;
 use crate::object_id::PrefixResolution;
@@ -65,6 +71,7 @@ use crate::op_heads_store::OpHeadResolutionError;
 use crate::op_heads_store::OpHeadsStore;
 use crate::op_heads_store::OpHeadsStoreError;
 use crate::op_store;
+use crate::op_store::BookmarkMetadata;
 use crate::op_store::OpStore;
 use crate::op_store::OpStoreError;
 use crate::op_store::OpStoreResult;
@@ -80,6 +87,7 @@ use crate::ref_name::RemoteName;
 use crate::ref_name::RemoteRefSymbol;
 use crate::ref_name::WorkspaceName;
 use crate::ref_name::WorkspaceNameBuf;
+use crate::refs::diff_named_bookmark_metadata;
 use crate::refs::diff_named_commit_ids;
 use crate::refs::diff_named_ref_targets;
 use crate::refs::diff_named_remote_refs;
@@ -163,6 +171,10 @@ pub enum RepoInitError {
     OpHeadsStore(#[from] OpHeadsStoreError),
     #[error(transparent)]
     Path(#[from] PathError),
+    #[error(transparent)]
+    Config(#[from] ConfigGetError),
+    #[error(transparent)]
+    MergeDrivers(#[from] MergeDriverConfigError),
 }
 
 impl ReadonlyRepo {
@@ -202,7 +214,13 @@ impl ReadonlyRepo {
         let backend = backend_initializer(settings, &store_path)?;
         let backend_path = store_path.join("type");
         fs::write(&backend_path, backend.name()).context(&backend_path)?;
-        let store = Store::new(backend, signer);
+        let store = Store::new(
+            backend,
+            signer,
+            MergeOptions::from_settings(settings)?,
+            MergeDrivers::from_settings(settings)?,
+            CopyDetectionOptions::from_settings(settings)?,
+        );
 
         let op_store_path = repo_path.join("op_store");
         fs::create_dir(&op_store_path).context(&op_store_path)?;
@@ -409,6 +427,10 @@ impl Default for StoreFactories {
             SimpleBackend::name(),
             Box::new(|_settings, store_path| Ok(Box::new(SimpleBackend::load(store_path)))),
         );
+        factories.add_backend(
+            ChunkedBackend::name(),
+            Box::new(|_settings, store_path| Ok(Box::new(ChunkedBackend::load(store_path)))),
+        );
         #[cfg(feature = "git")]
         factories.add_backend(
             crate::git_backend::GitBackend::name(),
@@ -474,6 +496,10 @@ pub enum StoreLoadError {
     Backend(#[from] BackendLoadError),
     #[error(transparent)]
     Signing(#[from] SignInitError),
+    #[error(transparent)]
+    Config(#[from] ConfigGetError),
+    #[error(transparent)]
+    MergeDrivers(#[from] MergeDriverConfigError),
 }
 
 impl StoreFactories {
@@ -677,6 +703,9 @@ impl RepoLoader {
         let store = Store::new(
             store_factories.load_backend(settings, &repo_path.join("store"))?,
             Signer::from_settings(settings)?,
+            MergeOptions::from_settings(settings)?,
+            MergeDrivers::from_settings(settings)?,
+            CopyDetectionOptions::from_settings(settings)?,
         );
         let root_op_data = RootOperationData {
             root_commit_id: store.root_commit_id().clone(),
@@ -1435,6 +1464,8 @@ impl MutableRepo {
     pub fn remove_wc_commit(&mut self, name: &WorkspaceName) -> Result<(), EditCommitError> {
         self.maybe_abandon_wc_commit(name)?;
         self.view_mut().remove_wc_commit(name);
+        self.view_mut()
+            .set_git_worktree_head_target(name, RefTarget::absent());
         Ok(())
     }
 
@@ -1646,6 +1677,33 @@ impl MutableRepo {
         self.set_local_bookmark_target(name, new_target);
     }
 
+    pub fn get_bookmark_metadata(&self, name: &RefName) -> Option<BookmarkMetadata> {
+        self.view
+            .with_ref(|v| v.get_bookmark_metadata(name).cloned())
+    }
+
+    pub fn set_bookmark_metadata(&mut self, name: &RefName, metadata: BookmarkMetadata) {
+        self.view_mut().set_bookmark_metadata(name, metadata);
+    }
+
+    fn merge_bookmark_metadata(
+        &mut self,
+        name: &RefName,
+        base_metadata: Option<&BookmarkMetadata>,
+        other_metadata: Option<&BookmarkMetadata>,
+    ) {
+        let view = self.view.get_mut();
+        let self_metadata = view.get_bookmark_metadata(name);
+        // Metadata is free-form text, so there's no meaningful way to merge
+        // conflicting changes. Keep our side in that case.
+        let new_metadata = trivial_merge(&[self_metadata, base_metadata, other_metadata])
+            .copied()
+            .unwrap_or(self_metadata)
+            .cloned()
+            .unwrap_or_default();
+        view.set_bookmark_metadata(name, new_metadata);
+    }
+
     pub fn get_remote_bookmark(&self, symbol: RemoteRefSymbol<'_>) -> RemoteRef {
         self.view
             .with_ref(|v| v.get_remote_bookmark(symbol).clone())
@@ -1738,6 +1796,28 @@ impl MutableRepo {
         self.view_mut().set_git_head_target(target);
     }
 
+    pub fn git_worktree_head(&self, name: &WorkspaceName) -> RefTarget {
+        self.view
+            .with_ref(|v| v.get_git_worktree_head(name).clone())
+    }
+
+    pub fn set_git_worktree_head_target(&mut self, name: &WorkspaceName, target: RefTarget) {
+        self.view_mut().set_git_worktree_head_target(name, target);
+    }
+
+    fn merge_git_worktree_head(
+        &mut self,
+        name: &WorkspaceName,
+        base_target: &RefTarget,
+        other_target: &RefTarget,
+    ) {
+        let view = self.view.get_mut();
+        let index = self.index.as_index();
+        let self_target = view.get_git_worktree_head(name);
+        let new_target = merge_ref_targets(index, self_target, base_target, other_target);
+        view.set_git_worktree_head_target(name, new_target);
+    }
+
     pub fn set_view(&mut self, data: op_store::View) {
         self.view_mut().set_view(data);
         self.view.mark_dirty();
@@ -1801,6 +1881,12 @@ impl MutableRepo {
             self.merge_local_bookmark(name, base_target, other_target);
         }
 
+        let changed_bookmark_metadata =
+            diff_named_bookmark_metadata(base.bookmark_metadata(), other.bookmark_metadata());
+        for (name, (base_metadata, other_metadata)) in changed_bookmark_metadata {
+            self.merge_bookmark_metadata(name, base_metadata, other_metadata);
+        }
+
         let changed_tags = diff_named_ref_targets(base.tags(), other.tags());
         for (name, (base_target, other_target)) in changed_tags {
             self.merge_tag(name, base_target, other_target);
@@ -1825,6 +1911,12 @@ impl MutableRepo {
         );
         self.set_git_head_target(new_git_head_target);
 
+        let changed_git_worktree_heads =
+            diff_named_ref_targets(base.git_worktree_heads(), other.git_worktree_heads());
+        for (name, (base_target, other_target)) in changed_git_worktree_heads {
+            self.merge_git_worktree_head(name, base_target, other_target);
+        }
+
         Ok(())
     }
 
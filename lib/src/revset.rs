@@ -266,6 +266,12 @@ pub enum RevsetExpression<St: ExpressionState> {
         candidates: Rc<Self>,
         count: usize,
     },
+    /// Commits in `candidates` whose changes are also made by a commit in
+    /// `upstream`.
+    Landed {
+        candidates: Rc<Self>,
+        upstream: Rc<Self>,
+    },
     Filter(RevsetFilterPredicate),
     /// Marker for subtree that should be intersected as filter.
     AsFilter(Rc<Self>),
@@ -382,6 +388,16 @@ impl<St: ExpressionState> RevsetExpression<St> {
         })
     }
 
+    /// Commits in `self` whose changes are also made by a commit in
+    /// `upstream`, e.g. because they were cherry-picked or squash-merged
+    /// upstream.
+    pub fn landed(self: &Rc<Self>, upstream: &Rc<Self>) -> Rc<Self> {
+        Rc::new(Self::Landed {
+            candidates: self.clone(),
+            upstream: upstream.clone(),
+        })
+    }
+
     /// Commits in `self` that don't have descendants in `self`.
     pub fn heads(self: &Rc<Self>) -> Rc<Self> {
         Rc::new(Self::Heads(self.clone()))
@@ -638,6 +654,11 @@ pub enum ResolvedExpression {
         candidates: Box<Self>,
         count: usize,
     },
+    /// Commits in `candidates` whose patch ID matches a commit in `upstream`.
+    Landed {
+        candidates: Box<Self>,
+        upstream: Box<Self>,
+    },
     Coalesce(Box<Self>, Box<Self>),
     Union(Box<Self>, Box<Self>),
     /// Intersects `candidates` with `predicate` by filtering.
@@ -784,6 +805,12 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         };
         Ok(candidates.latest(count))
     });
+    map.insert("landed", |diagnostics, function, context| {
+        let [candidates_arg, upstream_arg] = function.expect_exact_arguments()?;
+        let candidates = lower_expression(diagnostics, candidates_arg, context)?;
+        let upstream = lower_expression(diagnostics, upstream_arg, context)?;
+        Ok(candidates.landed(&upstream))
+    });
     map.insert("fork_point", |diagnostics, function, context| {
         let [expression_arg] = function.expect_exact_arguments()?;
         let expression = lower_expression(diagnostics, expression_arg, context)?;
@@ -1252,6 +1279,15 @@ fn try_transform_expression<St: ExpressionState, E>(
                     candidates,
                     count: *count,
                 }),
+            RevsetExpression::Landed {
+                candidates,
+                upstream,
+            } => transform_rec_pair((candidates, upstream), pre, post)?.map(
+                |(candidates, upstream)| RevsetExpression::Landed {
+                    candidates,
+                    upstream,
+                },
+            ),
             RevsetExpression::Filter(_) => None,
             RevsetExpression::AsFilter(candidates) => {
                 transform_rec(candidates, pre, post)?.map(RevsetExpression::AsFilter)
@@ -1446,6 +1482,18 @@ where
             let count = *count;
             RevsetExpression::Latest { candidates, count }.into()
         }
+        RevsetExpression::Landed {
+            candidates,
+            upstream,
+        } => {
+            let candidates = folder.fold_expression(candidates)?;
+            let upstream = folder.fold_expression(upstream)?;
+            RevsetExpression::Landed {
+                candidates,
+                upstream,
+            }
+            .into()
+        }
         RevsetExpression::Filter(predicate) => RevsetExpression::Filter(predicate.clone()).into(),
         RevsetExpression::AsFilter(candidates) => {
             let candidates = folder.fold_expression(candidates)?;
@@ -2327,6 +2375,13 @@ impl VisibilityResolutionContext<'_> {
                 candidates: self.resolve(candidates).into(),
                 count: *count,
             },
+            RevsetExpression::Landed {
+                candidates,
+                upstream,
+            } => ResolvedExpression::Landed {
+                candidates: self.resolve(candidates).into(),
+                upstream: self.resolve(upstream).into(),
+            },
             RevsetExpression::Filter(_) | RevsetExpression::AsFilter(_) => {
                 // Top-level filter without intersection: e.g. "~author(_)" is represented as
                 // `AsFilter(NotIn(Filter(Author(_))))`.
@@ -2428,7 +2483,8 @@ impl VisibilityResolutionContext<'_> {
             | RevsetExpression::Heads(_)
             | RevsetExpression::Roots(_)
             | RevsetExpression::ForkPoint(_)
-            | RevsetExpression::Latest { .. } => {
+            | RevsetExpression::Latest { .. }
+            | RevsetExpression::Landed { .. } => {
                 ResolvedPredicateExpression::Set(self.resolve(expression).into())
             }
             RevsetExpression::Filter(predicate) => {
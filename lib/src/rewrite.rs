@@ -32,6 +32,7 @@ use crate::backend::MergedTreeId;
 use crate::commit::Commit;
 use crate::commit::CommitIteratorExt as _;
 use crate::commit_builder::CommitBuilder;
+use crate::copies::CopyRecords;
 use crate::index::Index;
 use crate::index::IndexError;
 use crate::matchers::Matcher;
@@ -251,9 +252,40 @@ impl<'repo> CommitRewriter<'repo> {
             let old_base_tree = merge_commit_trees(self.mut_repo, &old_parents)?;
             let new_base_tree = merge_commit_trees(self.mut_repo, &new_parents)?;
             let old_tree = self.old_commit.tree()?;
+            let store = self.mut_repo.store();
+            let mut new_tree = new_base_tree.merge(&old_base_tree, &old_tree)?;
+            // Detecting renames can be expensive, so only try it if the
+            // path-based merge failed. Copy records are collected between two
+            // commits, so renames aren't followed when rebasing merge commits
+            // or onto multiple parents.
+            if let ([old_parent], [new_parent]) = (&old_parents[..], &new_parents[..]) {
+                if new_tree.has_conflict() {
+                    let copies = get_copy_records(store, old_parent.id(), new_parent.id())
+                        .and_then(|dest_copies| {
+                            let own_copies =
+                                get_copy_records(store, old_parent.id(), self.old_commit.id())?;
+                            Ok((dest_copies, own_copies))
+                        });
+                    match copies {
+                        Ok((dest_copies, own_copies)) => {
+                            new_tree = new_base_tree.merge_with_copies(
+                                &old_base_tree,
+                                &old_tree,
+                                &dest_copies,
+                                &own_copies,
+                            )?;
+                        }
+                        // Keep the result of the path-based merge
+                        Err(err) => {
+                            tracing::warn!(?err, "failed to get copy records for rebase");
+                        }
+                    }
+                }
+            }
+            let new_tree = store.merge_drivers().resolve_conflicts(store, new_tree)?;
             (
                 old_base_tree.id() == *self.old_commit.tree_id(),
-                new_base_tree.merge(&old_base_tree, &old_tree)?.id(),
+                new_tree.id(),
             )
         };
         // Ensure we don't abandon commits with multiple parents (merge commits), even
@@ -294,6 +326,18 @@ impl<'repo> CommitRewriter<'repo> {
     }
 }
 
+/// Collects the copy records between two commits.
+fn get_copy_records(
+    store: &Arc<Store>,
+    root: &CommitId,
+    head: &CommitId,
+) -> BackendResult<CopyRecords> {
+    let stream = store.get_copy_records(None, root, head)?;
+    let mut copy_records = CopyRecords::default();
+    copy_records.add_records(stream.collect::<Vec<_>>().block_on())?;
+    Ok(copy_records)
+}
+
 pub enum RebasedCommit {
     Rewritten(Commit),
     Abandoned { parent_id: CommitId },
@@ -1186,6 +1230,10 @@ pub fn squash_commits<'repo>(
         destination_tree =
             destination_tree.merge(&source.commit.parent_tree, &source.commit.selected_tree)?;
     }
+    let store = repo.store();
+    let destination_tree = store
+        .merge_drivers()
+        .resolve_conflicts(store, destination_tree)?;
     let mut predecessors = vec![destination.id().clone()];
     predecessors.extend(
         source_commits
@@ -1203,6 +1251,36 @@ pub fn squash_commits<'repo>(
     }))
 }
 
+/// Find commits from the target whose changes are already present in the
+/// destination, e.g. because they were cherry-picked or squash-merged there.
+/// Commits are compared by their [`PatchId`](crate::patch_id::PatchId). Only
+/// the commits that are new ancestors of the rebased commits are considered.
+pub fn find_landed_commits(
+    repo: &dyn Repo,
+    new_parent_ids: &[CommitId],
+    target: &MoveCommitsTarget,
+) -> BackendResult<Vec<Commit>> {
+    let (target_commits, target_root_ids) = match target {
+        MoveCommitsTarget::Commits(commit_ids) => {
+            (RevsetExpression::commits(commit_ids.clone()), commit_ids)
+        }
+        MoveCommitsTarget::Roots(root_ids) => (
+            RevsetExpression::commits(root_ids.clone()).descendants(),
+            root_ids,
+        ),
+    };
+    let new_ancestors = RevsetExpression::commits(target_root_ids.clone())
+        .range(&RevsetExpression::commits(new_parent_ids.to_owned()));
+    target_commits
+        .landed(&new_ancestors)
+        .evaluate(repo)
+        .map_err(|err| err.into_backend_error())?
+        .iter()
+        .commits(repo.store())
+        .try_collect()
+        .map_err(|err| err.into_backend_error())
+}
+
 /// Find divergent commits from the target that are already present with
 /// identical contents in the destination. These commits should be able to be
 /// safely abandoned.
@@ -34,6 +34,7 @@ use crate::backend::Conflict;
 use crate::backend::ConflictId;
 use crate::backend::CopyRecord;
 use crate::backend::FileId;
+use crate::backend::GcStats;
 use crate::backend::SigningFn;
 use crate::backend::SymlinkId;
 use crate::backend::Tree;
@@ -198,7 +199,12 @@ impl Backend for SecretBackend {
         self.inner.get_copy_records(paths, root, head)
     }
 
-    fn gc(&self, index: &dyn Index, keep_newer: SystemTime) -> BackendResult<()> {
-        self.inner.gc(index, keep_newer)
+    fn gc(
+        &self,
+        index: &dyn Index,
+        keep_newer: SystemTime,
+        dry_run: bool,
+    ) -> BackendResult<GcStats> {
+        self.inner.gc(index, keep_newer, dry_run)
     }
 }
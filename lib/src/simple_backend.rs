@@ -15,9 +15,12 @@
 #![allow(missing_docs)]
 
 use std::any::Any;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fmt::Debug;
 use std::fs;
 use std::fs::File;
+use std::io;
 use std::io::Cursor;
 use std::io::Read as _;
 use std::io::Write as _;
@@ -31,6 +34,7 @@ use blake2::Blake2b512;
 use blake2::Digest as _;
 use futures::stream;
 use futures::stream::BoxStream;
+use itertools::Itertools as _;
 use pollster::FutureExt as _;
 use prost::Message as _;
 use tempfile::NamedTempFile;
@@ -49,6 +53,7 @@ use crate::backend::ConflictId;
 use crate::backend::ConflictTerm;
 use crate::backend::CopyRecord;
 use crate::backend::FileId;
+use crate::backend::GcStats;
 use crate::backend::MergedTreeId;
 use crate::backend::MillisSinceEpoch;
 use crate::backend::SecureSig;
@@ -71,7 +76,7 @@ use crate::repo_path::RepoPathComponentBuf;
 const COMMIT_ID_LENGTH: usize = 64;
 const CHANGE_ID_LENGTH: usize = 16;
 
-fn map_not_found_err(err: std::io::Error, id: &impl ObjectId) -> BackendError {
+pub(crate) fn map_not_found_err(err: std::io::Error, id: &impl ObjectId) -> BackendError {
     if err.kind() == std::io::ErrorKind::NotFound {
         BackendError::ObjectNotFound {
             object_type: id.object_type(),
@@ -87,7 +92,9 @@ fn map_not_found_err(err: std::io::Error, id: &impl ObjectId) -> BackendError {
     }
 }
 
-fn to_other_err(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> BackendError {
+pub(crate) fn to_other_err(
+    err: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+) -> BackendError {
     BackendError::Other(err.into())
 }
 
@@ -362,8 +369,206 @@ impl Backend for SimpleBackend {
         Ok(Box::pin(stream::empty()))
     }
 
-    fn gc(&self, _index: &dyn Index, _keep_newer: SystemTime) -> BackendResult<()> {
-        Ok(())
+    fn gc(
+        &self,
+        index: &dyn Index,
+        keep_newer: SystemTime,
+        dry_run: bool,
+    ) -> BackendResult<GcStats> {
+        let garbage = self.find_garbage(index, keep_newer)?;
+        remove_garbage(garbage.values().flatten(), dry_run)
+    }
+}
+
+impl SimpleBackend {
+    /// Finds objects which are unreachable from the commits in the `index`,
+    /// and which were created before `keep_newer`. Returns the objects keyed
+    /// by directory name.
+    #[tracing::instrument(skip(self, index))]
+    pub(crate) fn find_garbage(
+        &self,
+        index: &dyn Index,
+        keep_newer: SystemTime,
+    ) -> BackendResult<HashMap<&'static str, Vec<StoredObject>>> {
+        let mut objects = HashMap::new();
+        for dir_name in OBJECT_DIR_NAMES {
+            let dir = self.path.join(dir_name);
+            objects.insert(dir_name, list_stored_objects(&dir, keep_newer)?);
+        }
+        let new_ids = |dir_name: &str| -> Vec<Vec<u8>> {
+            objects[dir_name]
+                .iter()
+                .filter(|object| object.is_new)
+                .map(|object| object.id.clone())
+                .collect()
+        };
+
+        // Objects created after keep_newer are preserved along with the
+        // objects they refer to, so new commits won't be left incomplete.
+        let mut reachable = GcReachableObjects::default();
+        let mut pending_commits = index
+            .all_heads_for_gc()
+            .map_err(|err| BackendError::Other(err.into()))?
+            .chain(new_ids("commits").into_iter().map(CommitId::new))
+            .map(|id| (id, false))
+            .collect_vec();
+        while let Some((id, is_predecessor)) = pending_commits.pop() {
+            if id == self.root_commit_id || reachable.commits.contains(id.as_bytes()) {
+                continue;
+            }
+            let commit = match self.read_commit(&id).block_on() {
+                Ok(commit) => commit,
+                // Predecessors may have been removed by "jj op abandon" + gc.
+                Err(BackendError::ObjectNotFound { .. }) if is_predecessor => {
+                    tracing::trace!(?id, "skipping missing predecessor");
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            reachable.commits.insert(id.to_bytes());
+            pending_commits.extend(commit.parents.into_iter().map(|id| (id, false)));
+            pending_commits.extend(commit.predecessors.into_iter().map(|id| (id, true)));
+            reachable.pending_trees.extend(match commit.root_tree {
+                MergedTreeId::Legacy(tree_id) => vec![tree_id],
+                MergedTreeId::Merge(tree_ids) => tree_ids.into_iter().collect(),
+            });
+        }
+        reachable.pending_trees.push(self.empty_tree_id.clone());
+        reachable
+            .pending_trees
+            .extend(new_ids("trees").into_iter().map(TreeId::new));
+        reachable
+            .pending_conflicts
+            .extend(new_ids("conflicts").into_iter().map(ConflictId::new));
+        loop {
+            if let Some(id) = reachable.pending_trees.pop() {
+                if reachable.trees.insert(id.to_bytes()) {
+                    let tree = self.read_tree(RepoPath::root(), &id).block_on()?;
+                    for entry in tree.entries() {
+                        reachable.add_value(entry.value());
+                    }
+                }
+            } else if let Some(id) = reachable.pending_conflicts.pop() {
+                if reachable.conflicts.insert(id.to_bytes()) {
+                    let conflict = self.read_conflict(RepoPath::root(), &id)?;
+                    for term in itertools::chain(&conflict.removes, &conflict.adds) {
+                        reachable.add_value(&term.value);
+                    }
+                }
+            } else {
+                break;
+            }
+        }
+        tracing::info!(
+            reachable_commit_count = reachable.commits.len(),
+            reachable_tree_count = reachable.trees.len(),
+            "collected reachable objects"
+        );
+
+        for (dir_name, objects) in &mut objects {
+            let reachable_ids = match *dir_name {
+                "commits" => &reachable.commits,
+                "trees" => &reachable.trees,
+                "files" => &reachable.files,
+                "symlinks" => &reachable.symlinks,
+                "conflicts" => &reachable.conflicts,
+                _ => unreachable!(),
+            };
+            objects.retain(|object| !object.is_new && !reachable_ids.contains(&object.id));
+        }
+        Ok(objects)
+    }
+}
+
+/// Names of the directories where objects are stored.
+const OBJECT_DIR_NAMES: [&str; 5] = ["commits", "trees", "files", "symlinks", "conflicts"];
+
+/// Object stored in a file.
+#[derive(Debug)]
+pub(crate) struct StoredObject {
+    pub id: Vec<u8>,
+    pub path: PathBuf,
+    pub size: u64,
+    /// Whether the object was written after the `keep_newer` timestamp.
+    pub is_new: bool,
+}
+
+/// Lists objects stored in the `dir`. Files not named by hex id are skipped.
+pub(crate) fn list_stored_objects(
+    dir: &Path,
+    keep_newer: SystemTime,
+) -> BackendResult<Vec<StoredObject>> {
+    let mut objects = vec![];
+    for entry in dir.read_dir().map_err(to_other_err)? {
+        let entry = entry.map_err(to_other_err)?;
+        let Some(id) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| hex::decode(name).ok())
+        else {
+            tracing::trace!(?entry, "skipping invalid file name");
+            continue;
+        };
+        let metadata = entry.metadata().map_err(to_other_err)?;
+        let mtime = metadata.modified().expect("unsupported platform?");
+        objects.push(StoredObject {
+            id,
+            path: entry.path(),
+            size: metadata.len(),
+            is_new: mtime > keep_newer,
+        });
+    }
+    Ok(objects)
+}
+
+/// Removes the given objects unless `dry_run` is true.
+pub(crate) fn remove_garbage<'a>(
+    objects: impl IntoIterator<Item = &'a StoredObject>,
+    dry_run: bool,
+) -> BackendResult<GcStats> {
+    let mut stats = GcStats::default();
+    for object in objects {
+        stats.removed_objects += 1;
+        stats.removed_bytes += object.size;
+        if dry_run {
+            tracing::trace!(path = ?object.path, "not removing (dry run)");
+            continue;
+        }
+        tracing::trace!(path = ?object.path, "removing");
+        match fs::remove_file(&object.path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(to_other_err(err)),
+        }
+    }
+    Ok(stats)
+}
+
+/// Ids of objects reachable from the retained commits.
+#[derive(Debug, Default)]
+struct GcReachableObjects {
+    commits: HashSet<Vec<u8>>,
+    trees: HashSet<Vec<u8>>,
+    files: HashSet<Vec<u8>>,
+    symlinks: HashSet<Vec<u8>>,
+    conflicts: HashSet<Vec<u8>>,
+    pending_trees: Vec<TreeId>,
+    pending_conflicts: Vec<ConflictId>,
+}
+
+impl GcReachableObjects {
+    fn add_value(&mut self, value: &TreeValue) {
+        match value {
+            TreeValue::File { id, .. } => {
+                self.files.insert(id.to_bytes());
+            }
+            TreeValue::Symlink(id) => {
+                self.symlinks.insert(id.to_bytes());
+            }
+            TreeValue::Tree(id) => self.pending_trees.push(id.clone()),
+            TreeValue::GitSubmodule(_) => {}
+            TreeValue::Conflict(id) => self.pending_conflicts.push(id.clone()),
+        }
     }
 }
 
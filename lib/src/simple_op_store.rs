@@ -21,12 +21,20 @@ use std::collections::HashSet;
 use std::fmt::Debug;
 use std::fs;
 use std::io;
+use std::io::BufWriter;
 use std::io::ErrorKind;
+use std::io::Read as _;
+use std::io::Seek as _;
+use std::io::SeekFrom;
 use std::io::Write as _;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
 use std::time::SystemTime;
 
+use blake2::Blake2b512;
+use digest::Digest as _;
 use itertools::Itertools as _;
 use prost::Message as _;
 use tempfile::NamedTempFile;
@@ -46,6 +54,7 @@ use crate::object_id::HexPrefix;
 use crate::object_id::ObjectId;
 use crate::object_id::PrefixResolution;
 use crate::op_store;
+use crate::op_store::BookmarkMetadata;
 use crate::op_store::OpStore;
 use crate::op_store::OpStoreError;
 use crate::op_store::OpStoreResult;
@@ -60,6 +69,7 @@ use crate::op_store::RootOperationData;
 use crate::op_store::View;
 use crate::op_store::ViewId;
 use crate::ref_name::GitRefNameBuf;
+use crate::ref_name::RefName;
 use crate::ref_name::RefNameBuf;
 use crate::ref_name::RemoteNameBuf;
 use crate::ref_name::WorkspaceName;
@@ -69,6 +79,17 @@ use crate::ref_name::WorkspaceNameBuf;
 const OPERATION_ID_LENGTH: usize = 64;
 const VIEW_ID_LENGTH: usize = 64;
 
+/// Current format version of pack files.
+const PACK_FORMAT_VERSION: u32 = 1;
+const PACK_FILE_MAGIC: &[u8; 8] = b"jjoppack";
+const PACK_FILE_EXTENSION: &str = "pack";
+// magic, format version
+const PACK_HEADER_SIZE: u64 = 8 + 4;
+// index offset, number of operations, number of views
+const PACK_FOOTER_SIZE: u64 = 8 + 4 + 4;
+// id, data offset, data length
+const PACK_INDEX_ENTRY_SIZE: usize = OPERATION_ID_LENGTH + 8 + 4;
+
 /// Error that may occur during [`SimpleOpStore`] initialization.
 #[derive(Debug, Error)]
 #[error("Failed to initialize simple operation store")]
@@ -80,12 +101,20 @@ impl From<SimpleOpStoreInitError> for BackendInitError {
     }
 }
 
+/// Operation store that saves each operation and view in a file.
+///
+/// Newly written objects are stored as loose files named by their hex id.
+/// Garbage collection moves reachable objects to pack files, which also
+/// contain an index of the packed objects sorted by id.
 #[derive(Debug)]
 pub struct SimpleOpStore {
     path: PathBuf,
     root_data: RootOperationData,
     root_operation_id: OperationId,
     root_view_id: ViewId,
+    /// Packs loaded so far, or `None` if the packs directory hasn't been
+    /// scanned yet.
+    packs: Mutex<Option<Vec<Arc<OpPack>>>>,
 }
 
 impl SimpleOpStore {
@@ -114,11 +143,12 @@ impl SimpleOpStore {
             root_data,
             root_operation_id: OperationId::from_bytes(&[0; OPERATION_ID_LENGTH]),
             root_view_id: ViewId::from_bytes(&[0; VIEW_ID_LENGTH]),
+            packs: Mutex::new(None),
         }
     }
 
     fn init_base_dirs(&self) -> Result<(), PathError> {
-        for dir in [self.views_dir(), self.operations_dir()] {
+        for dir in [self.views_dir(), self.operations_dir(), self.packs_dir()] {
             fs::create_dir(&dir).context(&dir)?;
         }
         Ok(())
@@ -131,6 +161,200 @@ impl SimpleOpStore {
     fn operations_dir(&self) -> PathBuf {
         self.path.join("operations")
     }
+
+    /// Directory where pack files are stored. This may not exist in repos
+    /// created by older versions.
+    fn packs_dir(&self) -> PathBuf {
+        self.path.join("packs")
+    }
+
+    fn loose_objects_dir(&self, kind: PackObjectKind) -> PathBuf {
+        match kind {
+            PackObjectKind::Operation => self.operations_dir(),
+            PackObjectKind::View => self.views_dir(),
+        }
+    }
+
+    /// Returns the known packs. If `reload` is true, or if the packs haven't
+    /// been loaded yet, the packs directory is scanned for new packs.
+    fn packs(&self, reload: bool) -> Result<Vec<Arc<OpPack>>, PathError> {
+        let mut cached = self.packs.lock().unwrap();
+        if let Some(packs) = cached.as_ref().filter(|_| !reload) {
+            return Ok(packs.clone());
+        }
+        let dir = self.packs_dir();
+        let entries = match dir.read_dir() {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                *cached = Some(vec![]);
+                return Ok(vec![]);
+            }
+            Err(err) => return Err(err).context(&dir),
+        };
+        let mut packs = vec![];
+        for entry in entries {
+            let path = entry.context(&dir)?.path();
+            if path.extension() != Some(PACK_FILE_EXTENSION.as_ref()) {
+                continue; // Skip temporary files
+            }
+            if let Some(pack) = cached.iter().flatten().find(|pack| pack.path == path) {
+                packs.push(pack.clone());
+                continue;
+            }
+            match OpPack::load(&path) {
+                Ok(pack) => packs.push(Arc::new(pack)),
+                // Removed by concurrent gc
+                Err(err) if err.error.kind() == ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+        packs.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+        *cached = Some(packs.clone());
+        Ok(packs)
+    }
+
+    fn read_packed_object(
+        &self,
+        kind: PackObjectKind,
+        id: &[u8],
+        reload: bool,
+    ) -> Result<Option<Vec<u8>>, PathError> {
+        for pack in self.packs(reload)? {
+            match pack.read_object(kind, id) {
+                Ok(Some(data)) => return Ok(Some(data)),
+                Ok(None) => {}
+                // The pack may have been replaced by concurrent gc
+                Err(err) if err.error.kind() == ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Reads the encoded object from the known packs or the loose file.
+    fn read_object_data(&self, kind: PackObjectKind, id: &impl ObjectId) -> OpStoreResult<Vec<u8>> {
+        // Look up the loaded pack indexes first since it doesn't involve
+        // file system access if the object isn't packed.
+        if let Some(data) = self
+            .read_packed_object(kind, id.as_bytes(), false)
+            .map_err(|err| io_to_read_error(err, id))?
+        {
+            return Ok(data);
+        }
+        let path = self.loose_objects_dir(kind).join(id.hex());
+        match fs::read(&path).context(&path) {
+            Ok(data) => Ok(data),
+            Err(err) if err.error.kind() == ErrorKind::NotFound => {
+                // The object may have been packed since the packs were loaded.
+                match self.read_packed_object(kind, id.as_bytes(), true) {
+                    Ok(Some(data)) => Ok(data),
+                    Ok(None) => Err(io_to_read_error(err, id)),
+                    Err(err) => Err(io_to_read_error(err, id)),
+                }
+            }
+            Err(err) => Err(io_to_read_error(err, id)),
+        }
+    }
+
+    /// Writes the given objects to a new pack, and removes the loose files
+    /// and the old packs which are superseded by it.
+    ///
+    /// Unreachable objects in the old packs are dropped unless the pack is
+    /// newer than `keep_newer`. Such objects are written back as loose files
+    /// so they will expire by the next gc.
+    fn repack(
+        &self,
+        reachable_ops: &HashSet<&OperationId>,
+        reachable_views: &HashSet<&ViewId>,
+        keep_newer: SystemTime,
+    ) -> Result<(), PathError> {
+        let old_packs = self.packs(true)?;
+        for pack in &old_packs {
+            let metadata = pack.path.metadata().context(&pack.path)?;
+            let mtime = metadata.modified().expect("unsupported platform?");
+            if mtime <= keep_newer {
+                continue;
+            }
+            let unreachable_entries = itertools::chain(
+                pack.operations
+                    .iter()
+                    .filter(|entry| !reachable_ops.contains(&OperationId::from_bytes(&entry.id)))
+                    .map(|entry| (PackObjectKind::Operation, entry)),
+                pack.views
+                    .iter()
+                    .filter(|entry| !reachable_views.contains(&ViewId::from_bytes(&entry.id)))
+                    .map(|entry| (PackObjectKind::View, entry)),
+            );
+            for (kind, entry) in unreachable_entries {
+                let data = pack.read_entry(entry)?;
+                let dir = self.loose_objects_dir(kind);
+                let temp_file = NamedTempFile::new_in(&dir).context(&dir)?;
+                temp_file
+                    .as_file()
+                    .write_all(&data)
+                    .context(temp_file.path())?;
+                temp_file
+                    .as_file()
+                    .set_modified(mtime)
+                    .context(temp_file.path())?;
+                let new_path = dir.join(hex::encode(&entry.id));
+                persist_content_addressed_temp_file(temp_file, &new_path).context(&new_path)?;
+            }
+        }
+
+        // The root operation and view aren't stored.
+        let op_ids = reachable_ops
+            .iter()
+            .filter(|&&id| *id != self.root_operation_id)
+            .map(|id| id.as_bytes())
+            .sorted()
+            .collect_vec();
+        let view_ids = reachable_views
+            .iter()
+            .filter(|&&id| *id != self.root_view_id)
+            .map(|id| id.as_bytes())
+            .sorted()
+            .collect_vec();
+        let new_pack_path = if op_ids.is_empty() && view_ids.is_empty() {
+            None
+        } else {
+            let dir = self.packs_dir();
+            fs::create_dir_all(&dir).context(&dir)?;
+            let read_data = |kind, id: &[u8]| {
+                let read_error = |err: OpStoreError| PathError {
+                    path: self.loose_objects_dir(kind).join(hex::encode(id)),
+                    error: io::Error::other(err),
+                };
+                match kind {
+                    PackObjectKind::Operation => {
+                        self.read_object_data(kind, &OperationId::from_bytes(id))
+                    }
+                    PackObjectKind::View => self.read_object_data(kind, &ViewId::from_bytes(id)),
+                }
+                .map_err(read_error)
+            };
+            Some(write_pack(&dir, &op_ids, &view_ids, read_data)?)
+        };
+        tracing::info!(?new_pack_path, "wrote pack");
+
+        for pack in &old_packs {
+            if Some(&pack.path) != new_pack_path.as_ref() {
+                tracing::trace!(path = ?pack.path, "removing old pack");
+                remove_file_if_exists(&pack.path)?;
+            }
+        }
+        for (kind, ids) in [
+            (PackObjectKind::Operation, &op_ids),
+            (PackObjectKind::View, &view_ids),
+        ] {
+            let dir = self.loose_objects_dir(kind);
+            for id in ids {
+                remove_file_if_exists(&dir.join(hex::encode(id)))?;
+            }
+        }
+        self.packs(true)?;
+        Ok(())
+    }
 }
 
 impl OpStore for SimpleOpStore {
@@ -151,11 +375,7 @@ impl OpStore for SimpleOpStore {
             return Ok(View::make_root(self.root_data.root_commit_id.clone()));
         }
 
-        let path = self.views_dir().join(id.hex());
-        let buf = fs::read(&path)
-            .context(&path)
-            .map_err(|err| io_to_read_error(err, id))?;
-
+        let buf = self.read_object_data(PackObjectKind::View, id)?;
         let proto = crate::protos::op_store::View::decode(&*buf)
             .map_err(|err| to_read_error(err.into(), id))?;
         Ok(view_from_proto(proto))
@@ -188,11 +408,7 @@ impl OpStore for SimpleOpStore {
             return Ok(Operation::make_root(self.root_view_id.clone()));
         }
 
-        let path = self.operations_dir().join(id.hex());
-        let buf = fs::read(&path)
-            .context(&path)
-            .map_err(|err| io_to_read_error(err, id))?;
-
+        let buf = self.read_object_data(PackObjectKind::Operation, id)?;
         let proto = crate::protos::op_store::Operation::decode(&*buf)
             .map_err(|err| to_read_error(err.into(), id))?;
         let mut operation =
@@ -233,22 +449,46 @@ impl OpStore for SimpleOpStore {
         prefix: &HexPrefix,
     ) -> OpStoreResult<PrefixResolution<OperationId>> {
         let op_dir = self.operations_dir();
-        let find = || -> io::Result<_> {
+        let find = || -> Result<_, PathError> {
             let matches_root = prefix.matches(&self.root_operation_id);
             let hex_prefix = prefix.hex();
+            // Packs are reloaded since new operations could have been packed
+            // by concurrent gc.
+            let packs = self.packs(true)?;
             if hex_prefix.len() == OPERATION_ID_LENGTH * 2 {
                 // Fast path for full-length ID
-                if matches_root || op_dir.join(hex_prefix).try_exists()? {
-                    let id = OperationId::from_bytes(prefix.as_full_bytes().unwrap());
+                let full_bytes = prefix.as_full_bytes().unwrap();
+                if matches_root
+                    || packs.iter().any(|pack| pack.contains_operation(full_bytes))
+                    || op_dir.join(&hex_prefix).try_exists().context(&op_dir)?
+                {
+                    let id = OperationId::from_bytes(full_bytes);
                     return Ok(PrefixResolution::SingleMatch(id));
                 } else {
                     return Ok(PrefixResolution::NoMatch);
                 }
             }
 
+            // The same operation may be stored in both pack and loose file.
             let mut matched = matches_root.then(|| self.root_operation_id.clone());
-            for entry in op_dir.read_dir()? {
-                let Ok(name) = entry?.file_name().into_string() else {
+            let mut add_match = |id: OperationId| -> bool {
+                match &matched {
+                    Some(matched_id) => *matched_id == id,
+                    None => {
+                        matched = Some(id);
+                        true
+                    }
+                }
+            };
+            for pack in &packs {
+                for id in pack.resolve_operation_id_prefix(prefix) {
+                    if !add_match(id) {
+                        return Ok(PrefixResolution::AmbiguousMatch);
+                    }
+                }
+            }
+            for entry in op_dir.read_dir().context(&op_dir)? {
+                let Ok(name) = entry.context(&op_dir)?.file_name().into_string() else {
                     continue; // Skip invalid UTF-8
                 };
                 if !name.starts_with(&hex_prefix) {
@@ -257,10 +497,9 @@ impl OpStore for SimpleOpStore {
                 let Ok(id) = OperationId::try_from_hex(&name) else {
                     continue; // Skip invalid hex
                 };
-                if matched.is_some() {
+                if !add_match(id) {
                     return Ok(PrefixResolution::AmbiguousMatch);
                 }
-                matched = Some(id);
             }
             if let Some(id) = matched {
                 Ok(PrefixResolution::SingleMatch(id))
@@ -268,9 +507,7 @@ impl OpStore for SimpleOpStore {
                 Ok(PrefixResolution::NoMatch)
             }
         };
-        find()
-            .context(&op_dir)
-            .map_err(|err| OpStoreError::Other(err.into()))
+        find().map_err(|err| OpStoreError::Other(err.into()))
     }
 
     #[tracing::instrument(skip(self))]
@@ -353,10 +590,239 @@ impl OpStore for SimpleOpStore {
         };
         prune_views().map_err(|err| OpStoreError::Other(err.into()))?;
 
+        // Reachable objects are moved to a new pack. Unreachable objects newer
+        // than keep_newer are kept as loose files.
+        self.repack(
+            &reachable_ops.keys().collect(),
+            &reachable_views,
+            keep_newer,
+        )
+        .map_err(|err| OpStoreError::Other(err.into()))?;
+
         Ok(())
     }
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum PackObjectKind {
+    Operation,
+    View,
+}
+
+#[derive(Clone, Debug)]
+struct OpPackEntry {
+    id: Vec<u8>,
+    offset: u64,
+    len: u32,
+}
+
+/// Pack file containing operations and views.
+///
+/// File format:
+///
+/// ```text
+/// u8[8]: magic "jjoppack"
+/// u32: file format version
+/// [u8]: encoded objects
+/// for each operation, sorted by id:
+///   u8[64]: operation id
+///   u64: offset of the encoded object
+///   u32: length of the encoded object
+/// for each view, sorted by id:
+///   (same as above)
+/// u64: offset of the first index entry
+/// u32: number of operations
+/// u32: number of views
+/// ```
+///
+/// All integers are little-endian. The file is named after the hash of the
+/// contained ids.
+#[derive(Debug)]
+struct OpPack {
+    path: PathBuf,
+    operations: Vec<OpPackEntry>,
+    views: Vec<OpPackEntry>,
+}
+
+impl OpPack {
+    fn load(path: &Path) -> Result<Self, PathError> {
+        let invalid_data = |message: &str| io::Error::new(ErrorKind::InvalidData, message);
+        let load = || -> io::Result<Self> {
+            let mut file = fs::File::open(path)?;
+            let file_len = file.metadata()?.len();
+            if file_len < PACK_HEADER_SIZE + PACK_FOOTER_SIZE {
+                return Err(invalid_data("Pack file is too short"));
+            }
+            let mut header = [0; PACK_HEADER_SIZE as usize];
+            file.read_exact(&mut header)?;
+            if header[..8] != PACK_FILE_MAGIC[..] {
+                return Err(invalid_data("Not an operation pack file"));
+            }
+            let format_version = u32::from_le_bytes(header[8..12].try_into().unwrap());
+            if format_version != PACK_FORMAT_VERSION {
+                return Err(invalid_data(&format!(
+                    "Unsupported pack format version {format_version}"
+                )));
+            }
+
+            file.seek(SeekFrom::End(-(PACK_FOOTER_SIZE as i64)))?;
+            let mut footer = [0; PACK_FOOTER_SIZE as usize];
+            file.read_exact(&mut footer)?;
+            let index_offset = u64::from_le_bytes(footer[0..8].try_into().unwrap());
+            let num_operations = u32::from_le_bytes(footer[8..12].try_into().unwrap());
+            let num_views = u32::from_le_bytes(footer[12..16].try_into().unwrap());
+            let index_len =
+                (u64::from(num_operations) + u64::from(num_views)) * PACK_INDEX_ENTRY_SIZE as u64;
+            if index_offset < PACK_HEADER_SIZE
+                || index_offset + index_len + PACK_FOOTER_SIZE != file_len
+            {
+                return Err(invalid_data("Corrupt pack index"));
+            }
+
+            file.seek(SeekFrom::Start(index_offset))?;
+            let mut index = vec![0; index_len as usize];
+            file.read_exact(&mut index)?;
+            let mut entries = index
+                .chunks_exact(PACK_INDEX_ENTRY_SIZE)
+                .map(|chunk| {
+                    let (id, rest) = chunk.split_at(OPERATION_ID_LENGTH);
+                    let entry = OpPackEntry {
+                        id: id.to_vec(),
+                        offset: u64::from_le_bytes(rest[0..8].try_into().unwrap()),
+                        len: u32::from_le_bytes(rest[8..12].try_into().unwrap()),
+                    };
+                    if entry.offset < PACK_HEADER_SIZE
+                        || entry.offset + u64::from(entry.len) > index_offset
+                    {
+                        return Err(invalid_data("Corrupt pack index entry"));
+                    }
+                    Ok(entry)
+                })
+                .collect::<io::Result<Vec<_>>>()?;
+            let views = entries.split_off(num_operations as usize);
+            let operations = entries;
+            let is_sorted =
+                |entries: &[OpPackEntry]| entries.windows(2).all(|pair| pair[0].id < pair[1].id);
+            if !is_sorted(&operations) || !is_sorted(&views) {
+                return Err(invalid_data("Pack index is not sorted"));
+            }
+            Ok(OpPack {
+                path: path.to_owned(),
+                operations,
+                views,
+            })
+        };
+        load().context(path)
+    }
+
+    fn entries(&self, kind: PackObjectKind) -> &[OpPackEntry] {
+        match kind {
+            PackObjectKind::Operation => &self.operations,
+            PackObjectKind::View => &self.views,
+        }
+    }
+
+    fn find_entry(&self, kind: PackObjectKind, id: &[u8]) -> Option<&OpPackEntry> {
+        let entries = self.entries(kind);
+        let pos = entries
+            .binary_search_by(|entry| entry.id.as_slice().cmp(id))
+            .ok()?;
+        Some(&entries[pos])
+    }
+
+    fn contains_operation(&self, id: &[u8]) -> bool {
+        self.find_entry(PackObjectKind::Operation, id).is_some()
+    }
+
+    fn resolve_operation_id_prefix<'a>(
+        &'a self,
+        prefix: &'a HexPrefix,
+    ) -> impl Iterator<Item = OperationId> + 'a {
+        let min_bytes = prefix.min_prefix_bytes();
+        let pos = self
+            .operations
+            .partition_point(|entry| entry.id.as_slice() < min_bytes);
+        self.operations[pos..]
+            .iter()
+            .map(|entry| OperationId::from_bytes(&entry.id))
+            .take_while(|id| prefix.matches(id))
+    }
+
+    fn read_object(&self, kind: PackObjectKind, id: &[u8]) -> Result<Option<Vec<u8>>, PathError> {
+        self.find_entry(kind, id)
+            .map(|entry| self.read_entry(entry))
+            .transpose()
+    }
+
+    fn read_entry(&self, entry: &OpPackEntry) -> Result<Vec<u8>, PathError> {
+        let read = || -> io::Result<Vec<u8>> {
+            let mut file = fs::File::open(&self.path)?;
+            file.seek(SeekFrom::Start(entry.offset))?;
+            let mut data = vec![0; entry.len as usize];
+            file.read_exact(&mut data)?;
+            Ok(data)
+        };
+        read().context(&self.path)
+    }
+}
+
+/// Writes a pack of the given objects to `dir`, and returns the path to the
+/// new pack file. The ids must be sorted.
+fn write_pack(
+    dir: &Path,
+    operation_ids: &[&[u8]],
+    view_ids: &[&[u8]],
+    mut read_data: impl FnMut(PackObjectKind, &[u8]) -> Result<Vec<u8>, PathError>,
+) -> Result<PathBuf, PathError> {
+    let temp_file = NamedTempFile::new_in(dir).context(dir)?;
+    let mut hasher = Blake2b512::new();
+    let mut write = || -> Result<(), PathError> {
+        let mut writer = BufWriter::new(temp_file.as_file());
+        let mut write_bytes = |bytes: &[u8]| writer.write_all(bytes).context(temp_file.path());
+        write_bytes(PACK_FILE_MAGIC)?;
+        write_bytes(&PACK_FORMAT_VERSION.to_le_bytes())?;
+        let mut offset = PACK_HEADER_SIZE;
+        let mut index = vec![];
+        for (kind, ids) in [
+            (PackObjectKind::Operation, operation_ids),
+            (PackObjectKind::View, view_ids),
+        ] {
+            for &id in ids {
+                let data = read_data(kind, id)?;
+                let len = u32::try_from(data.len()).expect("object should be smaller than 4GiB");
+                write_bytes(&data)?;
+                hasher.update(id);
+                index.extend_from_slice(id);
+                index.extend_from_slice(&offset.to_le_bytes());
+                index.extend_from_slice(&len.to_le_bytes());
+                offset += u64::from(len);
+            }
+        }
+        write_bytes(&index)?;
+        write_bytes(&offset.to_le_bytes())?;
+        write_bytes(&u32::try_from(operation_ids.len()).unwrap().to_le_bytes())?;
+        write_bytes(&u32::try_from(view_ids.len()).unwrap().to_le_bytes())?;
+        writer.flush().context(temp_file.path())?;
+        Ok(())
+    };
+    write()?;
+    // Loose files will be removed once the pack is persisted.
+    temp_file.as_file().sync_all().context(temp_file.path())?;
+    let new_path = dir
+        .join(hex::encode(hasher.finalize()))
+        .with_extension(PACK_FILE_EXTENSION);
+    persist_content_addressed_temp_file(temp_file, &new_path).context(&new_path)?;
+    Ok(new_path)
+}
+
+fn remove_file_if_exists(path: &Path) -> Result<(), PathError> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err).context(path),
+    }
+}
+
 fn io_to_read_error(err: PathError, id: &impl ObjectId) -> OpStoreError {
     if err.error.kind() == ErrorKind::NotFound {
         OpStoreError::ObjectNotFound {
@@ -542,6 +1008,11 @@ fn view_to_proto(view: &View) -> crate::protos::op_store::View {
     }
 
     proto.bookmarks = bookmark_views_to_proto_legacy(&view.local_bookmarks, &view.remote_views);
+    for (name, metadata) in &view.bookmark_metadata {
+        proto
+            .bookmark_metadata
+            .push(bookmark_metadata_to_proto(name, metadata));
+    }
 
     for (name, target) in &view.tags {
         proto.tags.push(crate::protos::op_store::Tag {
@@ -559,6 +1030,14 @@ fn view_to_proto(view: &View) -> crate::protos::op_store::View {
     }
 
     proto.git_head = ref_target_to_proto(&view.git_head);
+    for (name, target) in &view.git_worktree_heads {
+        proto
+            .git_worktree_heads
+            .push(crate::protos::op_store::GitWorktreeHead {
+                workspace_name: name.into(),
+                target: ref_target_to_proto(target),
+            });
+    }
 
     proto
 }
@@ -586,6 +1065,10 @@ fn view_from_proto(proto: crate::protos::op_store::View) -> View {
     let (local_bookmarks, remote_views) = bookmark_views_from_proto_legacy(proto.bookmarks);
     view.local_bookmarks = local_bookmarks;
     view.remote_views = remote_views;
+    for metadata_proto in proto.bookmark_metadata {
+        let (name, metadata) = bookmark_metadata_from_proto(metadata_proto);
+        view.bookmark_metadata.insert(name, metadata);
+    }
 
     for tag_proto in proto.tags {
         let name: RefNameBuf = tag_proto.name.into();
@@ -610,10 +1093,38 @@ fn view_from_proto(proto: crate::protos::op_store::View) -> View {
     } else if !proto.git_head_legacy.is_empty() {
         view.git_head = RefTarget::normal(CommitId::new(proto.git_head_legacy));
     }
+    for head_proto in proto.git_worktree_heads {
+        let name: WorkspaceNameBuf = head_proto.workspace_name.into();
+        view.git_worktree_heads
+            .insert(name, ref_target_from_proto(head_proto.target));
+    }
 
     view
 }
 
+fn bookmark_metadata_to_proto(
+    name: &RefName,
+    metadata: &BookmarkMetadata,
+) -> crate::protos::op_store::BookmarkMetadata {
+    crate::protos::op_store::BookmarkMetadata {
+        name: name.into(),
+        description: metadata.description.clone(),
+        owner: metadata.owner.clone(),
+        base: metadata.base.as_ref().map(|base| base.into()),
+    }
+}
+
+fn bookmark_metadata_from_proto(
+    proto: crate::protos::op_store::BookmarkMetadata,
+) -> (RefNameBuf, BookmarkMetadata) {
+    let metadata = BookmarkMetadata {
+        description: proto.description,
+        owner: proto.owner,
+        base: proto.base.map(RefNameBuf::from),
+    };
+    (proto.name.into(), metadata)
+}
+
 fn bookmark_views_to_proto_legacy(
     local_bookmarks: &BTreeMap<RefNameBuf, RefTarget>,
     remote_views: &BTreeMap<RemoteNameBuf, RemoteView>,
@@ -819,6 +1330,18 @@ mod tests {
             local_bookmarks: btreemap! {
                 "main".into() => bookmark_main_local_target,
             },
+            bookmark_metadata: btreemap! {
+                "main".into() => BookmarkMetadata {
+                    description: "Main line".to_owned(),
+                    owner: "release team".to_owned(),
+                    base: None,
+                },
+                "feature".into() => BookmarkMetadata {
+                    description: "".to_owned(),
+                    owner: "".to_owned(),
+                    base: Some("main".into()),
+                },
+            },
             tags: btreemap! {
                 "v1.0".into() => tag_v1_target,
             },
@@ -835,6 +1358,9 @@ mod tests {
                 "refs/heads/feature".into() => git_refs_feature_target,
             },
             git_head: RefTarget::normal(CommitId::from_hex("fff111")),
+            git_worktree_heads: btreemap! {
+                "test".into() => RefTarget::normal(CommitId::from_hex("fff222")),
+            },
             wc_commit_ids: btreemap! {
                 WorkspaceName::DEFAULT.to_owned() => default_wc_commit_id,
                 "test".into() => test_wc_commit_id,
@@ -887,6 +1413,18 @@ mod tests {
         // Test exact output so we detect regressions in compatibility
         assert_snapshot!(
             ViewId::new(blake2b_hash(&create_view()).to_vec()).hex(),
+            @"93cae0d667e9c8cec5d6fdc074004e6d636fdae40f4d59fb0cfb7ca4a70f0263c58c57dabae61b0186d95834f58a3e07e84090bbf842528ce323e81d66802ee2"
+        );
+
+        // Views without bookmark metadata and worktree heads hash as before
+        // they were introduced
+        let view = View {
+            bookmark_metadata: BTreeMap::new(),
+            git_worktree_heads: BTreeMap::new(),
+            ..create_view()
+        };
+        assert_snapshot!(
+            ViewId::new(blake2b_hash(&view).to_vec()).hex(),
             @"f426676b3a2f7c6b9ec8677cb05ed249d0d244ab7e86a7c51117e2d8a4829db65e55970c761231e2107d303bf3d33a1f2afdd4ed2181f223e99753674b20a35e"
         );
     }
@@ -0,0 +1,267 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Sparse checkout patterns and profiles.
+//!
+//! A sparse pattern is a fileset expression relative to the workspace root.
+//! Plain paths include the file or the whole directory, like the cone mode of
+//! Git's sparse checkout. Other expressions such as `glob:"services/*/api/**"`
+//! can be used to include files more selectively.
+//!
+//! A sparse profile is a named set of patterns committed in the repo as
+//! `.jj-sparse/<name>.toml`:
+//!
+//! ```toml
+//! include = ["common"]
+//! patterns = ["backend", 'glob:"docs/*.md"']
+//! ```
+//!
+//! The patterns of the included profiles are added to the patterns of the
+//! profile.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use itertools::Itertools as _;
+use pollster::FutureExt as _;
+use serde::Deserialize;
+use thiserror::Error;
+use tokio::io::AsyncReadExt as _;
+
+use crate::backend::BackendError;
+use crate::backend::TreeValue;
+use crate::fileset;
+use crate::fileset::FilePattern;
+use crate::fileset::FilesetDiagnostics;
+use crate::fileset::FilesetExpression;
+use crate::fileset::FilesetParseError;
+use crate::merged_tree::MergedTree;
+use crate::repo_path::RepoPathBuf;
+use crate::repo_path::RepoPathUiConverter;
+
+/// Directory in the repo where the sparse profiles are stored.
+pub const SPARSE_PROFILE_DIR: &str = ".jj-sparse";
+
+/// Pattern that decides which paths are checked out in a sparse working copy.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum SparsePattern {
+    /// The file, or all files under the directory.
+    Prefix(RepoPathBuf),
+    /// Fileset expression relative to the workspace root.
+    Fileset(String),
+}
+
+impl SparsePattern {
+    /// Parses the `text` as a fileset expression relative to the workspace
+    /// root. Plain paths are recorded as prefixes.
+    pub fn parse(text: &str, workspace_root: &Path) -> Result<Self, FilesetParseError> {
+        match parse_fileset(text, workspace_root)? {
+            FilesetExpression::Pattern(FilePattern::PrefixPath(path)) => Ok(Self::Prefix(path)),
+            _ => Ok(Self::Fileset(text.trim().to_owned())),
+        }
+    }
+
+    /// Returns the fileset expression matching the paths of this pattern.
+    pub fn to_expression(
+        &self,
+        workspace_root: &Path,
+    ) -> Result<FilesetExpression, FilesetParseError> {
+        match self {
+            Self::Prefix(path) => Ok(FilesetExpression::prefix_path(path.clone())),
+            Self::Fileset(text) => parse_fileset(text, workspace_root),
+        }
+    }
+}
+
+impl From<RepoPathBuf> for SparsePattern {
+    fn from(path: RepoPathBuf) -> Self {
+        Self::Prefix(path)
+    }
+}
+
+/// Returns the fileset expression matching the union of the `patterns`.
+pub fn sparse_patterns_to_expression(
+    patterns: &[SparsePattern],
+    workspace_root: &Path,
+) -> Result<FilesetExpression, FilesetParseError> {
+    let expressions = patterns
+        .iter()
+        .map(|pattern| pattern.to_expression(workspace_root))
+        .try_collect()?;
+    Ok(FilesetExpression::union_all(expressions))
+}
+
+fn parse_fileset(
+    text: &str,
+    workspace_root: &Path,
+) -> Result<FilesetExpression, FilesetParseError> {
+    let path_converter = RepoPathUiConverter::Fs {
+        cwd: workspace_root.to_owned(),
+        base: workspace_root.to_owned(),
+    };
+    // Warnings aren't reported since the patterns may be evaluated while
+    // checking out.
+    let mut diagnostics = FilesetDiagnostics::new();
+    fileset::parse_maybe_bare(&mut diagnostics, text, &path_converter)
+}
+
+/// Error that may occur while resolving a sparse profile.
+#[derive(Debug, Error)]
+pub enum SparseProfileError {
+    /// The profile name can't be mapped to a file in the profile directory.
+    #[error(r#"Invalid sparse profile name "{0}""#)]
+    InvalidName(String),
+    /// The profile doesn't exist in the tree.
+    #[error(r#"Sparse profile "{0}" doesn't exist"#)]
+    NotFound(String),
+    /// The profile file is conflicted or isn't a regular file.
+    #[error(r#"Sparse profile "{0}" is not a regular file"#)]
+    NotFile(String),
+    /// The profile includes itself directly or through other profiles.
+    #[error(r#"Sparse profile "{0}" includes itself"#)]
+    Cycle(String),
+    /// The profile file couldn't be parsed.
+    #[error(r#"Failed to parse sparse profile "{name}""#)]
+    Parse {
+        /// Profile name.
+        name: String,
+        /// The underlying error.
+        source: Box<toml_edit::de::Error>,
+    },
+    /// A pattern in the profile isn't a valid fileset expression.
+    #[error(r#"Invalid pattern "{pattern}" in sparse profile "{name}""#)]
+    Pattern {
+        /// Profile name.
+        name: String,
+        /// The invalid pattern.
+        pattern: String,
+        /// The underlying error.
+        source: Box<FilesetParseError>,
+    },
+    /// Reading the profile from the backend failed.
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SparseProfileFile {
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default)]
+    patterns: Vec<String>,
+}
+
+/// Returns the path of the sparse profile named `name`.
+pub fn sparse_profile_path(name: &str) -> Result<RepoPathBuf, SparseProfileError> {
+    let is_valid = !name.is_empty() && !name.starts_with('.') && !name.contains(['/', '\\']);
+    if !is_valid {
+        return Err(SparseProfileError::InvalidName(name.to_owned()));
+    }
+    RepoPathBuf::from_internal_string(format!("{SPARSE_PROFILE_DIR}/{name}.toml"))
+        .map_err(|_| SparseProfileError::InvalidName(name.to_owned()))
+}
+
+/// Resolves the patterns of the sparse profile named `name` in the `tree`,
+/// including the patterns of the profiles it includes.
+///
+/// The profile directory is always included so the profiles can be edited.
+/// The returned patterns are sorted and deduplicated.
+pub fn resolve_sparse_profile(
+    tree: &MergedTree,
+    name: &str,
+    workspace_root: &Path,
+) -> Result<Vec<SparsePattern>, SparseProfileError> {
+    let mut patterns = vec![SparsePattern::Prefix(
+        RepoPathBuf::from_internal_string(SPARSE_PROFILE_DIR).unwrap(),
+    )];
+    let mut visited = HashSet::new();
+    let mut stack = vec![];
+    collect_profile_patterns(
+        tree,
+        name,
+        workspace_root,
+        &mut visited,
+        &mut stack,
+        &mut patterns,
+    )?;
+    patterns.sort_unstable();
+    patterns.dedup();
+    Ok(patterns)
+}
+
+fn collect_profile_patterns(
+    tree: &MergedTree,
+    name: &str,
+    workspace_root: &Path,
+    visited: &mut HashSet<String>,
+    stack: &mut Vec<String>,
+    patterns: &mut Vec<SparsePattern>,
+) -> Result<(), SparseProfileError> {
+    if stack.iter().any(|visiting| visiting == name) {
+        return Err(SparseProfileError::Cycle(name.to_owned()));
+    }
+    if !visited.insert(name.to_owned()) {
+        return Ok(());
+    }
+    let profile = read_sparse_profile(tree, name)?;
+    for text in &profile.patterns {
+        let pattern = SparsePattern::parse(text, workspace_root).map_err(|source| {
+            SparseProfileError::Pattern {
+                name: name.to_owned(),
+                pattern: text.clone(),
+                source: Box::new(source),
+            }
+        })?;
+        patterns.push(pattern);
+    }
+    stack.push(name.to_owned());
+    for included in &profile.include {
+        collect_profile_patterns(tree, included, workspace_root, visited, stack, patterns)?;
+    }
+    stack.pop();
+    Ok(())
+}
+
+fn read_sparse_profile(
+    tree: &MergedTree,
+    name: &str,
+) -> Result<SparseProfileFile, SparseProfileError> {
+    let path = sparse_profile_path(name)?;
+    let value = tree.path_value(&path)?;
+    if value.is_absent() {
+        return Err(SparseProfileError::NotFound(name.to_owned()));
+    }
+    let Some(TreeValue::File { id, .. }) = value.as_normal() else {
+        return Err(SparseProfileError::NotFile(name.to_owned()));
+    };
+    let mut content = vec![];
+    async {
+        let mut reader = tree.store().read_file(&path, id).await?;
+        reader
+            .read_to_end(&mut content)
+            .await
+            .map_err(|err| BackendError::ReadFile {
+                path: path.clone(),
+                id: id.clone(),
+                source: err.into(),
+            })
+    }
+    .block_on()?;
+    let text = String::from_utf8_lossy(&content);
+    toml_edit::de::from_str(&text).map_err(|source| SparseProfileError::Parse {
+        name: name.to_owned(),
+        source: Box::new(source),
+    })
+}
@@ -17,13 +17,18 @@
 use std::any::Any;
 use std::fmt::Debug;
 use std::fmt::Formatter;
+use std::iter;
+use std::mem;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::time::SystemTime;
 
 use clru::CLruCache;
+use futures::stream;
 use futures::stream::BoxStream;
+use futures::StreamExt as _;
+use itertools::Itertools as _;
 use pollster::FutureExt as _;
 use tokio::io::AsyncRead;
 
@@ -35,14 +40,23 @@ use crate::backend::CommitId;
 use crate::backend::ConflictId;
 use crate::backend::CopyRecord;
 use crate::backend::FileId;
+use crate::backend::GcStats;
 use crate::backend::MergedTreeId;
 use crate::backend::SigningFn;
 use crate::backend::SymlinkId;
 use crate::backend::TreeId;
 use crate::commit::Commit;
+use crate::copies::detect_copies;
+use crate::copies::CopyDetectionOptions;
+use crate::files::MergeOptions;
 use crate::index::Index;
+use crate::matchers::DifferenceMatcher;
+use crate::matchers::EverythingMatcher;
+use crate::matchers::FilesMatcher;
+use crate::matchers::Matcher;
 use crate::merge::Merge;
 use crate::merge::MergedTreeValue;
+use crate::merge_driver::MergeDrivers;
 use crate::merged_tree::MergedTree;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
@@ -60,6 +74,9 @@ const TREE_CACHE_CAPACITY: usize = 1000;
 pub struct Store {
     backend: Box<dyn Backend>,
     signer: Signer,
+    merge_options: MergeOptions,
+    merge_drivers: MergeDrivers,
+    copy_detection_options: CopyDetectionOptions,
     commit_cache: Mutex<CLruCache<CommitId, Arc<backend::Commit>>>,
     tree_cache: Mutex<CLruCache<(RepoPathBuf, TreeId), Arc<backend::Tree>>>,
 }
@@ -73,10 +90,19 @@ impl Debug for Store {
 }
 
 impl Store {
-    pub fn new(backend: Box<dyn Backend>, signer: Signer) -> Arc<Self> {
+    pub fn new(
+        backend: Box<dyn Backend>,
+        signer: Signer,
+        merge_options: MergeOptions,
+        merge_drivers: MergeDrivers,
+        copy_detection_options: CopyDetectionOptions,
+    ) -> Arc<Self> {
         Arc::new(Store {
             backend,
             signer,
+            merge_options,
+            merge_drivers,
+            copy_detection_options,
             commit_cache: Mutex::new(CLruCache::new(COMMIT_CACHE_CAPACITY.try_into().unwrap())),
             tree_cache: Mutex::new(CLruCache::new(TREE_CACHE_CAPACITY.try_into().unwrap())),
         })
@@ -94,13 +120,68 @@ impl Store {
         &self.signer
     }
 
+    pub fn merge_options(&self) -> &MergeOptions {
+        &self.merge_options
+    }
+
+    pub fn merge_drivers(&self) -> &MergeDrivers {
+        &self.merge_drivers
+    }
+
+    pub fn copy_detection_options(&self) -> &CopyDetectionOptions {
+        &self.copy_detection_options
+    }
+
+    /// Returns the copy records provided by the backend. If
+    /// `diff.renames.compare-contents` is enabled, they're followed by the
+    /// renames and copies detected by comparing the contents of the other
+    /// added files, once the backend records have been consumed.
     pub fn get_copy_records(
-        &self,
+        self: &Arc<Self>,
         paths: Option<&[RepoPathBuf]>,
         root: &CommitId,
         head: &CommitId,
     ) -> BackendResult<BoxStream<BackendResult<CopyRecord>>> {
-        self.backend.get_copy_records(paths, root, head)
+        let records = self.backend.get_copy_records(paths, root, head)?;
+        if !self.copy_detection_options.compare_contents {
+            return Ok(records);
+        }
+        let known_targets = Arc::new(Mutex::new(vec![]));
+        let records = records.inspect({
+            let known_targets = known_targets.clone();
+            move |record| {
+                if let Ok(record) = record {
+                    known_targets.lock().unwrap().push(record.target.clone());
+                }
+            }
+        });
+        let store = self.clone();
+        let paths = paths.map(|paths| paths.to_vec());
+        let root = root.clone();
+        let head = head.clone();
+        let detect = move || -> BackendResult<Vec<CopyRecord>> {
+            let path_matcher: Box<dyn Matcher> = match paths {
+                Some(paths) => Box::new(FilesMatcher::new(paths)),
+                None => Box::new(EverythingMatcher),
+            };
+            let known_targets = mem::take(&mut *known_targets.lock().unwrap());
+            let matcher = DifferenceMatcher::new(path_matcher, FilesMatcher::new(known_targets));
+            detect_copies(
+                &store.get_commit(&root)?.tree()?,
+                &store.get_commit(&head)?.tree()?,
+                &root,
+                &head,
+                &matcher,
+                &store.copy_detection_options,
+            )
+            .block_on()
+        };
+        // Detection is deferred until the backend records have been consumed.
+        let detected = iter::once_with(detect).flat_map(|result| match result {
+            Ok(records) => records.into_iter().map(Ok).collect_vec(),
+            Err(err) => vec![Err(err)],
+        });
+        Ok(Box::pin(records.chain(stream::iter(detected))))
     }
 
     pub fn commit_id_length(&self) -> usize {
@@ -281,7 +362,12 @@ impl Store {
         TreeBuilder::new(self.clone(), base_tree_id)
     }
 
-    pub fn gc(&self, index: &dyn Index, keep_newer: SystemTime) -> BackendResult<()> {
-        self.backend.gc(index, keep_newer)
+    pub fn gc(
+        &self,
+        index: &dyn Index,
+        keep_newer: SystemTime,
+        dry_run: bool,
+    ) -> BackendResult<GcStats> {
+        self.backend.gc(index, keep_newer, dry_run)
     }
 }
@@ -15,7 +15,13 @@
 #![allow(missing_docs)]
 
 use std::fmt::Debug;
+use std::path::PathBuf;
 
 pub trait SubmoduleStore: Send + Sync + Debug {
     fn name(&self) -> &str;
+
+    /// Returns the path to the Git directory of the nested repo for the
+    /// submodule named `submodule_name` in `.gitmodules`. The directory may
+    /// not exist yet.
+    fn git_dir(&self, submodule_name: &str) -> PathBuf;
 }
@@ -21,6 +21,7 @@ use std::hash::Hash;
 use std::hash::Hasher;
 use std::sync::Arc;
 
+use bstr::BString;
 use itertools::Itertools as _;
 use tokio::io::AsyncReadExt as _;
 use tracing::instrument;
@@ -35,6 +36,7 @@ use crate::backend::TreeValue;
 use crate::files;
 use crate::matchers::EverythingMatcher;
 use crate::matchers::Matcher;
+use crate::merge::Merge;
 use crate::merge::MergedTreeVal;
 use crate::object_id::ObjectId as _;
 use crate::repo_path::RepoPath;
@@ -262,6 +264,22 @@ pub async fn try_resolve_file_conflict(
     store: &Store,
     filename: &RepoPath,
     conflict: &MergedTreeVal<'_>,
+) -> BackendResult<Option<TreeValue>> {
+    try_resolve_file_conflict_with(store, filename, conflict, |contents| {
+        files::try_merge(contents, store.merge_options())
+    })
+    .await
+}
+
+/// Resolves file-level conflict by merging the file contents with `merge_fn`.
+///
+/// The input `conflict` has the same requirements as for
+/// [`try_resolve_file_conflict()`].
+pub(crate) async fn try_resolve_file_conflict_with(
+    store: &Store,
+    filename: &RepoPath,
+    conflict: &MergedTreeVal<'_>,
+    merge_fn: impl FnOnce(&Merge<Vec<u8>>) -> Option<BString>,
 ) -> BackendResult<Option<TreeValue>> {
     // If there are any non-file or any missing parts in the conflict, we can't
     // merge it. We check early so we don't waste time reading file contents if
@@ -315,7 +333,7 @@ pub async fn try_resolve_file_conflict(
             BackendResult::Ok(content)
         })
         .await?;
-    if let Some(merged_content) = files::try_merge(&contents) {
+    if let Some(merged_content) = merge_fn(&contents) {
         let id = store
             .write_file(filename, &mut merged_content.as_slice())
             .await?;
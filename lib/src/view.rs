@@ -22,6 +22,7 @@ use thiserror::Error;
 
 use crate::backend::CommitId;
 use crate::op_store;
+use crate::op_store::BookmarkMetadata;
 use crate::op_store::BookmarkTarget;
 use crate::op_store::RefTarget;
 use crate::op_store::RefTargetOptionExt as _;
@@ -94,6 +95,17 @@ impl View {
         &self.data.git_head
     }
 
+    /// Returns the HEADs of the linked Git worktrees by workspace name.
+    pub fn git_worktree_heads(&self) -> &BTreeMap<WorkspaceNameBuf, RefTarget> {
+        &self.data.git_worktree_heads
+    }
+
+    /// Returns the HEAD of the linked Git worktree shared with the workspace.
+    /// If the workspace has no such worktree, absent target will be returned.
+    pub fn get_git_worktree_head(&self, name: &WorkspaceName) -> &RefTarget {
+        self.data.git_worktree_heads.get(name).flatten()
+    }
+
     pub fn set_wc_commit(&mut self, name: WorkspaceNameBuf, commit_id: CommitId) {
         self.data.wc_commit_ids.insert(name, commit_id);
     }
@@ -117,6 +129,11 @@ impl View {
                 name: old_name.to_owned(),
             }
         })?;
+        if let Some(target) = self.data.git_worktree_heads.remove(old_name) {
+            self.data
+                .git_worktree_heads
+                .insert(new_name.clone(), target);
+        }
         self.data.wc_commit_ids.insert(new_name, wc_commit_id);
         Ok(())
     }
@@ -173,6 +190,31 @@ impl View {
         }
     }
 
+    /// Iterates local bookmark `(name, metadata)`s in lexicographical order.
+    pub fn bookmark_metadata(&self) -> impl Iterator<Item = (&RefName, &BookmarkMetadata)> {
+        self.data
+            .bookmark_metadata
+            .iter()
+            .map(|(name, metadata)| (name.as_ref(), metadata))
+    }
+
+    /// Returns metadata of the local bookmark, or `None` if no metadata is set.
+    pub fn get_bookmark_metadata(&self, name: &RefName) -> Option<&BookmarkMetadata> {
+        self.data.bookmark_metadata.get(name)
+    }
+
+    /// Sets metadata of the local bookmark. If the metadata is empty, it will
+    /// be removed.
+    pub fn set_bookmark_metadata(&mut self, name: &RefName, metadata: BookmarkMetadata) {
+        if metadata.is_empty() {
+            self.data.bookmark_metadata.remove(name);
+        } else {
+            self.data
+                .bookmark_metadata
+                .insert(name.to_owned(), metadata);
+        }
+    }
+
     /// Iterates over `(symbol, remote_ref)` for all remote bookmarks in
     /// lexicographical order.
     pub fn all_remote_bookmarks(&self) -> impl Iterator<Item = (RemoteRefSymbol<'_>, &RemoteRef)> {
@@ -356,6 +398,16 @@ impl View {
         self.data.git_head = target;
     }
 
+    /// Sets the HEAD of the linked Git worktree shared with the workspace. If
+    /// the target is absent, the entry will be removed.
+    pub fn set_git_worktree_head_target(&mut self, name: &WorkspaceName, target: RefTarget) {
+        if target.is_present() {
+            self.data.git_worktree_heads.insert(name.to_owned(), target);
+        } else {
+            self.data.git_worktree_heads.remove(name);
+        }
+    }
+
     /// Iterates all commit ids referenced by this view.
     ///
     /// This can include hidden commits referenced by remote bookmarks, previous
@@ -376,10 +428,12 @@ impl View {
         let op_store::View {
             head_ids,
             local_bookmarks,
+            bookmark_metadata: _,
             tags,
             remote_views,
             git_refs,
             git_head,
+            git_worktree_heads,
             wc_commit_ids,
         } = &self.data;
         itertools::chain!(
@@ -394,6 +448,7 @@ impl View {
             }),
             git_refs.values().flat_map(ref_target_ids),
             ref_target_ids(git_head),
+            git_worktree_heads.values().flat_map(ref_target_ids),
             wc_commit_ids.values()
         )
     }
@@ -46,6 +46,8 @@ use crate::repo::RewriteRootCommit;
 use crate::repo_path::InvalidRepoPathError;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
+use crate::sparse::SparsePattern;
+use crate::sparse::SparseProfileError;
 use crate::store::Store;
 use crate::transaction::TransactionCommitError;
 
@@ -69,9 +71,12 @@ pub trait WorkingCopy: Send {
 
     /// Patterns that decide which paths from the current tree should be checked
     /// out in the working copy. An empty list means that no paths should be
-    /// checked out in the working copy. A single `RepoPath::root()` entry means
-    /// that all files should be checked out.
-    fn sparse_patterns(&self) -> Result<&[RepoPathBuf], WorkingCopyStateError>;
+    /// checked out in the working copy. A single `RepoPath::root()` prefix
+    /// means that all files should be checked out.
+    fn sparse_patterns(&self) -> Result<&[SparsePattern], WorkingCopyStateError>;
+
+    /// The name of the sparse profile the sparse patterns follow, if any.
+    fn sparse_profile(&self) -> Result<Option<&str>, WorkingCopyStateError>;
 
     /// Locks the working copy and returns an instance with methods for updating
     /// the working copy files and state.
@@ -137,7 +142,7 @@ pub trait LockedWorkingCopy {
     fn recover(&mut self, commit: &Commit) -> Result<(), ResetError>;
 
     /// See `WorkingCopy::sparse_patterns()`
-    fn sparse_patterns(&self) -> Result<&[RepoPathBuf], WorkingCopyStateError>;
+    fn sparse_patterns(&self) -> Result<&[SparsePattern], WorkingCopyStateError>;
 
     /// Updates the patterns that decide which paths from the current tree
     /// should be checked out in the working copy.
@@ -147,7 +152,16 @@ pub trait LockedWorkingCopy {
     // to use sparse).
     fn set_sparse_patterns(
         &mut self,
-        new_sparse_patterns: Vec<RepoPathBuf>,
+        new_sparse_patterns: Vec<SparsePattern>,
+        options: &CheckoutOptions,
+    ) -> Result<CheckoutStats, CheckoutError>;
+
+    /// Updates the sparse patterns to the patterns of the sparse profile
+    /// `name` in the current tree. The patterns are updated again when a tree
+    /// with a different version of the profile is checked out.
+    fn set_sparse_profile(
+        &mut self,
+        name: String,
         options: &CheckoutOptions,
     ) -> Result<CheckoutStats, CheckoutError>;
 
@@ -293,6 +307,9 @@ pub struct CheckoutStats {
     /// working copy but were skipped because there was an untracked (probably
     /// ignored) file in its place.
     pub skipped_files: u32,
+    /// Problems with the sparse patterns that didn't prevent the checkout,
+    /// e.g. a sparse profile that doesn't exist in the new tree.
+    pub sparse_warnings: Vec<String>,
 }
 
 /// The working-copy checkout failed.
@@ -323,6 +340,9 @@ pub enum CheckoutError {
     /// Reading or writing from the commit backend failed.
     #[error("Internal backend error")]
     InternalBackendError(#[from] BackendError),
+    /// The sparse profile couldn't be resolved.
+    #[error(transparent)]
+    SparseProfile(#[from] SparseProfileError),
     /// Some other error happened while checking out the working copy.
     #[error("{message}")]
     Other {
@@ -27,11 +27,14 @@ use thiserror::Error;
 
 use crate::backend::BackendInitError;
 use crate::backend::MergedTreeId;
+use crate::chunked_backend::ChunkedBackend;
 use crate::commit::Commit;
+use crate::config::ConfigGetError;
 use crate::file_util::IoResultExt as _;
 use crate::file_util::PathError;
 use crate::local_working_copy::LocalWorkingCopy;
 use crate::local_working_copy::LocalWorkingCopyFactory;
+use crate::merge_driver::MergeDriverConfigError;
 use crate::op_heads_store::OpHeadsStoreError;
 use crate::op_store::OperationId;
 use crate::ref_name::WorkspaceName;
@@ -61,6 +64,8 @@ use crate::working_copy::LockedWorkingCopy;
 use crate::working_copy::WorkingCopy;
 use crate::working_copy::WorkingCopyFactory;
 use crate::working_copy::WorkingCopyStateError;
+use crate::workspace_store::SimpleWorkspaceStore;
+use crate::workspace_store::WorkspaceEntry;
 
 #[derive(Error, Debug)]
 pub enum WorkspaceInitError {
@@ -81,6 +86,10 @@ pub enum WorkspaceInitError {
     #[error(transparent)]
     SignInit(#[from] SignInitError),
     #[error(transparent)]
+    Config(#[from] ConfigGetError),
+    #[error(transparent)]
+    MergeDrivers(#[from] MergeDriverConfigError),
+    #[error(transparent)]
     TransactionCommit(#[from] TransactionCommitError),
 }
 
@@ -153,6 +162,23 @@ fn init_working_copy(
     Ok((working_copy, repo))
 }
 
+/// Records the location of the new workspace in the workspace store.
+///
+/// The store is only needed to find workspaces on disk, so the workspace is
+/// still usable if it can't be recorded (e.g. because its path isn't valid
+/// UTF-8).
+fn register_workspace(workspace: &Workspace) {
+    let store = SimpleWorkspaceStore::load(workspace.repo_path());
+    let entry = WorkspaceEntry {
+        name: workspace.workspace_name().to_owned(),
+        path: workspace.workspace_root().to_owned(),
+        temporary: false,
+    };
+    if let Err(err) = store.add(entry) {
+        tracing::warn!(?err, "failed to record workspace in the workspace store");
+    }
+}
+
 impl Workspace {
     pub fn new(
         workspace_root: &Path,
@@ -193,6 +219,18 @@ impl Workspace {
         Self::init_with_backend(user_settings, workspace_root, backend_initializer, signer)
     }
 
+    /// Initializes a workspace with a new chunked backend, which splits file
+    /// contents into content-defined chunks.
+    pub fn init_chunked(
+        user_settings: &UserSettings,
+        workspace_root: &Path,
+    ) -> Result<(Self, Arc<ReadonlyRepo>), WorkspaceInitError> {
+        let backend_initializer: &BackendInitializer =
+            &|_settings, store_path| Ok(Box::new(ChunkedBackend::init(store_path)));
+        let signer = Signer::from_settings(user_settings)?;
+        Self::init_with_backend(user_settings, workspace_root, backend_initializer, signer)
+    }
+
     /// Initializes a workspace with a new Git backend and bare Git repo in
     /// `.jj/repo/store/git`.
     #[cfg(feature = "git")]
@@ -310,6 +348,8 @@ impl Workspace {
                 RepoInitError::Backend(err) => WorkspaceInitError::Backend(err),
                 RepoInitError::OpHeadsStore(err) => WorkspaceInitError::OpHeadsStore(err),
                 RepoInitError::Path(err) => WorkspaceInitError::Path(err),
+                RepoInitError::Config(err) => WorkspaceInitError::Config(err),
+                RepoInitError::MergeDrivers(err) => WorkspaceInitError::MergeDrivers(err),
             })?;
             let (working_copy, repo) = init_working_copy(
                 &repo,
@@ -320,6 +360,7 @@ impl Workspace {
             )?;
             let repo_loader = repo.loader().clone();
             let workspace = Workspace::new(workspace_root, repo_dir, working_copy, repo_loader)?;
+            register_workspace(&workspace);
             Ok((workspace, repo))
         })()
         .inspect_err(|_err| {
@@ -381,6 +422,7 @@ impl Workspace {
             working_copy,
             repo.loader().clone(),
         )?;
+        register_workspace(&workspace);
         Ok((workspace, repo))
     }
 
@@ -0,0 +1,227 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Records where the workspaces of a repo are located on disk.
+//!
+//! The working-copy commits of the workspaces are tracked in the view, but the
+//! view is shared by all clones of the operation log, so the local paths of
+//! the workspaces are stored separately in the repo directory.
+
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use prost::Message as _;
+use tempfile::NamedTempFile;
+use thiserror::Error;
+
+use crate::file_util::IoResultExt as _;
+use crate::file_util::PathError;
+use crate::lock::FileLock;
+use crate::lock::FileLockError;
+use crate::ref_name::WorkspaceName;
+use crate::ref_name::WorkspaceNameBuf;
+
+/// Error that may occur while reading or updating the workspace store.
+#[derive(Debug, Error)]
+pub enum WorkspaceStoreError {
+    /// Failed to read or write the store file.
+    #[error(transparent)]
+    Io(#[from] PathError),
+    /// Failed to lock the store.
+    #[error(transparent)]
+    Lock(#[from] FileLockError),
+    /// The store file is corrupt.
+    #[error("Failed to decode workspace store {path}")]
+    Decode {
+        /// Path to the store file.
+        path: PathBuf,
+        /// Decode error.
+        source: prost::DecodeError,
+    },
+    /// The workspace path isn't valid UTF-8.
+    #[error("Workspace path {} is not valid UTF-8", .0.display())]
+    NonUnicodePath(PathBuf),
+}
+
+/// Location of a workspace on disk.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WorkspaceEntry {
+    /// Name of the workspace.
+    pub name: WorkspaceNameBuf,
+    /// Absolute path to the workspace root.
+    pub path: PathBuf,
+    /// Whether the workspace should be forgotten and deleted once its
+    /// working-copy commit is abandoned.
+    pub temporary: bool,
+}
+
+/// Stores the workspace locations in a file in the repo directory.
+#[derive(Debug)]
+pub struct SimpleWorkspaceStore {
+    dir: PathBuf,
+}
+
+impl SimpleWorkspaceStore {
+    /// Returns the workspace store of the repo at `repo_path`. The store
+    /// directory is created when the store is first updated.
+    pub fn load(repo_path: &Path) -> Self {
+        Self {
+            dir: repo_path.join("workspace_store"),
+        }
+    }
+
+    /// Returns all recorded workspaces, sorted by name.
+    pub fn list(&self) -> Result<Vec<WorkspaceEntry>, WorkspaceStoreError> {
+        self.read()
+    }
+
+    /// Looks up the workspace named `name`.
+    pub fn get(&self, name: &WorkspaceName) -> Result<Option<WorkspaceEntry>, WorkspaceStoreError> {
+        Ok(self.read()?.into_iter().find(|entry| entry.name == name))
+    }
+
+    /// Records the workspace, replacing any existing entry with the same name.
+    pub fn add(&self, entry: WorkspaceEntry) -> Result<(), WorkspaceStoreError> {
+        self.update(|entries| {
+            entries.retain(|existing| existing.name != entry.name);
+            entries.push(entry);
+        })
+    }
+
+    /// Removes the workspaces named `names`. Unknown names are ignored.
+    pub fn forget(&self, names: &[&WorkspaceName]) -> Result<(), WorkspaceStoreError> {
+        self.update(|entries| entries.retain(|entry| !names.contains(&&*entry.name)))
+    }
+
+    /// Renames the workspace `old_name` to `new_name` if it's recorded.
+    pub fn rename(
+        &self,
+        old_name: &WorkspaceName,
+        new_name: &WorkspaceName,
+    ) -> Result<(), WorkspaceStoreError> {
+        self.update(|entries| {
+            entries.retain(|entry| entry.name != new_name);
+            for entry in entries.iter_mut().filter(|entry| entry.name == old_name) {
+                entry.name = new_name.to_owned();
+            }
+        })
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.dir.join("index")
+    }
+
+    fn read(&self) -> Result<Vec<WorkspaceEntry>, WorkspaceStoreError> {
+        let path = self.index_path();
+        let buf = match fs::read(&path) {
+            Ok(buf) => buf,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(PathError { path, error: err }.into()),
+        };
+        let proto = crate::protos::workspace_store::Workspaces::decode(&*buf)
+            .map_err(|source| WorkspaceStoreError::Decode { path, source })?;
+        let mut entries = proto
+            .workspaces
+            .into_iter()
+            .map(|workspace| WorkspaceEntry {
+                name: workspace.name.into(),
+                path: workspace.path.into(),
+                temporary: workspace.temporary,
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(entries)
+    }
+
+    fn update(&self, f: impl FnOnce(&mut Vec<WorkspaceEntry>)) -> Result<(), WorkspaceStoreError> {
+        fs::create_dir_all(&self.dir).context(&self.dir)?;
+        let _lock = FileLock::lock(self.dir.join("lock"))?;
+        let mut entries = self.read()?;
+        f(&mut entries);
+        let workspaces = entries
+            .into_iter()
+            .map(|entry| {
+                let path = entry
+                    .path
+                    .into_os_string()
+                    .into_string()
+                    .map_err(|path| WorkspaceStoreError::NonUnicodePath(path.into()))?;
+                Ok(crate::protos::workspace_store::Workspace {
+                    name: entry.name.into(),
+                    path,
+                    temporary: entry.temporary,
+                })
+            })
+            .collect::<Result<_, WorkspaceStoreError>>()?;
+        let proto = crate::protos::workspace_store::Workspaces { workspaces };
+        let mut temp_file = NamedTempFile::new_in(&self.dir).context(&self.dir)?;
+        temp_file
+            .as_file_mut()
+            .write_all(&proto.encode_to_vec())
+            .context(temp_file.path())?;
+        let path = self.index_path();
+        temp_file.persist(&path).map_err(|err| PathError {
+            path,
+            error: err.error,
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::new_temp_dir;
+
+    fn entry(name: &str, path: &str, temporary: bool) -> WorkspaceEntry {
+        WorkspaceEntry {
+            name: name.into(),
+            path: path.into(),
+            temporary,
+        }
+    }
+
+    #[test]
+    fn test_add_rename_forget() {
+        let temp_dir = new_temp_dir();
+        let store = SimpleWorkspaceStore::load(temp_dir.path());
+        assert_eq!(store.list().unwrap(), vec![]);
+
+        store.add(entry("b", "/b", false)).unwrap();
+        store.add(entry("a", "/a", true)).unwrap();
+        assert_eq!(
+            store.list().unwrap(),
+            vec![entry("a", "/a", true), entry("b", "/b", false)]
+        );
+
+        // Adding an existing workspace replaces it
+        store.add(entry("a", "/a2", false)).unwrap();
+        assert_eq!(
+            store.get("a".as_ref()).unwrap(),
+            Some(entry("a", "/a2", false))
+        );
+
+        store.rename("a".as_ref(), "c".as_ref()).unwrap();
+        assert_eq!(
+            store.list().unwrap(),
+            vec![entry("b", "/b", false), entry("c", "/a2", false)]
+        );
+
+        store.forget(&["b".as_ref(), "unknown".as_ref()]).unwrap();
+        assert_eq!(store.list().unwrap(), vec![entry("c", "/a2", false)]);
+    }
+}
@@ -8,15 +8,20 @@ fn test_no_forgotten_test_files() {
 
 mod test_annotate;
 mod test_bad_locking;
+mod test_chunked_backend;
 mod test_commit_builder;
 mod test_commit_concurrent;
 mod test_conflicts;
+mod test_copies;
 mod test_default_revset_graph_iterator;
 mod test_evolution_predecessors;
 mod test_fix;
+mod test_fsck;
 mod test_git;
 mod test_git_backend;
+mod test_git_submodule;
 mod test_gpg;
+mod test_hunk_selection;
 mod test_id_prefix;
 mod test_index;
 mod test_init;
@@ -24,6 +29,7 @@ mod test_load_repo;
 mod test_local_working_copy;
 mod test_local_working_copy_concurrent;
 mod test_local_working_copy_sparse;
+mod test_merge_driver;
 mod test_merge_trees;
 mod test_merged_tree;
 mod test_mut_repo;
@@ -34,6 +40,7 @@ mod test_rewrite;
 mod test_rewrite_duplicate;
 mod test_rewrite_transform;
 mod test_signing;
+mod test_simple_backend;
 mod test_ssh_signing;
 mod test_view;
 mod test_workspace;
@@ -25,11 +25,15 @@ use jj_lib::backend::Signature;
 use jj_lib::backend::Timestamp;
 use jj_lib::backend::TreeValue;
 use jj_lib::commit::Commit;
+use jj_lib::config::ConfigLayer;
+use jj_lib::config::ConfigSource;
 use jj_lib::repo::MutableRepo;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::revset::ResolvedRevsetExpression;
 use jj_lib::revset::RevsetExpression;
+use jj_lib::settings::UserSettings;
+use testutils::base_user_config;
 use testutils::create_tree;
 use testutils::read_file;
 use testutils::repo_path;
@@ -409,3 +413,39 @@ fn test_annotate_file_directory_transition() {
 
     insta::assert_snapshot!(annotate(tx.repo(), &commit2, file_path2), @"commit2 : 2");
 }
+
+#[test]
+fn test_annotate_renamed_file() {
+    let mut config = base_user_config();
+    config.add_layer(
+        ConfigLayer::parse(ConfigSource::User, "diff.renames.compare-contents = true").unwrap(),
+    );
+    let settings = UserSettings::from_config(config).unwrap();
+    let test_repo = TestRepo::init_with_settings(&settings);
+    let repo = &test_repo.repo;
+
+    let root_commit_id = repo.store().root_commit_id();
+    let old_path = repo_path("old");
+    let new_path = repo_path("new");
+
+    let mut tx = repo.start_transaction();
+    let mut create_commit = create_commit_fn(tx.repo_mut());
+    let tree1 = create_tree(repo, &[(old_path, "1\n2\n3\n")]);
+    let tree2 = create_tree(repo, &[(old_path, "1\n2\n3\n4\n")]);
+    let tree3 = create_tree(repo, &[(new_path, "1\n2\nthree\n4\n")]);
+    let tree4 = create_tree(repo, &[(new_path, "1\n2\nthree\n4\n5\n")]);
+    let commit1 = create_commit("commit1", &[root_commit_id], tree1.id());
+    let commit2 = create_commit("commit2", &[commit1.id()], tree2.id());
+    let commit3 = create_commit("commit3", &[commit2.id()], tree3.id());
+    let commit4 = create_commit("commit4", &[commit3.id()], tree4.id());
+    drop(create_commit);
+
+    // The history of the old file is followed
+    insta::assert_snapshot!(annotate(tx.repo(), &commit4, new_path), @r"
+    commit1 : 1
+    commit1 : 2
+    commit3 : three
+    commit2 : 4
+    commit4 : 5
+    ");
+}
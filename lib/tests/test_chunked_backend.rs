@@ -0,0 +1,105 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use jj_lib::backend::CommitId;
+use jj_lib::backend::TreeValue;
+use jj_lib::commit::Commit;
+use jj_lib::repo::Repo as _;
+use jj_lib::transaction::Transaction;
+use testutils::create_tree;
+use testutils::read_file;
+use testutils::repo_path;
+use testutils::TestRepo;
+use testutils::TestRepoBackend;
+
+/// Generates text which is split into many chunks.
+fn random_text(len: usize) -> String {
+    let mut state: u64 = 1;
+    (0..len)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            char::from(b'a' + ((state >> 59) as u8 % 26))
+        })
+        .collect()
+}
+
+fn make_commit(tx: &mut Transaction, parents: Vec<CommitId>, contents: &str) -> Commit {
+    let tree = create_tree(tx.base_repo(), &[(repo_path("file"), contents)]);
+    tx.repo_mut()
+        .new_commit(parents, tree.id())
+        .write()
+        .unwrap()
+}
+
+fn count_files(dir: &Path) -> usize {
+    std::fs::read_dir(dir).unwrap().count()
+}
+
+#[test]
+fn test_gc() {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Chunked);
+    let store_path = test_repo.repo_path().join("store");
+    let repo = &test_repo.repo;
+    assert_eq!(repo.store().backend().name(), "chunked");
+    let base_index = repo.readonly_index();
+
+    // B modifies the middle of the file added in A, so most of the chunks are
+    // shared.
+    let original = random_text(4 * 1024 * 1024);
+    let mut modified = original.clone();
+    modified.insert_str(original.len() / 2, "modified");
+    let mut tx = repo.start_transaction();
+    let root_commit_id = repo.store().root_commit_id().clone();
+    let commit_a = make_commit(&mut tx, vec![root_commit_id], &original);
+    make_commit(&mut tx, vec![commit_a.id().clone()], &modified);
+    let repo = tx.commit("test").unwrap();
+    let chunk_count = count_files(&store_path.join("chunks"));
+    let tree_a = commit_a.tree().unwrap();
+    let file_a = tree_a.path_value(repo_path("file")).unwrap();
+    let Some(TreeValue::File { id: file_id_a, .. }) = file_a.as_normal() else {
+        panic!("unexpected value: {file_a:?}");
+    };
+
+    let now = || SystemTime::now() + Duration::from_secs(1);
+
+    // All reachable
+    let stats = repo.store().gc(repo.index(), now(), false).unwrap();
+    assert_eq!(stats.removed_objects, 0);
+    assert_eq!(count_files(&store_path.join("chunks")), chunk_count);
+
+    // Only A is reachable. Chunks unique to B are removed.
+    let mut mut_index = base_index.start_modification();
+    mut_index.add_commit(&commit_a);
+    repo.store().gc(mut_index.as_index(), now(), false).unwrap();
+    let remaining_chunk_count = count_files(&store_path.join("chunks"));
+    assert!(remaining_chunk_count < chunk_count);
+    assert!(remaining_chunk_count + 3 >= chunk_count);
+    assert_eq!(
+        read_file(repo.store(), repo_path("file"), file_id_a),
+        original.as_bytes()
+    );
+
+    // All unreachable
+    repo.store()
+        .gc(base_index.as_index(), now(), false)
+        .unwrap();
+    assert_eq!(count_files(&store_path.join("files")), 0);
+    assert_eq!(count_files(&store_path.join("chunks")), 0);
+}
@@ -813,6 +813,147 @@ fn test_materialize_conflict_two_forward_diffs() {
     );
 }
 
+#[test]
+fn test_materialize_conflict_minimal() {
+    let test_repo = TestRepo::init();
+    let store = test_repo.repo.store();
+
+    let path = repo_path("file");
+    let base_id = testutils::write_file(
+        store,
+        path,
+        indoc! {"
+            line 1
+            the quick fox
+            line 3
+            one two
+            three four
+            five six
+        "},
+    );
+    let left_id = testutils::write_file(
+        store,
+        path,
+        indoc! {"
+            line 1
+            the slow fox
+            line 3
+            ONE two
+            three four
+            five SIX
+        "},
+    );
+    let right_id = testutils::write_file(
+        store,
+        path,
+        indoc! {"
+            line 1
+            the quick wolf
+            line 3
+            one two
+            three FOUR
+            five 6
+        "},
+    );
+
+    let conflict = Merge::from_removes_adds(
+        vec![Some(base_id.clone())],
+        vec![Some(left_id.clone()), Some(right_id.clone())],
+    );
+    insta::assert_snapshot!(
+        &materialize_conflict_string(store, path, &conflict, ConflictMarkerStyle::Git),
+        @r"
+    line 1
+    <<<<<<< Side #1 (Conflict 1 of 2)
+    the slow fox
+    ||||||| Base
+    the quick fox
+    =======
+    the quick wolf
+    >>>>>>> Side #2 (Conflict 1 of 2 ends)
+    line 3
+    <<<<<<< Side #1 (Conflict 2 of 2)
+    ONE two
+    three four
+    five SIX
+    ||||||| Base
+    one two
+    three four
+    five six
+    =======
+    one two
+    three FOUR
+    five 6
+    >>>>>>> Side #2 (Conflict 2 of 2 ends)
+    "
+    );
+    // The non-overlapping word changes are resolved, and only the line with
+    // conflicting words is left between the markers
+    let materialized =
+        materialize_conflict_string(store, path, &conflict, ConflictMarkerStyle::Minimal);
+    insta::assert_snapshot!(&materialized, @r"
+    line 1
+    the slow wolf
+    line 3
+    ONE two
+    three FOUR
+    <<<<<<< Side #1 (Conflict 1 of 1)
+    five SIX
+    ||||||| Base
+    five six
+    =======
+    five 6
+    >>>>>>> Side #2 (Conflict 1 of 1 ends)
+    ");
+    let hunks = parse_conflict(materialized.as_bytes(), 2, MIN_CONFLICT_MARKER_LEN).unwrap();
+    insta::assert_debug_snapshot!(hunks, @r#"
+    [
+        Resolved(
+            "line 1\nthe slow wolf\nline 3\nONE two\nthree FOUR\n",
+        ),
+        Conflicted(
+            [
+                "five SIX\n",
+                "five six\n",
+                "five 6\n",
+            ],
+        ),
+    ]
+    "#);
+
+    // Conflicts with more than two sides use snapshot markers
+    let conflict = Merge::from_removes_adds(
+        vec![Some(base_id.clone()), Some(base_id.clone())],
+        vec![
+            Some(left_id.clone()),
+            Some(right_id.clone()),
+            Some(base_id.clone()),
+        ],
+    );
+    insta::assert_snapshot!(
+        &materialize_conflict_string(store, path, &conflict, ConflictMarkerStyle::Minimal),
+        @r"
+    line 1
+    the slow wolf
+    line 3
+    ONE two
+    three FOUR
+    <<<<<<< Conflict 1 of 1
+    +++++++ Contents of side #1
+    five SIX
+    ------- Contents of base #1
+    five six
+    +++++++ Contents of side #2
+    five 6
+    ------- Contents of base #2
+    five six
+    +++++++ Contents of side #3
+    five six
+    >>>>>>> Conflict 1 of 1 ends
+    "
+    );
+}
+
 #[test]
 fn test_parse_conflict_resolved() {
     assert_eq!(
@@ -0,0 +1,236 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use futures::executor::block_on_stream;
+use itertools::Itertools as _;
+use jj_lib::backend::CopyRecord;
+use jj_lib::config::ConfigLayer;
+use jj_lib::config::ConfigSource;
+use jj_lib::copies::detect_copies;
+use jj_lib::copies::CopyDetectionOptions;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::matchers::FilesMatcher;
+use jj_lib::matchers::Matcher;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::repo::Repo as _;
+use jj_lib::settings::UserSettings;
+use pollster::FutureExt as _;
+use testutils::base_user_config;
+use testutils::create_tree;
+use testutils::repo_path;
+use testutils::TestRepo;
+
+const TEN_LINES: &str = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n";
+const NINE_SAME_LINES: &str = "1\n2\n3\n4\n5\n6\n7\n8\n9\nten\n";
+
+fn detect(
+    source_tree: &MergedTree,
+    target_tree: &MergedTree,
+    matcher: &dyn Matcher,
+    options: &CopyDetectionOptions,
+) -> Vec<(String, String)> {
+    let commit_id = source_tree.store().root_commit_id();
+    detect_copies(
+        source_tree,
+        target_tree,
+        commit_id,
+        commit_id,
+        matcher,
+        options,
+    )
+    .block_on()
+    .unwrap()
+    .into_iter()
+    .map(|record| {
+        (
+            record.source.as_internal_file_string().to_owned(),
+            record.target.as_internal_file_string().to_owned(),
+        )
+    })
+    .collect()
+}
+
+#[test]
+fn test_detect_renames() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let options = CopyDetectionOptions::default();
+
+    let before = create_tree(
+        repo,
+        &[
+            (repo_path("exact"), "exact\n"),
+            (repo_path("similar"), TEN_LINES),
+            (repo_path("removed"), "removed\n"),
+        ],
+    );
+    let after = create_tree(
+        repo,
+        &[
+            (repo_path("dir/exact"), "exact\n"),
+            (repo_path("dir/similar"), NINE_SAME_LINES),
+            (repo_path("added"), "added\n"),
+        ],
+    );
+    insta::assert_debug_snapshot!(detect(&before, &after, &EverythingMatcher, &options), @r#"
+    [
+        (
+            "exact",
+            "dir/exact",
+        ),
+        (
+            "similar",
+            "dir/similar",
+        ),
+    ]
+    "#);
+
+    // Only the matching targets are considered
+    let matcher = FilesMatcher::new([repo_path("dir/similar")]);
+    insta::assert_debug_snapshot!(detect(&before, &after, &matcher, &options), @r#"
+    [
+        (
+            "similar",
+            "dir/similar",
+        ),
+    ]
+    "#);
+
+    // The similar file is below the threshold
+    let strict_options = CopyDetectionOptions {
+        similarity_threshold: 95,
+        ..options.clone()
+    };
+    insta::assert_debug_snapshot!(detect(&before, &after, &EverythingMatcher, &strict_options), @r#"
+    [
+        (
+            "exact",
+            "dir/exact",
+        ),
+    ]
+    "#);
+
+    // Detection is disabled
+    let disabled_options = CopyDetectionOptions {
+        limit: 0,
+        ..options.clone()
+    };
+    assert_eq!(
+        detect(&before, &after, &EverythingMatcher, &disabled_options),
+        []
+    );
+}
+
+#[test]
+fn test_detect_copies() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let before = create_tree(repo, &[(repo_path("file"), TEN_LINES)]);
+    let after = create_tree(
+        repo,
+        &[
+            (repo_path("file"), NINE_SAME_LINES),
+            (repo_path("copy1"), TEN_LINES),
+            (repo_path("copy2"), NINE_SAME_LINES),
+        ],
+    );
+    // Modified files aren't copy sources by default
+    let options = CopyDetectionOptions::default();
+    assert_eq!(detect(&before, &after, &EverythingMatcher, &options), []);
+
+    // Each source is matched with the most similar target
+    let options = CopyDetectionOptions {
+        copies: true,
+        ..options
+    };
+    insta::assert_debug_snapshot!(detect(&before, &after, &EverythingMatcher, &options), @r#"
+    [
+        (
+            "file",
+            "copy1",
+        ),
+    ]
+    "#);
+}
+
+#[test]
+fn test_detect_renames_of_binary_files() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let options = CopyDetectionOptions::default();
+
+    let before = create_tree(
+        repo,
+        &[
+            (repo_path("exact"), "exact\0\n"),
+            (repo_path("similar"), "\0\n1\n2\n3\n4\n5\n6\n"),
+        ],
+    );
+    let after = create_tree(
+        repo,
+        &[
+            (repo_path("dir/exact"), "exact\0\n"),
+            (repo_path("dir/similar"), "\0\n1\n2\n3\n4\n5\n6\n7\n"),
+        ],
+    );
+    // Binary files are only matched if they're identical
+    insta::assert_debug_snapshot!(detect(&before, &after, &EverythingMatcher, &options), @r#"
+    [
+        (
+            "exact",
+            "dir/exact",
+        ),
+    ]
+    "#);
+}
+
+#[test]
+fn test_store_copy_records_without_backend_support() {
+    // The test backend doesn't record copies, but they're detected from the
+    // file contents.
+    let mut config = base_user_config();
+    config.add_layer(
+        ConfigLayer::parse(ConfigSource::User, "diff.renames.compare-contents = true").unwrap(),
+    );
+    let settings = UserSettings::from_config(config).unwrap();
+    let test_repo = TestRepo::init_with_settings(&settings);
+    let repo = &test_repo.repo;
+    let root_commit_id = repo.store().root_commit_id();
+
+    let mut tx = repo.start_transaction();
+    let tree1 = create_tree(repo, &[(repo_path("old"), TEN_LINES)]);
+    let tree2 = create_tree(repo, &[(repo_path("new"), NINE_SAME_LINES)]);
+    let commit1 = tx
+        .repo_mut()
+        .new_commit(vec![root_commit_id.clone()], tree1.id())
+        .write()
+        .unwrap();
+    let commit2 = tx
+        .repo_mut()
+        .new_commit(vec![commit1.id().clone()], tree2.id())
+        .write()
+        .unwrap();
+
+    let stream = repo
+        .store()
+        .get_copy_records(None, commit1.id(), commit2.id())
+        .unwrap();
+    let records: Vec<CopyRecord> = block_on_stream(stream).try_collect().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].source.as_ref(), repo_path("old"));
+    assert_eq!(records[0].target.as_ref(), repo_path("new"));
+    assert_eq!(&records[0].source_commit, commit1.id());
+    assert_eq!(&records[0].target_commit, commit2.id());
+}
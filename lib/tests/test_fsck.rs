@@ -0,0 +1,123 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::sync::Arc;
+
+use assert_matches::assert_matches;
+use jj_lib::backend::TreeValue;
+use jj_lib::fsck;
+use jj_lib::fsck::FsckProblem;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::repo::Repo as _;
+use testutils::create_tree;
+use testutils::repo_path;
+use testutils::write_random_commit;
+use testutils::TestRepo;
+use testutils::TestRepoBackend;
+
+fn init_repo_with_file() -> (TestRepo, Arc<ReadonlyRepo>) {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Simple);
+    let repo = &test_repo.repo;
+    let tree = create_tree(repo, &[(repo_path("dir/file"), "contents")]);
+    let mut tx = repo.start_transaction();
+    let root_commit_id = repo.store().root_commit_id().clone();
+    tx.repo_mut()
+        .new_commit(vec![root_commit_id], tree.id())
+        .write()
+        .unwrap();
+    let repo = tx.commit("test").unwrap();
+    (test_repo, repo)
+}
+
+#[test]
+fn test_check_repo_no_problems() {
+    let (_test_repo, repo) = init_repo_with_file();
+    let report = fsck::check_repo(repo.loader()).unwrap();
+    assert_matches!(&report.problems[..], []);
+    // root and "test" operations
+    assert_eq!(report.num_operations, 2);
+    // root and the new commit
+    assert_eq!(report.num_commits, 2);
+    // root tree, new tree, "dir" tree, and the file
+    assert_eq!(report.num_objects, 4);
+}
+
+#[test]
+fn test_check_repo_missing_file() {
+    let (test_repo, repo) = init_repo_with_file();
+    let commit = repo
+        .store()
+        .get_commit(&repo.view().heads().iter().next().unwrap().clone());
+    let tree = commit.unwrap().tree().unwrap();
+    let value = tree.path_value(repo_path("dir/file")).unwrap();
+    let Some(TreeValue::File { id, .. }) = value.as_normal() else {
+        panic!("unexpected value: {value:?}");
+    };
+    let store_path = test_repo.repo_path().join("store");
+    fs::remove_file(store_path.join("files").join(id.hex())).unwrap();
+
+    let report = fsck::check_repo(repo.loader()).unwrap();
+    assert_matches!(
+        &report.problems[..],
+        [FsckProblem::UnreadableObject { path, hash, .. }]
+            if path.as_ref() == repo_path("dir/file") && *hash == id.hex()
+    );
+    assert!(!report.problems[0].is_repairable());
+}
+
+#[test]
+fn test_check_repo_dangling_op_head() {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Simple);
+    let repo = &test_repo.repo;
+    let mut tx = repo.start_transaction();
+    write_random_commit(tx.repo_mut());
+    let repo = tx.commit("test").unwrap();
+    let op_heads_dir = test_repo.repo_path().join("op_heads").join("heads");
+    let bogus_op_id = "ab".repeat(64);
+    fs::write(op_heads_dir.join(&bogus_op_id), "").unwrap();
+
+    let report = fsck::check_repo(repo.loader()).unwrap();
+    assert_matches!(
+        &report.problems[..],
+        [FsckProblem::DanglingOpHead { op_id, .. }] if op_id.hex() == bogus_op_id
+    );
+    assert!(report.problems[0].is_repairable());
+
+    assert_eq!(fsck::repair(repo.loader(), &report.problems).unwrap(), 1);
+    assert!(!op_heads_dir.join(&bogus_op_id).exists());
+    assert!(op_heads_dir.join(repo.op_id().hex()).exists());
+    let report = fsck::check_repo(repo.loader()).unwrap();
+    assert_matches!(&report.problems[..], []);
+}
+
+#[test]
+fn test_check_repo_corrupt_index() {
+    let (test_repo, repo) = init_repo_with_file();
+    let segments_dir = test_repo.repo_path().join("index").join("segments");
+    for entry in fs::read_dir(&segments_dir).unwrap() {
+        fs::write(entry.unwrap().path(), "garbage").unwrap();
+    }
+
+    let report = fsck::check_repo(repo.loader()).unwrap();
+    assert_matches!(
+        &report.problems[..],
+        [FsckProblem::UnreadableIndex { op_id, .. }] if op_id == repo.op_id()
+    );
+
+    assert_eq!(fsck::repair(repo.loader(), &report.problems).unwrap(), 1);
+    let report = fsck::check_repo(repo.loader()).unwrap();
+    assert_matches!(&report.problems[..], []);
+}
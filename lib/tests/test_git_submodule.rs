@@ -0,0 +1,307 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::path::Path;
+
+use assert_matches::assert_matches;
+use jj_lib::backend::CommitId;
+use jj_lib::backend::MergedTreeId;
+use jj_lib::backend::TreeValue;
+use jj_lib::commit::Commit;
+use jj_lib::git_submodule;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::merge::Merge;
+use jj_lib::merged_tree::MergedTreeBuilder;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::settings::GitSettings;
+use jj_lib::working_copy::CheckoutOptions;
+use testutils::commit_with_tree;
+use testutils::git;
+use testutils::repo_path;
+use testutils::TestRepoBackend;
+use testutils::TestWorkspace;
+
+/// Creates a Git repo with two commits to be used as a submodule.
+fn init_submodule_source(path: &Path) -> (CommitId, CommitId) {
+    let git_repo = git::init(path);
+    let commit1 = git::add_commit(&git_repo, "refs/heads/main", "file", b"1\n", "1", &[]);
+    let commit2 = git::add_commit(
+        &git_repo,
+        "refs/heads/main",
+        "file",
+        b"2\n",
+        "2",
+        &[commit1.commit_id],
+    );
+    (
+        CommitId::from_bytes(commit1.commit_id.as_bytes()),
+        CommitId::from_bytes(commit2.commit_id.as_bytes()),
+    )
+}
+
+fn write_tree_with_submodule(
+    test_workspace: &TestWorkspace,
+    url: &str,
+    commit_id: &CommitId,
+) -> MergedTreeId {
+    let store = test_workspace.repo.store();
+    let gitmodules = format!("[submodule \"sub\"]\n\tpath = sub\n\turl = {url}\n");
+    let mut tree_builder = MergedTreeBuilder::new(store.empty_merged_tree_id());
+    tree_builder.set_or_remove(
+        repo_path(".gitmodules").to_owned(),
+        Merge::normal(TreeValue::File {
+            id: testutils::write_file(store, repo_path(".gitmodules"), &gitmodules),
+            executable: false,
+        }),
+    );
+    tree_builder.set_or_remove(
+        repo_path("sub").to_owned(),
+        Merge::normal(TreeValue::GitSubmodule(commit_id.clone())),
+    );
+    tree_builder.write_tree(store).unwrap()
+}
+
+#[test]
+fn test_check_out_and_snapshot_submodule() {
+    let mut test_workspace = TestWorkspace::init_with_backend(TestRepoBackend::Git);
+    let repo = test_workspace.repo.clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let source_path = test_workspace.root_dir().join("source");
+    let (commit_id1, commit_id2) = init_submodule_source(&source_path);
+
+    let tree_id1 =
+        write_tree_with_submodule(&test_workspace, source_path.to_str().unwrap(), &commit_id1);
+    let commit1 = commit_with_tree(repo.store(), tree_id1.clone());
+    test_workspace
+        .workspace
+        .check_out(
+            repo.op_id().clone(),
+            None,
+            &commit1,
+            &CheckoutOptions::empty_for_test(),
+        )
+        .unwrap();
+    let new_tree = repo.store().get_root_tree(&tree_id1).unwrap();
+    let stats = git_submodule::check_out_submodules(
+        repo.as_ref(),
+        None,
+        &new_tree,
+        &EverythingMatcher,
+        &workspace_root,
+        None,
+        &GitSettings::default(),
+    )
+    .unwrap();
+    assert_eq!(stats.updated_paths, [repo_path("sub").to_owned()]);
+    assert_matches!(&stats.failed[..], []);
+    let submodule_dir = workspace_root.join("sub");
+    assert_eq!(fs::read(submodule_dir.join("file")).unwrap(), b"1\n");
+    // The objects are stored in the submodule store
+    let git_dir = repo.submodule_store().git_dir("sub");
+    assert!(git_dir.join("HEAD").exists());
+
+    // The submodule is already checked out
+    let stats = git_submodule::check_out_submodules(
+        repo.as_ref(),
+        Some(&new_tree),
+        &new_tree,
+        &EverythingMatcher,
+        &workspace_root,
+        None,
+        &GitSettings::default(),
+    )
+    .unwrap();
+    assert_eq!(stats.updated_paths, []);
+
+    // The files in the submodule aren't snapshotted
+    let tree = test_workspace.snapshot().unwrap();
+    assert_eq!(tree.id(), tree_id1);
+
+    // Moving the submodule HEAD updates the tree
+    git::checkout_detached(&submodule_dir, &commit_id2.hex());
+    let tree = test_workspace.snapshot().unwrap();
+    assert_eq!(
+        tree.path_value(repo_path("sub")).unwrap(),
+        Merge::normal(TreeValue::GitSubmodule(commit_id2))
+    );
+    assert_eq!(
+        tree.path_value(repo_path(".gitmodules")).unwrap(),
+        new_tree.path_value(repo_path(".gitmodules")).unwrap()
+    );
+}
+
+#[test]
+fn test_snapshot_submodule_not_updated() {
+    let mut test_workspace = TestWorkspace::init_with_backend(TestRepoBackend::Git);
+    let repo = test_workspace.repo.clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let source_path = test_workspace.root_dir().join("source");
+    let (commit_id1, commit_id2) = init_submodule_source(&source_path);
+    let url = source_path.to_str().unwrap();
+
+    let tree_id1 = write_tree_with_submodule(&test_workspace, url, &commit_id1);
+    let tree_id2 = write_tree_with_submodule(&test_workspace, url, &commit_id2);
+    let commit1 = commit_with_tree(repo.store(), tree_id1.clone());
+    let commit2 = commit_with_tree(repo.store(), tree_id2.clone());
+    test_workspace
+        .workspace
+        .check_out(
+            repo.op_id().clone(),
+            None,
+            &commit1,
+            &CheckoutOptions::empty_for_test(),
+        )
+        .unwrap();
+    let tree1 = repo.store().get_root_tree(&tree_id1).unwrap();
+    git_submodule::check_out_submodules(
+        repo.as_ref(),
+        None,
+        &tree1,
+        &EverythingMatcher,
+        &workspace_root,
+        None,
+        &GitSettings::default(),
+    )
+    .unwrap();
+    let tree = test_workspace.snapshot().unwrap();
+    assert_eq!(tree.id(), tree_id1);
+
+    // Update the working copy but not the submodule, as if updating the
+    // submodule failed
+    test_workspace
+        .workspace
+        .check_out(
+            repo.op_id().clone(),
+            Some(&tree_id1),
+            &commit2,
+            &CheckoutOptions::empty_for_test(),
+        )
+        .unwrap();
+
+    // The submodule HEAD wasn't moved, so the commit in the tree is kept
+    let tree = test_workspace.snapshot().unwrap();
+    assert_eq!(tree.id(), tree_id2);
+}
+
+#[test]
+fn test_fetch_submodules() {
+    let test_workspace = TestWorkspace::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_workspace.repo;
+    let source_path = test_workspace.root_dir().join("source");
+    let (_commit_id1, commit_id2) = init_submodule_source(&source_path);
+    let tree_id =
+        write_tree_with_submodule(&test_workspace, source_path.to_str().unwrap(), &commit_id2);
+    let tree = repo.store().get_root_tree(&tree_id).unwrap();
+
+    let stats =
+        git_submodule::fetch_submodules(repo.as_ref(), &tree, None, &GitSettings::default())
+            .unwrap();
+    assert_eq!(stats.updated_paths, [repo_path("sub").to_owned()]);
+    assert_matches!(&stats.failed[..], []);
+    let git_repo = git::open(repo.submodule_store().git_dir("sub"));
+    assert!(git_repo
+        .find_commit(gix::ObjectId::from_bytes_or_panic(commit_id2.as_bytes()))
+        .is_ok());
+
+    // Nothing to fetch the second time
+    let stats =
+        git_submodule::fetch_submodules(repo.as_ref(), &tree, None, &GitSettings::default())
+            .unwrap();
+    assert_eq!(stats.updated_paths, []);
+}
+
+#[test]
+fn test_fetch_submodules_missing_url() {
+    let test_workspace = TestWorkspace::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_workspace.repo;
+    let source_path = test_workspace.root_dir().join("source");
+    let (commit_id1, _commit_id2) = init_submodule_source(&source_path);
+    let tree_id = write_tree_with_submodule(&test_workspace, "", &commit_id1);
+    let tree = repo.store().get_root_tree(&tree_id).unwrap();
+
+    let stats =
+        git_submodule::fetch_submodules(repo.as_ref(), &tree, None, &GitSettings::default())
+            .unwrap();
+    assert_eq!(stats.updated_paths, []);
+    assert_matches!(
+        &stats.failed[..],
+        [(path, git_submodule::GitSubmoduleError::MissingUrl { name })]
+            if path.as_ref() == repo_path("sub") && name == "sub"
+    );
+}
+
+#[test]
+fn test_remove_submodule() {
+    let mut test_workspace = TestWorkspace::init_with_backend(TestRepoBackend::Git);
+    let repo = test_workspace.repo.clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let source_path = test_workspace.root_dir().join("source");
+    let (commit_id1, _commit_id2) = init_submodule_source(&source_path);
+    let tree_id =
+        write_tree_with_submodule(&test_workspace, source_path.to_str().unwrap(), &commit_id1);
+    let commit = commit_with_tree(repo.store(), tree_id.clone());
+    let tree = repo.store().get_root_tree(&tree_id).unwrap();
+    let empty_tree = repo.store().root_commit().tree().unwrap();
+    let check_out = |test_workspace: &mut TestWorkspace, old_tree, commit: &Commit| {
+        test_workspace
+            .workspace
+            .check_out(
+                repo.op_id().clone(),
+                None,
+                commit,
+                &CheckoutOptions::empty_for_test(),
+            )
+            .unwrap();
+        git_submodule::check_out_submodules(
+            repo.as_ref(),
+            old_tree,
+            &commit.tree().unwrap(),
+            &EverythingMatcher,
+            &workspace_root,
+            None,
+            &GitSettings::default(),
+        )
+        .unwrap()
+    };
+    check_out(&mut test_workspace, None, &commit);
+    let submodule_dir = workspace_root.join("sub");
+    assert!(submodule_dir.join("file").exists());
+
+    // The submodule is removed along with its working tree
+    let stats = check_out(
+        &mut test_workspace,
+        Some(&tree),
+        &repo.store().root_commit(),
+    );
+    assert_eq!(stats.updated_paths, [repo_path("sub").to_owned()]);
+    assert!(!submodule_dir.exists());
+
+    // It can be checked out again
+    let stats = check_out(&mut test_workspace, Some(&empty_tree), &commit);
+    assert_eq!(stats.updated_paths, [repo_path("sub").to_owned()]);
+    assert_eq!(fs::read(submodule_dir.join("file")).unwrap(), b"1\n");
+
+    // A submodule with local changes isn't removed
+    fs::write(submodule_dir.join("file"), "modified\n").unwrap();
+    let stats = check_out(
+        &mut test_workspace,
+        Some(&tree),
+        &repo.store().root_commit(),
+    );
+    assert_eq!(stats.updated_paths, []);
+    assert_matches!(&stats.failed[..], [(path, _)] if path.as_ref() == repo_path("sub"));
+    assert!(submodule_dir.join("file").exists());
+}
@@ -0,0 +1,139 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use jj_lib::hunk_selection::select_hunks;
+use jj_lib::hunk_selection::HunkSelection;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::merged_tree::MergedTree;
+use pollster::FutureExt as _;
+use testutils::create_tree;
+use testutils::repo_path;
+use testutils::TestRepo;
+
+fn select(
+    left_tree: &MergedTree,
+    right_tree: &MergedTree,
+    selection: &HunkSelection,
+) -> MergedTree {
+    let tree_id = select_hunks(left_tree, right_tree, &EverythingMatcher, selection)
+        .block_on()
+        .unwrap();
+    left_tree.store().get_root_tree(&tree_id).unwrap()
+}
+
+#[test]
+fn test_select_hunks_by_lines() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let file = repo_path("file");
+    let other = repo_path("other");
+    let left_tree = create_tree(repo, &[(file, "a\nb\nc\nd\ne\nf\ng\n"), (other, "other\n")]);
+    let right_tree = create_tree(repo, &[(file, "a\nB\nc\nd\nf\ng\nh\n"), (other, "OTHER\n")]);
+
+    // Nothing selected
+    let selection = HunkSelection::new();
+    assert_eq!(
+        select(&left_tree, &right_tree, &selection).id(),
+        left_tree.id()
+    );
+
+    // Modified line
+    let mut selection = HunkSelection::new();
+    selection.select_lines(file.to_owned(), 1..2);
+    let expected_tree = create_tree(repo, &[(file, "a\nB\nc\nd\ne\nf\ng\n"), (other, "other\n")]);
+    assert_eq!(
+        select(&left_tree, &right_tree, &selection).id(),
+        expected_tree.id()
+    );
+
+    // Deleted line is adjacent to the selected range
+    let mut selection = HunkSelection::new();
+    selection.select_lines(file.to_owned(), 3..4);
+    let expected_tree = create_tree(repo, &[(file, "a\nb\nc\nd\nf\ng\n"), (other, "other\n")]);
+    assert_eq!(
+        select(&left_tree, &right_tree, &selection).id(),
+        expected_tree.id()
+    );
+
+    // Multiple ranges, and whole file
+    let mut selection = HunkSelection::new();
+    selection.select_lines(file.to_owned(), 0..2);
+    selection.select_lines(file.to_owned(), 6..7);
+    selection.select_file(other.to_owned());
+    let expected_tree = create_tree(
+        repo,
+        &[(file, "a\nB\nc\nd\ne\nf\ng\nh\n"), (other, "OTHER\n")],
+    );
+    assert_eq!(
+        select(&left_tree, &right_tree, &selection).id(),
+        expected_tree.id()
+    );
+}
+
+#[test]
+fn test_select_hunks_added_and_deleted_files() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let added = repo_path("added");
+    let deleted = repo_path("deleted");
+    let left_tree = create_tree(repo, &[(deleted, "a\nb\n")]);
+    let right_tree = create_tree(repo, &[(added, "a\nb\nc\n")]);
+
+    // Part of the added file
+    let mut selection = HunkSelection::new();
+    selection.select_lines(added.to_owned(), 0..1);
+    assert_eq!(
+        select(&left_tree, &right_tree, &selection).id(),
+        create_tree(repo, &[(added, "a\nb\nc\n"), (deleted, "a\nb\n")]).id()
+    );
+
+    // Deletion of the whole file
+    let mut selection = HunkSelection::new();
+    selection.select_lines(deleted.to_owned(), 0..1);
+    assert_eq!(
+        select(&left_tree, &right_tree, &selection).id(),
+        create_tree(repo, &[]).id()
+    );
+}
+
+#[test]
+fn test_select_hunks_from_patch() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let file = repo_path("file");
+    let left_tree = create_tree(repo, &[(file, "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n")]);
+    let right_tree = create_tree(repo, &[(file, "A\nb\nc\nd\ne\nf\ng\nh\ni\nJ\n")]);
+
+    // Only the second hunk of `jj diff --git` output
+    let patch = indoc::indoc! {b"
+        diff --git a/file b/file
+        index 0000000000..1111111111 100644
+        --- a/file
+        +++ b/file
+        @@ -7,4 +7,4 @@
+         g
+         h
+         i
+        -j
+        +J
+    "};
+    let selection = HunkSelection::from_patch(patch).unwrap();
+    assert_eq!(
+        select(&left_tree, &right_tree, &selection).id(),
+        create_tree(repo, &[(file, "a\nb\nc\nd\ne\nf\ng\nh\ni\nJ\n")]).id()
+    );
+}
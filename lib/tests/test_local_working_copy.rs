@@ -42,6 +42,7 @@ use jj_lib::repo::Repo as _;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::secret_backend::SecretBackend;
+use jj_lib::sparse::SparsePattern;
 use jj_lib::working_copy::CheckoutError;
 use jj_lib::working_copy::CheckoutOptions;
 use jj_lib::working_copy::CheckoutStats;
@@ -112,7 +113,10 @@ fn test_root() {
     let mut test_workspace = TestWorkspace::init();
 
     let wc = test_workspace.workspace.working_copy();
-    assert_eq!(wc.sparse_patterns().unwrap(), vec![RepoPathBuf::root()]);
+    assert_eq!(
+        wc.sparse_patterns().unwrap(),
+        vec![SparsePattern::Prefix(RepoPathBuf::root())]
+    );
     let new_tree = test_workspace.snapshot().unwrap();
     let repo = &test_workspace.repo;
     let wc_commit_id = repo
@@ -649,7 +653,8 @@ fn test_conflicting_changes_on_disk() {
             updated_files: 0,
             added_files: 3,
             removed_files: 0,
-            skipped_files: 3
+            skipped_files: 3,
+            sparse_warnings: vec![],
         }
     );
 
@@ -877,7 +882,8 @@ fn test_materialize_snapshot_conflicted_files() {
             updated_files: 0,
             added_files: 2,
             removed_files: 0,
-            skipped_files: 0
+            skipped_files: 0,
+            sparse_warnings: vec![],
         }
     );
 
@@ -1428,7 +1434,8 @@ fn test_git_submodule(gitignore_content: &str) {
         .unwrap();
     assert_eq!(new_tree.id(), tree_id2);
 
-    // Check out the empty tree, which shouldn't fail
+    // Check out the empty tree, which shouldn't fail. The submodule directory
+    // is left for the caller to remove.
     let ws = &mut test_workspace.workspace;
     let stats = ws
         .check_out(
@@ -1438,7 +1445,11 @@ fn test_git_submodule(gitignore_content: &str) {
             &CheckoutOptions::empty_for_test(),
         )
         .unwrap();
-    assert_eq!(stats.skipped_files, 1);
+    assert_eq!(stats.skipped_files, 0);
+    assert!(
+        file_in_submodule_path.metadata().is_ok(),
+        "{file_in_submodule_path:?} should exist"
+    );
 }
 
 #[test]
@@ -2052,7 +2063,7 @@ fn test_fsmonitor() {
     let ws = &mut test_workspace.workspace;
     assert_eq!(
         ws.working_copy().sparse_patterns().unwrap(),
-        vec![RepoPathBuf::root()]
+        vec![SparsePattern::Prefix(RepoPathBuf::root())]
     );
 
     let foo_path = repo_path("foo");
@@ -12,13 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use assert_matches::assert_matches;
 use futures::StreamExt as _;
+use indoc::indoc;
 use itertools::Itertools as _;
 use jj_lib::local_working_copy::LocalWorkingCopy;
 use jj_lib::matchers::EverythingMatcher;
 use jj_lib::repo::Repo as _;
 use jj_lib::repo_path::RepoPath;
-use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::sparse::resolve_sparse_profile;
+use jj_lib::sparse::SparsePattern;
+use jj_lib::sparse::SparseProfileError;
 use jj_lib::working_copy::CheckoutOptions;
 use jj_lib::working_copy::CheckoutStats;
 use jj_lib::working_copy::WorkingCopy as _;
@@ -28,8 +32,11 @@ use testutils::create_tree;
 use testutils::repo_path;
 use testutils::TestWorkspace;
 
-fn to_owned_path_vec(paths: &[&RepoPath]) -> Vec<RepoPathBuf> {
-    paths.iter().map(|&path| path.to_owned()).collect()
+fn to_owned_path_vec(paths: &[&RepoPath]) -> Vec<SparsePattern> {
+    paths
+        .iter()
+        .map(|&path| SparsePattern::Prefix(path.to_owned()))
+        .collect()
 }
 
 #[test]
@@ -86,6 +93,7 @@ fn test_sparse_checkout() {
             added_files: 0,
             removed_files: 3,
             skipped_files: 0,
+            sparse_warnings: vec![],
         }
     );
     assert_eq!(
@@ -145,6 +153,7 @@ fn test_sparse_checkout() {
             added_files: 2,
             removed_files: 2,
             skipped_files: 0,
+            sparse_warnings: vec![],
         }
     );
     assert_eq!(locked_wc.sparse_patterns().unwrap(), sparse_patterns);
@@ -317,3 +326,253 @@ fn test_sparse_commit_gitignore() {
     assert_eq!(entries.len(), 1);
     assert_eq!(entries[0].0.as_ref(), dir1_file2_path);
 }
+
+#[test]
+fn test_sparse_checkout_fileset() {
+    let mut test_workspace = TestWorkspace::init();
+    let repo = &test_workspace.repo;
+    let working_copy_path = test_workspace.workspace.workspace_root().to_owned();
+
+    let root_file1_path = repo_path("file1.md");
+    let dir1_file1_path = repo_path("dir1/file1.md");
+    let dir1_file2_path = repo_path("dir1/file2.rs");
+    let dir2_file1_path = repo_path("dir2/file1.md");
+
+    let tree = create_tree(
+        repo,
+        &[
+            (root_file1_path, "contents"),
+            (dir1_file1_path, "contents"),
+            (dir1_file2_path, "contents"),
+            (dir2_file1_path, "contents"),
+        ],
+    );
+    let commit = commit_with_tree(repo.store(), tree.id());
+
+    let ws = &mut test_workspace.workspace;
+    ws.check_out(
+        repo.op_id().clone(),
+        None,
+        &commit,
+        &CheckoutOptions::empty_for_test(),
+    )
+    .unwrap();
+
+    // Set sparse patterns to the Markdown files in dir1/ and dir2/
+    let mut locked_ws = ws.start_working_copy_mutation().unwrap();
+    let sparse_patterns = vec![
+        SparsePattern::parse("dir2", &working_copy_path).unwrap(),
+        SparsePattern::parse("glob:'*/*.md' ~ dir2", &working_copy_path).unwrap(),
+    ];
+    assert_eq!(
+        sparse_patterns,
+        vec![
+            SparsePattern::Prefix(repo_path("dir2").to_owned()),
+            SparsePattern::Fileset("glob:'*/*.md' ~ dir2".to_owned()),
+        ]
+    );
+    let stats = locked_ws
+        .locked_wc()
+        .set_sparse_patterns(sparse_patterns.clone(), &CheckoutOptions::empty_for_test())
+        .unwrap();
+    assert_eq!(
+        stats,
+        CheckoutStats {
+            updated_files: 0,
+            added_files: 0,
+            removed_files: 2,
+            skipped_files: 0,
+            sparse_warnings: vec![],
+        }
+    );
+    locked_ws.finish(repo.op_id().clone()).unwrap();
+
+    // Reload the state to check that the patterns were persisted
+    let wc: &LocalWorkingCopy = ws.working_copy().as_any().downcast_ref().unwrap();
+    let wc = LocalWorkingCopy::load(
+        repo.store().clone(),
+        ws.workspace_root().to_path_buf(),
+        wc.state_path().to_path_buf(),
+    );
+    assert_eq!(wc.sparse_patterns().unwrap(), sparse_patterns);
+    assert_eq!(
+        wc.file_states().unwrap().paths().collect_vec(),
+        vec![dir1_file1_path, dir2_file1_path]
+    );
+}
+
+#[test]
+fn test_resolve_sparse_profile() {
+    let test_workspace = TestWorkspace::init();
+    let repo = &test_workspace.repo;
+    let workspace_root = test_workspace.workspace.workspace_root();
+
+    let tree = create_tree(
+        repo,
+        &[
+            (
+                repo_path(".jj-sparse/backend.toml"),
+                indoc! {r#"
+                    include = ["common", "docs"]
+                    patterns = ["backend", 'glob:"services/*/api/**"']
+                "#},
+            ),
+            (
+                repo_path(".jj-sparse/common.toml"),
+                indoc! {r#"
+                    include = ["docs"]
+                    patterns = ["build", "backend"]
+                "#},
+            ),
+            (repo_path(".jj-sparse/docs.toml"), r#"patterns = ["docs"]"#),
+            (
+                repo_path(".jj-sparse/cycle1.toml"),
+                r#"include = ["cycle2"]"#,
+            ),
+            (
+                repo_path(".jj-sparse/cycle2.toml"),
+                r#"include = ["cycle1"]"#,
+            ),
+            (repo_path(".jj-sparse/bad.toml"), r#"pattern = ["docs"]"#),
+            (
+                repo_path(".jj-sparse/bad-pattern.toml"),
+                r#"patterns = ["glob:("]"#,
+            ),
+        ],
+    );
+
+    let to_prefix = |path: &str| SparsePattern::Prefix(repo_path(path).to_owned());
+    assert_eq!(
+        resolve_sparse_profile(&tree, "backend", workspace_root).unwrap(),
+        vec![
+            to_prefix(".jj-sparse"),
+            to_prefix("backend"),
+            to_prefix("build"),
+            to_prefix("docs"),
+            SparsePattern::Fileset(r#"glob:"services/*/api/**""#.to_owned()),
+        ]
+    );
+    assert_eq!(
+        resolve_sparse_profile(&tree, "docs", workspace_root).unwrap(),
+        vec![to_prefix(".jj-sparse"), to_prefix("docs")]
+    );
+
+    assert_matches!(
+        resolve_sparse_profile(&tree, "cycle1", workspace_root),
+        Err(SparseProfileError::Cycle(name)) if name == "cycle1"
+    );
+    assert_matches!(
+        resolve_sparse_profile(&tree, "missing", workspace_root),
+        Err(SparseProfileError::NotFound(name)) if name == "missing"
+    );
+    assert_matches!(
+        resolve_sparse_profile(&tree, "../docs", workspace_root),
+        Err(SparseProfileError::InvalidName(_))
+    );
+    assert_matches!(
+        resolve_sparse_profile(&tree, "bad", workspace_root),
+        Err(SparseProfileError::Parse { name, .. }) if name == "bad"
+    );
+    assert_matches!(
+        resolve_sparse_profile(&tree, "bad-pattern", workspace_root),
+        Err(SparseProfileError::Pattern { name, .. }) if name == "bad-pattern"
+    );
+}
+
+#[test]
+fn test_sparse_profile_updated_on_checkout() {
+    let mut test_workspace = TestWorkspace::init();
+    let repo = &test_workspace.repo;
+    let working_copy_path = test_workspace.workspace.workspace_root().to_owned();
+
+    let profile_path = repo_path(".jj-sparse/dev.toml");
+    let dir1_file1_path = repo_path("dir1/file1");
+    let dir2_file1_path = repo_path("dir2/file1");
+
+    let tree1 = create_tree(
+        repo,
+        &[
+            (profile_path, r#"patterns = ["dir1"]"#),
+            (dir1_file1_path, "contents"),
+            (dir2_file1_path, "contents"),
+        ],
+    );
+    let commit1 = commit_with_tree(repo.store(), tree1.id());
+    let tree2 = create_tree(
+        repo,
+        &[
+            (profile_path, r#"patterns = ["dir2"]"#),
+            (dir1_file1_path, "contents"),
+            (dir2_file1_path, "contents"),
+        ],
+    );
+    let commit2 = commit_with_tree(repo.store(), tree2.id());
+
+    let ws = &mut test_workspace.workspace;
+    ws.check_out(
+        repo.op_id().clone(),
+        None,
+        &commit1,
+        &CheckoutOptions::empty_for_test(),
+    )
+    .unwrap();
+
+    // Use the profile
+    let mut locked_ws = ws.start_working_copy_mutation().unwrap();
+    let stats = locked_ws
+        .locked_wc()
+        .set_sparse_profile("dev".to_owned(), &CheckoutOptions::empty_for_test())
+        .unwrap();
+    assert_eq!(stats.removed_files, 1);
+    locked_ws.finish(repo.op_id().clone()).unwrap();
+    assert_eq!(ws.working_copy().sparse_profile().unwrap(), Some("dev"));
+    assert!(dir1_file1_path
+        .to_fs_path_unchecked(&working_copy_path)
+        .exists());
+    assert!(!dir2_file1_path
+        .to_fs_path_unchecked(&working_copy_path)
+        .exists());
+
+    // Checking out a commit with a different version of the profile updates the
+    // patterns
+    ws.check_out(
+        repo.op_id().clone(),
+        Some(&tree1.id()),
+        &commit2,
+        &CheckoutOptions::empty_for_test(),
+    )
+    .unwrap();
+    assert_eq!(
+        ws.working_copy().sparse_patterns().unwrap(),
+        to_owned_path_vec(&[repo_path(".jj-sparse"), repo_path("dir2")])
+    );
+    assert!(!dir1_file1_path
+        .to_fs_path_unchecked(&working_copy_path)
+        .exists());
+    assert!(dir2_file1_path
+        .to_fs_path_unchecked(&working_copy_path)
+        .exists());
+
+    // Setting patterns explicitly stops using the profile
+    let mut locked_ws = ws.start_working_copy_mutation().unwrap();
+    locked_ws
+        .locked_wc()
+        .set_sparse_patterns(
+            to_owned_path_vec(&[dir1_file1_path]),
+            &CheckoutOptions::empty_for_test(),
+        )
+        .unwrap();
+    locked_ws.finish(repo.op_id().clone()).unwrap();
+    assert_eq!(ws.working_copy().sparse_profile().unwrap(), None);
+    ws.check_out(
+        repo.op_id().clone(),
+        Some(&tree2.id()),
+        &commit1,
+        &CheckoutOptions::empty_for_test(),
+    )
+    .unwrap();
+    assert_eq!(
+        ws.working_copy().sparse_patterns().unwrap(),
+        to_owned_path_vec(&[dir1_file1_path])
+    );
+}
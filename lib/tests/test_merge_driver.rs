@@ -0,0 +1,193 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use assert_matches::assert_matches;
+use jj_lib::config::ConfigLayer;
+use jj_lib::config::ConfigSource;
+use jj_lib::merge::Merge;
+use jj_lib::merge_driver::MergeDriverConfigError;
+use jj_lib::merge_driver::MergeDrivers;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::repo::Repo as _;
+use jj_lib::rewrite::rebase_commit;
+use jj_lib::settings::UserSettings;
+use testutils::base_user_config;
+use testutils::create_single_tree;
+use testutils::create_tree;
+use testutils::repo_path;
+use testutils::TestRepo;
+
+fn settings_with_drivers(config_text: &str) -> UserSettings {
+    let mut config = base_user_config();
+    config.add_layer(ConfigLayer::parse(ConfigSource::User, config_text).unwrap());
+    UserSettings::from_config(config).unwrap()
+}
+
+/// Creates a tree where both sides changed the first line of each file.
+fn conflicting_tree(test_repo: &TestRepo, paths: &[&str]) -> MergedTree {
+    let repo = &test_repo.repo;
+    let create = |contents: &str| {
+        let path_contents = paths
+            .iter()
+            .map(|path| (repo_path(path), contents))
+            .collect::<Vec<_>>();
+        create_single_tree(repo, &path_contents)
+    };
+    MergedTree::new(Merge::from_removes_adds(
+        vec![create("base\n")],
+        vec![create("left\n"), create("right\n")],
+    ))
+}
+
+#[cfg(unix)]
+#[test]
+fn test_merge_driver_resolves_matching_files() {
+    let settings = settings_with_drivers(
+        r#"
+        [merge-drivers.concat]
+        program = "sh"
+        merge-args = ["-c", 'cat "$1" "$2" > "$3"', "", "$left", "$right", "$output"]
+        patterns = ["glob:'**/*.txt'"]
+        "#,
+    );
+    let test_repo = TestRepo::init_with_settings(&settings);
+    let repo = &test_repo.repo;
+
+    let tree = conflicting_tree(&test_repo, &["dir/file.txt", "file.json"]);
+    // Drivers aren't run by plain tree merges
+    let merged_tree = tree.resolve().unwrap();
+    let value = merged_tree.path_value(repo_path("dir/file.txt")).unwrap();
+    assert!(!value.is_resolved());
+
+    let store = repo.store();
+    let resolved_tree = store
+        .merge_drivers()
+        .resolve_conflicts(store, merged_tree)
+        .unwrap();
+    let value = resolved_tree.path_value(repo_path("dir/file.txt")).unwrap();
+    let expected_tree = create_single_tree(repo, &[(repo_path("dir/file.txt"), "left\nright\n")]);
+    assert_eq!(
+        value.as_resolved().unwrap(),
+        &expected_tree.path_value(repo_path("dir/file.txt")).unwrap()
+    );
+    // The driver doesn't apply to the other file
+    let value = resolved_tree.path_value(repo_path("file.json")).unwrap();
+    assert!(!value.is_resolved());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_merge_driver_failure_leaves_conflict() {
+    let settings = settings_with_drivers(
+        r#"
+        [merge-drivers.fail]
+        program = "false"
+        merge-args = []
+        patterns = ["all()"]
+
+        [merge-drivers.missing]
+        program = "jj-nonexistent-merge-driver"
+        merge-args = []
+        patterns = ["all()"]
+        "#,
+    );
+    let test_repo = TestRepo::init_with_settings(&settings);
+    let store = test_repo.repo.store();
+
+    let tree = conflicting_tree(&test_repo, &["file"]);
+    let resolved_tree = store
+        .merge_drivers()
+        .resolve_conflicts(store, tree.resolve().unwrap())
+        .unwrap();
+    let value = resolved_tree.path_value(repo_path("file")).unwrap();
+    assert!(!value.is_resolved());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_merge_driver_runs_on_rebase() {
+    let settings = settings_with_drivers(
+        r#"
+        [merge-drivers.concat]
+        program = "sh"
+        merge-args = ["-c", 'cat "$1" "$2" > "$3"', "", "$left", "$right", "$output"]
+        patterns = ["all()"]
+        "#,
+    );
+    let test_repo = TestRepo::init_with_settings(&settings);
+    let repo = &test_repo.repo;
+
+    // B and C make conflicting changes to the file. Rebasing C onto B runs the
+    // driver.
+    let path = repo_path("file");
+    let mut tx = repo.start_transaction();
+    let tree_a = create_tree(repo, &[(path, "base\n")]);
+    let commit_a = tx
+        .repo_mut()
+        .new_commit(vec![repo.store().root_commit_id().clone()], tree_a.id())
+        .write()
+        .unwrap();
+    let tree_b = create_tree(repo, &[(path, "left\n")]);
+    let commit_b = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree_b.id())
+        .write()
+        .unwrap();
+    let tree_c = create_tree(repo, &[(path, "right\n")]);
+    let commit_c = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree_c.id())
+        .write()
+        .unwrap();
+
+    let commit_c2 = rebase_commit(tx.repo_mut(), commit_c, vec![commit_b.id().clone()]).unwrap();
+    let expected_tree = create_tree(repo, &[(path, "left\nright\n")]);
+    assert_eq!(*commit_c2.tree_id(), expected_tree.id());
+}
+
+#[test]
+fn test_merge_drivers_from_settings() {
+    let drivers = MergeDrivers::from_settings(&settings_with_drivers("")).unwrap();
+    assert!(drivers.is_empty());
+
+    let drivers = MergeDrivers::from_settings(&settings_with_drivers(
+        r#"
+        [merge-drivers.json]
+        merge-args = ["$base", "$left", "$right", "-o", "$output"]
+        patterns = ["glob:'**/*.json'"]
+        "#,
+    ))
+    .unwrap();
+    assert!(!drivers.is_empty());
+
+    let result = MergeDrivers::from_settings(&settings_with_drivers(
+        r#"
+        [merge-drivers.json]
+        merge-args = []
+        patterns = ["bad:pattern"]
+        "#,
+    ));
+    assert_matches!(
+        result,
+        Err(MergeDriverConfigError::Pattern { name, .. }) if name == "json"
+    );
+
+    let result = MergeDrivers::from_settings(&settings_with_drivers(
+        r#"
+        [merge-drivers.json]
+        patterns = []
+        "#,
+    ));
+    assert_matches!(result, Err(MergeDriverConfigError::Config(_)));
+}
@@ -19,10 +19,13 @@ use jj_lib::backend::CopyRecord;
 use jj_lib::backend::FileId;
 use jj_lib::backend::MergedTreeId;
 use jj_lib::backend::TreeValue;
+use jj_lib::config::ConfigLayer;
+use jj_lib::config::ConfigSource;
 use jj_lib::copies::CopiesTreeDiffEntryPath;
 use jj_lib::copies::CopyOperation;
 use jj_lib::copies::CopyRecords;
 use jj_lib::files;
+use jj_lib::files::MergeOptions;
 use jj_lib::matchers::EverythingMatcher;
 use jj_lib::matchers::FilesMatcher;
 use jj_lib::matchers::Matcher;
@@ -38,8 +41,10 @@ use jj_lib::merged_tree::TreeDiffStreamImpl;
 use jj_lib::repo::Repo as _;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::settings::UserSettings;
 use pollster::FutureExt as _;
 use pretty_assertions::assert_eq;
+use testutils::base_user_config;
 use testutils::create_single_tree;
 use testutils::create_tree;
 use testutils::repo_path;
@@ -548,6 +553,41 @@ fn test_resolve_with_conflict() {
     );
 }
 
+#[test]
+fn test_resolve_with_word_level_merge() {
+    let mut config = base_user_config();
+    config.add_layer(ConfigLayer::parse(ConfigSource::User, "merge.hunk-level = 'word'").unwrap());
+    let settings = UserSettings::from_config(config).unwrap();
+    let test_repo = TestRepo::init_with_settings(&settings);
+    let repo = &test_repo.repo;
+
+    // Changes to different words in the same line are merged, but changes to the
+    // same word are not
+    let resolved_path = repo_path("resolved");
+    let conflict_path = repo_path("conflict");
+    let base1 = create_single_tree(repo, &[(resolved_path, "a b c\n"), (conflict_path, "a\n")]);
+    let side1 = create_single_tree(
+        repo,
+        &[(resolved_path, "a1 b c\n"), (conflict_path, "a1\n")],
+    );
+    let side2 = create_single_tree(
+        repo,
+        &[(resolved_path, "a b c2\n"), (conflict_path, "a2\n")],
+    );
+    let expected = create_single_tree(repo, &[(resolved_path, "a1 b c2\n")]);
+
+    let tree = MergedTree::new(Merge::from_removes_adds(vec![base1], vec![side1, side2]));
+    let resolved_tree = tree.resolve().unwrap();
+    assert_eq!(
+        resolved_tree.path_value(resolved_path).unwrap(),
+        Merge::normal(expected.path_value(resolved_path).unwrap().unwrap())
+    );
+    assert!(!resolved_tree
+        .path_value(conflict_path)
+        .unwrap()
+        .is_resolved());
+}
+
 #[test]
 fn test_resolve_with_conflict_containing_empty_subtree() {
     let test_repo = TestRepo::init();
@@ -1605,7 +1645,7 @@ fn test_merge_simplify_file_conflict() {
             Merge::resolved(child2_text.as_bytes()),
         ],
     );
-    assert!(files::try_merge(&text_merge.flatten()).is_none());
+    assert!(files::try_merge(&text_merge.flatten(), &MergeOptions::default()).is_none());
 }
 
 /// Like `test_merge_simplify_file_conflict()`, but some of the conflicts are
@@ -22,7 +22,10 @@ use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
 use jj_lib::config::ConfigLayer;
 use jj_lib::config::ConfigSource;
+use jj_lib::object_id::HexPrefix;
 use jj_lib::object_id::ObjectId as _;
+use jj_lib::object_id::PrefixResolution;
+use jj_lib::op_store::OpStoreError;
 use jj_lib::op_store::OperationId;
 use jj_lib::op_walk;
 use jj_lib::op_walk::OpsetEvaluationError;
@@ -660,6 +663,7 @@ fn test_gc() {
     let test_repo = TestRepo::init_with_settings(&settings);
     let op_dir = test_repo.repo_path().join("op_store").join("operations");
     let view_dir = test_repo.repo_path().join("op_store").join("views");
+    let pack_dir = test_repo.repo_path().join("op_store").join("packs");
     let repo_0 = test_repo.repo;
     let op_store = repo_0.op_store();
 
@@ -686,50 +690,126 @@ fn test_gc() {
     let repo_e = empty_tx(&repo_b).commit("op E").unwrap();
     let repo_f = random_tx(&repo_e).commit("op F").unwrap();
 
+    let all_repos = [&repo_a, &repo_b, &repo_c, &repo_d, &repo_e, &repo_f];
+    let has_op = |repo: &ReadonlyRepo| match op_store.read_operation(repo.op_id()) {
+        Ok(_) => true,
+        Err(OpStoreError::ObjectNotFound { .. }) => false,
+        Err(err) => panic!("unexpected error: {err}"),
+    };
+    let has_view = |repo: &ReadonlyRepo| match op_store.read_view(repo.operation().view_id()) {
+        Ok(_) => true,
+        Err(OpStoreError::ObjectNotFound { .. }) => false,
+        Err(err) => panic!("unexpected error: {err}"),
+    };
+    let stored_ops = || all_repos.iter().filter(|repo| has_op(repo)).count();
+    let stored_views = || all_repos.iter().filter(|repo| has_view(repo)).count();
+
     // Sanity check for the original state
-    let mut expected_op_entries = list_dir(&op_dir);
-    let mut expected_view_entries = list_dir(&view_dir);
+    let expected_op_entries = list_dir(&op_dir);
+    let expected_view_entries = list_dir(&view_dir);
     assert_eq!(expected_op_entries.len(), 6);
     assert_eq!(expected_view_entries.len(), 5);
+    assert!(list_dir(&pack_dir).is_empty());
 
     // No heads, but all kept by file modification time
     op_store.gc(&[], SystemTime::UNIX_EPOCH).unwrap();
     assert_eq!(list_dir(&op_dir), expected_op_entries);
     assert_eq!(list_dir(&view_dir), expected_view_entries);
+    assert!(list_dir(&pack_dir).is_empty());
 
-    // All reachable from heads
-    let now = SystemTime::now();
+    // All reachable from heads, moved to pack
     let head_ids = [repo_d.op_id().clone(), repo_f.op_id().clone()];
-    op_store.gc(&head_ids, now).unwrap();
-    assert_eq!(list_dir(&op_dir), expected_op_entries);
-    assert_eq!(list_dir(&view_dir), expected_view_entries);
+    op_store.gc(&head_ids, SystemTime::now()).unwrap();
+    assert!(list_dir(&op_dir).is_empty());
+    assert!(list_dir(&view_dir).is_empty());
+    assert_eq!(list_dir(&pack_dir).len(), 1);
+    assert_eq!(stored_ops(), 6);
+    // E's view is the same as B's
+    assert_eq!(stored_views(), 6);
 
     // E|F are no longer reachable, but E's view is still reachable
-    op_store.gc(slice::from_ref(repo_d.op_id()), now).unwrap();
-    expected_op_entries
-        .retain(|name| *name != repo_e.op_id().hex() && *name != repo_f.op_id().hex());
-    expected_view_entries.retain(|name| *name != repo_f.operation().view_id().hex());
-    assert_eq!(list_dir(&op_dir), expected_op_entries);
-    assert_eq!(list_dir(&view_dir), expected_view_entries);
+    op_store
+        .gc(slice::from_ref(repo_d.op_id()), SystemTime::now())
+        .unwrap();
+    assert!(!has_op(&repo_e));
+    assert!(!has_op(&repo_f));
+    assert!(has_view(&repo_e));
+    assert!(!has_view(&repo_f));
+    assert_eq!(stored_ops(), 4);
+    assert_eq!(list_dir(&pack_dir).len(), 1);
 
     // B|C|D are no longer reachable
-    op_store.gc(slice::from_ref(repo_a.op_id()), now).unwrap();
-    expected_op_entries.retain(|name| {
-        *name != repo_b.op_id().hex()
-            && *name != repo_c.op_id().hex()
-            && *name != repo_d.op_id().hex()
-    });
-    expected_view_entries.retain(|name| {
-        *name != repo_b.operation().view_id().hex()
-            && *name != repo_c.operation().view_id().hex()
-            && *name != repo_d.operation().view_id().hex()
-    });
-    assert_eq!(list_dir(&op_dir), expected_op_entries);
-    assert_eq!(list_dir(&view_dir), expected_view_entries);
+    op_store
+        .gc(slice::from_ref(repo_a.op_id()), SystemTime::now())
+        .unwrap();
+    assert!(has_op(&repo_a));
+    assert!(has_view(&repo_a));
+    assert_eq!(stored_ops(), 1);
+    assert_eq!(stored_views(), 1);
+    assert!(list_dir(&op_dir).is_empty());
+    assert!(list_dir(&view_dir).is_empty());
+    assert_eq!(list_dir(&pack_dir).len(), 1);
+}
 
-    // Sanity check for the last state
-    assert_eq!(expected_op_entries.len(), 1);
-    assert_eq!(expected_view_entries.len(), 1);
+#[test]
+fn test_gc_packed_operations() {
+    let settings = stable_op_id_settings();
+    let test_repo = TestRepo::init_with_settings(&settings);
+    let op_dir = test_repo.repo_path().join("op_store").join("operations");
+    let pack_dir = test_repo.repo_path().join("op_store").join("packs");
+    let repo_0 = &test_repo.repo;
+
+    let repo_a = repo_0.start_transaction().commit("op A").unwrap();
+    let repo_b = repo_a.start_transaction().commit("op B").unwrap();
+    repo_b
+        .op_store()
+        .gc(slice::from_ref(repo_b.op_id()), SystemTime::now())
+        .unwrap();
+    assert!(list_dir(&op_dir).is_empty());
+
+    // New operations are written as loose files on top of the pack
+    let repo_c = repo_b.start_transaction().commit("op C").unwrap();
+    assert_eq!(list_dir(&op_dir), vec![repo_c.op_id().hex()]);
+
+    // Operations can be loaded from fresh store
+    let repo = test_repo
+        .env
+        .load_repo_at_head(&settings, test_repo.repo_path());
+    let op_store = repo.op_store();
+    for op_id in [repo_a.op_id(), repo_b.op_id(), repo_c.op_id()] {
+        assert!(op_store.read_operation(op_id).is_ok());
+        let prefix = HexPrefix::new(&op_id.hex()[..8]).unwrap();
+        assert_eq!(
+            op_store.resolve_operation_id_prefix(&prefix).unwrap(),
+            PrefixResolution::SingleMatch(op_id.clone())
+        );
+        let prefix = HexPrefix::new(&op_id.hex()).unwrap();
+        assert_eq!(
+            op_store.resolve_operation_id_prefix(&prefix).unwrap(),
+            PrefixResolution::SingleMatch(op_id.clone())
+        );
+    }
+    assert_eq!(
+        op_store
+            .resolve_operation_id_prefix(&HexPrefix::new("").unwrap())
+            .unwrap(),
+        PrefixResolution::AmbiguousMatch
+    );
+
+    // Packs are merged, and the loose file is removed
+    op_store
+        .gc(slice::from_ref(repo_c.op_id()), SystemTime::now())
+        .unwrap();
+    assert!(list_dir(&op_dir).is_empty());
+    assert_eq!(list_dir(&pack_dir).len(), 1);
+    assert!(op_store.read_operation(repo_c.op_id()).is_ok());
+
+    // Recent unreachable operations are moved back to loose files
+    op_store
+        .gc(slice::from_ref(repo_b.op_id()), SystemTime::UNIX_EPOCH)
+        .unwrap();
+    assert_eq!(list_dir(&op_dir), vec![repo_c.op_id().hex()]);
+    assert!(op_store.read_operation(repo_c.op_id()).is_ok());
 }
 
 #[track_caller]
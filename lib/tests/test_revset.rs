@@ -2458,6 +2458,77 @@ fn test_evaluate_expression_latest() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_landed() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+
+    let file = repo_path("file");
+    let other_file = repo_path("other_file");
+    let base_tree = create_tree(repo, &[(file, "a\nb\nc\n")]);
+    let base = mut_repo
+        .new_commit(vec![repo.store().root_commit_id().clone()], base_tree.id())
+        .write()
+        .unwrap();
+    // Upstream modifies the context, then cherry-picks local1 with different
+    // whitespace.
+    let upstream1_tree = create_tree(repo, &[(file, "a\nb\nc2\n")]);
+    let upstream1 = mut_repo
+        .new_commit(vec![base.id().clone()], upstream1_tree.id())
+        .write()
+        .unwrap();
+    let upstream2_tree = create_tree(repo, &[(file, "a\n  b1\nc2\n")]);
+    let upstream2 = mut_repo
+        .new_commit(vec![upstream1.id().clone()], upstream2_tree.id())
+        .write()
+        .unwrap();
+    let upstream3 = mut_repo
+        .new_commit(vec![upstream2.id().clone()], upstream2_tree.id())
+        .write()
+        .unwrap();
+    let local1_tree = create_tree(repo, &[(file, "a\nb1\nc\n")]);
+    let local1 = mut_repo
+        .new_commit(vec![base.id().clone()], local1_tree.id())
+        .write()
+        .unwrap();
+    let local2_tree = create_tree(repo, &[(file, "a\nb1\nc\n"), (other_file, "1\n")]);
+    let local2 = mut_repo
+        .new_commit(vec![local1.id().clone()], local2_tree.id())
+        .write()
+        .unwrap();
+    let local3 = mut_repo
+        .new_commit(vec![base.id().clone()], base_tree.id())
+        .write()
+        .unwrap();
+
+    let upstream = format!("{}::{}", upstream1.id(), upstream3.id());
+    let local = format!("{}::{} | {}", local1.id(), local2.id(), local3.id());
+    // The cherry-picked commit is found, but not the empty commit
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("landed({local}, {upstream})")),
+        vec![local1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("landed({local}, {})", upstream1.id())),
+        vec![]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("landed({local}, none())")),
+        vec![]
+    );
+    // A commit matches itself
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("landed({}, ::{})", local2.id(), local2.id())
+        ),
+        vec![local2.id().clone()]
+    );
+}
+
 #[test]
 fn test_evaluate_expression_fork_point() {
     let test_repo = TestRepo::init();
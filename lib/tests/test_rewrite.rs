@@ -29,6 +29,7 @@ use jj_lib::ref_name::WorkspaceName;
 use jj_lib::ref_name::WorkspaceNameBuf;
 use jj_lib::repo::Repo as _;
 use jj_lib::rewrite::find_duplicate_divergent_commits;
+use jj_lib::rewrite::rebase_commit;
 use jj_lib::rewrite::rebase_commit_with_options;
 use jj_lib::rewrite::restore_tree;
 use jj_lib::rewrite::CommitRewriter;
@@ -49,6 +50,7 @@ use testutils::repo_path;
 use testutils::write_random_commit;
 use testutils::CommitGraphBuilder;
 use testutils::TestRepo;
+use testutils::TestRepoBackend;
 
 fn remote_symbol<'a, N, M>(name: &'a N, remote: &'a M) -> RemoteRefSymbol<'a>
 where
@@ -928,6 +930,43 @@ fn test_rebase_descendants_contents() {
     );
 }
 
+#[test]
+fn test_rebase_follows_renamed_files() {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_repo.repo;
+
+    // Commit B renamed the file which commit C modified. Rebasing C onto B, or
+    // B onto C, should apply the modification to the renamed file.
+    //
+    // B C
+    // |/
+    // A
+    let old_path = repo_path("old");
+    let new_path = repo_path("new");
+    let base_content = "1\n2\n3\n4\n5\n";
+    let modified_content = "1\n2\n3\n4\nmodified\n";
+    let mut tx = repo.start_transaction();
+    let mut new_commit = |parent: &Commit, path, content| {
+        let tree = create_tree(repo, &[(path, content)]);
+        tx.repo_mut()
+            .new_commit(vec![parent.id().clone()], tree.id())
+            .write()
+            .unwrap()
+    };
+    let commit_a = new_commit(&repo.store().root_commit(), old_path, base_content);
+    let commit_b = new_commit(&commit_a, new_path, base_content);
+    let commit_c = new_commit(&commit_a, old_path, modified_content);
+    let expected_tree = create_tree(repo, &[(new_path, modified_content)]);
+
+    let rebased_c =
+        rebase_commit(tx.repo_mut(), commit_c.clone(), vec![commit_b.id().clone()]).unwrap();
+    assert_eq!(*rebased_c.tree_id(), expected_tree.id());
+
+    let rebased_b =
+        rebase_commit(tx.repo_mut(), commit_b.clone(), vec![commit_c.id().clone()]).unwrap();
+    assert_eq!(*rebased_b.tree_id(), expected_tree.id());
+}
+
 #[test]
 fn test_rebase_descendants_basic_bookmark_update() {
     let test_repo = TestRepo::init();
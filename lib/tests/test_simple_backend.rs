@@ -0,0 +1,141 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use itertools::Itertools as _;
+use jj_lib::backend::BackendError;
+use jj_lib::backend::CommitId;
+use jj_lib::backend::GcStats;
+use jj_lib::commit::Commit;
+use jj_lib::repo::Repo as _;
+use jj_lib::transaction::Transaction;
+use pollster::FutureExt as _;
+use testutils::create_tree;
+use testutils::repo_path;
+use testutils::TestRepo;
+use testutils::TestRepoBackend;
+
+fn make_commit(tx: &mut Transaction, parents: Vec<CommitId>, name: &str) -> Commit {
+    let path = repo_path(name);
+    let tree = create_tree(tx.base_repo(), &[(path, name)]);
+    tx.repo_mut()
+        .new_commit(parents, tree.id())
+        .set_description(name)
+        .write()
+        .unwrap()
+}
+
+fn count_files(dir: &Path) -> usize {
+    std::fs::read_dir(dir).unwrap().count()
+}
+
+#[test]
+fn test_gc() {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Simple);
+    let store_path = test_repo.repo_path().join("store");
+    let repo = test_repo.repo;
+    let base_index = repo.readonly_index();
+
+    // Set up commits:
+    //
+    //   D (predecessor: C)
+    // C |
+    // |/
+    // B
+    // A
+    let mut tx = repo.start_transaction();
+    let root_commit_id = repo.store().root_commit_id().clone();
+    let commit_a = make_commit(&mut tx, vec![root_commit_id], "a");
+    let commit_b = make_commit(&mut tx, vec![commit_a.id().clone()], "b");
+    let commit_c = make_commit(&mut tx, vec![commit_b.id().clone()], "c");
+    let commit_d = tx
+        .repo_mut()
+        .new_commit(vec![commit_b.id().clone()], commit_c.tree_id().clone())
+        .set_predecessors(vec![commit_c.id().clone()])
+        .set_description("d")
+        .write()
+        .unwrap();
+    let repo = tx.commit("test").unwrap();
+    let has_commit =
+        |commit: &Commit| match repo.store().backend().read_commit(commit.id()).block_on() {
+            Ok(_) => true,
+            Err(BackendError::ObjectNotFound { .. }) => false,
+            Err(err) => panic!("unexpected error: {err}"),
+        };
+    let all_commits = [&commit_a, &commit_b, &commit_c, &commit_d];
+    let stored_commits = || {
+        all_commits
+            .iter()
+            .filter(|commit| has_commit(commit))
+            .map(|commit| commit.id().clone())
+            .collect_vec()
+    };
+    let all_commit_ids = all_commits.map(|commit| commit.id().clone());
+    assert_eq!(count_files(&store_path.join("files")), 3);
+
+    // Don't rely on the exact system time because file modification time might
+    // have lower precision for example.
+    let now = || SystemTime::now() + Duration::from_secs(1);
+
+    // Empty index, but all kept by file modification time
+    let stats = repo
+        .store()
+        .gc(base_index.as_index(), SystemTime::UNIX_EPOCH, false)
+        .unwrap();
+    assert_eq!(stats, GcStats::default());
+    assert_eq!(stored_commits(), all_commit_ids);
+
+    // All reachable
+    let stats = repo.store().gc(repo.index(), now(), false).unwrap();
+    assert_eq!(stats, GcStats::default());
+    assert_eq!(stored_commits(), all_commit_ids);
+
+    // Only A is reachable, but nothing is removed in dry-run mode
+    let mut mut_index = base_index.start_modification();
+    mut_index.add_commit(&commit_a);
+    let dry_run_stats = repo.store().gc(mut_index.as_index(), now(), true).unwrap();
+    // B|C|D, and trees and files of B|C
+    assert_eq!(dry_run_stats.removed_objects, 7);
+    assert!(dry_run_stats.removed_bytes > 0);
+    assert_eq!(stored_commits(), all_commit_ids);
+
+    // D is reachable, which keeps its predecessor C
+    let mut mut_index = base_index.start_modification();
+    mut_index.add_commit(&commit_a);
+    mut_index.add_commit(&commit_b);
+    mut_index.add_commit(&commit_d);
+    let stats = repo.store().gc(mut_index.as_index(), now(), false).unwrap();
+    assert_eq!(stats, GcStats::default());
+    assert_eq!(stored_commits(), all_commit_ids);
+
+    // Only A is reachable
+    let mut mut_index = base_index.start_modification();
+    mut_index.add_commit(&commit_a);
+    let stats = repo.store().gc(mut_index.as_index(), now(), false).unwrap();
+    assert_eq!(stats, dry_run_stats);
+    assert_eq!(stored_commits(), [commit_a.id().clone()]);
+    assert_eq!(count_files(&store_path.join("files")), 1);
+
+    // All unreachable, but the empty tree is kept
+    repo.store()
+        .gc(base_index.as_index(), now(), false)
+        .unwrap();
+    assert!(stored_commits().is_empty());
+    assert_eq!(count_files(&store_path.join("commits")), 0);
+    assert_eq!(count_files(&store_path.join("trees")), 1);
+    assert_eq!(count_files(&store_path.join("files")), 0);
+}
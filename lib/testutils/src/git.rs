@@ -398,3 +398,18 @@ pub fn fetch(repo_dir: impl AsRef<Path>, remote: &str) {
         bstr::BString::from(output.stderr),
     );
 }
+
+pub fn checkout_detached(work_tree: impl AsRef<Path>, commit: &str) {
+    let output = std::process::Command::new("git")
+        .current_dir(work_tree)
+        .args(["checkout", "--quiet", "--detach", commit])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git checkout {commit} failed with {}:\n{}\n----- stderr -----\n{}",
+        output.status,
+        bstr::BString::from(output.stdout),
+        bstr::BString::from(output.stderr),
+    );
+}
@@ -37,6 +37,7 @@ use jj_lib::backend::MillisSinceEpoch;
 use jj_lib::backend::Signature;
 use jj_lib::backend::Timestamp;
 use jj_lib::backend::TreeValue;
+use jj_lib::chunked_backend::ChunkedBackend;
 use jj_lib::commit::Commit;
 use jj_lib::commit_builder::CommitBuilder;
 use jj_lib::config::ConfigLayer;
@@ -195,6 +196,7 @@ pub struct TestRepo {
 pub enum TestRepoBackend {
     Git,
     Simple,
+    Chunked,
     Test,
 }
 
@@ -208,6 +210,7 @@ impl TestRepoBackend {
         match self {
             TestRepoBackend::Git => Ok(Box::new(GitBackend::init_internal(settings, store_path)?)),
             TestRepoBackend::Simple => Ok(Box::new(SimpleBackend::init(store_path))),
+            TestRepoBackend::Chunked => Ok(Box::new(ChunkedBackend::init(store_path))),
             TestRepoBackend::Test => Ok(Box::new(env.test_backend_factory.init(store_path))),
         }
     }
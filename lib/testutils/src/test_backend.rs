@@ -40,6 +40,7 @@ use jj_lib::backend::Conflict;
 use jj_lib::backend::ConflictId;
 use jj_lib::backend::CopyRecord;
 use jj_lib::backend::FileId;
+use jj_lib::backend::GcStats;
 use jj_lib::backend::SecureSig;
 use jj_lib::backend::SigningFn;
 use jj_lib::backend::SymlinkId;
@@ -352,7 +353,12 @@ impl Backend for TestBackend {
         Ok(Box::pin(stream::empty()))
     }
 
-    fn gc(&self, _index: &dyn Index, _keep_newer: SystemTime) -> BackendResult<()> {
-        Ok(())
+    fn gc(
+        &self,
+        _index: &dyn Index,
+        _keep_newer: SystemTime,
+        _dry_run: bool,
+    ) -> BackendResult<GcStats> {
+        Ok(GcStats::default())
     }
 }